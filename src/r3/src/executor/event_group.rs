@@ -0,0 +1,174 @@
+//! The async adapter for event groups.
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{
+    waker_list::{Waiter, WakerList},
+    WaitError,
+};
+use crate::{
+    hunk::Hunk,
+    kernel::{
+        event_group::EventGroupDefiner, prelude::*, traits, Cfg, EventGroupBits,
+        EventGroupWaitFlags, PollEventGroupError, QueueOrder, StaticEventGroup,
+        UpdateEventGroupError,
+    },
+};
+
+/// A statically defined [event group](crate::kernel::EventGroup) that can be
+/// waited for by futures.
+///
+/// The futures are woken up when bits are set by [`set`](Self::set). Setting
+/// bits of the underlying event group directly doesn't wake them up.
+pub struct StaticAsyncEventGroup<System: traits::KernelEventGroup + traits::KernelStatic> {
+    event_group: StaticEventGroup<System>,
+    waiters: Hunk<System, WakerList<System>>,
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> Clone
+    for StaticAsyncEventGroup<System>
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> Copy
+    for StaticAsyncEventGroup<System>
+{
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> fmt::Debug
+    for StaticAsyncEventGroup<System>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticAsyncEventGroup")
+            .field("event_group", &self.event_group)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The definer (static builder) for [`StaticAsyncEventGroup`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct AsyncEventGroupDefiner<System: traits::KernelEventGroup> {
+    inner: EventGroupDefiner<System>,
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> StaticAsyncEventGroup<System> {
+    /// Construct a `AsyncEventGroupDefiner` to define an event group in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> AsyncEventGroupDefiner<System> {
+        AsyncEventGroupDefiner {
+            inner: StaticEventGroup::define(),
+        }
+    }
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> AsyncEventGroupDefiner<System> {
+    /// Specify the initial bit pattern. See [`EventGroupDefiner::initial`].
+    pub const fn initial(self, initial: EventGroupBits) -> Self {
+        Self {
+            inner: self.inner.initial(initial),
+        }
+    }
+
+    /// Specify how tasks are sorted in the wait queue of the underlying
+    /// event group. See [`EventGroupDefiner::queue_order`].
+    pub const fn queue_order(self, queue_order: QueueOrder) -> Self {
+        Self {
+            inner: self.inner.queue_order(queue_order),
+        }
+    }
+
+    /// Complete the definition of an event group, returning a reference to
+    /// the event group.
+    pub const fn finish<C: ~const traits::CfgEventGroup<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticAsyncEventGroup<System> {
+        StaticAsyncEventGroup {
+            event_group: self.inner.finish(cfg),
+            waiters: Hunk::<_, WakerList<System>>::define().finish(cfg),
+        }
+    }
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> StaticAsyncEventGroup<System> {
+    /// Get the underlying event group.
+    #[inline]
+    pub const fn event_group(&self) -> StaticEventGroup<System> {
+        self.event_group
+    }
+
+    /// Set the specified bits and wake up the waiting futures.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn set(&self, bits: EventGroupBits) -> Result<(), UpdateEventGroupError> {
+        self.event_group.set(bits)?;
+        self.waiters.wake_all();
+        Ok(())
+    }
+
+    /// Get a future that waits for all or any of the specified bits to be
+    /// set. See [`EventGroupMethods::wait`] for the meanings of the
+    /// parameters and the output.
+    ///
+    /// [`EventGroupMethods::wait`]: crate::kernel::traits::EventGroupMethods::wait
+    pub fn wait(
+        &self,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+    ) -> WaitEventGroup<'_, System> {
+        WaitEventGroup {
+            event_group: self.event_group,
+            bits,
+            flags,
+            waiter: Waiter::new(&self.waiters),
+        }
+    }
+}
+
+/// The future returned by [`StaticAsyncEventGroup::wait`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitEventGroup<'a, System: traits::KernelEventGroup + traits::KernelStatic> {
+    event_group: StaticEventGroup<System>,
+    bits: EventGroupBits,
+    flags: EventGroupWaitFlags,
+    waiter: Waiter<'a, System>,
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> fmt::Debug
+    for WaitEventGroup<'_, System>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitEventGroup")
+            .field("event_group", &self.event_group)
+            .field("bits", &self.bits)
+            .field("flags", &self.flags)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<System: traits::KernelEventGroup + traits::KernelStatic> Future
+    for WaitEventGroup<'_, System>
+{
+    type Output = Result<EventGroupBits, WaitError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.into_ref().get_ref();
+        // Safety: `waiter` is structurally pinned
+        let waiter = unsafe { Pin::new_unchecked(&this.waiter) };
+        waiter.poll_with(cx.waker(), || {
+            match this.event_group.poll(this.bits, this.flags) {
+                Ok(bits) => Poll::Ready(Ok(bits)),
+                Err(PollEventGroupError::Timeout) => Poll::Pending,
+                Err(PollEventGroupError::NoAccess) => Poll::Ready(Err(WaitError::NoAccess)),
+                Err(PollEventGroupError::BadContext) => Poll::Ready(Err(WaitError::BadContext)),
+            }
+        })
+    }
+}
@@ -0,0 +1,619 @@
+//! A single-task executor for futures.
+//!
+//! An [executor](StaticExecutor) owns a kernel task and polls the futures of
+//! the [async tasks](StaticAsyncTask) spawned onto it. An async task is a
+//! statically defined future whose storage is a [hunk](crate::hunk::Hunk)
+//! sized for its type, so no memory allocator is needed. The [`Waker`]s passed
+//! to the futures put the async tasks back into the executor's ready queue and
+//! [unpark](crate::kernel::traits::TaskMethods::unpark_exact) the executor's task, which
+//! [parks](crate::kernel::Kernel::park) itself while there's nothing to poll.
+//!
+//! Kernel objects don't have a way to notify futures, so this module provides
+//! async adapters that wrap them:
+//!
+//!  - [`StaticAsyncSemaphore`] wraps [`StaticSemaphore`]. Its futures wait
+//!    for the semaphore to be signaled through the adapter.
+//!  - [`StaticAsyncEventGroup`] wraps [`StaticEventGroup`]. Its futures wait
+//!    for bits to be set through the adapter.
+//!  - [`StaticAsyncTimer`] uses a [`StaticTimer`] to implement
+//!    [`sleep`](StaticAsyncTimer::sleep).
+//!  - [`StaticSignal`] is a flag that can be set by an interrupt handler to
+//!    wake up a future.
+//!
+//! The adapters' futures can be polled by any executor, not just
+//! [`StaticExecutor`].
+//!
+//! This module is built only on the kernel traits defined by [`r3_core`] and
+//! works with any kernel implementation.
+//!
+//! [`StaticSemaphore`]: crate::kernel::StaticSemaphore
+//! [`StaticEventGroup`]: crate::kernel::StaticEventGroup
+//! [`StaticTimer`]: crate::kernel::StaticTimer
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    pin::Pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use r3_core::closure::Closure;
+
+use crate::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, ActivateTaskError, Cfg, ParkError, StaticTask, UnparkExactError},
+    klock::{CpuLockCell, CpuLockGuard},
+    utils::Init,
+};
+
+mod event_group;
+mod semaphore;
+mod signal;
+mod timer;
+mod waker_list;
+
+pub use self::{
+    event_group::{AsyncEventGroupDefiner, StaticAsyncEventGroup, WaitEventGroup},
+    semaphore::{AsyncSemaphoreDefiner, StaticAsyncSemaphore, WaitSemaphore},
+    signal::{SignalDefiner, SignalError, StaticSignal, WaitSignal},
+    timer::{AsyncTimerDefiner, Sleep, SleepError, StaticAsyncTimer},
+};
+
+/// A statically defined executor, which polls the futures of the
+/// [spawned](StaticAsyncTask::spawn) async tasks in a dedicated kernel task.
+///
+/// The executor's task is activated by the first spawned async task. It parks
+/// itself while all async tasks are waiting to be woken up and exits when
+/// all async tasks have completed.
+///
+/// The [`Waker`]s created by the executor must not be woken up with CPU Lock
+/// active.
+///
+/// # Example
+///
+#[doc = crate::tests::doc_test!(
+/// ```rust
+/// use r3::{
+///     executor::{StaticAsyncTask, StaticExecutor, StaticSignal},
+///     kernel::{StaticTask, StaticTimer},
+///     time::Duration,
+/// };
+///
+/// struct Objects {
+///     worker: StaticAsyncTask<System>,
+///     signal: StaticSignal<System>,
+/// }
+///
+/// const fn configure_app<C>(cfg: &mut Cfg<C>) -> Objects
+/// where
+///     C: ~const traits::CfgTask<System = System> +
+///        ~const traits::CfgTimer,
+/// {
+///     let executor = StaticExecutor::define()
+///         .priority(1)
+///         .finish(cfg);
+///
+///     let worker = StaticAsyncTask::define()
+///         .executor(executor)
+///         .start(worker_body)
+///         .finish(cfg);
+///
+///     let signal = StaticSignal::define().finish(cfg);
+///
+///     StaticTask::define()
+///         .start(|| COTTAGE.worker.spawn().unwrap())
+///         .priority(2)
+///         .active(true)
+///         .finish(cfg);
+///
+///     // The callback function of a timer is called in an interrupt context
+///     // by default
+///     StaticTimer::define()
+///         .delay(Duration::from_millis(10))
+///         .active(true)
+///         .start(|| COTTAGE.signal.signal().unwrap())
+///         .finish(cfg);
+///
+///     Objects { worker, signal }
+/// }
+///
+/// async fn worker_body() {
+///     COTTAGE.signal.wait().await;
+/// #   exit(0);
+/// }
+/// ```
+)]
+pub struct StaticExecutor<System: traits::KernelBase + traits::KernelStatic> {
+    task: StaticTask<System>,
+    state: Hunk<System, ExecutorState<System>>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for StaticExecutor<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for StaticExecutor<System> {}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for StaticExecutor<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticExecutor")
+            .field("task", &self.task)
+            .finish_non_exhaustive()
+    }
+}
+
+struct ExecutorState<System: traits::KernelBase + traits::KernelStatic> {
+    /// The first async task in the ready queue.
+    head: CpuLockCell<Option<Hunk<System, Header<System>>>>,
+    /// The last async task in the ready queue.
+    tail: CpuLockCell<Option<Hunk<System, Header<System>>>>,
+    /// The number of async tasks that have been spawned and haven't completed
+    /// yet.
+    num_tasks: CpuLockCell<usize>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Init for ExecutorState<System> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        head: Init::INIT,
+        tail: Init::INIT,
+        num_tasks: Init::INIT,
+    };
+}
+
+/// The definer (static builder) for [`StaticExecutor`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct ExecutorDefiner<System> {
+    _phantom: PhantomData<System>,
+    priority: Option<usize>,
+    stack_size: Option<usize>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticExecutor<System> {
+    /// Construct a `ExecutorDefiner` to define an executor in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> ExecutorDefiner<System> {
+        ExecutorDefiner {
+            _phantom: PhantomData,
+            priority: None,
+            stack_size: None,
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> ExecutorDefiner<System> {
+    /// \[**Required**\] Specify the executor task's priority.
+    pub const fn priority(self, priority: usize) -> Self {
+        Self {
+            priority: Some(priority),
+            ..self
+        }
+    }
+
+    /// Specify the executor task's stack size.
+    pub const fn stack_size(self, stack_size: usize) -> Self {
+        Self {
+            stack_size: Some(stack_size),
+            ..self
+        }
+    }
+
+    /// Complete the definition of an executor, returning a reference to the
+    /// executor.
+    pub const fn finish<C: ~const traits::CfgTask<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticExecutor<System> {
+        let priority = self
+            .priority
+            .expect("`priority` (executor task priority) is not specified");
+
+        let state = Hunk::<_, ExecutorState<System>>::define().finish(cfg);
+
+        let task = StaticTask::define()
+            .start(move || executor_body(state))
+            .priority(priority)
+            // Allow `StaticAsyncTask::spawn` to succeed while the executor
+            // task is exiting after finding no async tasks to poll
+            .max_queued_activations(1);
+        let task = if let Some(stack_size) = self.stack_size {
+            task.stack_size(stack_size)
+        } else {
+            task
+        };
+        let task = task.finish(cfg);
+
+        StaticExecutor { task, state }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticExecutor<System> {
+    /// Get the executor task.
+    #[inline]
+    pub const fn task(&self) -> StaticTask<System> {
+        self.task
+    }
+
+    /// Push `header` to the ready queue.
+    fn push(&self, lock: &CpuLockGuard<System>, header: Hunk<System, Header<System>>) {
+        header.next.set(lock, None);
+        match self.state.tail.get(lock) {
+            Some(tail) => tail.next.set(lock, Some(header)),
+            None => self.state.head.set(lock, Some(header)),
+        }
+        self.state.tail.set(lock, Some(header));
+    }
+
+    /// Wake up the executor task. CPU Lock must be inactive.
+    ///
+    /// `activate` must be `true` if the executor task might have exited or
+    /// might be about to exit after finding no async tasks to poll.
+    fn wake(&self, activate: bool) {
+        if activate {
+            match self.task.activate() {
+                // `QueueOverflow` means the executor task is already running
+                // and has a pending activation request, which guarantees that
+                // it'll check the ready queue again
+                Ok(()) | Err(ActivateTaskError::QueueOverflow) => {}
+                Err(e) => panic!("failed to activate the executor task: {e:?}"),
+            }
+        } else {
+            match self.task.unpark_exact() {
+                // `BadObjectState` means the executor task hasn't started yet,
+                // in which case it'll check the ready queue when it does
+                Ok(())
+                | Err(UnparkExactError::QueueOverflow)
+                | Err(UnparkExactError::BadObjectState) => {}
+                Err(e) => panic!("failed to unpark the executor task: {e:?}"),
+            }
+        }
+    }
+}
+
+/// The entry point of an executor task. Polls the async tasks in the ready
+/// queue and exits when all async tasks have completed.
+fn executor_body<System: traits::KernelBase + traits::KernelStatic>(
+    state: Hunk<System, ExecutorState<System>>,
+) {
+    loop {
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        let Some(header) = state.head.get(&lock) else {
+            if state.num_tasks.get(&lock) == 0 {
+                break;
+            }
+            drop(lock);
+            match System::park() {
+                Ok(()) | Err(ParkError::Interrupted) => continue,
+                Err(e) => panic!("failed to park the executor task: {e:?}"),
+            }
+        };
+
+        let next = header.next.get(&lock);
+        state.head.set(&lock, next);
+        if next.is_none() {
+            state.tail.set(&lock, None);
+        }
+        let fresh = header.state.get(&lock) == TaskState::Spawned;
+        header.state.set(&lock, TaskState::Running);
+        let task = header
+            .task
+            .get(&lock)
+            .expect("spawned async task is not bound");
+        drop(lock);
+
+        if fresh {
+            task.init.call();
+        }
+
+        let waker = task.waker();
+        let mut cx = Context::from_waker(&waker);
+        // Safety: The future was initialized by `init`, and it's not accessed
+        //         by anyone else while the async task is `Running`
+        let poll = unsafe { (task.poll)(task.future, &mut cx) };
+
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        let new_state = match (poll, header.state.get(&lock)) {
+            (Poll::Ready(()), _) => {
+                state.num_tasks.set(&lock, state.num_tasks.get(&lock) - 1);
+                TaskState::Idle
+            }
+            (Poll::Pending, TaskState::RunningWoken) => {
+                task.executor.push(&lock, header);
+                TaskState::Queued
+            }
+            (Poll::Pending, _) => TaskState::Waiting,
+        };
+        header.state.set(&lock, new_state);
+    }
+}
+
+/// A statically defined async task, which can be [spawned](Self::spawn) onto
+/// a [`StaticExecutor`].
+///
+/// An async task is defined with a function returning a future. Each time
+/// the async task is spawned, the executor calls the function to construct
+/// the future and polls it until completion. The future is stored in a hunk,
+/// so it doesn't have to be [`Unpin`].
+///
+/// # Example
+///
+/// See [`StaticExecutor`].
+pub struct StaticAsyncTask<System: traits::KernelBase + traits::KernelStatic> {
+    executor: StaticExecutor<System>,
+    header: Hunk<System, Header<System>>,
+    /// Constructs the future in `future`.
+    init: Closure,
+    poll: PollFn<System>,
+    /// The type-erased storage for the future.
+    future: Hunk<System, ()>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for StaticAsyncTask<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for StaticAsyncTask<System> {}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for StaticAsyncTask<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticAsyncTask")
+            .field("executor", &self.executor)
+            .field("init", &self.init)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Polls the future stored in the given hunk and drops it when it completes.
+type PollFn<System> = unsafe fn(Hunk<System, ()>, &mut Context<'_>) -> Poll<()>;
+
+/// The part of [`StaticAsyncTask`] accessed by [`Waker`]s.
+struct Header<System: traits::KernelBase + traits::KernelStatic> {
+    state: CpuLockCell<TaskState>,
+    /// The next async task in the ready queue.
+    next: CpuLockCell<Option<Hunk<System, Header<System>>>>,
+    /// Set by the first call to [`StaticAsyncTask::spawn`].
+    task: CpuLockCell<Option<StaticAsyncTask<System>>>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Init for Header<System> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        state: Init::INIT,
+        next: Init::INIT,
+        task: Init::INIT,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskState {
+    Idle,
+    /// In the ready queue. The future hasn't been constructed yet.
+    Spawned,
+    /// In the ready queue.
+    Queued,
+    /// Being polled.
+    Running,
+    /// Being polled, and woken up during the poll.
+    RunningWoken,
+    /// Waiting to be woken up.
+    Waiting,
+}
+
+impl Init for TaskState {
+    const INIT: Self = Self::Idle;
+}
+
+/// The definer (static builder) for [`StaticAsyncTask`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct AsyncTaskDefiner<System: traits::KernelBase + traits::KernelStatic, Start> {
+    executor: Option<StaticExecutor<System>>,
+    start: Start,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticAsyncTask<System> {
+    /// Construct a `AsyncTaskDefiner` to define an async task in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> AsyncTaskDefiner<System, ()> {
+        AsyncTaskDefiner {
+            executor: None,
+            start: (),
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic, Start> AsyncTaskDefiner<System, Start> {
+    /// \[**Required**\] Specify the executor to spawn the async task onto.
+    pub const fn executor(self, executor: StaticExecutor<System>) -> Self {
+        Self {
+            executor: Some(executor),
+            ..self
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> AsyncTaskDefiner<System, ()> {
+    /// \[**Required**\] Specify the function to construct the async task's
+    /// future, such as an `async fn` taking no parameters.
+    pub const fn start<Start, Fut>(self, start: Start) -> AsyncTaskDefiner<System, Start>
+    where
+        Start: FnOnce() -> Fut + Copy + Send + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        AsyncTaskDefiner {
+            executor: self.executor,
+            start,
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic, Start> AsyncTaskDefiner<System, Start> {
+    /// Complete the definition of an async task, returning a reference to the
+    /// async task.
+    pub const fn finish<C: ~const traits::CfgBase<System = System>, Fut>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticAsyncTask<System>
+    where
+        Start: FnOnce() -> Fut + Copy + Send + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let start = self.start;
+        let future = Hunk::<_, UnsafeCell<MaybeUninit<Fut>>>::define().finish(cfg);
+        // Safety: `future` is only accessed through `init` and `poll_future`,
+        //         which transmute it back
+        let future = unsafe { future.transmute::<()>() };
+
+        StaticAsyncTask {
+            executor: self.executor.expect("`executor` is not specified"),
+            header: Hunk::<_, Header<System>>::define().finish(cfg),
+            init: Closure::from_fn_const(move || {
+                // Safety: `future` was transmuted from this type
+                let future = unsafe { future.transmute::<UnsafeCell<MaybeUninit<Fut>>>() };
+                // Safety: The executor task is the only one accessing the
+                //         storage, and the previous future has been dropped
+                unsafe { (*future.get()).write(start()) };
+            }),
+            poll: poll_future::<System, Fut>,
+            future,
+        }
+    }
+}
+
+/// The [`PollFn`] for `Fut`.
+///
+/// # Safety
+///
+/// `future` must have been transmuted from a hunk of
+/// `UnsafeCell<MaybeUninit<Fut>>`, and it must contain a valid `Fut`. The
+/// caller must have exclusive access to the hunk.
+unsafe fn poll_future<
+    System: traits::KernelBase + traits::KernelStatic,
+    Fut: Future<Output = ()>,
+>(
+    future: Hunk<System, ()>,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    // Safety: Upheld by the caller
+    let future = unsafe { future.transmute::<UnsafeCell<MaybeUninit<Fut>>>() };
+    let ptr = future.get().cast::<Fut>();
+    // Safety: The future is stored in a hunk, so it never moves
+    let poll = unsafe { Pin::new_unchecked(&mut *ptr) }.poll(cx);
+    if poll.is_ready() {
+        // Safety: The future is initialized, and it's not used anymore
+        unsafe { ptr.drop_in_place() };
+    }
+    poll
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticAsyncTask<System> {
+    /// Spawn the async task onto the executor.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn spawn(&self) -> Result<(), SpawnError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| SpawnError::BadContext)?;
+        if self.header.state.get(&lock) != TaskState::Idle {
+            return Err(SpawnError::AlreadySpawned);
+        }
+        self.header.task.set(&lock, Some(*self));
+        self.header.state.set(&lock, TaskState::Spawned);
+        self.executor.push(&lock, self.header);
+        let num_tasks = self.executor.state.num_tasks.get(&lock);
+        self.executor.state.num_tasks.set(&lock, num_tasks + 1);
+        drop(lock);
+
+        self.executor.wake(num_tasks == 0);
+        Ok(())
+    }
+
+    /// Get a flag indicating whether the async task has been spawned and
+    /// hasn't completed yet.
+    ///
+    /// This method can be called with CPU Lock active.
+    pub fn is_running(&self) -> bool {
+        let state = if System::has_cpu_lock() {
+            // Safety: CPU Lock is already active. The guard is not dropped.
+            let lock = ManuallyDrop::new(unsafe { CpuLockGuard::<System>::assume() });
+            self.header.state.get(&lock)
+        } else {
+            let lock = CpuLockGuard::<System>::new().unwrap();
+            self.header.state.get(&lock)
+        };
+        state != TaskState::Idle
+    }
+
+    /// Construct a [`Waker`] for the async task.
+    fn waker(&self) -> Waker {
+        let data = Hunk::as_ptr(self.header).cast::<()>();
+        // Safety: `data` points to a `Header<System>`, which is what the
+        //         vtable expects
+        unsafe { Waker::from_raw(RawWaker::new(data, &WakerVtable::<System>::VTABLE)) }
+    }
+}
+
+/// The [`RawWakerVTable`] of the [`Waker`]s created by [`StaticExecutor`].
+/// The data pointer points to a `Header<System>`, which is statically
+/// allocated, so the wakers don't own anything.
+struct WakerVtable<System>(PhantomData<System>);
+
+impl<System: traits::KernelBase + traits::KernelStatic> WakerVtable<System> {
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone, Self::wake, Self::wake, Self::drop);
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        RawWaker::new(data, &Self::VTABLE)
+    }
+
+    unsafe fn wake(data: *const ()) {
+        // Safety: `data` points to a `Header<System>`
+        let header = unsafe { &*data.cast::<Header<System>>() };
+
+        let lock = CpuLockGuard::<System>::new()
+            .expect("an async task can't be woken up with CPU Lock active");
+        let new_state = match header.state.get(&lock) {
+            TaskState::Running => TaskState::RunningWoken,
+            TaskState::Waiting => TaskState::Queued,
+            // Already woken up or completed
+            _ => return,
+        };
+        header.state.set(&lock, new_state);
+        let task = header
+            .task
+            .get(&lock)
+            .expect("spawned async task is not bound");
+        if new_state == TaskState::Queued {
+            task.executor.push(&lock, task.header);
+        }
+        drop(lock);
+
+        if new_state == TaskState::Queued {
+            task.executor.wake(false);
+        }
+    }
+
+    unsafe fn drop(_data: *const ()) {}
+}
+
+/// Error type of [`StaticAsyncTask::spawn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnError {
+    /// CPU Lock is active.
+    BadContext,
+    /// The async task has already been spawned and hasn't completed yet.
+    AlreadySpawned,
+}
+
+/// Error type of the futures returned by [`StaticAsyncSemaphore::wait_one`]
+/// and [`StaticAsyncEventGroup::wait`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError {
+    /// Invalid object access.
+    NoAccess,
+    /// CPU Lock is active.
+    BadContext,
+}
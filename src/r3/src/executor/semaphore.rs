@@ -0,0 +1,171 @@
+//! The async adapter for semaphores.
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{
+    waker_list::{Waiter, WakerList},
+    WaitError,
+};
+use crate::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, semaphore::SemaphoreDefiner, traits, Cfg, PollSemaphoreError, QueueOrder,
+        SemaphoreValue, SignalSemaphoreError, StaticSemaphore,
+    },
+};
+
+/// A statically defined [semaphore](crate::kernel::Semaphore) that can be
+/// waited for by futures.
+///
+/// The futures are woken up when the semaphore is signaled by
+/// [`signal`](Self::signal) or [`signal_one`](Self::signal_one). Signaling
+/// the underlying semaphore directly doesn't wake them up.
+pub struct StaticAsyncSemaphore<System: traits::KernelSemaphore + traits::KernelStatic> {
+    semaphore: StaticSemaphore<System>,
+    waiters: Hunk<System, WakerList<System>>,
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> Clone
+    for StaticAsyncSemaphore<System>
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> Copy for StaticAsyncSemaphore<System> {}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> fmt::Debug
+    for StaticAsyncSemaphore<System>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticAsyncSemaphore")
+            .field("semaphore", &self.semaphore)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The definer (static builder) for [`StaticAsyncSemaphore`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct AsyncSemaphoreDefiner<System: traits::KernelSemaphore> {
+    inner: SemaphoreDefiner<System>,
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> StaticAsyncSemaphore<System> {
+    /// Construct a `AsyncSemaphoreDefiner` to define a semaphore in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> AsyncSemaphoreDefiner<System> {
+        AsyncSemaphoreDefiner {
+            inner: StaticSemaphore::define(),
+        }
+    }
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> AsyncSemaphoreDefiner<System> {
+    /// \[**Required**\] Specify the initial semaphore value. See
+    /// [`SemaphoreDefiner::initial`].
+    pub const fn initial(self, initial: SemaphoreValue) -> Self {
+        Self {
+            inner: self.inner.initial(initial),
+        }
+    }
+
+    /// \[**Required**\] Specify the maximum semaphore value. See
+    /// [`SemaphoreDefiner::maximum`].
+    pub const fn maximum(self, maximum: SemaphoreValue) -> Self {
+        Self {
+            inner: self.inner.maximum(maximum),
+        }
+    }
+
+    /// Specify how tasks are sorted in the wait queue of the underlying
+    /// semaphore. See [`SemaphoreDefiner::queue_order`].
+    pub const fn queue_order(self, queue_order: QueueOrder) -> Self {
+        Self {
+            inner: self.inner.queue_order(queue_order),
+        }
+    }
+
+    /// Complete the definition of a semaphore, returning a reference to the
+    /// semaphore.
+    pub const fn finish<C: ~const traits::CfgSemaphore<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticAsyncSemaphore<System> {
+        StaticAsyncSemaphore {
+            semaphore: self.inner.finish(cfg),
+            waiters: Hunk::<_, WakerList<System>>::define().finish(cfg),
+        }
+    }
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> StaticAsyncSemaphore<System> {
+    /// Get the underlying semaphore.
+    #[inline]
+    pub const fn semaphore(&self) -> StaticSemaphore<System> {
+        self.semaphore
+    }
+
+    /// Increment the semaphore value by the specified amount and wake up the
+    /// waiting futures.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn signal(&self, count: SemaphoreValue) -> Result<(), SignalSemaphoreError> {
+        self.semaphore.signal(count)?;
+        self.waiters.wake_all();
+        Ok(())
+    }
+
+    /// Increment the semaphore value by one and wake up the waiting futures.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn signal_one(&self) -> Result<(), SignalSemaphoreError> {
+        self.signal(1)
+    }
+
+    /// Get a future that decrements the semaphore value by one, waiting until
+    /// it's positive.
+    pub fn wait_one(&self) -> WaitSemaphore<'_, System> {
+        WaitSemaphore {
+            semaphore: self.semaphore,
+            waiter: Waiter::new(&self.waiters),
+        }
+    }
+}
+
+/// The future returned by [`StaticAsyncSemaphore::wait_one`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitSemaphore<'a, System: traits::KernelSemaphore + traits::KernelStatic> {
+    semaphore: StaticSemaphore<System>,
+    waiter: Waiter<'a, System>,
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> fmt::Debug
+    for WaitSemaphore<'_, System>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitSemaphore")
+            .field("semaphore", &self.semaphore)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<System: traits::KernelSemaphore + traits::KernelStatic> Future for WaitSemaphore<'_, System> {
+    type Output = Result<(), WaitError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.into_ref().get_ref();
+        // Safety: `waiter` is structurally pinned
+        let waiter = unsafe { Pin::new_unchecked(&this.waiter) };
+        waiter.poll_with(cx.waker(), || match this.semaphore.poll_one() {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(PollSemaphoreError::Timeout) => Poll::Pending,
+            Err(PollSemaphoreError::NoAccess) => Poll::Ready(Err(WaitError::NoAccess)),
+            Err(PollSemaphoreError::BadContext) => Poll::Ready(Err(WaitError::BadContext)),
+        })
+    }
+}
@@ -0,0 +1,154 @@
+//! Signals for waking up futures from interrupt handlers.
+use core::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::waker_list::{Waiter, WakerList};
+use crate::{
+    hunk::Hunk,
+    kernel::{traits, Cfg},
+    klock::{CpuLockCell, CpuLockGuard},
+    utils::Init,
+};
+
+/// A statically defined flag that futures can wait for.
+///
+/// [`signal`](Self::signal) sets the flag and wakes up the futures returned
+/// by [`wait`](Self::wait). One of them clears the flag and completes, and
+/// the rest keep waiting. The flag stays set if there are no waiting futures,
+/// so a signal is not lost even if it's sent before a future starts waiting.
+///
+/// `signal` can be called in an interrupt context, which makes this type
+/// useful for waiting for interrupts.
+pub struct StaticSignal<System: traits::KernelBase + traits::KernelStatic> {
+    state: Hunk<System, SignalState<System>>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for StaticSignal<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for StaticSignal<System> {}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for StaticSignal<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSignal").finish_non_exhaustive()
+    }
+}
+
+struct SignalState<System: traits::KernelBase> {
+    signaled: CpuLockCell<bool>,
+    waiters: WakerList<System>,
+}
+
+impl<System: traits::KernelBase> Init for SignalState<System> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        signaled: Init::INIT,
+        waiters: Init::INIT,
+    };
+}
+
+/// The definer (static builder) for [`StaticSignal`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct SignalDefiner<System> {
+    _phantom: PhantomData<System>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticSignal<System> {
+    /// Construct a `SignalDefiner` to define a signal in [a configuration
+    /// function](crate#static-configuration).
+    pub const fn define() -> SignalDefiner<System> {
+        SignalDefiner {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> SignalDefiner<System> {
+    /// Complete the definition of a signal, returning a reference to the
+    /// signal.
+    pub const fn finish<C: ~const traits::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticSignal<System> {
+        StaticSignal {
+            state: Hunk::<_, SignalState<System>>::define().finish(cfg),
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticSignal<System> {
+    /// Set the flag and wake up the waiting futures.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn signal(&self) -> Result<(), SignalError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| SignalError::BadContext)?;
+        self.state.signaled.set(&lock, true);
+        drop(lock);
+
+        self.state.waiters.wake_all();
+        Ok(())
+    }
+
+    /// Clear the flag.
+    pub fn reset(&self) -> Result<(), SignalError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| SignalError::BadContext)?;
+        self.state.signaled.set(&lock, false);
+        Ok(())
+    }
+
+    /// Get a future that waits for the flag to be set and clears it.
+    pub fn wait(&self) -> WaitSignal<'_, System> {
+        WaitSignal {
+            signaled: &self.state.signaled,
+            waiter: Waiter::new(&self.state.waiters),
+        }
+    }
+}
+
+/// The future returned by [`StaticSignal::wait`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitSignal<'a, System: traits::KernelBase + traits::KernelStatic> {
+    signaled: &'a CpuLockCell<bool>,
+    waiter: Waiter<'a, System>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for WaitSignal<'_, System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitSignal").finish_non_exhaustive()
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Future for WaitSignal<'_, System> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.into_ref().get_ref();
+        // Safety: `waiter` is structurally pinned
+        let waiter = unsafe { Pin::new_unchecked(&this.waiter) };
+
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        if this.signaled.get(&lock) {
+            this.signaled.set(&lock, false);
+            waiter.unregister(&lock);
+            Poll::Ready(())
+        } else {
+            waiter.register(&lock, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+/// Error type of [`StaticSignal::signal`] and [`StaticSignal::reset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalError {
+    /// CPU Lock is active.
+    BadContext,
+}
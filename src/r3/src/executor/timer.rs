@@ -0,0 +1,217 @@
+//! The async adapter for timers.
+use core::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::waker_list::{Waiter, WakerList};
+use crate::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTimer},
+    klock::{CpuLockCell, CpuLockGuard},
+    time::Duration,
+    utils::Init,
+};
+
+/// A statically defined [timer](crate::kernel::Timer) that futures can use to
+/// [sleep](Self::sleep).
+///
+/// A timer can serve one sleeping future at a time. Define as many timers as
+/// the number of futures that need to sleep at the same time.
+pub struct StaticAsyncTimer<System: traits::KernelTimer + traits::KernelStatic> {
+    timer: StaticTimer<System>,
+    state: Hunk<System, TimerState<System>>,
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> Clone for StaticAsyncTimer<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> Copy for StaticAsyncTimer<System> {}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> fmt::Debug for StaticAsyncTimer<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticAsyncTimer")
+            .field("timer", &self.timer)
+            .finish_non_exhaustive()
+    }
+}
+
+struct TimerState<System: traits::KernelBase> {
+    status: CpuLockCell<TimerStatus>,
+    waiters: WakerList<System>,
+}
+
+impl<System: traits::KernelBase> Init for TimerState<System> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        status: Init::INIT,
+        waiters: Init::INIT,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimerStatus {
+    Idle,
+    /// A future is sleeping.
+    Sleeping,
+    /// The delay of the sleeping future has elapsed.
+    Elapsed,
+}
+
+impl Init for TimerStatus {
+    const INIT: Self = Self::Idle;
+}
+
+/// The definer (static builder) for [`StaticAsyncTimer`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct AsyncTimerDefiner<System> {
+    _phantom: PhantomData<System>,
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> StaticAsyncTimer<System> {
+    /// Construct a `AsyncTimerDefiner` to define a timer in [a configuration
+    /// function](crate#static-configuration).
+    pub const fn define() -> AsyncTimerDefiner<System> {
+        AsyncTimerDefiner {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> AsyncTimerDefiner<System> {
+    /// Complete the definition of a timer, returning a reference to the
+    /// timer.
+    pub const fn finish<C: ~const traits::CfgTimer<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticAsyncTimer<System> {
+        let state = Hunk::<_, TimerState<System>>::define().finish(cfg);
+        let timer = StaticTimer::define()
+            .start(move || timer_body(state))
+            .finish(cfg);
+        StaticAsyncTimer { timer, state }
+    }
+}
+
+/// The callback function of a [`StaticAsyncTimer`]'s timer.
+fn timer_body<System: traits::KernelBase + traits::KernelStatic>(
+    state: Hunk<System, TimerState<System>>,
+) {
+    let lock = CpuLockGuard::<System>::new().unwrap();
+    if state.status.get(&lock) != TimerStatus::Sleeping {
+        // The sleeping future was dropped
+        return;
+    }
+    state.status.set(&lock, TimerStatus::Elapsed);
+    drop(lock);
+
+    state.waiters.wake_all();
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> StaticAsyncTimer<System> {
+    /// Get a future that completes after the specified duration.
+    ///
+    /// The future completes with [`SleepError::Busy`] if another future is
+    /// sleeping on the same timer when it's first polled.
+    pub fn sleep(&self, duration: Duration) -> Sleep<'_, System> {
+        Sleep {
+            timer: self.timer,
+            status: &self.state.status,
+            duration,
+            armed: false,
+            waiter: Waiter::new(&self.state.waiters),
+        }
+    }
+}
+
+/// The future returned by [`StaticAsyncTimer::sleep`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Sleep<'a, System: traits::KernelTimer + traits::KernelStatic> {
+    timer: StaticTimer<System>,
+    status: &'a CpuLockCell<TimerStatus>,
+    duration: Duration,
+    /// `true` if this future owns the timer.
+    armed: bool,
+    waiter: Waiter<'a, System>,
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> fmt::Debug for Sleep<'_, System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("timer", &self.timer)
+            .field("duration", &self.duration)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> Future for Sleep<'_, System> {
+    type Output = Result<(), SleepError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `waiter` is the only field that's structurally pinned, and
+        //         it's not moved
+        let this = unsafe { self.get_unchecked_mut() };
+        // Safety: `waiter` is structurally pinned
+        let waiter = unsafe { Pin::new_unchecked(&this.waiter) };
+
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        if this.armed {
+            if this.status.get(&lock) == TimerStatus::Elapsed {
+                this.status.set(&lock, TimerStatus::Idle);
+                this.armed = false;
+                waiter.unregister(&lock);
+                return Poll::Ready(Ok(()));
+            }
+            waiter.register(&lock, cx.waker());
+            return Poll::Pending;
+        }
+
+        if this.duration.is_negative() {
+            return Poll::Ready(Err(SleepError::BadParam));
+        }
+        if this.status.get(&lock) != TimerStatus::Idle {
+            return Poll::Ready(Err(SleepError::Busy));
+        }
+        this.status.set(&lock, TimerStatus::Sleeping);
+        this.armed = true;
+        waiter.register(&lock, cx.waker());
+        drop(lock);
+
+        this.timer
+            .set_delay(Some(this.duration))
+            .expect("failed to set the timer delay");
+        this.timer.start().expect("failed to start the timer");
+        Poll::Pending
+    }
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> Drop for Sleep<'_, System> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        // Stop the timer before releasing it. Otherwise, another future could
+        // start sleeping on the timer in between, and we would stop the timer
+        // it's waiting for. If the timer has already fired, the status is
+        // `Elapsed`, which is reset here as well.
+        self.timer.stop().expect("failed to stop the timer");
+
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        self.status.set(&lock, TimerStatus::Idle);
+    }
+}
+
+/// Error type of the future returned by [`StaticAsyncTimer::sleep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepError {
+    /// The duration is negative.
+    BadParam,
+    /// Another future is sleeping on the timer.
+    Busy,
+}
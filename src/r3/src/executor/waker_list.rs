@@ -0,0 +1,206 @@
+//! An intrusive list of [`Waker`]s, used to implement the futures of the
+//! async adapters.
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomPinned,
+    pin::Pin,
+    ptr::NonNull,
+    task::{Poll, Waker},
+};
+
+use crate::{
+    kernel::traits,
+    klock::{CpuLockCell, CpuLockGuard},
+    utils::Init,
+};
+
+/// A FIFO list of wakers. The nodes are [`Waiter`]s embedded in the waiting
+/// futures, which are pinned while they are in the list.
+pub(super) struct WakerList<System: traits::KernelBase> {
+    head: CpuLockCell<Option<NodePtr<System>>>,
+    tail: CpuLockCell<Option<NodePtr<System>>>,
+    len: CpuLockCell<usize>,
+}
+
+impl<System: traits::KernelBase> Init for WakerList<System> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        head: Init::INIT,
+        tail: Init::INIT,
+        len: Init::INIT,
+    };
+}
+
+/// A list node.
+struct Node<System: traits::KernelBase> {
+    /// Only accessed with CPU Lock active.
+    waker: UnsafeCell<Option<Waker>>,
+    next: CpuLockCell<Option<NodePtr<System>>>,
+    linked: CpuLockCell<bool>,
+}
+
+struct NodePtr<System: traits::KernelBase>(NonNull<Node<System>>);
+
+impl<System: traits::KernelBase> Clone for NodePtr<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase> Copy for NodePtr<System> {}
+
+// Safety: The pointee is only accessed with CPU Lock active
+unsafe impl<System: traits::KernelBase> Send for NodePtr<System> {}
+
+impl<System: traits::KernelBase> NodePtr<System> {
+    /// Get a reference to the pointee.
+    ///
+    /// # Safety
+    ///
+    /// The node must be in a list. The returned reference must not outlive
+    /// `lock`.
+    #[inline]
+    unsafe fn get(self, _lock: &CpuLockGuard<System>) -> &Node<System> {
+        // Safety: A node is removed from the list before it's deallocated,
+        //         and the removal requires CPU Lock
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<System: traits::KernelBase> WakerList<System> {
+    fn push_back(&self, lock: &CpuLockGuard<System>, node: &Node<System>) {
+        let ptr = NodePtr(NonNull::from(node));
+        node.next.set(lock, None);
+        match self.tail.get(lock) {
+            // Safety: `tail` is in the list
+            Some(tail) => unsafe { tail.get(lock) }.next.set(lock, Some(ptr)),
+            None => self.head.set(lock, Some(ptr)),
+        }
+        self.tail.set(lock, Some(ptr));
+        self.len.set(lock, self.len.get(lock) + 1);
+        node.linked.set(lock, true);
+    }
+
+    fn remove(&self, lock: &CpuLockGuard<System>, node: &Node<System>) {
+        let target = NonNull::from(node);
+        let mut prev: Option<NodePtr<System>> = None;
+        let mut cur = self.head.get(lock);
+        while let Some(ptr) = cur {
+            // Safety: `ptr` is in the list
+            let next = unsafe { ptr.get(lock) }.next.get(lock);
+            if ptr.0 == target {
+                match prev {
+                    // Safety: `prev` is in the list
+                    Some(prev) => unsafe { prev.get(lock) }.next.set(lock, next),
+                    None => self.head.set(lock, next),
+                }
+                if next.is_none() {
+                    self.tail.set(lock, prev);
+                }
+                self.len.set(lock, self.len.get(lock) - 1);
+                node.linked.set(lock, false);
+                return;
+            }
+            prev = cur;
+            cur = next;
+        }
+        unreachable!("the node is not in the list");
+    }
+
+    /// Remove the first node from the list and take its waker.
+    fn pop_front(&self, lock: &CpuLockGuard<System>) -> Option<Waker> {
+        let ptr = self.head.get(lock)?;
+        // Safety: `ptr` is in the list
+        let node = unsafe { ptr.get(lock) };
+        self.remove(lock, node);
+        // Safety: CPU Lock is active
+        unsafe { (*node.waker.get()).take() }
+    }
+
+    /// Wake up all wakers currently in the list and remove them from the
+    /// list. CPU Lock must be inactive.
+    ///
+    /// Wakers added during the call are not woken up. This prevents a future
+    /// that re-registers itself on every poll from making this method loop
+    /// forever.
+    pub(super) fn wake_all(&self) {
+        let len = self.len.get(&CpuLockGuard::<System>::new().unwrap());
+        for _ in 0..len {
+            let lock = CpuLockGuard::<System>::new().unwrap();
+            let Some(waker) = self.pop_front(&lock) else {
+                // Some of the nodes were removed in the meantime
+                break;
+            };
+            drop(lock);
+            waker.wake();
+        }
+    }
+}
+
+/// A [`WakerList`] node embedded in a future. Removes itself from the list
+/// when dropped.
+pub(super) struct Waiter<'a, System: traits::KernelBase> {
+    list: &'a WakerList<System>,
+    node: Node<System>,
+    _pin: PhantomPinned,
+}
+
+impl<'a, System: traits::KernelBase> Waiter<'a, System> {
+    pub(super) const fn new(list: &'a WakerList<System>) -> Self {
+        Self {
+            list,
+            node: Node {
+                waker: UnsafeCell::new(None),
+                next: Init::INIT,
+                linked: Init::INIT,
+            },
+            _pin: PhantomPinned,
+        }
+    }
+
+    /// Store `waker` in the node and add the node to the list if it's not in
+    /// the list yet.
+    pub(super) fn register(self: Pin<&Self>, lock: &CpuLockGuard<System>, waker: &Waker) {
+        // Safety: CPU Lock is active
+        let slot = unsafe { &mut *self.node.waker.get() };
+        if !slot.as_ref().map_or(false, |w| w.will_wake(waker)) {
+            *slot = Some(waker.clone());
+        }
+        if !self.node.linked.get(lock) {
+            // Safety: The node is pinned, and it removes itself from the list
+            //         before it's dropped
+            self.list.push_back(lock, &self.node);
+        }
+    }
+
+    /// Remove the node from the list if it's in the list.
+    pub(super) fn unregister(&self, lock: &CpuLockGuard<System>) {
+        if self.node.linked.get(lock) {
+            self.list.remove(lock, &self.node);
+        }
+    }
+
+    /// Call `f`. If it returns `Poll::Pending`, register `waker` and call `f`
+    /// again to find out if the condition was satisfied in the meantime.
+    pub(super) fn poll_with<T>(
+        self: Pin<&Self>,
+        waker: &Waker,
+        mut f: impl FnMut() -> Poll<T>,
+    ) -> Poll<T> {
+        if let ready @ Poll::Ready(_) = f() {
+            return ready;
+        }
+        self.register(&CpuLockGuard::<System>::new().unwrap(), waker);
+        let poll = f();
+        if poll.is_ready() {
+            self.unregister(&CpuLockGuard::<System>::new().unwrap());
+        }
+        poll
+    }
+}
+
+impl<System: traits::KernelBase> Drop for Waiter<'_, System> {
+    fn drop(&mut self) {
+        self.unregister(&CpuLockGuard::<System>::new().unwrap());
+    }
+}
@@ -0,0 +1,70 @@
+//! CPU Lock utilities
+use core::{cell::UnsafeCell, marker::PhantomData};
+
+use crate::{
+    kernel::{prelude::*, traits},
+    utils::Init,
+};
+
+/// A cell that can only be accessed with CPU Lock active.
+pub(crate) struct CpuLockCell<T>(UnsafeCell<T>);
+
+// Safety: The contents are only accessed with CPU Lock active, which
+//         serializes the accesses
+unsafe impl<T: Send> Send for CpuLockCell<T> {}
+unsafe impl<T: Send> Sync for CpuLockCell<T> {}
+
+impl<T: Init> Init for CpuLockCell<T> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self(Init::INIT);
+}
+
+impl<T: Copy> CpuLockCell<T> {
+    #[inline]
+    pub(crate) fn get<System: traits::KernelBase>(&self, _lock: &CpuLockGuard<System>) -> T {
+        // Safety: CPU Lock is active
+        unsafe { *self.0.get() }
+    }
+
+    #[inline]
+    pub(crate) fn set<System: traits::KernelBase>(&self, _lock: &CpuLockGuard<System>, value: T) {
+        // Safety: CPU Lock is active
+        unsafe { *self.0.get() = value };
+    }
+}
+
+/// An RAII guard for CPU Lock.
+pub(crate) struct CpuLockGuard<System: traits::KernelBase> {
+    _phantom: PhantomData<(System, *mut ())>,
+}
+
+impl<System: traits::KernelBase> CpuLockGuard<System> {
+    /// Acquire CPU Lock. Fails if CPU Lock is already active.
+    #[inline]
+    pub(crate) fn new() -> Result<Self, ()> {
+        System::acquire_cpu_lock().map_err(|_| ())?;
+        Ok(Self {
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Construct a `CpuLockGuard` for CPU Lock that is already active.
+    ///
+    /// # Safety
+    ///
+    /// CPU Lock must be active, and the guard must not be dropped.
+    #[inline]
+    pub(crate) unsafe fn assume() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<System: traits::KernelBase> Drop for CpuLockGuard<System> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: CPU Lock is active, and we are the owner of it
+        unsafe { System::release_cpu_lock() }.unwrap();
+    }
+}
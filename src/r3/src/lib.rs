@@ -3,6 +3,7 @@
 #![feature(type_alias_impl_trait)]
 #![feature(const_trait_impl)]
 #![feature(const_mut_refs)]
+#![feature(const_option)]
 #![feature(lint_reasons)]
 #![feature(cell_update)]
 #![feature(decl_macro)]
@@ -51,11 +52,22 @@ pub mod _changelog_ {}
 
 pub mod bind;
 
+#[cfg(feature = "executor")]
+#[doc(cfg(feature = "executor"))]
+pub mod executor;
+
+#[cfg(any(feature = "executor", feature = "sync", feature = "workqueue"))]
+mod klock;
+
 #[cfg(feature = "sync")]
 #[doc(cfg(feature = "sync"))]
 pub mod sync;
 mod tests;
 
+#[cfg(feature = "workqueue")]
+#[doc(cfg(feature = "workqueue"))]
+pub mod workqueue;
+
 pub use r3_core::{bag, hunk, kernel, time};
 
 /// Utilities. This module re-exports items from [`r3_core::utils`] that are
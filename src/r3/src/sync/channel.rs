@@ -0,0 +1,543 @@
+//! Multi-producer, single-consumer FIFO queue communication primitives.
+use core::{cell::UnsafeCell, fmt, marker::PhantomData, mem::MaybeUninit};
+
+use crate::{
+    bind::Bind,
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, PollSemaphoreError, SemaphoreValue, StaticSemaphore,
+        WaitSemaphoreError, WaitSemaphoreTimeoutError,
+    },
+    klock::{CpuLockCell, CpuLockGuard},
+    time::Duration,
+    utils::Init,
+};
+
+/// The definer (static builder) for [`StaticChannel`][].
+#[doc = include_str!("../common.md")]
+pub struct Definer<System, T, const N: usize> {
+    _phantom: PhantomData<(System, T)>,
+}
+
+/// A bounded multi-producer, single-consumer FIFO queue with a capacity of
+/// `N` elements.
+///
+/// A channel is defined by [`StaticChannel::define`], which produces a
+/// [`Sender`] and a [binding][1] of [`Receiver`]. `Sender` is a `Copy` handle
+/// that can be used anywhere. `Receiver` is handed to the consuming task
+/// through the binding, e.g., by [`ExecutableDefinerExt::start_with_bind`][2],
+/// so that the configuration system can check that there is only one consumer.
+///
+/// This type is implemented using two [semaphores][3], which count the empty
+/// and occupied slots respectively, and therefore works with any kernel that
+/// supports semaphores. The elements are moved in and out of the buffer in
+/// CPU Lock sections.
+///
+/// The elements remaining in the channel are never dropped.
+///
+/// # Example
+///
+#[doc = crate::tests::doc_test!(
+/// ```rust
+/// use r3::{
+///     kernel::StaticTask,
+///     prelude::*,
+///     sync::channel::{Receiver, Sender, StaticChannel},
+/// };
+///
+/// struct Objects {
+///     tx: Sender<System, u32, 4>,
+/// }
+///
+/// const fn configure_app<C>(cfg: &mut Cfg<C>) -> Objects
+/// where
+///     C: ~const traits::CfgTask<System = System> +
+///        ~const traits::CfgSemaphore,
+/// {
+///     let (tx, rx) = StaticChannel::define().finish(cfg);
+///
+///     StaticTask::define()
+///         .start_with_bind((rx.borrow_mut(),), task1_body)
+///         .priority(2)
+///         .active(true)
+///         .finish(cfg);
+///
+///     StaticTask::define()
+///         .start(task2_body)
+///         .priority(1)
+///         .active(true)
+///         .finish(cfg);
+///
+///     Objects { tx }
+/// }
+///
+/// fn task1_body(rx: &mut Receiver<System, u32, 4>) {
+///     assert_eq!(rx.recv().unwrap(), 1);
+///     assert_eq!(rx.recv().unwrap(), 2);
+/// #   exit(0);
+/// }
+///
+/// fn task2_body() {
+///     COTTAGE.tx.send(1).unwrap();
+///     COTTAGE.tx.send(2).unwrap();
+/// }
+/// ```
+)]
+///
+/// [1]: crate::bind::Bind
+/// [2]: crate::bind::ExecutableDefinerExt::start_with_bind
+/// [3]: crate::kernel::StaticSemaphore
+pub struct StaticChannel<System, T, const N: usize>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    buffer: Hunk<System, UnsafeCell<MaybeUninit<[T; N]>>>,
+    indices: Hunk<System, CpuLockCell<Indices>>,
+    /// Counts the empty slots.
+    free: StaticSemaphore<System>,
+    /// Counts the occupied slots.
+    filled: StaticSemaphore<System>,
+}
+
+impl<System, T, const N: usize> Clone for StaticChannel<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System, T, const N: usize> Copy for StaticChannel<System, T, N> where
+    System: traits::KernelSemaphore + traits::KernelStatic
+{
+}
+
+// Safety: The buffer is only accessed with CPU Lock active, and each element is
+//         moved in and out exactly once, as guaranteed by the semaphores
+unsafe impl<System, T: Send, const N: usize> Send for StaticChannel<System, T, N> where
+    System: traits::KernelSemaphore + traits::KernelStatic
+{
+}
+unsafe impl<System, T: Send, const N: usize> Sync for StaticChannel<System, T, N> where
+    System: traits::KernelSemaphore + traits::KernelStatic
+{
+}
+
+impl<System, T, const N: usize> fmt::Debug for StaticChannel<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StaticChannel")
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Indices {
+    /// The index of the next element to receive.
+    head: usize,
+    /// The index of the next slot to fill.
+    tail: usize,
+}
+
+impl Init for Indices {
+    const INIT: Self = Self { head: 0, tail: 0 };
+}
+
+/// The sending half of [`StaticChannel`].
+pub struct Sender<System, T, const N: usize>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    channel: StaticChannel<System, T, N>,
+}
+
+impl<System, T, const N: usize> Clone for Sender<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System, T, const N: usize> Copy for Sender<System, T, N> where
+    System: traits::KernelSemaphore + traits::KernelStatic
+{
+}
+
+impl<System, T, const N: usize> fmt::Debug for Sender<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sender")
+    }
+}
+
+/// The receiving half of [`StaticChannel`]. This is provided as a [binding][1]
+/// by [`Definer::finish`].
+///
+/// [1]: crate::bind::Bind
+pub struct Receiver<System, T, const N: usize>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    channel: StaticChannel<System, T, N>,
+}
+
+impl<System, T, const N: usize> fmt::Debug for Receiver<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Receiver")
+    }
+}
+
+/// Error type of [`Sender::send`]. The unsent value is returned in it.
+pub enum SendError<T> {
+    /// CPU Lock is active, or the current context is not [waitable].
+    ///
+    /// [waitable]: crate#contexts
+    BadContext(T),
+    /// The wait operation was interrupted by [`Task::interrupt`].
+    ///
+    /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
+    Interrupted(T),
+}
+
+/// Error type of [`Sender::try_send`]. The unsent value is returned in it.
+pub enum TrySendError<T> {
+    /// CPU Lock is active.
+    BadContext(T),
+    /// The channel is full.
+    Full(T),
+}
+
+/// Error type of [`Sender::send_timeout`]. The unsent value is returned in it.
+pub enum SendTimeoutError<T> {
+    /// CPU Lock is active, or the current context is not [waitable].
+    ///
+    /// [waitable]: crate#contexts
+    BadContext(T),
+    /// The wait operation was interrupted by [`Task::interrupt`].
+    ///
+    /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
+    Interrupted(T),
+    /// The operation timed out.
+    Timeout(T),
+    /// The timeout is negative or too long.
+    BadParam(T),
+}
+
+/// Error type of [`Receiver::recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// CPU Lock is active, or the current context is not [waitable].
+    ///
+    /// [waitable]: crate#contexts
+    BadContext,
+    /// The wait operation was interrupted by [`Task::interrupt`].
+    ///
+    /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
+    Interrupted,
+}
+
+/// Error type of [`Receiver::try_recv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// CPU Lock is active.
+    BadContext,
+    /// The channel is empty.
+    Empty,
+}
+
+/// Error type of [`Receiver::recv_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// CPU Lock is active, or the current context is not [waitable].
+    ///
+    /// [waitable]: crate#contexts
+    BadContext,
+    /// The wait operation was interrupted by [`Task::interrupt`].
+    ///
+    /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
+    Interrupted,
+    /// The operation timed out.
+    Timeout,
+    /// The timeout is negative or too long.
+    BadParam,
+}
+
+impl<T> SendError<T> {
+    /// Get the value that could not be sent.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::BadContext(x) | Self::Interrupted(x) => x,
+        }
+    }
+}
+
+impl<T> TrySendError<T> {
+    /// Get the value that could not be sent.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::BadContext(x) | Self::Full(x) => x,
+        }
+    }
+}
+
+impl<T> SendTimeoutError<T> {
+    /// Get the value that could not be sent.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::BadContext(x) | Self::Interrupted(x) | Self::Timeout(x) | Self::BadParam(x) => x,
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BadContext(_) => "BadContext",
+            Self::Interrupted(_) => "Interrupted",
+        })
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BadContext(_) => "BadContext",
+            Self::Full(_) => "Full",
+        })
+    }
+}
+
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BadContext(_) => "BadContext",
+            Self::Interrupted(_) => "Interrupted",
+            Self::Timeout(_) => "Timeout",
+            Self::BadParam(_) => "BadParam",
+        })
+    }
+}
+
+impl<System, T, const N: usize> StaticChannel<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+    T: Send + 'static,
+{
+    /// Construct a `Definer` to define a channel in [a configuration
+    /// function](crate#static-configuration).
+    pub const fn define() -> Definer<System, T, N> {
+        Definer {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// # Finalization
+///
+/// The following method completes the definition of a channel.
+impl<System, T, const N: usize> Definer<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+    T: Send + 'static,
+{
+    /// Complete the definition of a channel, returning its sending half and a
+    /// binding of its receiving half.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `N` is zero.
+    pub const fn finish<'pool, C: ~const traits::CfgSemaphore<System = System>>(
+        self,
+        cfg: &mut Cfg<'pool, C>,
+    ) -> (
+        Sender<System, T, N>,
+        Bind<'pool, System, Receiver<System, T, N>>,
+    ) {
+        assert!(N > 0, "the capacity must be non-zero");
+
+        let channel = StaticChannel {
+            buffer: Hunk::<_, UnsafeCell<MaybeUninit<[T; N]>>>::define().finish(cfg),
+            indices: Hunk::<_, CpuLockCell<Indices>>::define().finish(cfg),
+            free: StaticSemaphore::define()
+                .initial(N as SemaphoreValue)
+                .maximum(N as SemaphoreValue)
+                .finish(cfg),
+            filled: StaticSemaphore::define()
+                .initial(0)
+                .maximum(N as SemaphoreValue)
+                .finish(cfg),
+        };
+
+        let receiver = Bind::define()
+            .init(move || Receiver { channel })
+            .finish(cfg);
+
+        (Sender { channel }, receiver)
+    }
+}
+
+impl<System, T, const N: usize> StaticChannel<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    /// Move `value` into the buffer. The caller must have acquired an empty
+    /// slot from `free`.
+    fn push(&self, value: T) {
+        {
+            let lock = CpuLockGuard::<System>::new().unwrap();
+            let Indices { head, tail } = self.indices.get(&lock);
+
+            // Safety: The slot is empty, and CPU Lock prevents other producers
+            //         from choosing the same slot
+            unsafe {
+                (*self.buffer.get())
+                    .as_mut_ptr()
+                    .cast::<T>()
+                    .add(tail)
+                    .write(value)
+            };
+
+            self.indices.set(
+                &lock,
+                Indices {
+                    head,
+                    tail: (tail + 1) % N,
+                },
+            );
+        }
+        self.filled.signal_one().unwrap();
+    }
+
+    /// Move out an element from the buffer. The caller must have acquired an
+    /// occupied slot from `filled`.
+    fn pop(&self) -> T {
+        let value;
+        {
+            let lock = CpuLockGuard::<System>::new().unwrap();
+            let Indices { head, tail } = self.indices.get(&lock);
+
+            // Safety: The slot is occupied, and CPU Lock prevents other
+            //         consumers from choosing the same slot
+            value = unsafe { (*self.buffer.get()).as_ptr().cast::<T>().add(head).read() };
+
+            self.indices.set(
+                &lock,
+                Indices {
+                    head: (head + 1) % N,
+                    tail,
+                },
+            );
+        }
+        self.free.signal_one().unwrap();
+        value
+    }
+}
+
+impl<System, T, const N: usize> Sender<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    /// Send a value, blocking the current task until there is an empty slot
+    /// in the channel.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        match self.channel.free.wait_one() {
+            Ok(()) => {}
+            Err(WaitSemaphoreError::NoAccess) => unreachable!(),
+            Err(WaitSemaphoreError::BadContext) => return Err(SendError::BadContext(value)),
+            Err(WaitSemaphoreError::Interrupted) => return Err(SendError::Interrupted(value)),
+        }
+        self.channel.push(value);
+        Ok(())
+    }
+
+    /// Attempt to send a value without blocking.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        match self.channel.free.poll_one() {
+            Ok(()) => {}
+            Err(PollSemaphoreError::NoAccess) => unreachable!(),
+            Err(PollSemaphoreError::BadContext) => return Err(TrySendError::BadContext(value)),
+            Err(PollSemaphoreError::Timeout) => return Err(TrySendError::Full(value)),
+        }
+        self.channel.push(value);
+        Ok(())
+    }
+
+    /// [`send`](Self::send) with timeout.
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        match self.channel.free.wait_one_timeout(timeout) {
+            Ok(()) => {}
+            Err(WaitSemaphoreTimeoutError::NoAccess) => unreachable!(),
+            Err(WaitSemaphoreTimeoutError::BadContext) => {
+                return Err(SendTimeoutError::BadContext(value))
+            }
+            Err(WaitSemaphoreTimeoutError::Interrupted) => {
+                return Err(SendTimeoutError::Interrupted(value))
+            }
+            Err(WaitSemaphoreTimeoutError::Timeout) => {
+                return Err(SendTimeoutError::Timeout(value))
+            }
+            Err(WaitSemaphoreTimeoutError::BadParam) => {
+                return Err(SendTimeoutError::BadParam(value))
+            }
+        }
+        self.channel.push(value);
+        Ok(())
+    }
+}
+
+impl<System, T, const N: usize> Receiver<System, T, N>
+where
+    System: traits::KernelSemaphore + traits::KernelStatic,
+{
+    /// Receive a value, blocking the current task until there is one in the
+    /// channel.
+    pub fn recv(&mut self) -> Result<T, RecvError> {
+        self.channel.filled.wait_one().map_err(|e| match e {
+            WaitSemaphoreError::NoAccess => unreachable!(),
+            WaitSemaphoreError::BadContext => RecvError::BadContext,
+            WaitSemaphoreError::Interrupted => RecvError::Interrupted,
+        })?;
+        Ok(self.channel.pop())
+    }
+
+    /// Attempt to receive a value without blocking.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.channel.filled.poll_one().map_err(|e| match e {
+            PollSemaphoreError::NoAccess => unreachable!(),
+            PollSemaphoreError::BadContext => TryRecvError::BadContext,
+            PollSemaphoreError::Timeout => TryRecvError::Empty,
+        })?;
+        Ok(self.channel.pop())
+    }
+
+    /// [`recv`](Self::recv) with timeout.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.channel
+            .filled
+            .wait_one_timeout(timeout)
+            .map_err(|e| match e {
+                WaitSemaphoreTimeoutError::NoAccess => unreachable!(),
+                WaitSemaphoreTimeoutError::BadContext => RecvTimeoutError::BadContext,
+                WaitSemaphoreTimeoutError::Interrupted => RecvTimeoutError::Interrupted,
+                WaitSemaphoreTimeoutError::Timeout => RecvTimeoutError::Timeout,
+                WaitSemaphoreTimeoutError::BadParam => RecvTimeoutError::BadParam,
+            })?;
+        Ok(self.channel.pop())
+    }
+}
@@ -0,0 +1,336 @@
+use core::{cell::UnsafeCell, fmt, marker::PhantomData, mem::MaybeUninit, ops::Deref};
+
+use crate::{
+    hunk::Hunk,
+    kernel::{mutex, prelude::*, traits, Cfg, ParkTimeoutError},
+    klock::CpuLockGuard,
+    sync::{
+        mutex::{GenericMutexGuard, LockError, LockResult},
+        wait_queue::{Registration, WaitQueue, Waiter},
+    },
+    time::Duration,
+};
+
+/// The definer (static builder) for [`StaticCondvar`][].
+#[doc = include_str!("../common.md")]
+pub struct Definer<System> {
+    _phantom: PhantomData<System>,
+}
+
+/// A condition variable, which allows tasks to block while waiting for an
+/// event to occur, atomically releasing a [mutex] while doing so.
+///
+/// This type is implemented using [`Kernel::park`]. A waiting task parks
+/// itself until it's notified, which has the following implications:
+///
+///  - Like [`std::sync::Condvar`], a waiting task may wake up spuriously,
+///    e.g., when it's [unparked] by something other than this condition
+///    variable. Use [`wait_while`] or check the condition in a loop to handle
+///    this.
+///
+///  - Waiting tasks are notified in a FIFO order regardless of their
+///    priorities.
+///
+/// The mutex is re-acquired by [`GenericMutex::lock`] before returning from
+/// a wait function, so the same errors can be returned. For example, if the
+/// previous owner of the mutex exited while holding the lock, the wait
+/// function will return `Err(LockError::Abandoned(lock_guard))` (wrapped in
+/// [`WaitTimeoutError::Lock`] in the case of [`wait_timeout`]).
+///
+/// # Example
+///
+#[doc = crate::tests::doc_test!(
+/// ```rust
+/// use r3::{
+///     kernel::StaticTask,
+///     sync::{StaticCondvar, StaticMutex},
+/// };
+///
+/// struct Objects {
+///     task2: StaticTask<System>,
+///     mutex: StaticMutex<System, bool>,
+///     condvar: StaticCondvar<System>,
+/// }
+///
+/// const fn configure_app<C>(cfg: &mut Cfg<C>) -> Objects
+/// where
+///     C: ~const traits::CfgTask<System = System> +
+///        ~const traits::CfgMutex,
+/// {
+///     StaticTask::define()
+///         .start(task1_body)
+///         .priority(1)
+///         .active(true)
+///         .finish(cfg);
+///
+///     let task2 = StaticTask::define()
+///         .start(task2_body)
+///         .priority(2)
+///         .finish(cfg);
+///
+///     let mutex = StaticMutex::define().finish(cfg);
+///     let condvar = StaticCondvar::define().finish(cfg);
+///
+///     Objects { task2, mutex, condvar }
+/// }
+///
+/// fn task1_body() {
+///     COTTAGE.task2.activate().unwrap();
+///
+///     // Wait until `task2` sets the flag
+///     let guard = COTTAGE.mutex.lock().unwrap();
+///     let guard = COTTAGE.condvar.wait_while(guard, |ready| !*ready).unwrap();
+///     assert!(*guard);
+/// #   exit(0);
+/// }
+///
+/// fn task2_body() {
+///     *COTTAGE.mutex.lock().unwrap() = true;
+///     COTTAGE.condvar.notify_one().unwrap();
+/// }
+/// ```
+)]
+///
+/// [mutex]: crate::sync::mutex
+/// [`Kernel::park`]: crate::kernel::Kernel::park
+/// [`std::sync::Condvar`]: https://doc.rust-lang.org/std/sync/struct.Condvar.html
+/// [unparked]: crate::kernel::task::TaskMethods::unpark
+/// [`wait_while`]: Self::wait_while
+/// [`wait_timeout`]: Self::wait_timeout
+/// [`GenericMutex::lock`]: crate::sync::mutex::GenericMutex::lock
+pub struct StaticCondvar<System: traits::KernelBase + traits::KernelStatic> {
+    queue: Hunk<System, WaitQueue<System, ()>>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for StaticCondvar<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for StaticCondvar<System> {}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for StaticCondvar<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StaticCondvar")
+    }
+}
+
+/// A type indicating whether [`StaticCondvar::wait_timeout`] returned because
+/// of a timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitTimeoutResult(bool);
+
+impl WaitTimeoutResult {
+    /// Get a flag indicating whether the wait timed out.
+    #[inline]
+    pub fn timed_out(&self) -> bool {
+        self.0
+    }
+}
+
+/// Error type of [`StaticCondvar::wait_timeout`].
+pub enum WaitTimeoutError<Guard> {
+    /// The timeout duration is negative. The mutex is not released, and the
+    /// guard is returned to the caller.
+    BadParam(Guard),
+    /// The mutex couldn't be re-acquired after waiting.
+    Lock(LockError<(Guard, WaitTimeoutResult)>),
+}
+
+impl<Guard> fmt::Debug for WaitTimeoutError<Guard> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadParam(_) => f.write_str("BadParam"),
+            Self::Lock(e) => f.debug_tuple("Lock").field(e).finish(),
+        }
+    }
+}
+
+/// Error type of [`StaticCondvar::notify_one`] and
+/// [`StaticCondvar::notify_all`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyError {
+    /// CPU Lock is active.
+    BadContext,
+}
+
+impl<System> StaticCondvar<System>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    /// Construct a `Definer` to define a condition variable in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> Definer<System> {
+        Definer {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// # Finalization
+///
+/// The following method completes the definition of a condition variable.
+impl<System> Definer<System>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    /// Complete the definition of a condition variable, returning a reference
+    /// to the condition variable.
+    pub const fn finish<C: ~const traits::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticCondvar<System> {
+        StaticCondvar {
+            queue: Hunk::<_, WaitQueue<System, ()>>::define().finish(cfg),
+        }
+    }
+}
+
+impl<System> StaticCondvar<System>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    /// Release the mutex held by `guard` and block the current task until
+    /// this condition variable is notified, and then re-acquire the mutex.
+    ///
+    /// This function is susceptible to spurious wakeups. See
+    /// [`Self::wait_while`] for a version that handles them.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if CPU Lock is active or the mutex can't be
+    /// unlocked because it's not the mutex most recently locked by the current
+    /// task.
+    pub fn wait<'a, Cell, Mutex, T>(
+        &self,
+        guard: GenericMutexGuard<'a, Cell, Mutex>,
+    ) -> LockResult<GenericMutexGuard<'a, Cell, Mutex>>
+    where
+        Cell: Deref<Target = UnsafeCell<MaybeUninit<T>>>,
+        Mutex: mutex::MutexHandle,
+    {
+        let mutex = guard.mutex;
+        let waiter = Waiter::current(()).expect("not in a task context");
+        let registration = self.register(&waiter);
+        drop(guard);
+
+        // An interruption is treated as a spurious wakeup
+        let park_result = System::park();
+
+        registration.finish_wait(park_result.is_ok());
+        mutex.lock()
+    }
+
+    /// Block the current task while `condition` returns `true`, re-checking
+    /// it each time this condition variable is notified.
+    ///
+    /// `condition` is called with the mutex held.
+    ///
+    /// # Panics
+    ///
+    /// See [`Self::wait`].
+    pub fn wait_while<'a, Cell, Mutex, T, F>(
+        &self,
+        mut guard: GenericMutexGuard<'a, Cell, Mutex>,
+        mut condition: F,
+    ) -> LockResult<GenericMutexGuard<'a, Cell, Mutex>>
+    where
+        Cell: Deref<Target = UnsafeCell<MaybeUninit<T>>>,
+        Mutex: mutex::MutexHandle,
+        F: FnMut(&mut T) -> bool,
+    {
+        while condition(&mut *guard) {
+            guard = self.wait(guard)?;
+        }
+        Ok(guard)
+    }
+
+    /// Like [`Self::wait`], but returns after the specified duration even if
+    /// this condition variable is not notified.
+    ///
+    /// This method returns [`WaitTimeoutError::BadParam`] without releasing
+    /// the mutex if the duration is negative.
+    ///
+    /// # Panics
+    ///
+    /// See [`Self::wait`].
+    #[allow(clippy::type_complexity)]
+    pub fn wait_timeout<'a, Cell, Mutex, T>(
+        &self,
+        guard: GenericMutexGuard<'a, Cell, Mutex>,
+        timeout: Duration,
+    ) -> Result<
+        (GenericMutexGuard<'a, Cell, Mutex>, WaitTimeoutResult),
+        WaitTimeoutError<GenericMutexGuard<'a, Cell, Mutex>>,
+    >
+    where
+        Cell: Deref<Target = UnsafeCell<MaybeUninit<T>>>,
+        Mutex: mutex::MutexHandle,
+    {
+        // Check the duration before releasing the mutex. `park_timeout`
+        // doesn't fail with `BadParam` for any other reason.
+        if timeout.is_negative() {
+            return Err(WaitTimeoutError::BadParam(guard));
+        }
+
+        let mutex = guard.mutex;
+        let waiter = Waiter::current(()).expect("not in a task context");
+        let registration = self.register(&waiter);
+        drop(guard);
+
+        let park_result = System::park_timeout(timeout);
+
+        let notified = registration.finish_wait(park_result.is_ok());
+        let result = WaitTimeoutResult(!notified && park_result == Err(ParkTimeoutError::Timeout));
+
+        match mutex.lock() {
+            Ok(guard) => Ok((guard, result)),
+            Err(LockError::BadContext) => Err(WaitTimeoutError::Lock(LockError::BadContext)),
+            Err(LockError::Interrupted) => Err(WaitTimeoutError::Lock(LockError::Interrupted)),
+            Err(LockError::Timeout) => Err(WaitTimeoutError::Lock(LockError::Timeout)),
+            Err(LockError::WouldDeadlock) => Err(WaitTimeoutError::Lock(LockError::WouldDeadlock)),
+            Err(LockError::Deadlock) => Err(WaitTimeoutError::Lock(LockError::Deadlock)),
+            Err(LockError::BadParam) => Err(WaitTimeoutError::Lock(LockError::BadParam)),
+            Err(LockError::Abandoned(guard)) => Err(WaitTimeoutError::Lock(LockError::Abandoned(
+                (guard, result),
+            ))),
+        }
+    }
+
+    /// Wake up one of the tasks waiting on this condition variable, if any.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn notify_one(&self) -> Result<(), NotifyError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| NotifyError::BadContext)?;
+        let Some(task) = self.queue.wake_first(&lock, |()| true) else { return Ok(()) };
+        drop(lock);
+
+        // The task might have woken up spuriously and exited already
+        let _ = task.unpark();
+        Ok(())
+    }
+
+    /// Wake up all tasks waiting on this condition variable.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn notify_all(&self) -> Result<(), NotifyError> {
+        // Only wake up the tasks that are waiting at this point. Otherwise, a
+        // woken task that starts waiting again could keep this loop running
+        // forever.
+        let len = {
+            let lock = CpuLockGuard::<System>::new().map_err(|()| NotifyError::BadContext)?;
+            self.queue.len(&lock)
+        };
+        for _ in 0..len {
+            self.notify_one()?;
+        }
+        Ok(())
+    }
+
+    /// Add `waiter` to the wait queue.
+    fn register<'a>(&'a self, waiter: &'a Waiter<System, ()>) -> Registration<'a, System, ()> {
+        let lock = CpuLockGuard::<System>::new().expect("CPU Lock is active");
+        self.queue.push_back(&lock, waiter)
+    }
+}
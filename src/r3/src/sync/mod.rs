@@ -1,7 +1,14 @@
 //! Safe synchronization primitives.
 #[macro_use]
 pub mod source;
+pub mod channel;
+pub mod condvar;
 pub mod mutex;
 pub mod recursive_mutex;
+pub mod rwlock;
+mod wait_queue;
 #[doc(no_inline)]
-pub use self::{mutex::StaticMutex, recursive_mutex::StaticRecursiveMutex};
+pub use self::{
+    channel::StaticChannel, condvar::StaticCondvar, mutex::StaticMutex,
+    recursive_mutex::StaticRecursiveMutex, rwlock::StaticRwLock,
+};
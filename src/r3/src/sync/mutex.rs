@@ -9,10 +9,11 @@ use core::{
 use crate::{
     hunk::Hunk,
     kernel::{
-        mutex, prelude::*, traits, Cfg, LockMutexError, MarkConsistentMutexError, MutexProtocol,
-        TryLockMutexError,
+        mutex, prelude::*, traits, Cfg, LockMutexError, LockMutexTimeoutError,
+        MarkConsistentMutexError, MutexProtocol, TryLockMutexError,
     },
     sync::source::{DefaultSource, Source},
+    time::Duration,
     utils::Init,
 };
 
@@ -126,7 +127,7 @@ unsafe impl<Cell, Mutex, T: Send> Sync for GenericMutex<Cell, Mutex> where
 /// [`try_lock`]: GenericMutex::try_lock
 #[must_use = "if unused the GenericMutex will immediately unlock"]
 pub struct GenericMutexGuard<'a, Cell, Mutex: mutex::MutexHandle> {
-    mutex: &'a GenericMutex<Cell, Mutex>,
+    pub(super) mutex: &'a GenericMutex<Cell, Mutex>,
     _no_send_sync: PhantomData<*mut ()>,
 }
 
@@ -141,13 +142,14 @@ where
 {
 }
 
-/// Type alias for the result of [`GenericMutex::lock`].
+/// Type alias for the result of [`GenericMutex::lock`] and
+/// [`GenericMutex::lock_timeout`].
 pub type LockResult<Guard> = Result<Guard, LockError<Guard>>;
 
 /// Type alias for the result of [`GenericMutex::try_lock`].
 pub type TryLockResult<Guard> = Result<Guard, TryLockError<Guard>>;
 
-/// Error type of [`GenericMutex::lock`].
+/// Error type of [`GenericMutex::lock`] and [`GenericMutex::lock_timeout`].
 #[repr(i8)]
 pub enum LockError<Guard> {
     /// CPU Lock is active, or the current context is not [waitable].
@@ -158,11 +160,18 @@ pub enum LockError<Guard> {
     ///
     /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
     Interrupted = LockMutexError::Interrupted as i8,
+    /// The operation timed out. Only returned by
+    /// [`GenericMutex::lock_timeout`].
+    Timeout = LockMutexTimeoutError::Timeout as i8,
     /// The current task already owns the mutex.
     WouldDeadlock = LockMutexError::WouldDeadlock as i8,
-    /// The mutex was created with the protocol attribute having the value
-    /// [`Ceiling`] and the current task's priority is higher than the
-    /// mutex's priority ceiling.
+    /// The mutex's owner is waiting, directly or through a chain of mutex
+    /// owners, on a mutex held by the current task. Only returned by kernels
+    /// that detect deadlocks.
+    Deadlock = LockMutexError::Deadlock as i8,
+    /// The timeout duration is negative or too long, or the mutex was created
+    /// with the protocol attribute having the value [`Ceiling`] and the
+    /// current task's priority is higher than the mutex's priority ceiling.
     ///
     /// [`Ceiling`]: crate::kernel::MutexProtocol::Ceiling
     BadParam = LockMutexError::BadParam as i8,
@@ -177,7 +186,9 @@ impl<Guard> fmt::Debug for LockError<Guard> {
         f.write_str(match self {
             Self::BadContext => "BadContext",
             Self::Interrupted => "Interrupted",
+            Self::Timeout => "Timeout",
             Self::WouldDeadlock => "WouldDeadlock",
+            Self::Deadlock => "Deadlock",
             Self::BadParam => "BadParam",
             Self::Abandoned(_) => "Abandoned",
         })
@@ -302,6 +313,7 @@ where
             Err(LockMutexError::BadContext) => Err(LockError::BadContext),
             Err(LockMutexError::Interrupted) => Err(LockError::Interrupted),
             Err(LockMutexError::WouldDeadlock) => Err(LockError::WouldDeadlock),
+            Err(LockMutexError::Deadlock) => Err(LockError::Deadlock),
             Err(LockMutexError::BadParam) => Err(LockError::BadParam),
             Err(LockMutexError::Abandoned) => Err(LockError::Abandoned(GenericMutexGuard {
                 mutex: self,
@@ -310,6 +322,30 @@ where
         }
     }
 
+    /// [`lock`](Self::lock) with timeout.
+    pub fn lock_timeout(
+        &self,
+        timeout: Duration,
+    ) -> LockResult<GenericMutexGuard<'_, Cell, Mutex>> {
+        match self.mutex.lock_timeout(timeout) {
+            Ok(()) => Ok(GenericMutexGuard {
+                mutex: self,
+                _no_send_sync: PhantomData,
+            }),
+            Err(LockMutexTimeoutError::NoAccess) => unreachable!(),
+            Err(LockMutexTimeoutError::BadContext) => Err(LockError::BadContext),
+            Err(LockMutexTimeoutError::Interrupted) => Err(LockError::Interrupted),
+            Err(LockMutexTimeoutError::Timeout) => Err(LockError::Timeout),
+            Err(LockMutexTimeoutError::WouldDeadlock) => Err(LockError::WouldDeadlock),
+            Err(LockMutexTimeoutError::Deadlock) => Err(LockError::Deadlock),
+            Err(LockMutexTimeoutError::BadParam) => Err(LockError::BadParam),
+            Err(LockMutexTimeoutError::Abandoned) => Err(LockError::Abandoned(GenericMutexGuard {
+                mutex: self,
+                _no_send_sync: PhantomData,
+            })),
+        }
+    }
+
     /// Attempt to acquire the mutex.
     pub fn try_lock(&self) -> TryLockResult<GenericMutexGuard<'_, Cell, Mutex>> {
         match self.mutex.try_lock() {
@@ -3,10 +3,11 @@ use core::{cell::Cell, fmt, marker::PhantomData, mem::MaybeUninit, ops::Deref};
 use crate::{
     hunk::Hunk,
     kernel::{
-        mutex, prelude::*, traits, Cfg, LockMutexError, MarkConsistentMutexError, MutexProtocol,
-        TryLockMutexError,
+        mutex, prelude::*, traits, Cfg, LockMutexError, LockMutexTimeoutError,
+        MarkConsistentMutexError, MutexProtocol, TryLockMutexError,
     },
     sync::source::{DefaultSource, Source},
+    time::Duration,
     utils::Init,
 };
 
@@ -188,13 +189,15 @@ where
 {
 }
 
-/// Type alias for the result of [`GenericRecursiveMutex::lock`].
+/// Type alias for the result of [`GenericRecursiveMutex::lock`] and
+/// [`GenericRecursiveMutex::lock_timeout`].
 pub type LockResult<Guard> = Result<Guard, LockError<Guard>>;
 
 /// Type alias for the result of [`GenericRecursiveMutex::try_lock`].
 pub type TryLockResult<Guard> = Result<Guard, TryLockError<Guard>>;
 
-/// Error type of [`GenericRecursiveMutex::lock`].
+/// Error type of [`GenericRecursiveMutex::lock`] and
+/// [`GenericRecursiveMutex::lock_timeout`].
 #[repr(i8)]
 pub enum LockError<Guard> {
     /// CPU Lock is active, or the current context is not [waitable].
@@ -205,9 +208,16 @@ pub enum LockError<Guard> {
     ///
     /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
     Interrupted = LockMutexError::Interrupted as i8,
-    /// The mutex was created with the protocol attribute having the value
-    /// [`Ceiling`] and the current task's priority is higher than the
-    /// mutex's priority ceiling.
+    /// The operation timed out. Only returned by
+    /// [`GenericRecursiveMutex::lock_timeout`].
+    Timeout = LockMutexTimeoutError::Timeout as i8,
+    /// The mutex's owner is waiting, directly or through a chain of mutex
+    /// owners, on a mutex held by the current task. Only returned by kernels
+    /// that detect deadlocks.
+    Deadlock = LockMutexError::Deadlock as i8,
+    /// The timeout duration is negative or too long, or the mutex was created
+    /// with the protocol attribute having the value [`Ceiling`] and the
+    /// current task's priority is higher than the mutex's priority ceiling.
     ///
     /// [`Ceiling`]: crate::kernel::MutexProtocol::Ceiling
     BadParam = LockMutexError::BadParam as i8,
@@ -222,6 +232,8 @@ impl<Guard> fmt::Debug for LockError<Guard> {
         f.write_str(match self {
             Self::BadContext => "BadContext",
             Self::Interrupted => "Interrupted",
+            Self::Timeout => "Timeout",
+            Self::Deadlock => "Deadlock",
             Self::BadParam => "BadParam",
             Self::Abandoned(_) => "Abandoned",
         })
@@ -338,15 +350,44 @@ where
     ///
     /// This method will panic if the nesting count would overflow.
     pub fn lock(&self) -> LockResult<GenericMutexGuard<'_, Cell, Mutex, T>> {
+        self.lock_with(self.mutex.lock().map_err(|e| match e {
+            LockMutexError::NoAccess => LockMutexTimeoutError::NoAccess,
+            LockMutexError::BadContext => LockMutexTimeoutError::BadContext,
+            LockMutexError::Interrupted => LockMutexTimeoutError::Interrupted,
+            LockMutexError::WouldDeadlock => LockMutexTimeoutError::WouldDeadlock,
+            LockMutexError::Deadlock => LockMutexTimeoutError::Deadlock,
+            LockMutexError::BadParam => LockMutexTimeoutError::BadParam,
+            LockMutexError::Abandoned => LockMutexTimeoutError::Abandoned,
+        }))
+    }
+
+    /// [`lock`](Self::lock) with timeout.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the nesting count would overflow.
+    pub fn lock_timeout(
+        &self,
+        timeout: Duration,
+    ) -> LockResult<GenericMutexGuard<'_, Cell, Mutex, T>> {
+        self.lock_with(self.mutex.lock_timeout(timeout))
+    }
+
+    /// Complete [`Self::lock`] or [`Self::lock_timeout`] based on the result
+    /// of the underlying mutex's lock operation.
+    fn lock_with(
+        &self,
+        result: Result<(), LockMutexTimeoutError>,
+    ) -> LockResult<GenericMutexGuard<'_, Cell, Mutex, T>> {
         let level;
 
-        match self.mutex.lock() {
+        match result {
             Ok(()) => {
                 // Safety: We are in a task, which means `self.cell` is
                 // initialized [ref:source_cell]
                 level = unsafe { &self.cell.assume_init_ref().level };
             }
-            Err(LockMutexError::WouldDeadlock) => {
+            Err(LockMutexTimeoutError::WouldDeadlock) => {
                 // Safety: We are the owning task, which means `self.cell` is
                 // initialized [ref:source_cell]
                 level = unsafe { &self.cell.assume_init_ref().level };
@@ -355,11 +396,13 @@ where
                         .expect("nesting count overflow")
                 });
             }
-            Err(LockMutexError::NoAccess) => unreachable!(),
-            Err(LockMutexError::BadContext) => return Err(LockError::BadContext),
-            Err(LockMutexError::Interrupted) => return Err(LockError::Interrupted),
-            Err(LockMutexError::BadParam) => return Err(LockError::BadParam),
-            Err(LockMutexError::Abandoned) => {
+            Err(LockMutexTimeoutError::NoAccess) => unreachable!(),
+            Err(LockMutexTimeoutError::BadContext) => return Err(LockError::BadContext),
+            Err(LockMutexTimeoutError::Interrupted) => return Err(LockError::Interrupted),
+            Err(LockMutexTimeoutError::Timeout) => return Err(LockError::Timeout),
+            Err(LockMutexTimeoutError::Deadlock) => return Err(LockError::Deadlock),
+            Err(LockMutexTimeoutError::BadParam) => return Err(LockError::BadParam),
+            Err(LockMutexTimeoutError::Abandoned) => {
                 // Safety: It being abandoned means there was a task that owned
                 // the lock, which means we are past the boot phase, which means
                 // `self.cell` is initialized [ref:source_cell]
@@ -0,0 +1,740 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, LocalTask, ParkError, ParkTimeoutError, TimeError},
+    klock::{CpuLockCell, CpuLockGuard},
+    sync::{
+        source::{DefaultSource, Source},
+        wait_queue::{WaitQueue, Waiter},
+    },
+    time::{Duration, Time},
+    utils::Init,
+};
+
+/// The definer (static builder) for [`StaticRwLock`][].
+#[doc = include_str!("../common.md")]
+pub struct Definer<System, Source> {
+    _phantom: PhantomData<System>,
+    policy: RwLockPolicy,
+    source: Source,
+}
+
+/// A reader-writer lock, which allows any number of readers or at most one
+/// writer to access the protected data at any point of time.
+///
+/// This type is implemented using [`Kernel::park`]. Unlike [`StaticMutex`],
+/// it doesn't support priority inheritance or priority ceiling, and waiting
+/// tasks are woken up in a FIFO order regardless of their priorities. The
+/// order in which readers and writers acquire the lock is controlled by
+/// [`RwLockPolicy`].
+///
+/// All locking methods can only be called in a task context.
+///
+/// # Example
+///
+#[doc = crate::tests::doc_test!(
+/// ```rust
+/// use r3::{kernel::StaticTask, sync::StaticRwLock};
+///
+/// struct Objects {
+///     task2: StaticTask<System>,
+///     rwlock: StaticRwLock<System, i32>,
+/// }
+///
+/// const fn configure_app<C>(cfg: &mut Cfg<C>) -> Objects
+/// where
+///     C: ~const traits::CfgTask<System = System>,
+/// {
+///     StaticTask::define()
+///         .start(task1_body)
+///         .priority(2)
+///         .active(true)
+///         .finish(cfg);
+///
+///     let task2 = StaticTask::define()
+///         .start(task2_body)
+///         .priority(1)
+///         .finish(cfg);
+///
+///     let rwlock = StaticRwLock::define().init(|| 1).finish(cfg);
+///
+///     Objects { task2, rwlock }
+/// }
+///
+/// fn task1_body() {
+///     let guard = COTTAGE.rwlock.read().unwrap();
+///
+///     // `task2` can read the data concurrently but has to wait until
+///     // `task1` releases the lock to modify it
+///     COTTAGE.task2.activate().unwrap();
+///
+///     assert_eq!(*guard, 1);
+/// }
+///
+/// fn task2_body() {
+///     assert_eq!(*COTTAGE.rwlock.read().unwrap(), 1);
+///
+///     let mut guard = COTTAGE.rwlock.write().unwrap();
+///     *guard = 2;
+/// #   exit(0);
+/// }
+/// ```
+)]
+///
+/// [`Kernel::park`]: crate::kernel::Kernel::park
+/// [`StaticMutex`]: crate::sync::StaticMutex
+pub struct StaticRwLock<System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    cell: Hunk<System, UnsafeCell<MaybeUninit<T>>>,
+    state: Hunk<System, RwLockState<System>>,
+    policy: RwLockPolicy,
+}
+
+/// Specifies which of readers and writers are given precedence by
+/// [`StaticRwLock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RwLockPolicy {
+    /// A reader can't acquire the lock while there's a writer waiting for
+    /// the lock. This prevents writers from being starved by a continuous
+    /// stream of readers.
+    #[default]
+    WriterPreferred,
+    /// A reader can acquire the lock whenever it's not held by a writer.
+    /// Writers may be starved if there are always one or more readers.
+    ReaderPreferred,
+}
+
+struct RwLockState<System: traits::KernelBase> {
+    /// The number of readers holding the lock.
+    readers: CpuLockCell<usize>,
+    /// Set when a writer is holding the lock.
+    writer: CpuLockCell<bool>,
+    /// The waiting tasks. A waiter is granted the lock before it's removed
+    /// from the queue and woken up.
+    queue: WaitQueue<System, Access>,
+}
+
+impl<System: traits::KernelBase> Init for RwLockState<System> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        readers: Init::INIT,
+        writer: Init::INIT,
+        queue: Init::INIT,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+}
+
+/// The timeout of a blocking lock operation.
+#[derive(Clone, Copy)]
+struct Timeout {
+    duration: Duration,
+    /// [`Kernel::time`], which is used to calculate the remaining time after
+    /// a spurious wakeup.
+    ///
+    /// [`Kernel::time`]: crate::kernel::Kernel::time
+    time: fn() -> Result<Time, TimeError>,
+}
+
+unsafe impl<System, T: Send> Send for StaticRwLock<System, T> where
+    System: traits::KernelBase + traits::KernelStatic
+{
+}
+unsafe impl<System, T: Send + Sync> Sync for StaticRwLock<System, T> where
+    System: traits::KernelBase + traits::KernelStatic
+{
+}
+
+/// An RAII implementation of a shared read access to a reader-writer lock.
+/// When this structure is dropped, the shared access will be released.
+///
+/// This structure is created by the [`read`], [`try_read`], and
+/// [`read_timeout`] methods of [`StaticRwLock`].
+///
+/// [`read`]: StaticRwLock::read
+/// [`try_read`]: StaticRwLock::try_read
+/// [`read_timeout`]: StaticRwLock::read_timeout
+#[must_use = "if unused the StaticRwLock will immediately unlock"]
+pub struct StaticRwLockReadGuard<'a, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    lock: &'a StaticRwLock<System, T>,
+    _no_send_sync: PhantomData<*mut ()>,
+}
+
+unsafe impl<System, T: Sync> Sync for StaticRwLockReadGuard<'_, System, T> where
+    System: traits::KernelBase + traits::KernelStatic
+{
+}
+
+/// An RAII implementation of an exclusive write access to a reader-writer
+/// lock. When this structure is dropped, the exclusive access will be
+/// released.
+///
+/// This structure is created by the [`write`], [`try_write`], and
+/// [`write_timeout`] methods of [`StaticRwLock`].
+///
+/// [`write`]: StaticRwLock::write
+/// [`try_write`]: StaticRwLock::try_write
+/// [`write_timeout`]: StaticRwLock::write_timeout
+#[must_use = "if unused the StaticRwLock will immediately unlock"]
+pub struct StaticRwLockWriteGuard<'a, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    lock: &'a StaticRwLock<System, T>,
+    _no_send_sync: PhantomData<*mut ()>,
+}
+
+unsafe impl<System, T: Sync> Sync for StaticRwLockWriteGuard<'_, System, T> where
+    System: traits::KernelBase + traits::KernelStatic
+{
+}
+
+/// Error type of [`StaticRwLock::read`] and [`StaticRwLock::write`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockError {
+    /// CPU Lock is active, or the current context is not [waitable].
+    ///
+    /// [waitable]: crate#contexts
+    BadContext,
+    /// The wait operation was interrupted by [`Task::interrupt`].
+    ///
+    /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
+    Interrupted,
+}
+
+/// Error type of [`StaticRwLock::try_read`] and [`StaticRwLock::try_write`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryLockError {
+    /// CPU Lock is active, or the current context is not [a task context].
+    ///
+    /// [a task context]: crate#contexts
+    BadContext,
+    /// The lock could not be acquire at this time because the operation would
+    /// otherwise block.
+    WouldBlock,
+}
+
+/// Error type of [`StaticRwLock::read_timeout`] and
+/// [`StaticRwLock::write_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockTimeoutError {
+    /// CPU Lock is active, or the current context is not [waitable].
+    ///
+    /// [waitable]: crate#contexts
+    BadContext,
+    /// The wait operation was interrupted by [`Task::interrupt`].
+    ///
+    /// [`Task::interrupt`]: crate::kernel::task::TaskMethods::interrupt
+    Interrupted,
+    /// The operation timed out.
+    Timeout,
+    /// The timeout is negative or too long.
+    BadParam,
+}
+
+impl<System, T: 'static> StaticRwLock<System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    /// Construct a `Definer` to define a reader-writer lock in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> Definer<System, DefaultSource<T>> {
+        Definer {
+            _phantom: PhantomData,
+            policy: RwLockPolicy::WriterPreferred,
+            source: DefaultSource::INIT, // [ref:default_source_is_default]
+        }
+    }
+}
+
+impl<System, Source> Definer<System, Source> {
+    /// Specify the lock's policy. Defaults to
+    /// [`WriterPreferred`](RwLockPolicy::WriterPreferred) when unspecified.
+    pub const fn policy(self, policy: RwLockPolicy) -> Self {
+        Self { policy, ..self }
+    }
+}
+
+// Define methods to set `Definer::source`
+impl_source_setter!(
+    #[no_autowrap()]
+    impl Definer<System, #Source>
+);
+
+/// # Finalization
+///
+/// The following method completes the definition of a reader-writer lock.
+impl<System, Source> Definer<System, Source>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    /// Complete the definition of a reader-writer lock, returning a reference
+    /// to the lock.
+    pub const fn finish<C: ~const traits::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticRwLock<System, Source::Target>
+    where
+        Source: ~const self::Source<System>,
+    {
+        StaticRwLock {
+            cell: self.source.into_unsafe_cell_hunk(cfg),
+            state: Hunk::<_, RwLockState<System>>::define().finish(cfg),
+            policy: self.policy,
+        }
+    }
+}
+
+impl<System, T> StaticRwLock<System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    /// Acquire a shared read access to the lock, blocking the current task
+    /// until it is able to do so.
+    pub fn read(&self) -> Result<StaticRwLockReadGuard<'_, System, T>, LockError> {
+        self.lock(Access::Read, None).map_err(|e| match e {
+            LockTimeoutError::BadContext => LockError::BadContext,
+            LockTimeoutError::Interrupted => LockError::Interrupted,
+            LockTimeoutError::Timeout | LockTimeoutError::BadParam => unreachable!(),
+        })?;
+        Ok(StaticRwLockReadGuard {
+            lock: self,
+            _no_send_sync: PhantomData,
+        })
+    }
+
+    /// Acquire an exclusive write access to the lock, blocking the current
+    /// task until it is able to do so.
+    pub fn write(&self) -> Result<StaticRwLockWriteGuard<'_, System, T>, LockError> {
+        self.lock(Access::Write, None).map_err(|e| match e {
+            LockTimeoutError::BadContext => LockError::BadContext,
+            LockTimeoutError::Interrupted => LockError::Interrupted,
+            LockTimeoutError::Timeout | LockTimeoutError::BadParam => unreachable!(),
+        })?;
+        Ok(StaticRwLockWriteGuard {
+            lock: self,
+            _no_send_sync: PhantomData,
+        })
+    }
+
+    /// Attempt to acquire a shared read access to the lock.
+    pub fn try_read(&self) -> Result<StaticRwLockReadGuard<'_, System, T>, TryLockError> {
+        self.try_lock(Access::Read)?;
+        Ok(StaticRwLockReadGuard {
+            lock: self,
+            _no_send_sync: PhantomData,
+        })
+    }
+
+    /// Attempt to acquire an exclusive write access to the lock.
+    pub fn try_write(&self) -> Result<StaticRwLockWriteGuard<'_, System, T>, TryLockError> {
+        self.try_lock(Access::Write)?;
+        Ok(StaticRwLockWriteGuard {
+            lock: self,
+            _no_send_sync: PhantomData,
+        })
+    }
+
+    /// Get a raw pointer to the contained data.
+    #[inline]
+    pub fn get_ptr(&self) -> *mut T {
+        self.cell.get().cast()
+    }
+
+    fn try_lock(&self, access: Access) -> Result<(), TryLockError> {
+        if !System::is_task_context() {
+            return Err(TryLockError::BadContext);
+        }
+        let lock = CpuLockGuard::<System>::new().map_err(|()| TryLockError::BadContext)?;
+        if self.try_acquire(&lock, access) {
+            Ok(())
+        } else {
+            Err(TryLockError::WouldBlock)
+        }
+    }
+
+    fn lock(&self, access: Access, timeout: Option<Timeout>) -> Result<(), LockTimeoutError> {
+        if !System::is_task_context() || System::is_priority_boost_active() {
+            return Err(LockTimeoutError::BadContext);
+        }
+        if timeout.map_or(false, |t| t.duration.is_negative()) {
+            return Err(LockTimeoutError::BadParam);
+        }
+
+        // Calculate the deadline once so that spurious wakeups don't extend
+        // the timeout
+        let deadline = match timeout {
+            Some(t) => Some((
+                (t.time)().map_err(|_| LockTimeoutError::BadContext)? + t.duration,
+                t.time,
+            )),
+            None => None,
+        };
+
+        // `Waiter::current` requires CPU Lock to be inactive
+        let waiter = Waiter::current(access).ok_or(LockTimeoutError::BadContext)?;
+
+        let lock = CpuLockGuard::<System>::new().map_err(|()| LockTimeoutError::BadContext)?;
+        if self.try_acquire(&lock, access) {
+            return Ok(());
+        }
+
+        // Wait until an unlocking task grants us the lock
+        let registration = self.state.queue.push_back(&lock, &waiter);
+        drop(lock);
+
+        let error = loop {
+            let result = match deadline {
+                None => System::park().map_err(|e| match e {
+                    ParkError::BadContext => LockTimeoutError::BadContext,
+                    ParkError::Interrupted => LockTimeoutError::Interrupted,
+                }),
+                Some((deadline, time)) => remaining_time(deadline, time).and_then(|remaining| {
+                    System::park_timeout(remaining).map_err(|e| match e {
+                        ParkTimeoutError::BadContext => LockTimeoutError::BadContext,
+                        ParkTimeoutError::Interrupted => LockTimeoutError::Interrupted,
+                        ParkTimeoutError::Timeout => LockTimeoutError::Timeout,
+                        ParkTimeoutError::BadParam => LockTimeoutError::BadParam,
+                    })
+                }),
+            };
+
+            match result {
+                Ok(()) if registration.is_woken() => return Ok(()),
+                // Spurious wakeup
+                Ok(()) => continue,
+                Err(e) => break e,
+            }
+        };
+
+        // The last park call failed, so it didn't consume the token of a task
+        // that granted us the lock
+        if registration.finish_wait(false) {
+            // We were granted the lock just before giving up
+            return Ok(());
+        }
+
+        // Leaving the queue might allow the waiters behind us to proceed
+        self.wake_waiters();
+        Err(error)
+    }
+
+    /// Acquire the lock without waiting.
+    fn try_acquire(&self, lock: &CpuLockGuard<System>, access: Access) -> bool {
+        let state = &*self.state;
+        if state.writer.get(lock) {
+            return false;
+        }
+        match access {
+            Access::Read => {
+                if self.policy == RwLockPolicy::WriterPreferred
+                    && state.queue.any(lock, |a| *a == Access::Write)
+                {
+                    return false;
+                }
+                state.readers.set(lock, state.readers.get(lock) + 1);
+            }
+            Access::Write => {
+                if state.readers.get(lock) != 0 || state.queue.len(lock) != 0 {
+                    return false;
+                }
+                state.writer.set(lock, true);
+            }
+        }
+        true
+    }
+
+    fn unlock_read(&self) {
+        {
+            let lock = CpuLockGuard::<System>::new().expect("CPU Lock is active");
+            let state = &*self.state;
+            state.readers.set(&lock, state.readers.get(&lock) - 1);
+        }
+        self.wake_waiters();
+    }
+
+    fn unlock_write(&self) {
+        {
+            let lock = CpuLockGuard::<System>::new().expect("CPU Lock is active");
+            self.state.writer.set(&lock, false);
+        }
+        self.wake_waiters();
+    }
+
+    /// Grant the lock to as many waiting tasks as possible and wake them up.
+    fn wake_waiters(&self) {
+        while let Some(task) = self.grant_one() {
+            // The task might have timed out and exited already
+            let _ = task.unpark();
+        }
+    }
+
+    /// Grant the lock to one of the waiting tasks and remove it from the
+    /// wait queue. Returns the task, which should be unparked by the caller.
+    fn grant_one(&self) -> Option<LocalTask<System>> {
+        let lock = CpuLockGuard::<System>::new().expect("CPU Lock is active");
+        let state = &*self.state;
+        if state.writer.get(&lock) {
+            return None;
+        }
+        let readers = state.readers.get(&lock);
+
+        let access = match self.policy {
+            RwLockPolicy::WriterPreferred => match state.queue.front(&lock)? {
+                Access::Write if readers != 0 => return None,
+                access => access,
+            },
+            RwLockPolicy::ReaderPreferred => {
+                if state.queue.any(&lock, |a| *a == Access::Read) {
+                    Access::Read
+                } else if readers == 0 {
+                    Access::Write
+                } else {
+                    return None;
+                }
+            }
+        };
+
+        let task = state.queue.wake_first(&lock, |a| *a == access)?;
+        match access {
+            Access::Read => state.readers.set(&lock, readers + 1),
+            Access::Write => state.writer.set(&lock, true),
+        }
+        Some(task)
+    }
+}
+
+/// # Timed Operations
+///
+/// The following methods require [`traits::KernelTime`] because the deadline
+/// is tracked using [`Kernel::time`]. Changing the system time by
+/// [`Kernel::set_time`] or [`Kernel::adjust_time`] during a wait therefore
+/// shortens or extends the wait.
+///
+/// [`Kernel::time`]: crate::kernel::Kernel::time
+/// [`Kernel::set_time`]: crate::kernel::Kernel::set_time
+/// [`Kernel::adjust_time`]: crate::kernel::Kernel::adjust_time
+impl<System, T> StaticRwLock<System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic + traits::KernelTime,
+{
+    /// [`read`](Self::read) with timeout.
+    pub fn read_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<StaticRwLockReadGuard<'_, System, T>, LockTimeoutError> {
+        self.lock(
+            Access::Read,
+            Some(Timeout {
+                duration: timeout,
+                time: System::time,
+            }),
+        )?;
+        Ok(StaticRwLockReadGuard {
+            lock: self,
+            _no_send_sync: PhantomData,
+        })
+    }
+
+    /// [`write`](Self::write) with timeout.
+    pub fn write_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<StaticRwLockWriteGuard<'_, System, T>, LockTimeoutError> {
+        self.lock(
+            Access::Write,
+            Some(Timeout {
+                duration: timeout,
+                time: System::time,
+            }),
+        )?;
+        Ok(StaticRwLockWriteGuard {
+            lock: self,
+            _no_send_sync: PhantomData,
+        })
+    }
+}
+
+/// Get the time remaining until `deadline`, which is zero if `deadline` has
+/// passed.
+fn remaining_time(
+    deadline: Time,
+    time: fn() -> Result<Time, TimeError>,
+) -> Result<Duration, LockTimeoutError> {
+    let now = time().map_err(|_| LockTimeoutError::BadContext)?;
+    Ok(if now >= deadline {
+        Duration::ZERO
+    } else {
+        deadline.duration_since(now).unwrap_or(Duration::MAX)
+    })
+}
+
+impl<System, T: fmt::Debug> fmt::Debug for StaticRwLock<System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_read() {
+            Ok(guard) => f
+                .debug_struct("StaticRwLock")
+                .field("data", &&*guard)
+                .finish(),
+            Err(TryLockError::BadContext) => {
+                struct BadContextPlaceholder;
+                impl fmt::Debug for BadContextPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<bad context>")
+                    }
+                }
+
+                f.debug_struct("StaticRwLock")
+                    .field("data", &BadContextPlaceholder)
+                    .finish()
+            }
+            Err(TryLockError::WouldBlock) => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+
+                f.debug_struct("StaticRwLock")
+                    .field("data", &LockedPlaceholder)
+                    .finish()
+            }
+        }
+    }
+}
+
+impl<System, T: fmt::Debug> fmt::Debug for StaticRwLockReadGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<System, T: fmt::Display> fmt::Display for StaticRwLockReadGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<System, T: fmt::Debug> fmt::Debug for StaticRwLockWriteGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<System, T: fmt::Display> fmt::Display for StaticRwLockWriteGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+/// The destructor of `StaticRwLockReadGuard` that releases the shared access.
+/// It will panic if CPU Lock is active.
+impl<System, T> Drop for StaticRwLockReadGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.unlock_read();
+    }
+}
+
+/// The destructor of `StaticRwLockWriteGuard` that releases the exclusive
+/// access. It will panic if CPU Lock is active.
+impl<System, T> Drop for StaticRwLockWriteGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.unlock_write();
+    }
+}
+
+impl<System, T> Deref for StaticRwLockReadGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: `StaticRwLockReadGuard` represents a shared access to the
+        // lock, which prevents anyone else from mutably borrowing the
+        // underlying data. Since this `Hunk` was given by `Source`
+        // ([ref:source_cell]), we are authorized to enforce the runtime borrow
+        // rules on its contents.
+        //
+        // [ref:source_cell] says that the contents may be unavailable outside
+        // the context of an executable object. We are in the clear because
+        // `StaticRwLock` can only be locked in a task context.
+        unsafe { (*self.lock.cell.get()).assume_init_ref() }
+    }
+}
+
+impl<System, T> Deref for StaticRwLockWriteGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: `StaticRwLockWriteGuard` represents an exclusive access to
+        // the lock. See `StaticRwLockReadGuard::deref` for the rest.
+        unsafe { (*self.lock.cell.get()).assume_init_ref() }
+    }
+}
+
+impl<System, T> DerefMut for StaticRwLockWriteGuard<'_, System, T>
+where
+    System: traits::KernelBase + traits::KernelStatic,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safety: See the `deref` above.
+        unsafe { (*self.lock.cell.get()).assume_init_mut() }
+    }
+}
+
+// Safety: `StaticRwLockReadGuard::deref` provides a stable address
+unsafe impl<System, T> stable_deref_trait::StableDeref for StaticRwLockReadGuard<'_, System, T> where
+    System: traits::KernelBase + traits::KernelStatic
+{
+}
+
+// Safety: `StaticRwLockWriteGuard::deref` provides a stable address
+unsafe impl<System, T> stable_deref_trait::StableDeref for StaticRwLockWriteGuard<'_, System, T> where
+    System: traits::KernelBase + traits::KernelStatic
+{
+}
@@ -0,0 +1,244 @@
+//! An intrusive FIFO queue of waiting tasks, used to implement the blocking
+//! operations of the synchronization primitives on top of [`Kernel::park`].
+//!
+//! [`Kernel::park`]: crate::kernel::Kernel::park
+use core::ptr::NonNull;
+
+use crate::{
+    kernel::{prelude::*, traits, LocalTask, ParkError},
+    klock::{CpuLockCell, CpuLockGuard},
+    utils::Init,
+};
+
+/// A FIFO queue of waiting tasks. The nodes are [`Waiter`]s allocated in the
+/// waiting tasks' stacks.
+pub(super) struct WaitQueue<System: traits::KernelBase, T> {
+    head: CpuLockCell<Option<WaiterPtr<System, T>>>,
+    tail: CpuLockCell<Option<WaiterPtr<System, T>>>,
+    len: CpuLockCell<usize>,
+}
+
+impl<System: traits::KernelBase, T> Init for WaitQueue<System, T> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        head: Init::INIT,
+        tail: Init::INIT,
+        len: Init::INIT,
+    };
+}
+
+/// A waiting task. `T` is an application-defined payload.
+pub(super) struct Waiter<System: traits::KernelBase, T> {
+    task: LocalTask<System>,
+    data: T,
+    next: CpuLockCell<Option<WaiterPtr<System, T>>>,
+    /// Set when the waiter is removed from the queue to be woken up.
+    woken: CpuLockCell<bool>,
+}
+
+impl<System: traits::KernelBase, T> Waiter<System, T> {
+    /// Construct a `Waiter` for the current task. Returns `None` if the
+    /// current context is not a task context or CPU Lock is active.
+    pub(super) fn current(data: T) -> Option<Self> {
+        Some(Self {
+            task: LocalTask::current().ok()?,
+            data,
+            next: Init::INIT,
+            woken: Init::INIT,
+        })
+    }
+}
+
+struct WaiterPtr<System: traits::KernelBase, T>(NonNull<Waiter<System, T>>);
+
+impl<System: traits::KernelBase, T> Clone for WaiterPtr<System, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase, T> Copy for WaiterPtr<System, T> {}
+
+// Safety: The pointee is only accessed with CPU Lock active
+unsafe impl<System: traits::KernelBase, T> Send for WaiterPtr<System, T> {}
+
+impl<System: traits::KernelBase, T> WaiterPtr<System, T> {
+    /// Get a reference to the pointee.
+    ///
+    /// # Safety
+    ///
+    /// The waiter must be in a wait queue. The returned reference must not
+    /// outlive `lock`.
+    #[inline]
+    unsafe fn get(self, _lock: &CpuLockGuard<System>) -> &Waiter<System, T> {
+        // Safety: A waiter is removed from the queue before it's
+        //         deallocated, and the removal requires CPU Lock
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<System: traits::KernelBase, T> WaitQueue<System, T> {
+    /// Get the number of waiters in the queue.
+    #[inline]
+    pub(super) fn len(&self, lock: &CpuLockGuard<System>) -> usize {
+        self.len.get(lock)
+    }
+
+    /// Get the payload of the first waiter in the queue.
+    pub(super) fn front(&self, lock: &CpuLockGuard<System>) -> Option<T>
+    where
+        T: Copy,
+    {
+        // Safety: `head` is in the queue
+        Some(unsafe { self.head.get(lock)?.get(lock) }.data)
+    }
+
+    /// Get a flag indicating whether any of the waiters satisfies `pred`.
+    pub(super) fn any(
+        &self,
+        lock: &CpuLockGuard<System>,
+        mut pred: impl FnMut(&T) -> bool,
+    ) -> bool {
+        let mut cur = self.head.get(lock);
+        while let Some(ptr) = cur {
+            // Safety: `ptr` is in the queue
+            let waiter = unsafe { ptr.get(lock) };
+            if pred(&waiter.data) {
+                return true;
+            }
+            cur = waiter.next.get(lock);
+        }
+        false
+    }
+
+    /// Add `waiter` to the end of the queue. The waiter is removed when the
+    /// returned [`Registration`] is finished or dropped unless it has been
+    /// woken up by then.
+    pub(super) fn push_back<'a>(
+        &'a self,
+        lock: &CpuLockGuard<System>,
+        waiter: &'a Waiter<System, T>,
+    ) -> Registration<'a, System, T> {
+        let ptr = WaiterPtr(NonNull::from(waiter));
+        waiter.next.set(lock, None);
+        match self.tail.get(lock) {
+            // Safety: `tail` is in the queue
+            Some(tail) => unsafe { tail.get(lock) }.next.set(lock, Some(ptr)),
+            None => self.head.set(lock, Some(ptr)),
+        }
+        self.tail.set(lock, Some(ptr));
+        self.len.set(lock, self.len.get(lock) + 1);
+
+        Registration {
+            queue: self,
+            waiter,
+        }
+    }
+
+    /// Remove the first waiter satisfying `pred` from the queue and mark it as
+    /// woken up. Returns the waiter's task, which the caller is responsible
+    /// for unparking after releasing CPU Lock.
+    pub(super) fn wake_first(
+        &self,
+        lock: &CpuLockGuard<System>,
+        mut pred: impl FnMut(&T) -> bool,
+    ) -> Option<LocalTask<System>> {
+        let mut cur = self.head.get(lock);
+        while let Some(ptr) = cur {
+            // Safety: `ptr` is in the queue
+            let waiter = unsafe { ptr.get(lock) };
+            if pred(&waiter.data) {
+                self.remove(lock, waiter);
+                waiter.woken.set(lock, true);
+                // `waiter` may be deallocated after `lock` is released, so
+                // return a copy of the task
+                return Some(waiter.task);
+            }
+            cur = waiter.next.get(lock);
+        }
+        None
+    }
+
+    fn remove(&self, lock: &CpuLockGuard<System>, waiter: &Waiter<System, T>) {
+        let target = NonNull::from(waiter);
+        let mut prev: Option<WaiterPtr<System, T>> = None;
+        let mut cur = self.head.get(lock);
+        while let Some(ptr) = cur {
+            // Safety: `ptr` is in the queue
+            let next = unsafe { ptr.get(lock) }.next.get(lock);
+            if ptr.0 == target {
+                match prev {
+                    // Safety: `prev` is in the queue
+                    Some(prev) => unsafe { prev.get(lock) }.next.set(lock, next),
+                    None => self.head.set(lock, next),
+                }
+                if next.is_none() {
+                    self.tail.set(lock, prev);
+                }
+                self.len.set(lock, self.len.get(lock) - 1);
+                return;
+            }
+            prev = cur;
+            cur = next;
+        }
+        unreachable!("the waiter is not in the queue");
+    }
+}
+
+/// Removes a waiter from a wait queue when finished or dropped, unless it has
+/// been woken up.
+pub(super) struct Registration<'a, System: traits::KernelBase, T> {
+    queue: &'a WaitQueue<System, T>,
+    waiter: &'a Waiter<System, T>,
+}
+
+impl<System: traits::KernelBase, T> Registration<'_, System, T> {
+    /// Get a flag indicating whether the waiter has been woken up.
+    pub(super) fn is_woken(&self) -> bool {
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        self.waiter.woken.get(&lock)
+    }
+
+    /// Unregister the waiter. Returns `true` if it has been woken up.
+    pub(super) fn finish(self) -> bool {
+        let woken = self.unregister();
+        core::mem::forget(self);
+        woken
+    }
+
+    /// Unregister the waiter after a call to [`Kernel::park`] or
+    /// [`Kernel::park_timeout`]. Returns `true` if it has been woken up.
+    ///
+    /// `token_consumed` indicates whether the park call returned by consuming
+    /// a park token. If the waiter has been woken up but the park call didn't
+    /// consume a token (e.g., because it timed out), the waker's token is
+    /// pending or about to be deposited. This method consumes it so that it
+    /// doesn't cause an unrelated park call to return early.
+    ///
+    /// [`Kernel::park`]: crate::kernel::Kernel::park
+    /// [`Kernel::park_timeout`]: crate::kernel::Kernel::park_timeout
+    pub(super) fn finish_wait(self, token_consumed: bool) -> bool {
+        let woken = self.finish();
+        if woken && !token_consumed {
+            // The waker unparks the task after releasing CPU Lock, so the
+            // token might not have arrived yet
+            while let Err(ParkError::Interrupted) = System::park() {}
+        }
+        woken
+    }
+
+    fn unregister(&self) -> bool {
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        let woken = self.waiter.woken.get(&lock);
+        if !woken {
+            self.queue.remove(&lock, self.waiter);
+        }
+        woken
+    }
+}
+
+impl<System: traits::KernelBase, T> Drop for Registration<'_, System, T> {
+    fn drop(&mut self) {
+        self.unregister();
+    }
+}
@@ -0,0 +1,707 @@
+//! Work queues for deferring work to a task context.
+//!
+//! A [work queue](StaticWorkQueue) owns a worker task and a fixed-capacity
+//! FIFO queue of [work items](StaticWorkItem). Submitting a work item pushes it
+//! to the queue and wakes up the worker task, which calls the work items'
+//! functions in a task context in the order they were submitted. Submission
+//! is allowed in an interrupt context, which makes work queues useful for
+//! moving lengthy or blocking operations out of interrupt handlers.
+//!
+//! A work item is either *idle* or *pending*. Submitting a pending work item
+//! fails with [`SubmitError::AlreadyQueued`], so a work item is never queued
+//! more than once. A work item stops being pending just before its function is
+//! called, so the function may submit the work item again.
+//!
+//! [`StaticDelayedWorkItem`] is a work item that can also be submitted after a
+//! delay. It uses a [timer](crate::kernel::Timer) to do so.
+//!
+//! This module is built only on the kernel traits defined by [`r3_core`] and
+//! works with any kernel implementation.
+use core::{
+    fmt,
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+};
+
+use r3_core::closure::{Closure, IntoClosureConst};
+
+use crate::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, ActivateTaskError, Cfg, StaticTask, StaticTimer},
+    klock::{CpuLockCell, CpuLockGuard},
+    time::Duration,
+    utils::Init,
+};
+
+/// A statically defined work queue, which calls the functions of the
+/// [submitted](StaticWorkItem::submit) work items in a dedicated worker task.
+///
+/// The worker task is only active while the queue is not empty. It's
+/// activated by the first submission and exits when it has processed all
+/// work items in the queue.
+///
+/// # Example
+///
+#[doc = crate::tests::doc_test!(
+/// ```rust
+/// use r3::{
+///     kernel::StaticTimer,
+///     time::Duration,
+///     workqueue::{StaticWorkItem, StaticWorkQueue},
+/// };
+///
+/// struct Objects {
+///     work: StaticWorkItem<System>,
+/// }
+///
+/// const fn configure_app<C>(cfg: &mut Cfg<C>) -> Objects
+/// where
+///     C: ~const traits::CfgTask<System = System> +
+///        ~const traits::CfgTimer,
+/// {
+///     let queue = StaticWorkQueue::define()
+///         .priority(1)
+///         .capacity(4)
+///         .finish(cfg);
+///
+///     let work = StaticWorkItem::define()
+///         .queue(queue)
+///         .start(work_body)
+///         .finish(cfg);
+///
+///     // The callback function of a timer is called in an interrupt context
+///     // by default
+///     StaticTimer::define()
+///         .delay(Duration::from_millis(10))
+///         .active(true)
+///         .start(timer_body)
+///         .finish(cfg);
+///
+///     Objects { work }
+/// }
+///
+/// fn timer_body() {
+///     COTTAGE.work.submit().unwrap();
+/// }
+///
+/// fn work_body() {
+///     assert!(System::is_task_context());
+/// #   exit(0);
+/// }
+/// ```
+)]
+pub struct StaticWorkQueue<System: traits::KernelBase + traits::KernelStatic> {
+    task: StaticTask<System>,
+    shared: Shared<System>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for StaticWorkQueue<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for StaticWorkQueue<System> {}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for StaticWorkQueue<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticWorkQueue")
+            .field("task", &self.task)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The part of [`StaticWorkQueue`] accessed by the worker task.
+struct Shared<System: traits::KernelBase + traits::KernelStatic> {
+    state: Hunk<System, CpuLockCell<QueueState>>,
+    /// The ring buffer. The elements in range `head..head + len` (wrapping
+    /// around) are initialized.
+    slots: Hunk<System, [CpuLockCell<MaybeUninit<Entry<System>>>]>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for Shared<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for Shared<System> {}
+
+#[derive(Clone, Copy)]
+struct QueueState {
+    /// The position of the oldest element in the queue.
+    head: usize,
+    /// The number of elements in the queue.
+    len: usize,
+}
+
+impl Init for QueueState {
+    const INIT: Self = Self { head: 0, len: 0 };
+}
+
+/// An element of the queue.
+struct Entry<System: traits::KernelBase + traits::KernelStatic> {
+    state: Hunk<System, CpuLockCell<ItemState>>,
+    start: Closure,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for Entry<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for Entry<System> {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemState {
+    Idle,
+    /// The work item will be queued when the timer fires.
+    Delayed,
+    Queued,
+}
+
+impl Init for ItemState {
+    const INIT: Self = Self::Idle;
+}
+
+/// The definer (static builder) for [`StaticWorkQueue`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct WorkQueueDefiner<System> {
+    _phantom: PhantomData<System>,
+    priority: Option<usize>,
+    capacity: Option<usize>,
+    stack_size: Option<usize>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticWorkQueue<System> {
+    /// Construct a `WorkQueueDefiner` to define a work queue in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> WorkQueueDefiner<System> {
+        WorkQueueDefiner {
+            _phantom: PhantomData,
+            priority: None,
+            capacity: None,
+            stack_size: None,
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> WorkQueueDefiner<System> {
+    /// \[**Required**\] Specify the worker task's priority.
+    pub const fn priority(self, priority: usize) -> Self {
+        Self {
+            priority: Some(priority),
+            ..self
+        }
+    }
+
+    /// \[**Required**\] Specify the maximum number of work items that can be
+    /// queued at the same time. Must be greater than zero.
+    pub const fn capacity(self, capacity: usize) -> Self {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        Self {
+            capacity: Some(capacity),
+            ..self
+        }
+    }
+
+    /// Specify the worker task's stack size.
+    pub const fn stack_size(self, stack_size: usize) -> Self {
+        Self {
+            stack_size: Some(stack_size),
+            ..self
+        }
+    }
+
+    /// Complete the definition of a work queue, returning a reference to the
+    /// work queue.
+    pub const fn finish<C: ~const traits::CfgTask<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticWorkQueue<System> {
+        let capacity = self
+            .capacity
+            .expect("`capacity` (work queue capacity) is not specified");
+        let priority = self
+            .priority
+            .expect("`priority` (worker task priority) is not specified");
+
+        let shared = Shared {
+            state: Hunk::<_, CpuLockCell<QueueState>>::define().finish(cfg),
+            // Safety: An uninitialized `MaybeUninit` can be zero-initialized
+            slots: unsafe {
+                Hunk::<_, [CpuLockCell<MaybeUninit<Entry<System>>>]>::define()
+                    .len(capacity)
+                    .zeroed_unchecked()
+                    .finish(cfg)
+            },
+        };
+
+        let task = StaticTask::define()
+            .start(move || worker_body(shared))
+            .priority(priority)
+            // Allow `StaticWorkQueue::wake` to succeed while the worker task is
+            // exiting after finding the queue empty
+            .max_queued_activations(1);
+        let task = if let Some(stack_size) = self.stack_size {
+            task.stack_size(stack_size)
+        } else {
+            task
+        };
+        let task = task.finish(cfg);
+
+        StaticWorkQueue { task, shared }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticWorkQueue<System> {
+    /// Get the worker task.
+    #[inline]
+    pub const fn task(&self) -> StaticTask<System> {
+        self.task
+    }
+
+    /// Push `entry` to the queue.
+    fn push(&self, lock: &CpuLockGuard<System>, entry: Entry<System>) -> Result<(), SubmitError> {
+        let slots = &*self.shared.slots;
+        let QueueState { head, len } = self.shared.state.get(lock);
+        if len == slots.len() {
+            return Err(SubmitError::QueueFull);
+        }
+        slots[(head + len) % slots.len()].set(lock, MaybeUninit::new(entry));
+        self.shared
+            .state
+            .set(lock, QueueState { head, len: len + 1 });
+        Ok(())
+    }
+
+    /// Remove the entry for the work item `state` from the queue.
+    fn remove(&self, lock: &CpuLockGuard<System>, state: Hunk<System, CpuLockCell<ItemState>>) {
+        let slots = &*self.shared.slots;
+        let QueueState { head, len } = self.shared.state.get(lock);
+
+        // Shift the following entries to fill the gap
+        let mut new_len = 0;
+        for i in 0..len {
+            // Safety: The elements in range `head..head + len` are initialized
+            let entry = unsafe { slots[(head + i) % slots.len()].get(lock).assume_init() };
+            if Hunk::as_ptr(entry.state) != Hunk::as_ptr(state) {
+                slots[(head + new_len) % slots.len()].set(lock, MaybeUninit::new(entry));
+                new_len += 1;
+            }
+        }
+        self.shared
+            .state
+            .set(lock, QueueState { head, len: new_len });
+    }
+
+    /// Activate the worker task. CPU Lock must be inactive.
+    fn wake(&self) {
+        match self.task.activate() {
+            // `QueueOverflow` means the worker task is already running and has
+            // a pending activation request, which guarantees that it'll check
+            // the queue again
+            Ok(()) | Err(ActivateTaskError::QueueOverflow) => {}
+            Err(e) => panic!("failed to activate the worker task: {e:?}"),
+        }
+    }
+}
+
+/// The entry point of a worker task. Calls the functions of the queued work
+/// items and exits when the queue is empty.
+fn worker_body<System: traits::KernelBase + traits::KernelStatic>(shared: Shared<System>) {
+    loop {
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        let QueueState { head, len } = shared.state.get(&lock);
+        if len == 0 {
+            break;
+        }
+
+        // Safety: The elements in range `head..head + len` are initialized
+        let entry = unsafe { shared.slots[head].get(&lock).assume_init() };
+        shared.state.set(
+            &lock,
+            QueueState {
+                head: (head + 1) % shared.slots.len(),
+                len: len - 1,
+            },
+        );
+        entry.state.set(&lock, ItemState::Idle);
+        drop(lock);
+
+        entry.start.call();
+    }
+}
+
+/// A statically defined work item, which can be [submitted](Self::submit) to
+/// a [`StaticWorkQueue`].
+///
+/// # Example
+///
+/// See [`StaticWorkQueue`].
+pub struct StaticWorkItem<System: traits::KernelBase + traits::KernelStatic> {
+    queue: StaticWorkQueue<System>,
+    entry: Entry<System>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Clone for StaticWorkItem<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> Copy for StaticWorkItem<System> {}
+
+impl<System: traits::KernelBase + traits::KernelStatic> fmt::Debug for StaticWorkItem<System> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticWorkItem")
+            .field("queue", &self.queue)
+            .field("start", &self.entry.start)
+            .finish()
+    }
+}
+
+/// The definer (static builder) for [`StaticWorkItem`] and
+/// [`StaticDelayedWorkItem`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct WorkItemDefiner<System: traits::KernelBase + traits::KernelStatic> {
+    queue: Option<StaticWorkQueue<System>>,
+    start: Option<Closure>,
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticWorkItem<System> {
+    /// Construct a `WorkItemDefiner` to define a work item in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> WorkItemDefiner<System> {
+        WorkItemDefiner {
+            queue: None,
+            start: None,
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> WorkItemDefiner<System> {
+    /// \[**Required**\] Specify the work queue to submit the work item to.
+    pub const fn queue(self, queue: StaticWorkQueue<System>) -> Self {
+        Self {
+            queue: Some(queue),
+            ..self
+        }
+    }
+
+    /// \[**Required**\] Specify the function to be called by the worker task.
+    pub const fn start<C: ~const IntoClosureConst>(self, start: C) -> Self {
+        Self {
+            start: Some(start.into_closure_const()),
+            ..self
+        }
+    }
+
+    /// Complete the definition of a work item, returning a reference to the
+    /// work item.
+    pub const fn finish<C: ~const traits::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticWorkItem<System> {
+        StaticWorkItem {
+            queue: self.queue.expect("`queue` (work queue) is not specified"),
+            entry: Entry {
+                state: Hunk::<_, CpuLockCell<ItemState>>::define().finish(cfg),
+                start: self
+                    .start
+                    .expect("`start` (work item function) is not specified"),
+            },
+        }
+    }
+
+    /// Complete the definition of a work item that can be submitted after a
+    /// delay, returning a reference to the work item.
+    pub const fn finish_delayed<C: ~const traits::CfgTimer<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> StaticDelayedWorkItem<System>
+    where
+        System: traits::KernelTimer,
+    {
+        let item = self.finish(cfg);
+        let timer = StaticTimer::define()
+            .start(move || item.submit_delayed())
+            .finish(cfg);
+        let timer_request = Hunk::<_, CpuLockCell<TimerRequest>>::define().finish(cfg);
+        StaticDelayedWorkItem {
+            item,
+            timer,
+            timer_request,
+        }
+    }
+}
+
+impl<System: traits::KernelBase + traits::KernelStatic> StaticWorkItem<System> {
+    /// Submit the work item to the work queue.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn submit(&self) -> Result<(), SubmitError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| SubmitError::BadContext)?;
+        if self.entry.state.get(&lock) != ItemState::Idle {
+            return Err(SubmitError::AlreadyQueued);
+        }
+        self.queue.push(&lock, self.entry)?;
+        self.entry.state.set(&lock, ItemState::Queued);
+        drop(lock);
+
+        self.queue.wake();
+        Ok(())
+    }
+
+    /// Remove the work item from the work queue.
+    ///
+    /// This method doesn't wait for the work item's function to complete if
+    /// it's already running.
+    pub fn cancel(&self) -> Result<(), CancelError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| CancelError::BadContext)?;
+        self.cancel_locked(&lock)?;
+        Ok(())
+    }
+
+    /// Mark the work item as `Idle`, removing it from the work queue if
+    /// necessary. Returns the previous state.
+    fn cancel_locked(&self, lock: &CpuLockGuard<System>) -> Result<ItemState, CancelError> {
+        let state = self.entry.state.get(lock);
+        match state {
+            ItemState::Idle => return Err(CancelError::NotQueued),
+            ItemState::Delayed => {}
+            ItemState::Queued => self.queue.remove(lock, self.entry.state),
+        }
+        self.entry.state.set(lock, ItemState::Idle);
+        Ok(state)
+    }
+
+    /// Get a flag indicating whether the work item is pending, i.e., it's
+    /// either in the work queue or waiting for a delay to elapse.
+    ///
+    /// This method can be called with CPU Lock active.
+    pub fn is_pending(&self) -> bool {
+        let state = if System::has_cpu_lock() {
+            // Safety: CPU Lock is already active. The guard is not dropped.
+            let lock = ManuallyDrop::new(unsafe { CpuLockGuard::<System>::assume() });
+            self.entry.state.get(&lock)
+        } else {
+            let lock = CpuLockGuard::<System>::new().unwrap();
+            self.entry.state.get(&lock)
+        };
+        state != ItemState::Idle
+    }
+
+    /// Mark the work item as waiting for a delay to elapse.
+    fn start_delay(&self, lock: &CpuLockGuard<System>) -> Result<(), SubmitError> {
+        if self.entry.state.get(lock) != ItemState::Idle {
+            return Err(SubmitError::AlreadyQueued);
+        }
+        self.entry.state.set(lock, ItemState::Delayed);
+        Ok(())
+    }
+
+    /// The timer callback of [`StaticDelayedWorkItem`]. Submits the work item
+    /// if it's still waiting for the delay to elapse.
+    fn submit_delayed(&self) {
+        let lock = CpuLockGuard::<System>::new().unwrap();
+        if self.entry.state.get(&lock) != ItemState::Delayed {
+            // Cancelled
+            return;
+        }
+        let new_state = match self.queue.push(&lock, self.entry) {
+            Ok(()) => ItemState::Queued,
+            // There's no one to report the error to. Drop the submission.
+            Err(_) => ItemState::Idle,
+        };
+        self.entry.state.set(&lock, new_state);
+        drop(lock);
+
+        if new_state == ItemState::Queued {
+            self.queue.wake();
+        }
+    }
+}
+
+/// A statically defined [work item](StaticWorkItem) that can also be
+/// [submitted after a delay](Self::submit_after).
+///
+/// This type is defined by [`WorkItemDefiner::finish_delayed`]. It uses a
+/// dedicated [timer](crate::kernel::Timer), whose callback function queues
+/// the work item when the delay elapses. If the work queue is full at that
+/// point, the submission is dropped.
+pub struct StaticDelayedWorkItem<System: traits::KernelTimer + traits::KernelStatic> {
+    item: StaticWorkItem<System>,
+    timer: StaticTimer<System>,
+    /// The latest request for the state of `timer`
+    timer_request: Hunk<System, CpuLockCell<TimerRequest>>,
+}
+
+/// The state of the timer of a [`StaticDelayedWorkItem`] requested by
+/// [`StaticDelayedWorkItem::submit_after`] or
+/// [`StaticDelayedWorkItem::cancel`].
+///
+/// These methods can't update the work item's state and operate the timer
+/// atomically because the timer can't be operated with CPU Lock active. A
+/// context preempting them in between could make another request, which the
+/// preempted context's timer operation would override. To resolve this, every
+/// request is numbered, and the context making a request keeps applying the
+/// latest one until no newer one is made while it's doing so.
+#[derive(Clone, Copy)]
+struct TimerRequest {
+    /// Incremented by every request.
+    seq: usize,
+    /// `Some(delay)` to start the timer with `delay`, or `None` to stop it.
+    delay: Option<Duration>,
+}
+
+impl Init for TimerRequest {
+    const INIT: Self = Self {
+        seq: 0,
+        delay: None,
+    };
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> Clone for StaticDelayedWorkItem<System> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> Copy for StaticDelayedWorkItem<System> {}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> fmt::Debug
+    for StaticDelayedWorkItem<System>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticDelayedWorkItem")
+            .field("item", &self.item)
+            .field("timer", &self.timer)
+            .finish()
+    }
+}
+
+impl<System: traits::KernelTimer + traits::KernelStatic> StaticDelayedWorkItem<System> {
+    /// Construct a `WorkItemDefiner` to define a work item in [a
+    /// configuration function](crate#static-configuration). Use
+    /// [`WorkItemDefiner::finish_delayed`] to complete the definition.
+    pub const fn define() -> WorkItemDefiner<System> {
+        StaticWorkItem::define()
+    }
+
+    /// Submit the work item to the work queue immediately. See
+    /// [`StaticWorkItem::submit`].
+    #[inline]
+    pub fn submit(&self) -> Result<(), SubmitError> {
+        self.item.submit()
+    }
+
+    /// Submit the work item to the work queue after the specified delay.
+    ///
+    /// This method can be called in a task or interrupt context.
+    pub fn submit_after(&self, delay: Duration) -> Result<(), SubmitError> {
+        if delay.is_negative() {
+            return Err(SubmitError::BadParam);
+        }
+        let lock = CpuLockGuard::<System>::new().map_err(|()| SubmitError::BadContext)?;
+        self.item.start_delay(&lock)?;
+        let request = self.request_timer(&lock, Some(delay));
+        drop(lock);
+
+        self.apply_timer_request(request);
+        Ok(())
+    }
+
+    /// Cancel the submission of the work item. See [`StaticWorkItem::cancel`].
+    pub fn cancel(&self) -> Result<(), CancelError> {
+        let lock = CpuLockGuard::<System>::new().map_err(|()| CancelError::BadContext)?;
+        if self.item.cancel_locked(&lock)? != ItemState::Delayed {
+            // The timer has already fired
+            return Ok(());
+        }
+        let request = self.request_timer(&lock, None);
+        drop(lock);
+
+        self.apply_timer_request(request);
+        Ok(())
+    }
+
+    /// Record a new [`TimerRequest`] and return it.
+    fn request_timer(&self, lock: &CpuLockGuard<System>, delay: Option<Duration>) -> TimerRequest {
+        let request = TimerRequest {
+            seq: self.timer_request.get(lock).seq.wrapping_add(1),
+            delay,
+        };
+        self.timer_request.set(lock, request);
+        request
+    }
+
+    /// Operate the timer as requested by `request`, repeating it for the
+    /// latest request until no newer request is made in the meantime. CPU
+    /// Lock must be inactive.
+    fn apply_timer_request(&self, mut request: TimerRequest) {
+        loop {
+            match request.delay {
+                Some(delay) => {
+                    // A stale expiration of the timer is ignored unless the
+                    // work item is marked as `Delayed`, and the following call
+                    // resets the delay in any case
+                    self.timer
+                        .set_delay(Some(delay))
+                        .expect("failed to set the timer delay");
+                    self.timer.start().expect("failed to start the timer");
+                }
+                None => self.timer.stop().expect("failed to stop the timer"),
+            }
+
+            let lock = CpuLockGuard::<System>::new().unwrap();
+            let latest = self.timer_request.get(&lock);
+            if latest.seq == request.seq {
+                break;
+            }
+
+            // A preempting context made a newer request, and we might have
+            // overridden its timer operation. Apply it again. The timer is
+            // restarted, so its expiration is delayed by the time elapsed
+            // since the request.
+            request = latest;
+        }
+    }
+
+    /// Get a flag indicating whether the work item is pending. See
+    /// [`StaticWorkItem::is_pending`].
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        self.item.is_pending()
+    }
+
+    /// Get the underlying [`StaticWorkItem`].
+    #[inline]
+    pub const fn item(&self) -> StaticWorkItem<System> {
+        self.item
+    }
+}
+
+/// Error type of [`StaticWorkItem::submit`] and
+/// [`StaticDelayedWorkItem::submit_after`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitError {
+    /// CPU Lock is active.
+    BadContext,
+    /// The work item is already pending.
+    AlreadyQueued,
+    /// The work queue is full.
+    QueueFull,
+    /// The delay is negative.
+    BadParam,
+}
+
+/// Error type of [`StaticWorkItem::cancel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelError {
+    /// CPU Lock is active.
+    BadContext,
+    /// The work item is not pending.
+    NotQueued,
+}
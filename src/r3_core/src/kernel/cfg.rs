@@ -1,6 +1,7 @@
 //! Kernel configuration
 use crate::{
     bind::CfgBindRegistry,
+    closure::Closure,
     kernel::{hook, interrupt, raw, raw_cfg},
     utils::{refcell::RefCell, ComptimeVec, ConstAllocator, Frozen, Init, PhantomInvariant},
 };
@@ -15,7 +16,10 @@ pub struct Cfg<'c, C: raw_cfg::CfgBase> {
     raw: &'c mut C,
     st: CfgSt,
     pub(crate) shared: &'c CfgShared,
-    pub(super) startup_hooks: ComptimeVec<hook::CfgStartupHook>,
+    pub(super) startup_hooks: ComptimeVec<hook::CfgHook<Closure>>,
+    pub(super) idle_hooks: ComptimeVec<hook::CfgHook<Closure>>,
+    pub(super) task_switch_hooks: ComptimeVec<hook::CfgHook<hook::TaskSwitchHookFn<C::System>>>,
+    pub(super) task_exit_hooks: ComptimeVec<hook::CfgHook<hook::TaskExitHookFn<C::System>>>,
     pub(super) hunk_pool_len: usize,
     pub(super) hunk_pool_align: usize,
     pub(super) interrupt_lines: ComptimeVec<interrupt::CfgInterruptLineInfo>,
@@ -57,6 +61,9 @@ impl<'c, C: raw_cfg::CfgBase> Cfg<'c, C> {
             st,
             shared,
             startup_hooks: ComptimeVec::new_in(allocator.clone()),
+            idle_hooks: ComptimeVec::new_in(allocator.clone()),
+            task_switch_hooks: ComptimeVec::new_in(allocator.clone()),
+            task_exit_hooks: ComptimeVec::new_in(allocator.clone()),
             hunk_pool_len: 0,
             hunk_pool_align: 1,
             interrupt_lines: ComptimeVec::new_in(allocator.clone()),
@@ -144,9 +151,7 @@ impl<'c, C: raw_cfg::CfgBase> Cfg<'c, C> {
 
         CfgPhase1Data {
             _phantom: Init::INIT,
-            startup_hooks: Frozen::leak_slice(
-                &self.startup_hooks.map(hook::CfgStartupHook::to_attr),
-            ),
+            startup_hooks: Frozen::leak_slice(&self.startup_hooks.map(hook::CfgHook::to_attr)),
             hunk_pool_len: self.hunk_pool_len,
             hunk_pool_align: self.hunk_pool_align,
             interrupt_handlers: Frozen::leak_slice(&self.interrupt_handlers),
@@ -243,7 +248,7 @@ impl<'c, C: raw_cfg::CfgBase> Cfg<'c, C> {
     /// process][1].
     ///
     /// [1]: KernelStatic
-    pub const fn finish_phase3(self) -> CfgPhase3Data<C::System>
+    pub const fn finish_phase3(mut self) -> CfgPhase3Data<C::System>
     where
         // `~const` must appear here due to [ref:impl_block_const_bounds]
         C: ~const raw_cfg::CfgBase,
@@ -269,6 +274,31 @@ impl<'c, C: raw_cfg::CfgBase> Cfg<'c, C> {
             }
         }
 
+        // Register the other hooks in the execution order
+        hook::sort_hooks(&mut self.idle_hooks);
+        hook::sort_hooks(&mut self.task_switch_hooks);
+        hook::sort_hooks(&mut self.task_exit_hooks);
+
+        let mut i = 0;
+        while i < self.idle_hooks.len() {
+            self.raw.idle_hook_define(self.idle_hooks[i].start());
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < self.task_switch_hooks.len() {
+            self.raw
+                .task_switch_hook_define(self.task_switch_hooks[i].start());
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < self.task_exit_hooks.len() {
+            self.raw
+                .task_exit_hook_define(self.task_exit_hooks[i].start());
+            i += 1;
+        }
+
         CfgPhase3Data {
             _phantom: Init::INIT,
         }
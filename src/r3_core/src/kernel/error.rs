@@ -157,6 +157,8 @@ define_result_code! {
         NotOwner = -4,
         /// Resource deadlock would occur.
         WouldDeadlock = -8,
+        /// A cycle of tasks waiting on each other was detected.
+        Deadlock = -12,
         /// A target object is in a state that disallows the operation.
         BadObjectState = -9,
         /// An operation or an object couldn't be enqueued because there are too
@@ -312,15 +314,18 @@ define_error! {
         NoAccess,
         /// CPU Lock is active.
         BadContext,
-        /// The task is already active (not in the Dormant state).
+        /// The task is already active (not in the Dormant state), and the
+        /// number of queued activation requests has reached the limit
+        /// specified by [`TaskDefiner::max_queued_activations`].
         ///
         /// This error code originates from `E_QOVR` defined in the μITRON 4.0
         /// specification. In this specification, the `act_tsk` (activate task)
         /// system service works by enqueueing an activation request. `E_QOVR`
         /// is used to report a condition in which an enqueue count limit has
-        /// been reached. Our kernel doesn't support enqueueing activation
-        /// request (at the moment), so any attempts to activate an
-        /// already-active task will fail.
+        /// been reached. The limit is zero by default, in which case any
+        /// attempts to activate an already-active task will fail.
+        ///
+        /// [`TaskDefiner::max_queued_activations`]: super::task::TaskDefiner::max_queued_activations
         QueueOverflow,
     }
 }
@@ -351,6 +356,23 @@ define_error! {
     }
 }
 
+define_error! {
+    mod set_task_deadline_error {}
+    /// Error type for [`Task::set_deadline`].
+    ///
+    /// [`Task::set_deadline`]: super::task::TaskMethods::set_deadline
+    pub enum SetTaskDeadlineError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// The duration is negative.
+        BadParam,
+        /// The task is in the Dormant state.
+        BadObjectState,
+    }
+}
+
 define_error! {
     mod set_task_priority_error {}
     /// Error type for [`Task::set_priority`].
@@ -372,6 +394,80 @@ define_error! {
     }
 }
 
+define_error! {
+    mod suspend_task_error {}
+    /// Error type for [`Task::suspend`].
+    ///
+    /// [`Task::suspend`]: super::task::TaskMethods::suspend
+    pub enum SuspendTaskError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, the task is the current task and the current
+        /// context is not [waitable], or the task is in the Running state and
+        /// Priority Boost is active.
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        /// The task is in the Dormant state.
+        BadObjectState,
+        /// The task is already suspended.
+        ///
+        /// This error code originates from `E_QOVR` defined in the μITRON 4.0
+        /// specification, in which the `sus_tsk` (suspend task) system service
+        /// increments a suspension count. Our kernel doesn't support nested
+        /// suspension, so any attempts to suspend an already-suspended task
+        /// will fail.
+        QueueOverflow,
+    }
+}
+
+define_error! {
+    mod resume_task_error {}
+    /// Error type for [`Task::resume`].
+    ///
+    /// [`Task::resume`]: super::task::TaskMethods::resume
+    pub enum ResumeTaskError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// The task is not suspended.
+        BadObjectState,
+    }
+}
+
+define_error! {
+    mod terminate_task_error {}
+    /// Error type for [`Task::terminate`].
+    ///
+    /// [`Task::terminate`]: super::task::TaskMethods::terminate
+    pub enum TerminateTaskError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the task is in the Running state.
+        BadContext,
+        /// The task is in the Dormant state.
+        BadObjectState,
+    }
+}
+
+define_error! {
+    mod notify_task_error {}
+    /// Error type for [`Task::notify`].
+    ///
+    /// [`Task::notify`]: super::task::TaskMethods::notify
+    pub enum NotifyTaskError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// The task is in the Dormant state.
+        BadObjectState,
+        /// The notification value couldn't be updated as requested.
+        QueueOverflow,
+    }
+}
+
 define_error! {
     mod get_task_priority_error {}
     /// Error type for [`Task::priority`].
@@ -387,6 +483,32 @@ define_error! {
     }
 }
 
+define_error! {
+    mod get_task_cpu_time_error {}
+    /// Error type for [`Task::cpu_time`].
+    ///
+    /// [`Task::cpu_time`]: super::task::TaskMethods::cpu_time
+    pub enum GetTaskCpuTimeError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+    }
+}
+
+define_error! {
+    mod get_task_stack_high_water_mark_error {}
+    /// Error type for [`Task::stack_high_water_mark`].
+    ///
+    /// [`Task::stack_high_water_mark`]: super::task::TaskMethods::stack_high_water_mark
+    pub enum GetTaskStackHighWaterMarkError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+    }
+}
+
 define_error! {
     mod exit_task_error {}
     /// Error type for [`Kernel::exit_task`].
@@ -438,6 +560,17 @@ define_error! {
     }
 }
 
+define_error! {
+    mod load_snapshot_error {}
+    /// Error type for [`Kernel::load_snapshot`].
+    ///
+    /// [`Kernel::load_snapshot`]: super::Kernel::load_snapshot
+    pub enum LoadSnapshotError {
+        /// CPU Lock is active.
+        BadContext,
+    }
+}
+
 define_error! {
     mod adjust_time_error {}
     /// Error type for [`Kernel::adjust_time`].
@@ -494,6 +627,38 @@ define_error! {
     ///
     /// [`Kernel::park_timeout`]: super::Kernel::park_timeout
     pub enum ParkTimeoutError: WaitTimeoutError {
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+        Timeout,
+        /// The timeout duration is negative, or the deadline is too far in the
+        /// future.
+        BadParam,
+    }
+}
+
+define_error! {
+    mod wait_notification_error {}
+    /// Error type for [`Kernel::wait_notification`].
+    ///
+    /// [`Kernel::wait_notification`]: super::Kernel::wait_notification
+    pub enum WaitNotificationError: WaitError {
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+    }
+}
+
+define_error! {
+    mod wait_notification_timeout_error {}
+    /// Error type for [`Kernel::wait_notification_timeout`].
+    ///
+    /// [`Kernel::wait_notification_timeout`]: super::Kernel::wait_notification_timeout
+    pub enum WaitNotificationTimeoutError: WaitTimeoutError {
         /// CPU Lock is active, or the current context is not [waitable].
         ///
         /// [waitable]: crate#contexts
@@ -505,6 +670,45 @@ define_error! {
     }
 }
 
+define_error! {
+    mod wait_any_error {}
+    /// Error type for [`Kernel::wait_any`].
+    ///
+    /// [`Kernel::wait_any`]: super::Kernel::wait_any
+    pub enum WaitAnyError: WaitError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+        /// No wait conditions were specified, or the same object was specified
+        /// more than once.
+        BadParam,
+    }
+}
+
+define_error! {
+    mod wait_any_timeout_error {}
+    /// Error type for [`Kernel::wait_any_timeout`].
+    ///
+    /// [`Kernel::wait_any_timeout`]: super::Kernel::wait_any_timeout
+    pub enum WaitAnyTimeoutError: WaitTimeoutError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+        Timeout,
+        /// The timeout duration is negative, no wait conditions were specified,
+        /// or the same object was specified more than once.
+        BadParam,
+    }
+}
+
 define_error! {
     mod unpark_error {}
     /// Error type for [`Task::unpark`].
@@ -548,7 +752,7 @@ define_error! {
         /// [waitable]: crate#contexts
         BadContext,
         Interrupted,
-        /// The duration is negative.
+        /// The duration is negative, or the deadline is too far in the future.
         BadParam,
     }
 }
@@ -623,7 +827,8 @@ define_error! {
         BadContext,
         Interrupted,
         Timeout,
-        /// The timeout duration is negative.
+        /// The timeout duration is negative, or the deadline is too far in the
+        /// future.
         BadParam,
     }
 }
@@ -713,7 +918,178 @@ define_error! {
         BadContext,
         Interrupted,
         Timeout,
-        /// The timeout duration is negative.
+        /// The timeout duration is negative, or the deadline is too far in the
+        /// future.
+        BadParam,
+    }
+}
+
+define_error! {
+    mod send_message_queue_error {}
+    /// Error type for [`MessageQueueRef::send`].
+    ///
+    /// [`MessageQueueRef::send`]: super::message_queue::MessageQueueRef::send
+    pub enum SendMessageQueueError: WaitError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+    }
+}
+
+define_error! {
+    mod send_message_queue_timeout_error {}
+    /// Error type for [`MessageQueueRef::send_timeout`].
+    ///
+    /// [`MessageQueueRef::send_timeout`]: super::message_queue::MessageQueueRef::send_timeout
+    pub enum SendMessageQueueTimeoutError: WaitTimeoutError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+        Timeout,
+        /// The timeout duration is negative, or the deadline is too far in the
+        /// future.
+        BadParam,
+    }
+}
+
+define_error! {
+    mod try_send_message_queue_error {}
+    /// Error type for [`MessageQueueRef::try_send`].
+    ///
+    /// [`MessageQueueRef::try_send`]: super::message_queue::MessageQueueRef::try_send
+    pub enum TrySendMessageQueueError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// The message queue is full, and there is no task waiting to receive
+        /// a message.
+        Timeout,
+    }
+}
+
+define_error! {
+    mod receive_message_queue_error {}
+    /// Error type for [`MessageQueueRef::receive`].
+    ///
+    /// [`MessageQueueRef::receive`]: super::message_queue::MessageQueueRef::receive
+    pub enum ReceiveMessageQueueError: WaitError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+    }
+}
+
+define_error! {
+    mod receive_message_queue_timeout_error {}
+    /// Error type for [`MessageQueueRef::receive_timeout`].
+    ///
+    /// [`MessageQueueRef::receive_timeout`]: super::message_queue::MessageQueueRef::receive_timeout
+    pub enum ReceiveMessageQueueTimeoutError: WaitTimeoutError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+        Timeout,
+        /// The timeout duration is negative, or the deadline is too far in the
+        /// future.
+        BadParam,
+    }
+}
+
+define_error! {
+    mod try_receive_message_queue_error {}
+    /// Error type for [`MessageQueueRef::try_receive`].
+    ///
+    /// [`MessageQueueRef::try_receive`]: super::message_queue::MessageQueueRef::try_receive
+    pub enum TryReceiveMessageQueueError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// The message queue is empty, and there is no task waiting to send a
+        /// message.
+        Timeout,
+    }
+}
+
+define_error! {
+    mod get_memory_pool_error {}
+    /// Error type for [`StaticMemoryPool::get`].
+    ///
+    /// [`StaticMemoryPool::get`]: super::memory_pool::StaticMemoryPool::get
+    pub enum GetMemoryPoolError: WaitError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+    }
+}
+
+define_error! {
+    mod get_memory_pool_timeout_error {}
+    /// Error type for [`StaticMemoryPool::get_timeout`].
+    ///
+    /// [`StaticMemoryPool::get_timeout`]: super::memory_pool::StaticMemoryPool::get_timeout
+    pub enum GetMemoryPoolTimeoutError: WaitTimeoutError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active, or the current context is not [waitable].
+        ///
+        /// [waitable]: crate#contexts
+        BadContext,
+        Interrupted,
+        Timeout,
+        /// The timeout duration is negative, or the deadline is too far in the
+        /// future.
+        BadParam,
+    }
+}
+
+define_error! {
+    mod try_get_memory_pool_error {}
+    /// Error type for [`StaticMemoryPool::try_get`].
+    ///
+    /// [`StaticMemoryPool::try_get`]: super::memory_pool::StaticMemoryPool::try_get
+    pub enum TryGetMemoryPoolError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// All blocks are in use.
+        Timeout,
+    }
+}
+
+define_error! {
+    mod release_memory_pool_error {}
+    /// Error type for [`raw::KernelMemoryPool::raw_memory_pool_release`].
+    ///
+    /// [`raw::KernelMemoryPool::raw_memory_pool_release`]: super::raw::KernelMemoryPool::raw_memory_pool_release
+    pub enum ReleaseMemoryPoolError {
+        /// Invalid object access.
+        NoAccess,
+        /// CPU Lock is active.
+        BadContext,
+        /// The specified block is out of range or is not in use.
         BadParam,
     }
 }
@@ -794,6 +1170,10 @@ define_error! {
         Interrupted,
         /// The current task already owns the mutex.
         WouldDeadlock,
+        /// The mutex's owner is waiting, directly or through a chain of mutex
+        /// owners, on a mutex held by the current task. Only kernels that
+        /// detect deadlocks return this error.
+        Deadlock,
         /// The mutex was created with the protocol attribute having the value
         /// [`Ceiling`] and the current task's priority is higher than the
         /// mutex's priority ceiling.
@@ -823,9 +1203,14 @@ define_error! {
         Timeout,
         /// The current task already owns the mutex.
         WouldDeadlock,
-        /// The timeout duration is negative, or the mutex was created with the
-        /// protocol attribute having the value [`Ceiling`] and the current
-        /// task's priority is higher than the mutex's priority ceiling.
+        /// The mutex's owner is waiting, directly or through a chain of mutex
+        /// owners, on a mutex held by the current task. Only kernels that
+        /// detect deadlocks return this error.
+        Deadlock,
+        /// The timeout duration is negative, the deadline is too far in the
+        /// future, or the mutex was created with the protocol attribute having
+        /// the value [`Ceiling`] and the current task's priority is higher than
+        /// the mutex's priority ceiling.
         ///
         /// [`Ceiling`]: crate::kernel::MutexProtocol::Ceiling
         BadParam,
@@ -5,7 +5,7 @@ use super::{
     raw, raw_cfg, Cfg, GetEventGroupError, PollEventGroupError, UpdateEventGroupError,
     WaitEventGroupError, WaitEventGroupTimeoutError,
 };
-use crate::time::Duration;
+use crate::time::{Duration, Time};
 
 pub use raw::{EventGroupBits, EventGroupWaitFlags};
 
@@ -116,6 +116,32 @@ pub trait EventGroupMethods: EventGroupHandle {
         }
     }
 
+    /// [`wait`](Self::wait) with an absolute deadline. See
+    /// [`Kernel::sleep_until`][1] for how the deadline is interpreted.
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    #[inline]
+    fn wait_until(
+        &self,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+        deadline: Time,
+    ) -> Result<EventGroupBits, WaitEventGroupTimeoutError>
+    where
+        Self::System: raw::KernelWaitUntil,
+    {
+        // Safety: `EventGroup` represents a permission to access the
+        //         referenced object.
+        unsafe {
+            <Self::System as raw::KernelWaitUntil>::raw_event_group_wait_until(
+                self.id(),
+                bits,
+                flags,
+                deadline,
+            )
+        }
+    }
+
     /// Non-blocking version of [`wait`](Self::wait). Returns immediately with
     /// [`PollEventGroupError::Timeout`] if the unblocking condition is not
     #[inline]
@@ -3,8 +3,10 @@ use core::fmt;
 
 use crate::{
     kernel::{
-        raw, AdjustTimeError, BoostPriorityError, CpuLockError, ExitTaskError, ParkError,
-        ParkTimeoutError, SleepError, TimeError,
+        raw, AdjustTimeError, BoostPriorityError, CpuLockError, ExitTaskError, LoadSnapshot,
+        LoadSnapshotError, NotificationValue, ParkError, ParkTimeoutError, SleepError, TimeError,
+        WaitAny, WaitAnyError, WaitAnyTimeoutError, WaitNotificationError,
+        WaitNotificationTimeoutError,
     },
     time::{Duration, Time},
 };
@@ -327,6 +329,11 @@ pub trait Kernel: private::Sealed {
     /// The kernel makes an implicit call to this function when a task entry
     /// point function returns.
     ///
+    /// If the task has a [queued activation request], the task is activated
+    /// again immediately, consuming the request.
+    ///
+    /// [queued activation request]: crate::kernel::task::TaskMethods::activate
+    ///
     /// # Safety
     ///
     /// On a successful call, this function destroys the current task's stack
@@ -360,6 +367,139 @@ pub trait Kernel: private::Sealed {
 
     /// Block the current task for the specified duration.
     fn sleep(duration: Duration) -> Result<(), SleepError>;
+
+    /// Block the current task until the system time reaches `deadline`.
+    /// Returns immediately if `deadline` is in the past. Returns
+    /// `Err(BadParam)` if `deadline` is further in the future than
+    /// [`Duration::MAX`].
+    ///
+    /// Unlike repeatedly calling [`sleep`](Self::sleep) with a fixed period,
+    /// repeatedly calling this method with deadlines incremented by a fixed
+    /// period does not accumulate drift.
+    ///
+    /// The deadline is converted to a relative timeout when this method is
+    /// called. Therefore, the wake-up time is unaffected by subsequent calls
+    /// to [`set_time`](Self::set_time), which only changes the system time.
+    /// On the other hand, [`adjust_time`](Self::adjust_time) moves both the
+    /// system time and all outstanding timeouts, so the task still wakes up
+    /// when the adjusted system time reaches `deadline`.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `vTaskDelayUntil` (FreeRTOS),
+    /// > `clock_nanosleep` with `TIMER_ABSTIME` (POSIX)
+    ///
+    /// [a non-waitable context]: crate#contexts
+    fn sleep_until(deadline: Time) -> Result<(), SleepError>
+    where
+        Self: raw::KernelWaitUntil;
+
+    /// [`park`](Self::park) with an absolute deadline. See
+    /// [`sleep_until`](Self::sleep_until) for how the deadline is interpreted.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// [a non-waitable context]: crate#contexts
+    fn park_until(deadline: Time) -> Result<(), ParkTimeoutError>
+    where
+        Self: raw::KernelWaitUntil;
+
+    /// Put the current task into the Waiting state until the task receives a
+    /// notification from [`Task::notify`][], and return the notification
+    /// value.
+    ///
+    /// If the task doesn't have a pending notification, the bits set in
+    /// `clear_on_entry` are cleared from the notification value before
+    /// waiting. When this method returns successfully, the pending flag is
+    /// cleared, and so are the bits set in `clear_on_exit` (after the
+    /// notification value to be returned is read).
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `xTaskNotifyWait` (FreeRTOS)
+    ///
+    /// [`Task::notify`]: crate::kernel::task::TaskMethods::notify
+    /// [a non-waitable context]: crate#contexts
+    fn wait_notification(
+        clear_on_entry: NotificationValue,
+        clear_on_exit: NotificationValue,
+    ) -> Result<NotificationValue, WaitNotificationError>
+    where
+        Self: raw::KernelTaskNotify;
+
+    /// [`wait_notification`](Self::wait_notification) with timeout.
+    ///
+    /// If the operation times out, the bits set in `clear_on_exit` aren't
+    /// cleared.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// [a non-waitable context]: crate#contexts
+    fn wait_notification_timeout(
+        clear_on_entry: NotificationValue,
+        clear_on_exit: NotificationValue,
+        timeout: Duration,
+    ) -> Result<NotificationValue, WaitNotificationTimeoutError>
+    where
+        Self: raw::KernelTaskNotify;
+
+    /// Put the current task into the Waiting state until any of the specified
+    /// wait conditions is satisfied, and return the index of the condition
+    /// that completed the call.
+    ///
+    /// If some conditions are already satisfied on entry, the first one in
+    /// `conditions` completes the call without blocking. Otherwise, the task
+    /// is enqueued to the wait queues of all specified objects and woken up by
+    /// whichever object satisfies the task's condition first. Only the
+    /// completing condition takes effect; for example, permits are not taken
+    /// from the other semaphores.
+    ///
+    /// Returns `Err(BadParam)` if `conditions` is empty or refers to the same
+    /// object more than once.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `WaitForMultipleObjects`
+    /// > (Win32), `xQueueSelectFromSet` (FreeRTOS)
+    ///
+    /// [a non-waitable context]: crate#contexts
+    fn wait_any<const N: usize>(conditions: [WaitAny<'_, Self>; N]) -> Result<usize, WaitAnyError>
+    where
+        Self: raw::KernelWaitAny;
+
+    /// [`wait_any`](Self::wait_any) with timeout.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// [a non-waitable context]: crate#contexts
+    fn wait_any_timeout<const N: usize>(
+        conditions: [WaitAny<'_, Self>; N],
+        timeout: Duration,
+    ) -> Result<usize, WaitAnyTimeoutError>
+    where
+        Self: raw::KernelWaitAny;
+
+    /// Get the total processor time spent by tasks and the processor idle time
+    /// accumulated since boot.
+    ///
+    /// The time spent by interrupt handlers is attributed to whichever task
+    /// (or the idle state) they interrupted. See [`LoadSnapshot`] for how to
+    /// calculate the processor load from snapshots.
+    fn load_snapshot() -> Result<LoadSnapshot, LoadSnapshotError>
+    where
+        Self: raw::KernelRuntimeStats;
 }
 
 mod private {
@@ -474,6 +614,82 @@ impl<T: raw::KernelBase> Kernel for T {
     fn sleep(duration: Duration) -> Result<(), SleepError> {
         <T as raw::KernelBase>::raw_sleep(duration)
     }
+
+    #[inline]
+    fn sleep_until(deadline: Time) -> Result<(), SleepError>
+    where
+        Self: raw::KernelWaitUntil,
+    {
+        <T as raw::KernelWaitUntil>::raw_sleep_until(deadline)
+    }
+
+    #[inline]
+    fn park_until(deadline: Time) -> Result<(), ParkTimeoutError>
+    where
+        Self: raw::KernelWaitUntil,
+    {
+        <T as raw::KernelWaitUntil>::raw_park_until(deadline)
+    }
+
+    #[inline]
+    fn wait_notification(
+        clear_on_entry: NotificationValue,
+        clear_on_exit: NotificationValue,
+    ) -> Result<NotificationValue, WaitNotificationError>
+    where
+        Self: raw::KernelTaskNotify,
+    {
+        <T as raw::KernelTaskNotify>::raw_wait_notification(clear_on_entry, clear_on_exit)
+    }
+
+    #[inline]
+    fn wait_notification_timeout(
+        clear_on_entry: NotificationValue,
+        clear_on_exit: NotificationValue,
+        timeout: Duration,
+    ) -> Result<NotificationValue, WaitNotificationTimeoutError>
+    where
+        Self: raw::KernelTaskNotify,
+    {
+        <T as raw::KernelTaskNotify>::raw_wait_notification_timeout(
+            clear_on_entry,
+            clear_on_exit,
+            timeout,
+        )
+    }
+
+    #[inline]
+    fn wait_any<const N: usize>(conditions: [WaitAny<'_, Self>; N]) -> Result<usize, WaitAnyError>
+    where
+        Self: raw::KernelWaitAny,
+    {
+        let specs = conditions.map(|c| c.raw());
+        // Safety: `WaitAny` borrows the objects and the output storage for
+        //         the duration of this call.
+        unsafe { <T as raw::KernelWaitAny>::raw_wait_any(&specs) }
+    }
+
+    #[inline]
+    fn wait_any_timeout<const N: usize>(
+        conditions: [WaitAny<'_, Self>; N],
+        timeout: Duration,
+    ) -> Result<usize, WaitAnyTimeoutError>
+    where
+        Self: raw::KernelWaitAny,
+    {
+        let specs = conditions.map(|c| c.raw());
+        // Safety: `WaitAny` borrows the objects and the output storage for
+        //         the duration of this call.
+        unsafe { <T as raw::KernelWaitAny>::raw_wait_any_timeout(&specs, timeout) }
+    }
+
+    #[inline]
+    fn load_snapshot() -> Result<LoadSnapshot, LoadSnapshotError>
+    where
+        Self: raw::KernelRuntimeStats,
+    {
+        <T as raw::KernelRuntimeStats>::raw_load_snapshot()
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -1,12 +1,12 @@
 //! Hooks
-use super::{raw, raw_cfg, Cfg};
+use core::marker::Destruct;
+
+use super::{raw, raw_cfg, Cfg, StaticTask};
 use crate::{
     closure::{Closure, IntoClosureConst},
     utils::{slice_sort_unstable_by, ComptimeVec, Init, PhantomInvariant},
 };
 
-// TODO: Other types of hooks
-
 /// Represents a registered startup hook in a system.
 ///
 /// There are no operations defined for startup hooks, so this type
@@ -105,7 +105,7 @@ impl<System: raw::KernelBase> StartupHookDefiner<System> {
 
         let startup_hooks = &mut cfg.startup_hooks;
         let order = startup_hooks.len();
-        startup_hooks.push(CfgStartupHook {
+        startup_hooks.push(CfgHook {
             start: self.start.expect("`start` is not specified"),
             priority: self.priority,
             order,
@@ -115,26 +115,290 @@ impl<System: raw::KernelBase> StartupHookDefiner<System> {
     }
 }
 
+/// Represents a registered idle hook in a system.
+///
+/// There are no operations defined for idle hooks, so this type
+/// is only used for static configuration.
+///
+/// Idle hooks execute with [CPU Lock][] active when the kernel finds no task
+/// to run, right before the port puts the processor into an idle state (e.g.,
+/// by executing a WFI instruction). An idle hook may execute more than once
+/// while the system stays idle, e.g., every time an interrupt handler makes
+/// the kernel re-evaluate the scheduling decision.
+///
+/// [CPU Lock]: crate#system-states
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** `vApplicationIdleHook`
+/// > (FreeRTOS), idle function (TI-RTOS).
+///
+#[doc = include_str!("../common.md")]
+pub struct IdleHook<System: raw::KernelBase>(PhantomInvariant<System>);
+
+impl<System: raw::KernelBase> IdleHook<System> {
+    /// Construct an `IdleHookDefiner` to register an idle hook in
+    /// [a configuration function](crate#static-configuration).
+    pub const fn define() -> IdleHookDefiner<System> {
+        IdleHookDefiner::new()
+    }
+
+    const fn new() -> Self {
+        Self(Init::INIT)
+    }
+}
+
+/// The definer (static builder) for [`IdleHook`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct IdleHookDefiner<System> {
+    _phantom: PhantomInvariant<System>,
+    start: Option<Closure>,
+    priority: i32,
+}
+
+impl<System: raw::KernelBase> IdleHookDefiner<System> {
+    const fn new() -> Self {
+        Self {
+            _phantom: Init::INIT,
+            start: None,
+            priority: 0,
+        }
+    }
+
+    /// \[**Required**\] Specify the entry point.
+    pub const fn start<C: ~const IntoClosureConst>(self, start: C) -> Self {
+        Self {
+            start: Some(start.into_closure_const()),
+            ..self
+        }
+    }
+
+    /// Specify the priority. Defaults to `0` when unspecified.
+    ///
+    /// Idle hooks will execute in the ascending order of priority. Idle hooks
+    /// with identical priority values will execute in the registration order.
+    pub const fn priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Complete the registration of an idle hook, returning an `IdleHook`
+    /// object.
+    pub const fn finish<C: ~const raw_cfg::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> IdleHook<System> {
+        let idle_hooks = &mut cfg.idle_hooks;
+        let order = idle_hooks.len();
+        idle_hooks.push(CfgHook {
+            start: self.start.expect("`start` is not specified"),
+            priority: self.priority,
+            order,
+        });
+
+        IdleHook::new()
+    }
+}
+
+/// The signature of a [task switch hook](TaskSwitchHook).
+pub type TaskSwitchHookFn<System> = fn(Option<StaticTask<System>>, Option<StaticTask<System>>);
+
+/// Represents a registered task switch hook in a system.
+///
+/// There are no operations defined for task switch hooks, so this type
+/// is only used for static configuration.
+///
+/// Task switch hooks execute with [CPU Lock][] active every time the running
+/// task changes. A task switch hook receives the task that has been running
+/// and the task that is going to run, in this order. `None` represents the
+/// absence of a running task, i.e., the system is or was idle. If the
+/// previously running task has just [exited][1], the former is that task. Both
+/// can be the same task if it has exited and been activated again.
+///
+/// Task switch hooks execute in the middle of a context switch. They should
+/// finish quickly and not call any kernel services.
+///
+/// [CPU Lock]: crate#system-states
+/// [1]: TaskExitHook
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** `traceTASK_SWITCHED_IN` (FreeRTOS),
+/// > task switch hook (TI-RTOS).
+///
+#[doc = include_str!("../common.md")]
+pub struct TaskSwitchHook<System: raw::KernelBase>(PhantomInvariant<System>);
+
+impl<System: raw::KernelBase> TaskSwitchHook<System> {
+    /// Construct a `TaskSwitchHookDefiner` to register a task switch hook in
+    /// [a configuration function](crate#static-configuration).
+    pub const fn define() -> TaskSwitchHookDefiner<System> {
+        TaskSwitchHookDefiner::new()
+    }
+
+    const fn new() -> Self {
+        Self(Init::INIT)
+    }
+}
+
+/// The definer (static builder) for [`TaskSwitchHook`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct TaskSwitchHookDefiner<System: raw::KernelBase> {
+    start: Option<TaskSwitchHookFn<System>>,
+    priority: i32,
+}
+
+impl<System: raw::KernelBase> TaskSwitchHookDefiner<System> {
+    const fn new() -> Self {
+        Self {
+            start: None,
+            priority: 0,
+        }
+    }
+
+    /// \[**Required**\] Specify the entry point.
+    pub const fn start(self, start: TaskSwitchHookFn<System>) -> Self {
+        Self {
+            start: Some(start),
+            ..self
+        }
+    }
+
+    /// Specify the priority. Defaults to `0` when unspecified.
+    ///
+    /// Task switch hooks will execute in the ascending order of priority.
+    /// Task switch hooks with identical priority values will execute in the
+    /// registration order.
+    pub const fn priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Complete the registration of a task switch hook, returning a
+    /// `TaskSwitchHook` object.
+    pub const fn finish<C: ~const raw_cfg::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> TaskSwitchHook<System> {
+        let task_switch_hooks = &mut cfg.task_switch_hooks;
+        let order = task_switch_hooks.len();
+        task_switch_hooks.push(CfgHook {
+            start: self.start.expect("`start` is not specified"),
+            priority: self.priority,
+            order,
+        });
+
+        TaskSwitchHook::new()
+    }
+}
+
+/// The signature of a [task exit hook](TaskExitHook).
+pub type TaskExitHookFn<System> = fn(StaticTask<System>);
+
+/// Represents a registered task exit hook in a system.
+///
+/// There are no operations defined for task exit hooks, so this type
+/// is only used for static configuration.
+///
+/// Task exit hooks execute with [CPU Lock][] active when a task exits by
+/// returning from its entry point or calling [`exit_task`][1], or when it's
+/// [terminated][2]. A task exit hook receives the exiting task and executes
+/// before the task releases the mutexes it holds. It executes in the context
+/// of the exiting task, or, if the task is being terminated, in the context
+/// that terminated it.
+///
+/// [CPU Lock]: crate#system-states
+/// [1]: crate::kernel::Kernel::exit_task
+/// [2]: crate::kernel::task::TaskMethods::terminate
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** task exit hook (TI-RTOS),
+/// > `TX_THREAD_COMPLETED` notification (ThreadX).
+///
+#[doc = include_str!("../common.md")]
+pub struct TaskExitHook<System: raw::KernelBase>(PhantomInvariant<System>);
+
+impl<System: raw::KernelBase> TaskExitHook<System> {
+    /// Construct a `TaskExitHookDefiner` to register a task exit hook in
+    /// [a configuration function](crate#static-configuration).
+    pub const fn define() -> TaskExitHookDefiner<System> {
+        TaskExitHookDefiner::new()
+    }
+
+    const fn new() -> Self {
+        Self(Init::INIT)
+    }
+}
+
+/// The definer (static builder) for [`TaskExitHook`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct TaskExitHookDefiner<System: raw::KernelBase> {
+    start: Option<TaskExitHookFn<System>>,
+    priority: i32,
+}
+
+impl<System: raw::KernelBase> TaskExitHookDefiner<System> {
+    const fn new() -> Self {
+        Self {
+            start: None,
+            priority: 0,
+        }
+    }
+
+    /// \[**Required**\] Specify the entry point.
+    pub const fn start(self, start: TaskExitHookFn<System>) -> Self {
+        Self {
+            start: Some(start),
+            ..self
+        }
+    }
+
+    /// Specify the priority. Defaults to `0` when unspecified.
+    ///
+    /// Task exit hooks will execute in the ascending order of priority.
+    /// Task exit hooks with identical priority values will execute in the
+    /// registration order.
+    pub const fn priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Complete the registration of a task exit hook, returning a
+    /// `TaskExitHook` object.
+    pub const fn finish<C: ~const raw_cfg::CfgBase<System = System>>(
+        self,
+        cfg: &mut Cfg<C>,
+    ) -> TaskExitHook<System> {
+        let task_exit_hooks = &mut cfg.task_exit_hooks;
+        let order = task_exit_hooks.len();
+        task_exit_hooks.push(CfgHook {
+            start: self.start.expect("`start` is not specified"),
+            priority: self.priority,
+            order,
+        });
+
+        TaskExitHook::new()
+    }
+}
+
+/// A hook registered through one of the definers in this module.
 #[derive(Debug, Clone, Copy)]
-pub(crate) struct CfgStartupHook {
-    start: Closure,
+pub(crate) struct CfgHook<Start> {
+    start: Start,
     priority: i32,
     /// The registration order.
     order: usize,
 }
 
-/// Sort startup hooks by (priority, order).
-pub(crate) const fn sort_hooks(startup_hooks: &mut ComptimeVec<CfgStartupHook>) {
-    slice_sort_unstable_by(
-        startup_hooks.as_mut_slice(),
-        closure!(|x: &CfgStartupHook, y: &CfgStartupHook| -> bool {
-            if x.priority != y.priority {
-                x.priority < y.priority
-            } else {
-                x.order < y.order
-            }
-        }),
-    );
+/// Sort hooks by (priority, order).
+pub(crate) const fn sort_hooks<Start: ~const Destruct>(hooks: &mut ComptimeVec<CfgHook<Start>>) {
+    slice_sort_unstable_by(hooks.as_mut_slice(), hook_precedes::<Start>);
+}
+
+const fn hook_precedes<Start>(x: &CfgHook<Start>, y: &CfgHook<Start>) -> bool {
+    if x.priority != y.priority {
+        x.priority < y.priority
+    } else {
+        x.order < y.order
+    }
 }
 
 /// A startup hook.
@@ -155,9 +419,15 @@ impl Init for StartupHookAttr {
     };
 }
 
-impl CfgStartupHook {
+impl CfgHook<Closure> {
     #[allow(clippy::wrong_self_convention)]
     pub const fn to_attr(&self) -> StartupHookAttr {
         StartupHookAttr { start: self.start }
     }
 }
+
+impl<Start: Copy> CfgHook<Start> {
+    pub const fn start(&self) -> Start {
+        self.start
+    }
+}
@@ -0,0 +1,409 @@
+//! Memory pools
+use core::{
+    cell::UnsafeCell,
+    fmt, hash,
+    ops::{Deref, DerefMut},
+};
+
+use super::{
+    cfg, raw, raw_cfg, Cfg, GetMemoryPoolError, GetMemoryPoolTimeoutError, QueueOrder,
+    ReleaseMemoryPoolError, TryGetMemoryPoolError,
+};
+use crate::{
+    hunk::Hunk,
+    time::{Duration, Time},
+    utils::{Init, PhantomInvariant},
+};
+
+// ----------------------------------------------------------------------------
+
+/// Represents a single statically defined fixed-block memory pool in a system.
+///
+/// A memory pool manages `N` blocks, each of which can store a value of type
+/// `T`. Tasks and interrupt handlers can [get][1] a block, which is handed out
+/// in the form of [`MemoryPoolBlock`], an owning handle that returns the block
+/// to the memory pool when dropped. A task trying to get a block blocks while
+/// all blocks are in use.
+///
+/// The blocks are allocated in a [hunk][2] and initialized with [`Init::INIT`]
+/// at boot time. A block retains the value left by its previous owner when it's
+/// reused.
+///
+/// Tasks waiting for a block are queued in a wait queue, which is sorted
+/// according to the [`QueueOrder`] specified at definition time.
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** fixed-sized memory pool
+/// > (μITRON4.0), partition (RTEMS), memory pool (RT-Thread)
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Rationale:** Unlike the other kernel objects, a memory pool is not
+/// > represented by a [`raw::KernelMemoryPool::RawMemoryPoolId`] alone because
+/// > the storage for the blocks is not managed by the kernel. This allows the
+/// > blocks to be safely typed without the kernel knowing `T`.
+///
+/// [1]: Self::get
+/// [2]: crate::hunk::Hunk
+#[doc = include_str!("../common.md")]
+pub struct StaticMemoryPool<System, T, const N: usize>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+{
+    id: System::RawMemoryPoolId,
+    blocks: Hunk<System, [BlockCell<T>; N]>,
+}
+
+/// The storage for a single block.
+#[repr(transparent)]
+struct BlockCell<T>(UnsafeCell<T>);
+
+// Safety: The contents are only accessed by the current owner of the block,
+// which is represented by `MemoryPoolBlock`
+unsafe impl<T: Send> Sync for BlockCell<T> {}
+
+impl<T: Init> Init for BlockCell<T> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self(Init::INIT);
+}
+
+impl<System, T, const N: usize> StaticMemoryPool<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    /// Construct a `MemoryPoolDefiner` to define a memory pool in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> MemoryPoolDefiner<System, T, N> {
+        MemoryPoolDefiner::new()
+    }
+
+    /// Get the raw `RawMemoryPoolId` value representing this memory pool.
+    #[inline]
+    pub const fn id(&self) -> System::RawMemoryPoolId {
+        self.id
+    }
+
+    /// Get a block, potentially blocking the calling thread until one is
+    /// returned to the memory pool.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// [a non-waitable context]: crate#contexts
+    #[inline]
+    pub fn get(&self) -> Result<MemoryPoolBlock<System, T, N>, GetMemoryPoolError> {
+        // Safety: `StaticMemoryPool` represents a permission to access the
+        //         referenced object.
+        let index = unsafe { <System as raw::KernelMemoryPool>::raw_memory_pool_get(self.id)? };
+        // Safety: The kernel gave us the ownership of the block
+        Ok(unsafe { MemoryPoolBlock::from_raw(*self, index) })
+    }
+
+    /// [`get`](Self::get) with timeout.
+    #[inline]
+    pub fn get_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<MemoryPoolBlock<System, T, N>, GetMemoryPoolTimeoutError> {
+        // Safety: `StaticMemoryPool` represents a permission to access the
+        //         referenced object.
+        let index = unsafe {
+            <System as raw::KernelMemoryPool>::raw_memory_pool_get_timeout(self.id, timeout)?
+        };
+        // Safety: The kernel gave us the ownership of the block
+        Ok(unsafe { MemoryPoolBlock::from_raw(*self, index) })
+    }
+
+    /// [`get`](Self::get) with an absolute deadline. See
+    /// [`Kernel::sleep_until`][1] for how the deadline is interpreted.
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    #[inline]
+    pub fn get_until(
+        &self,
+        deadline: Time,
+    ) -> Result<MemoryPoolBlock<System, T, N>, GetMemoryPoolTimeoutError>
+    where
+        System: raw::KernelWaitUntil,
+    {
+        // Safety: `StaticMemoryPool` represents a permission to access the
+        //         referenced object.
+        let index = unsafe {
+            <System as raw::KernelWaitUntil>::raw_memory_pool_get_until(self.id, deadline)?
+        };
+        // Safety: The kernel gave us the ownership of the block
+        Ok(unsafe { MemoryPoolBlock::from_raw(*self, index) })
+    }
+
+    /// Non-blocking version of [`get`](Self::get). Returns immediately with
+    /// [`TryGetMemoryPoolError::Timeout`] if all blocks are in use.
+    ///
+    /// This method can be called from an interrupt context.
+    #[inline]
+    pub fn try_get(&self) -> Result<MemoryPoolBlock<System, T, N>, TryGetMemoryPoolError> {
+        // Safety: `StaticMemoryPool` represents a permission to access the
+        //         referenced object.
+        let index = unsafe { <System as raw::KernelMemoryPool>::raw_memory_pool_try_get(self.id)? };
+        // Safety: The kernel gave us the ownership of the block
+        Ok(unsafe { MemoryPoolBlock::from_raw(*self, index) })
+    }
+}
+
+impl<System, T, const N: usize> Clone for StaticMemoryPool<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System, T, const N: usize> Copy for StaticMemoryPool<System, T, N> where
+    System: raw::KernelMemoryPool + cfg::KernelStatic
+{
+}
+
+impl<System, T, const N: usize> PartialEq for StaticMemoryPool<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<System, T, const N: usize> Eq for StaticMemoryPool<System, T, N> where
+    System: raw::KernelMemoryPool + cfg::KernelStatic
+{
+}
+
+impl<System, T, const N: usize> hash::Hash for StaticMemoryPool<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        hash::Hash::hash(&self.id, state);
+    }
+}
+
+impl<System, T, const N: usize> fmt::Debug for StaticMemoryPool<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("MemoryPool").field(&self.id).finish()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// An owning handle of a block allocated from a [`StaticMemoryPool`]. The block
+/// is returned to the memory pool when this handle is dropped.
+///
+/// # Panics
+///
+/// Dropping a `MemoryPoolBlock` panics if the block can't be returned because
+/// CPU Lock is active.
+#[must_use = "the block is returned to the memory pool immediately if unused"]
+pub struct MemoryPoolBlock<System, T, const N: usize>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    pool: StaticMemoryPool<System, T, N>,
+    index: usize,
+}
+
+// Safety: `MemoryPoolBlock` acts like `&mut T`
+unsafe impl<System, T, const N: usize> Send for MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+}
+unsafe impl<System, T, const N: usize> Sync for MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + Sync + 'static,
+{
+}
+
+impl<System, T, const N: usize> MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    /// Get the memory pool from which this block was allocated.
+    #[inline]
+    pub fn pool(this: &Self) -> StaticMemoryPool<System, T, N> {
+        this.pool
+    }
+
+    /// Get the index of this block in the memory pool.
+    #[inline]
+    pub fn index(this: &Self) -> usize {
+        this.index
+    }
+
+    /// Consume the handle, returning the index of the block without returning
+    /// the block to the memory pool.
+    ///
+    /// The returned index can be converted back to a handle by
+    /// [`from_raw`](Self::from_raw). This is useful for passing the ownership
+    /// of a block through a channel that can only carry `Copy` values, such as
+    /// a [message queue](crate::kernel::StaticMessageQueue).
+    #[inline]
+    pub fn into_raw(this: Self) -> usize {
+        let index = this.index;
+        core::mem::forget(this);
+        index
+    }
+
+    /// Construct a `MemoryPoolBlock` from a block index.
+    ///
+    /// # Safety
+    ///
+    /// The caller must be the owner of the specified block. This is the case if
+    /// `index` was obtained by [`into_raw`](Self::into_raw) on a handle of the
+    /// same memory pool, and no other handle has been created from it since.
+    #[inline]
+    pub unsafe fn from_raw(pool: StaticMemoryPool<System, T, N>, index: usize) -> Self {
+        debug_assert!(index < N);
+        Self { pool, index }
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *mut T {
+        let blocks = Hunk::as_ptr(self.pool.blocks);
+        // Safety: `self.index < N`
+        unsafe { (*blocks).get_unchecked(self.index).0.get() }
+    }
+}
+
+impl<System, T, const N: usize> Deref for MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: We own the block
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl<System, T, const N: usize> DerefMut for MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safety: We own the block
+        unsafe { &mut *self.as_ptr() }
+    }
+}
+
+impl<System, T, const N: usize> Drop for MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: We own the block, and we won't access it anymore
+        let result = unsafe {
+            <System as raw::KernelMemoryPool>::raw_memory_pool_release(self.pool.id, self.index)
+        };
+
+        // We own the block, so the memory pool is accessible, and the block
+        // is in range and in use
+        debug_assert!(
+            !matches!(
+                result,
+                Err(ReleaseMemoryPoolError::NoAccess | ReleaseMemoryPoolError::BadParam)
+            ),
+            "unexpected error while returning a memory pool block: {result:?}"
+        );
+
+        if let Err(ReleaseMemoryPoolError::BadContext) = result {
+            panic!("can't return a memory pool block while CPU Lock is active");
+        }
+    }
+}
+
+impl<System, T, const N: usize> fmt::Debug for MemoryPoolBlock<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + fmt::Debug + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryPoolBlock")
+            .field("pool", &self.pool)
+            .field("index", &self.index)
+            .field("value", &**self)
+            .finish()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The definer (static builder) for [`StaticMemoryPool`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct MemoryPoolDefiner<System, T, const N: usize> {
+    /// `System` and `T` only appear in the type of the defined memory pool
+    _phantom: PhantomInvariant<(System, T)>,
+    queue_order: QueueOrder,
+}
+
+impl<System, T, const N: usize> MemoryPoolDefiner<System, T, N>
+where
+    System: raw::KernelMemoryPool + cfg::KernelStatic,
+    T: Init + Send + 'static,
+{
+    const fn new() -> Self {
+        Self {
+            _phantom: Init::INIT,
+            queue_order: QueueOrder::TaskPriority,
+        }
+    }
+
+    /// Specify how tasks are sorted in the wait queue of the memory pool.
+    /// Defaults to [`QueueOrder::TaskPriority`] when unspecified.
+    pub const fn queue_order(self, queue_order: QueueOrder) -> Self {
+        Self {
+            queue_order,
+            ..self
+        }
+    }
+
+    /// Complete the definition of a memory pool, returning a reference to the
+    /// memory pool.
+    pub const fn finish<C: ~const raw_cfg::CfgMemoryPool<System = System>>(
+        self,
+        c: &mut Cfg<C>,
+    ) -> StaticMemoryPool<System, T, N> {
+        let blocks = Hunk::<System, [BlockCell<T>; N]>::define().finish(c);
+
+        let id = c.raw().memory_pool_define(
+            raw_cfg::MemoryPoolDescriptor {
+                phantom: Init::INIT,
+                num_blocks: N,
+                queue_order: self.queue_order,
+            },
+            (),
+        );
+
+        StaticMemoryPool { id, blocks }
+    }
+}
@@ -0,0 +1,349 @@
+//! Message queues
+use core::{fmt, hash, marker::PhantomData, mem::MaybeUninit};
+
+use super::{
+    raw, raw_cfg, Cfg, QueueOrder, ReceiveMessageQueueError, ReceiveMessageQueueTimeoutError,
+    SendMessageQueueError, SendMessageQueueTimeoutError, TryReceiveMessageQueueError,
+    TrySendMessageQueueError,
+};
+use crate::{
+    time::{Duration, Time},
+    utils::{Init, PhantomInvariant},
+};
+
+// ----------------------------------------------------------------------------
+
+/// Represents a single borrowed message queue in a system.
+///
+/// This type is ABI-compatible with `System::`[`RawMessageQueueId`][]. Unlike
+/// other kernel object handles, it's parameterized by the message type `T`,
+/// which is fixed when the message queue is [defined][1].
+///
+/// A message queue is a bounded FIFO queue of fixed-size messages. Tasks and
+/// interrupt handlers can send messages to and receive messages from it. A
+/// sender blocks while the message queue is full, and a receiver blocks while
+/// it's empty. A message queue with zero capacity can be used for synchronous
+/// message passing, in which a sender blocks until a receiver takes the
+/// message.
+///
+/// Waiting senders and waiting receivers are queued in separate wait queues,
+/// which are both sorted according to the [`QueueOrder`] specified at
+/// definition time.
+///
+/// Owned handles aren't supported yet.
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** queue (FreeRTOS), message queue
+/// > (POSIX, RTEMS, RT-Thread), data queue (μITRON4.0)
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Rationale:** `T` is required to be [`Copy`] because the messages are
+/// > transferred by copying their byte representation, and messages remaining
+/// > in a statically-defined message queue are never dropped.
+///
+/// [`RawMessageQueueId`]: raw::KernelMessageQueue::RawMessageQueueId
+/// [1]: StaticMessageQueue::define
+#[doc = include_str!("../common.md")]
+#[repr(transparent)]
+pub struct MessageQueueRef<'a, System: raw::KernelMessageQueue, T>(
+    System::RawMessageQueueId,
+    PhantomData<(&'a (), PhantomInvariant<T>)>,
+);
+
+/// A [static handle][] type: [`MessageQueueRef`][]`<'static, System, T>`
+///
+/// [Static handle]: crate#object-handles
+pub type StaticMessageQueue<System, T> = MessageQueueRef<'static, System, T>;
+
+impl<System: raw::KernelMessageQueue, T> MessageQueueRef<'_, System, T> {
+    /// Construct a `MessageQueueRef` from `RawMessageQueueId`.
+    ///
+    /// # Safety
+    ///
+    /// This function is marked as `unsafe` to prevent safe code from
+    /// compromising [object safety][1]. In addition, the message queue must
+    /// have been defined with a message size and alignment matching `T`.
+    ///
+    /// [1]: crate#object-safety
+    #[inline]
+    pub const unsafe fn from_id(id: System::RawMessageQueueId) -> Self {
+        Self(id, PhantomData)
+    }
+
+    /// Get the raw `RawMessageQueueId` value representing this object.
+    #[inline]
+    pub const fn id(&self) -> System::RawMessageQueueId {
+        self.0
+    }
+}
+
+impl<System: raw::KernelMessageQueue, T: Copy + Send + 'static> StaticMessageQueue<System, T> {
+    /// Construct a `MessageQueueDefiner` to define a message queue in [a
+    /// configuration function](crate#static-configuration).
+    pub const fn define() -> MessageQueueDefiner<System, T> {
+        MessageQueueDefiner::new()
+    }
+}
+
+impl<System: raw::KernelMessageQueue, T: Copy + Send + 'static> MessageQueueRef<'_, System, T> {
+    /// Send a message, potentially blocking the calling thread until there's
+    /// a vacant slot in the message queue.
+    ///
+    /// If there are tasks waiting to receive a message, the message is handed
+    /// over directly to the first of them.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// [a non-waitable context]: crate#contexts
+    #[inline]
+    pub fn send(&self, message: T) -> Result<(), SendMessageQueueError> {
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelMessageQueue>::raw_message_queue_send(
+                self.0,
+                (&message as *const T).cast(),
+            )
+        }
+    }
+
+    /// [`send`](Self::send) with timeout.
+    #[inline]
+    pub fn send_timeout(
+        &self,
+        message: T,
+        timeout: Duration,
+    ) -> Result<(), SendMessageQueueTimeoutError> {
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelMessageQueue>::raw_message_queue_send_timeout(
+                self.0,
+                (&message as *const T).cast(),
+                timeout,
+            )
+        }
+    }
+
+    /// [`send`](Self::send) with an absolute deadline. See
+    /// [`Kernel::sleep_until`][1] for how the deadline is interpreted.
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    #[inline]
+    pub fn send_until(&self, message: T, deadline: Time) -> Result<(), SendMessageQueueTimeoutError>
+    where
+        System: raw::KernelWaitUntil,
+    {
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelWaitUntil>::raw_message_queue_send_until(
+                self.0,
+                (&message as *const T).cast(),
+                deadline,
+            )
+        }
+    }
+
+    /// Non-blocking version of [`send`](Self::send). Returns immediately with
+    /// [`TrySendMessageQueueError::Timeout`] if the message queue is full.
+    ///
+    /// This method can be called from an interrupt context.
+    #[inline]
+    pub fn try_send(&self, message: T) -> Result<(), TrySendMessageQueueError> {
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelMessageQueue>::raw_message_queue_try_send(
+                self.0,
+                (&message as *const T).cast(),
+            )
+        }
+    }
+
+    /// Receive a message, potentially blocking the calling thread until one is
+    /// available.
+    ///
+    /// This system service may block. Therefore, calling this method is not
+    /// allowed in [a non-waitable context] and will return `Err(BadContext)`.
+    ///
+    /// [a non-waitable context]: crate#contexts
+    #[inline]
+    pub fn receive(&self) -> Result<T, ReceiveMessageQueueError> {
+        let mut message = MaybeUninit::<T>::uninit();
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelMessageQueue>::raw_message_queue_receive(
+                self.0,
+                message.as_mut_ptr().cast(),
+            )?;
+        }
+        // Safety: The kernel wrote a message of type `T`
+        Ok(unsafe { message.assume_init() })
+    }
+
+    /// [`receive`](Self::receive) with timeout.
+    #[inline]
+    pub fn receive_timeout(&self, timeout: Duration) -> Result<T, ReceiveMessageQueueTimeoutError> {
+        let mut message = MaybeUninit::<T>::uninit();
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelMessageQueue>::raw_message_queue_receive_timeout(
+                self.0,
+                message.as_mut_ptr().cast(),
+                timeout,
+            )?;
+        }
+        // Safety: The kernel wrote a message of type `T`
+        Ok(unsafe { message.assume_init() })
+    }
+
+    /// [`receive`](Self::receive) with an absolute deadline. See
+    /// [`Kernel::sleep_until`][1] for how the deadline is interpreted.
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    #[inline]
+    pub fn receive_until(&self, deadline: Time) -> Result<T, ReceiveMessageQueueTimeoutError>
+    where
+        System: raw::KernelWaitUntil,
+    {
+        let mut message = MaybeUninit::<T>::uninit();
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelWaitUntil>::raw_message_queue_receive_until(
+                self.0,
+                message.as_mut_ptr().cast(),
+                deadline,
+            )?;
+        }
+        // Safety: The kernel wrote a message of type `T`
+        Ok(unsafe { message.assume_init() })
+    }
+
+    /// Non-blocking version of [`receive`](Self::receive). Returns immediately
+    /// with [`TryReceiveMessageQueueError::Timeout`] if the message queue is
+    /// empty.
+    ///
+    /// This method can be called from an interrupt context.
+    #[inline]
+    pub fn try_receive(&self) -> Result<T, TryReceiveMessageQueueError> {
+        let mut message = MaybeUninit::<T>::uninit();
+        // Safety: `MessageQueueRef` represents a permission to access the
+        //         referenced object. The message queue was defined for `T`.
+        unsafe {
+            <System as raw::KernelMessageQueue>::raw_message_queue_try_receive(
+                self.0,
+                message.as_mut_ptr().cast(),
+            )?;
+        }
+        // Safety: The kernel wrote a message of type `T`
+        Ok(unsafe { message.assume_init() })
+    }
+}
+
+impl<System: raw::KernelMessageQueue, T> Clone for MessageQueueRef<'_, System, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<System: raw::KernelMessageQueue, T> Copy for MessageQueueRef<'_, System, T> {}
+
+impl<System: raw::KernelMessageQueue, T> PartialEq for MessageQueueRef<'_, System, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<System: raw::KernelMessageQueue, T> Eq for MessageQueueRef<'_, System, T> {}
+
+impl<System: raw::KernelMessageQueue, T> hash::Hash for MessageQueueRef<'_, System, T> {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        hash::Hash::hash(&self.0, state);
+    }
+}
+
+impl<System: raw::KernelMessageQueue, T> fmt::Debug for MessageQueueRef<'_, System, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("MessageQueue").field(&self.0).finish()
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The definer (static builder) for [`StaticMessageQueue`].
+#[must_use = "must call `finish()` to complete registration"]
+pub struct MessageQueueDefiner<System: raw::KernelMessageQueue, T> {
+    _phantom: PhantomInvariant<(System, T)>,
+    capacity: Option<usize>,
+    queue_order: QueueOrder,
+}
+
+impl<System: raw::KernelMessageQueue, T: Copy + Send + 'static> MessageQueueDefiner<System, T> {
+    const fn new() -> Self {
+        Self {
+            _phantom: Init::INIT,
+            capacity: None,
+            queue_order: QueueOrder::TaskPriority,
+        }
+    }
+
+    /// \[**Required**\] Specify the maximum number of messages the message
+    /// queue can hold.
+    ///
+    /// A message queue with zero capacity doesn't buffer messages. A sender
+    /// blocks until a receiver takes the message, and vice versa.
+    pub const fn capacity(self, capacity: usize) -> Self {
+        assert!(self.capacity.is_none(), "`capacity` is already specified");
+
+        Self {
+            capacity: Some(capacity),
+            ..self
+        }
+    }
+
+    /// Specify how tasks are sorted in the wait queues of the message queue.
+    /// Defaults to [`QueueOrder::TaskPriority`] when unspecified.
+    pub const fn queue_order(self, queue_order: QueueOrder) -> Self {
+        Self {
+            queue_order,
+            ..self
+        }
+    }
+
+    /// Complete the definition of a message queue, returning a reference to
+    /// the message queue.
+    pub const fn finish<C: ~const raw_cfg::CfgMessageQueue<System = System>>(
+        self,
+        c: &mut Cfg<C>,
+    ) -> StaticMessageQueue<System, T> {
+        let capacity = self.capacity.expect("`capacity` is not specified");
+
+        let id = c.raw().message_queue_define(
+            raw_cfg::MessageQueueDescriptor {
+                phantom: Init::INIT,
+                message_size: core::mem::size_of::<T>(),
+                message_align: core::mem::align_of::<T>(),
+                capacity,
+                queue_order: self.queue_order,
+            },
+            (),
+        );
+
+        // Safety: The message queue was defined with a message size and
+        // alignment matching `T`
+        unsafe { MessageQueueRef::from_id(id) }
+    }
+}
@@ -14,12 +14,15 @@ mod global;
 pub mod hook;
 pub mod hunk;
 pub mod interrupt;
+pub mod memory_pool;
+pub mod message_queue;
 pub mod mutex;
 pub mod raw;
 pub mod raw_cfg;
 pub mod semaphore;
 pub mod task;
 pub mod timer;
+pub mod wait_any;
 pub use {
     cfg::Cfg,
     error::*,
@@ -27,14 +30,17 @@ pub use {
         EventGroup, EventGroupBits, EventGroupRef, EventGroupWaitFlags, StaticEventGroup,
     },
     global::*,
-    hook::StartupHook,
+    hook::{IdleHook, StartupHook, TaskExitHook, TaskSwitchHook},
     hunk::Hunk,
     interrupt::{InterruptLine, InterruptNum, InterruptPriority, StaticInterruptHandler},
+    memory_pool::{MemoryPoolBlock, StaticMemoryPool},
+    message_queue::{MessageQueueRef, StaticMessageQueue},
     mutex::{Mutex, MutexProtocol, MutexRef, StaticMutex},
-    raw::{Id, QueueOrder},
+    raw::{Id, LoadSnapshot, QueueOrder},
     semaphore::{Semaphore, SemaphoreRef, SemaphoreValue, StaticSemaphore},
-    task::{LocalTask, StaticTask, Task, TaskRef},
-    timer::{StaticTimer, Timer, TimerRef},
+    task::{LocalTask, NotificationValue, NotifyAction, StaticTask, Task, TaskRef},
+    timer::{StaticTimer, Timer, TimerContext, TimerRef},
+    wait_any::WaitAny,
 };
 
 /// The prelude module. This module re-exports [`Kernel`][2] and other extension
@@ -77,11 +83,14 @@ pub mod traits {
         mutex::{MutexHandle, MutexMethods},
         raw::{
             KernelAdjustTime, KernelBase, KernelBoostPriority, KernelEventGroup,
-            KernelInterruptLine, KernelMutex, KernelSemaphore, KernelTaskSetPriority, KernelTime,
-            KernelTimer,
+            KernelInterruptLine, KernelMemoryPool, KernelMessageQueue, KernelMutex,
+            KernelRuntimeStats, KernelSemaphore, KernelTaskNotify, KernelTaskSetDeadline,
+            KernelTaskSetPriority, KernelTaskStackHighWaterMark, KernelTaskSuspend,
+            KernelTaskTerminate, KernelTime, KernelTimer, KernelWaitAny, KernelWaitUntil,
         },
         raw_cfg::{
-            CfgBase, CfgEventGroup, CfgInterruptLine, CfgMutex, CfgSemaphore, CfgTask, CfgTimer,
+            CfgBase, CfgEventGroup, CfgInterruptLine, CfgMemoryPool, CfgMessageQueue, CfgMutex,
+            CfgSemaphore, CfgTask, CfgTimer,
         },
         semaphore::{SemaphoreHandle, SemaphoreMethods},
         task::{TaskHandle, TaskMethods},
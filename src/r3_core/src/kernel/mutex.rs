@@ -5,7 +5,7 @@ use super::{
     raw, raw_cfg, Cfg, LockMutexError, LockMutexTimeoutError, MarkConsistentMutexError,
     QueryMutexError, TryLockMutexError, UnlockMutexError,
 };
-use crate::time::Duration;
+use crate::time::{Duration, Time};
 
 pub use raw::MutexProtocol;
 
@@ -141,8 +141,9 @@ define_object! {
 ///
 /// # Locking Protocols
 ///
-/// `Mutex` supports [the immediate priority ceiling protocol] to avoid
-/// unbounded [priority inversion].
+/// `Mutex` supports [the immediate priority ceiling protocol] and
+/// [the priority inheritance protocol] to avoid unbounded
+/// [priority inversion].
 ///
 /// A locking protocol can be chosen by [`MutexDefiner::protocol`][].
 /// Additional information can be found at [`MutexProtocol`][].
@@ -191,7 +192,7 @@ define_object! {
 /// > | TOPPERS Next Gen | no  | yes | arbitrary    | immediate      |
 /// > | VxWorks          | yes | yes | arbitrary    | ?              |
 /// > | μITRON4.0        | yes | yes | arbitrary    |                |
-/// > | **R3**           | no  | yes | lock-reverse | immediate      |
+/// > | **R3**           | yes | yes | lock-reverse | immediate      |
 /// >
 /// >  - The **PI** column indicates the availability of
 /// >    [the priority inheritance protocol].
@@ -215,13 +216,10 @@ define_object! {
 /// > <details>
 /// > <summary>Rationale</summary>
 /// >
-/// > There are numerous reasons that led to the decision not to implement the
-/// > priority inheritance protocol.
-/// >
-/// >  - We couldn't afford time to implement and test both protocols at this
-/// >    time.
-/// >    The entire project is at a prototyping stage, so we would better
-/// >    implement the other one when there is an actual need for it.
+/// > The priority inheritance protocol is provided for compatibility with
+/// > existing software designs, but the immediate priority ceiling protocol
+/// > should be preferred whenever the set of tasks accessing a mutex is known
+/// > in advance.
 /// >
 /// >  - There are many arguments against using the priority inheritance
 /// >    protocol in real-time systems, although they are somewhat out-dated.
@@ -333,6 +331,20 @@ pub trait MutexMethods: MutexHandle {
         unsafe { <Self::System as raw::KernelMutex>::raw_mutex_lock_timeout(self.id(), timeout) }
     }
 
+    /// [`lock`](Self::lock) with an absolute deadline. See
+    /// [`Kernel::sleep_until`][1] for how the deadline is interpreted.
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    #[inline]
+    fn lock_until(&self, deadline: Time) -> Result<(), LockMutexTimeoutError>
+    where
+        Self::System: raw::KernelWaitUntil,
+    {
+        // Safety: `Mutex` represents a permission to access the
+        //         referenced object.
+        unsafe { <Self::System as raw::KernelWaitUntil>::raw_mutex_lock_until(self.id(), deadline) }
+    }
+
     /// Non-blocking version of [`lock`](Self::lock). Returns
     /// immediately with [`TryLockMutexError::Timeout`] if the unblocking
     /// condition is not satisfied.
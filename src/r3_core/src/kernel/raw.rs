@@ -189,6 +189,169 @@ pub unsafe trait KernelTime: KernelBase {
     fn raw_time() -> Result<Time, TimeError>;
 }
 
+/// Provides access to the processor time accounting done by a kernel.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelRuntimeStats: KernelBase {
+    /// Implements [`Task::cpu_time`][1].
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::cpu_time
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_task_cpu_time(this: Self::RawTaskId) -> Result<u64, GetTaskCpuTimeError>;
+
+    /// Implements [`Kernel::load_snapshot`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::load_snapshot
+    fn raw_load_snapshot() -> Result<LoadSnapshot, LoadSnapshotError>;
+}
+
+/// The processor time accumulated since boot, returned by
+/// [`Kernel::load_snapshot`][1].
+///
+/// The processor load over a period of time can be calculated from two
+/// snapshots taken at the start and the end of the period:
+///
+/// ```rust
+/// # use r3_core::kernel::LoadSnapshot;
+/// fn load_percent(start: LoadSnapshot, end: LoadSnapshot) -> u64 {
+///     let busy = end.busy_time - start.busy_time;
+///     let idle = end.idle_time - start.idle_time;
+///     busy * 100 / (busy + idle).max(1)
+/// }
+///
+/// let start = LoadSnapshot { busy_time: 1000, idle_time: 3000 };
+/// let end = LoadSnapshot { busy_time: 4000, idle_time: 4000 };
+/// assert_eq!(load_percent(start, end), 75);
+/// ```
+///
+/// [1]: crate::kernel::Kernel::load_snapshot
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct LoadSnapshot {
+    /// The total processor time spent by tasks, measured in microseconds.
+    pub busy_time: u64,
+    /// The total processor time spent while no task was ready to run, measured
+    /// in microseconds.
+    pub idle_time: u64,
+}
+
+/// Provides the blocking methods taking an absolute deadline instead of a
+/// relative timeout, such as `sleep_until`.
+///
+/// A deadline is specified in terms of the system time ([`raw_time`]). An
+/// implementation converts a deadline to a relative timeout when the method
+/// is called. Therefore, an ongoing wait operation is affected by
+/// [`raw_adjust_time`] but not by [`raw_set_time`].
+///
+/// [`raw_time`]: KernelTime::raw_time
+/// [`raw_adjust_time`]: KernelAdjustTime::raw_adjust_time
+/// [`raw_set_time`]: KernelBase::raw_set_time
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelWaitUntil:
+    KernelTime
+    + KernelSemaphore
+    + KernelEventGroup
+    + KernelMutex
+    + KernelMessageQueue
+    + KernelMemoryPool
+{
+    /// Implements [`Kernel::sleep_until`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    fn raw_sleep_until(deadline: Time) -> Result<(), SleepError>;
+
+    /// Implements [`Kernel::park_until`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::park_until
+    fn raw_park_until(deadline: Time) -> Result<(), ParkTimeoutError>;
+
+    /// Implements [`Semaphore::wait_one_until`][1].
+    ///
+    /// [1]: crate::kernel::semaphore::SemaphoreMethods::wait_one_until
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_semaphore_wait_one_until(
+        this: Self::RawSemaphoreId,
+        deadline: Time,
+    ) -> Result<(), WaitSemaphoreTimeoutError>;
+
+    /// Implements [`EventGroup::wait_until`][1].
+    ///
+    /// [1]: crate::kernel::event_group::EventGroupMethods::wait_until
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_event_group_wait_until(
+        this: Self::RawEventGroupId,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+        deadline: Time,
+    ) -> Result<EventGroupBits, WaitEventGroupTimeoutError>;
+
+    /// Implements [`Mutex::lock_until`][1].
+    ///
+    /// [1]: crate::kernel::mutex::MutexMethods::lock_until
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_mutex_lock_until(
+        this: Self::RawMutexId,
+        deadline: Time,
+    ) -> Result<(), LockMutexTimeoutError>;
+
+    /// Implements [`MessageQueueRef::send_until`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::send_until
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of
+    /// [`KernelMessageQueue`]'s documentation.
+    unsafe fn raw_message_queue_send_until(
+        this: Self::RawMessageQueueId,
+        message: *const u8,
+        deadline: Time,
+    ) -> Result<(), SendMessageQueueTimeoutError>;
+
+    /// Implements [`MessageQueueRef::receive_until`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::receive_until
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of
+    /// [`KernelMessageQueue`]'s documentation.
+    unsafe fn raw_message_queue_receive_until(
+        this: Self::RawMessageQueueId,
+        message: *mut u8,
+        deadline: Time,
+    ) -> Result<(), ReceiveMessageQueueTimeoutError>;
+
+    /// Implements [`StaticMemoryPool::get_until`][1].
+    ///
+    /// [1]: crate::kernel::memory_pool::StaticMemoryPool::get_until
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMemoryPool#safety) section of
+    /// [`KernelMemoryPool`]'s documentation.
+    unsafe fn raw_memory_pool_get_until(
+        this: Self::RawMemoryPoolId,
+        deadline: Time,
+    ) -> Result<usize, GetMemoryPoolTimeoutError>;
+}
+
 /// Provides the `boost_priority` method.
 ///
 /// # Safety
@@ -220,6 +383,147 @@ pub unsafe trait KernelTaskSetPriority: KernelBase {
     ) -> Result<(), SetTaskPriorityError>;
 }
 
+/// Provides the `task_stack_high_water_mark` method.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelTaskStackHighWaterMark: KernelBase {
+    /// Implements [`Task::stack_high_water_mark`][1].
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::stack_high_water_mark
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_task_stack_high_water_mark(
+        this: Self::RawTaskId,
+    ) -> Result<usize, GetTaskStackHighWaterMarkError>;
+}
+
+/// Provides the `task_set_deadline` method.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelTaskSetDeadline: KernelBase {
+    /// Implements [`Task::set_deadline`][1].
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::set_deadline
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_task_set_deadline(
+        this: Self::RawTaskId,
+        deadline: Duration,
+    ) -> Result<(), SetTaskDeadlineError>;
+}
+
+/// Provides the `task_suspend` and `task_resume` methods.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelTaskSuspend: KernelBase {
+    /// Implements [`Task::suspend`][1].
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::suspend
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_task_suspend(this: Self::RawTaskId) -> Result<(), SuspendTaskError>;
+
+    /// Implements [`Task::resume`][1].
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::resume
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_task_resume(this: Self::RawTaskId) -> Result<(), ResumeTaskError>;
+}
+
+/// Provides the `task_terminate` method.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelTaskTerminate: KernelBase {
+    /// Implements [`Task::terminate`][1].
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation and
+    /// the [Safety][1] section of `Task::terminate`'s documentation.
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::terminate
+    unsafe fn raw_task_terminate(this: Self::RawTaskId) -> Result<(), TerminateTaskError>;
+}
+
+/// Provides the `task_notify` and `wait_notification` methods.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+pub unsafe trait KernelTaskNotify: KernelBase {
+    /// Implements [`Task::notify`][1].
+    ///
+    /// [1]: crate::kernel::task::TaskMethods::notify
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    unsafe fn raw_task_notify(
+        this: Self::RawTaskId,
+        value: NotificationValue,
+        action: NotifyAction,
+    ) -> Result<(), NotifyTaskError>;
+
+    /// Implements [`Kernel::wait_notification`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::wait_notification
+    fn raw_wait_notification(
+        clear_on_entry: NotificationValue,
+        clear_on_exit: NotificationValue,
+    ) -> Result<NotificationValue, WaitNotificationError>;
+
+    /// Implements [`Kernel::wait_notification_timeout`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::wait_notification_timeout
+    fn raw_wait_notification_timeout(
+        clear_on_entry: NotificationValue,
+        clear_on_exit: NotificationValue,
+        timeout: Duration,
+    ) -> Result<NotificationValue, WaitNotificationTimeoutError>;
+}
+
+/// Specifies how [`Task::notify`][1] updates the task's notification value.
+///
+/// [1]: crate::kernel::task::TaskMethods::notify
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** `eSetBits`, `eIncrement`,
+/// > `eSetValueWithOverwrite`, and `eSetValueWithoutOverwrite` (FreeRTOS)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NotifyAction {
+    /// Set the specified bits (bitwise OR).
+    SetBits,
+    /// Increment the value by one, ignoring the specified value. This is useful
+    /// for using the notification value as a counting semaphore.
+    Increment,
+    /// Replace the value with the specified value.
+    Overwrite,
+    /// Replace the value with the specified value only if the task doesn't
+    /// have a pending notification.
+    OverwriteIfEmpty,
+}
+
+/// Unsigned integer type backing task notification values.
+pub type NotificationValue = u32;
+
 /// Provides the `adjust_time` method.
 ///
 /// # Safety
@@ -495,9 +799,10 @@ pub unsafe trait KernelMutex: KernelBase {
 /// > **Relation to Other Specifications:** The operating systems and operating
 /// > system specifications providing an interface for specifying a mutex
 /// > protocol include (but are not limited to) the following: POSIX
-/// > (`pthread_mutexattr_setprotocol` and `PTHREAD_PRIO_PROTECT`, etc.), RTEMS
-/// > Classic API (`RTEMS_PRIORITY_CEILING`, etc.), and μITRON4.0 (`TA_CEILING`,
-/// > etc.).
+/// > (`pthread_mutexattr_setprotocol`, `PTHREAD_PRIO_PROTECT`, and
+/// > `PTHREAD_PRIO_INHERIT`, etc.), RTEMS Classic API
+/// > (`RTEMS_PRIORITY_CEILING` and `RTEMS_INHERIT_PRIORITY`, etc.), and
+/// > μITRON4.0 (`TA_CEILING` and `TA_INHERIT`, etc.).
 ///
 /// <div class="admonition-follows"></div>
 ///
@@ -524,6 +829,19 @@ pub enum MutexProtocol {
     /// [`num_task_priority_levels`]: crate::kernel::Cfg::num_task_priority_levels
     /// [the immediate priority ceiling protocol]: https://en.wikipedia.org/wiki/Priority_ceiling_protocol
     Ceiling(usize),
+    /// Locking the mutex causes the owning task to inherit the effective
+    /// priorities of the tasks waiting on the mutex according to [the
+    /// priority inheritance protocol]. The inheritance is transitive, i.e., if
+    /// the owning task is itself waiting on another mutex using this protocol,
+    /// the inherited priority is propagated to the owner of that mutex as well.
+    ///
+    /// The inherited priority is recalculated when a waiting task stops
+    /// waiting for any reason, e.g., by acquiring the mutex, by timing out, or
+    /// by being [interrupted].
+    ///
+    /// [the priority inheritance protocol]: https://en.wikipedia.org/wiki/Priority_inheritance
+    /// [interrupted]: crate::kernel::Task::interrupt
+    Inherit,
 }
 
 impl MutexProtocol {
@@ -537,6 +855,7 @@ impl MutexProtocol {
         let kind = match self {
             MutexProtocol::None => MutexProtocolKind::None,
             MutexProtocol::Ceiling(_) => MutexProtocolKind::Ceiling,
+            MutexProtocol::Inherit => MutexProtocolKind::Inherit,
         };
 
         // `[T]::iter` is unusable in `const fn` [ref:const_slice_iter]
@@ -568,6 +887,8 @@ pub enum MutexProtocolKind {
     None,
     /// [`MutexProtocol::Ceiling`][]`(_)`
     Ceiling,
+    /// [`MutexProtocol::Inherit`][]
+    Inherit,
 }
 
 /// Provides access to the semaphore API exposed by a kernel.
@@ -669,6 +990,253 @@ pub unsafe trait KernelSemaphore: KernelBase {
 #[doc = include_str!("../common.md")]
 pub type SemaphoreValue = usize;
 
+/// Provides access to the message queue API exposed by a kernel.
+///
+/// Messages are transferred as opaque byte sequences. Their size and alignment
+/// are fixed for each message queue and specified through
+/// [`raw_cfg::MessageQueueDescriptor`][1] at configuration time.
+///
+/// [1]: crate::kernel::raw_cfg::MessageQueueDescriptor
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+///
+/// In addition, the caller of the methods that take a message pointer must
+/// ensure the pointer is valid for reads (`*const u8`) or writes (`*mut u8`) of
+/// [`MessageQueueDescriptor::message_size`][2] bytes and suitably aligned for
+/// [`MessageQueueDescriptor::message_align`][3] until the method returns.
+///
+/// [2]: crate::kernel::raw_cfg::MessageQueueDescriptor::message_size
+/// [3]: crate::kernel::raw_cfg::MessageQueueDescriptor::message_align
+pub unsafe trait KernelMessageQueue: KernelBase {
+    /// The type to identify message queues.
+    type RawMessageQueueId: Id;
+
+    /// Implements [`MessageQueueRef::send`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::send
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_message_queue_send(
+        this: Self::RawMessageQueueId,
+        message: *const u8,
+    ) -> Result<(), SendMessageQueueError>;
+
+    /// Implements [`MessageQueueRef::send_timeout`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::send_timeout
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_message_queue_send_timeout(
+        this: Self::RawMessageQueueId,
+        message: *const u8,
+        timeout: Duration,
+    ) -> Result<(), SendMessageQueueTimeoutError>;
+
+    /// Implements [`MessageQueueRef::try_send`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::try_send
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_message_queue_try_send(
+        this: Self::RawMessageQueueId,
+        message: *const u8,
+    ) -> Result<(), TrySendMessageQueueError>;
+
+    /// Implements [`MessageQueueRef::receive`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::receive
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_message_queue_receive(
+        this: Self::RawMessageQueueId,
+        message: *mut u8,
+    ) -> Result<(), ReceiveMessageQueueError>;
+
+    /// Implements [`MessageQueueRef::receive_timeout`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::receive_timeout
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_message_queue_receive_timeout(
+        this: Self::RawMessageQueueId,
+        message: *mut u8,
+        timeout: Duration,
+    ) -> Result<(), ReceiveMessageQueueTimeoutError>;
+
+    /// Implements [`MessageQueueRef::try_receive`][1].
+    ///
+    /// [1]: crate::kernel::message_queue::MessageQueueRef::try_receive
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMessageQueue#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_message_queue_try_receive(
+        this: Self::RawMessageQueueId,
+        message: *mut u8,
+    ) -> Result<(), TryReceiveMessageQueueError>;
+}
+
+/// Provides the `wait_any` method.
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+///
+/// In addition, when `raw_wait_any[_timeout]` returns `Ok(i)`, the wait
+/// operation described by `specs[i]` must have been completed as if by the
+/// corresponding single-object wait method (e.g., [`raw_semaphore_wait_one`][1]),
+/// and the other wait specifiers must have had no effect.
+///
+/// [1]: KernelSemaphore::raw_semaphore_wait_one
+pub unsafe trait KernelWaitAny:
+    KernelSemaphore + KernelEventGroup + KernelMessageQueue
+{
+    /// Implements [`Kernel::wait_any`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::wait_any
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    ///
+    /// In addition, the pointers contained in `specs` must be valid for writes
+    /// until this method returns.
+    unsafe fn raw_wait_any<const N: usize>(
+        specs: &[WaitAnySpec<Self>; N],
+    ) -> Result<usize, WaitAnyError>;
+
+    /// Implements [`Kernel::wait_any_timeout`][1].
+    ///
+    /// [1]: crate::kernel::Kernel::wait_any_timeout
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](self#safety) section of the module documentation.
+    ///
+    /// In addition, the pointers contained in `specs` must be valid for writes
+    /// until this method returns.
+    unsafe fn raw_wait_any_timeout<const N: usize>(
+        specs: &[WaitAnySpec<Self>; N],
+        timeout: Duration,
+    ) -> Result<usize, WaitAnyTimeoutError>;
+}
+
+/// Describes one of the wait operations passed to
+/// [`KernelWaitAny::raw_wait_any`].
+#[derive(Debug, Clone, Copy)]
+pub enum WaitAnySpec<System: KernelSemaphore + KernelEventGroup + KernelMessageQueue> {
+    /// Take a permit from the semaphore, like
+    /// [`KernelSemaphore::raw_semaphore_wait_one`].
+    Semaphore(System::RawSemaphoreId),
+    /// Wait for the specified bits to be set in the event group, like
+    /// [`KernelEventGroup::raw_event_group_wait`]. The original bit pattern is
+    /// written to `orig_bits`.
+    EventGroup {
+        event_group: System::RawEventGroupId,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+        orig_bits: *mut EventGroupBits,
+    },
+    /// Receive a message from the message queue, like
+    /// [`KernelMessageQueue::raw_message_queue_receive`].
+    MessageQueueReceive {
+        message_queue: System::RawMessageQueueId,
+        message: *mut u8,
+    },
+}
+
+/// Provides access to the memory pool API exposed by a kernel.
+///
+/// A kernel only manages the allocation state of the blocks in a memory pool.
+/// Blocks are identified by indices in range `0..`[`MemoryPoolDescriptor::
+/// num_blocks`][1], and the storage for the blocks is provided by the caller.
+///
+/// [1]: crate::kernel::raw_cfg::MemoryPoolDescriptor::num_blocks
+///
+/// # Safety
+///
+/// See the [Safety](self#safety) section of the module documentation.
+///
+/// In addition, the methods that allocate a block must not return the index of
+/// a block that is currently in use.
+pub unsafe trait KernelMemoryPool: KernelBase {
+    /// The type to identify memory pools.
+    type RawMemoryPoolId: Id;
+
+    /// Implements [`StaticMemoryPool::get`][1].
+    ///
+    /// [1]: crate::kernel::memory_pool::StaticMemoryPool::get
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMemoryPool#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_memory_pool_get(
+        this: Self::RawMemoryPoolId,
+    ) -> Result<usize, GetMemoryPoolError>;
+
+    /// Implements [`StaticMemoryPool::get_timeout`][1].
+    ///
+    /// [1]: crate::kernel::memory_pool::StaticMemoryPool::get_timeout
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMemoryPool#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_memory_pool_get_timeout(
+        this: Self::RawMemoryPoolId,
+        timeout: Duration,
+    ) -> Result<usize, GetMemoryPoolTimeoutError>;
+
+    /// Implements [`StaticMemoryPool::try_get`][1].
+    ///
+    /// [1]: crate::kernel::memory_pool::StaticMemoryPool::try_get
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMemoryPool#safety) section of the trait
+    /// documentation.
+    unsafe fn raw_memory_pool_try_get(
+        this: Self::RawMemoryPoolId,
+    ) -> Result<usize, TryGetMemoryPoolError>;
+
+    /// Return the specified block to the memory pool. The block may be handed
+    /// over directly to a task waiting for a block.
+    ///
+    /// This method can be called from an interrupt context.
+    ///
+    /// # Safety
+    ///
+    /// See the [Safety](KernelMemoryPool#safety) section of the trait
+    /// documentation.
+    ///
+    /// The caller must be the owner of the block, which it has obtained by
+    /// calling one of the allocation methods. The caller must not access the
+    /// block after calling this method.
+    unsafe fn raw_memory_pool_release(
+        this: Self::RawMemoryPoolId,
+        block: usize,
+    ) -> Result<(), ReleaseMemoryPoolError>;
+}
+
 /// Provides access to the timer API exposed by a kernel.
 ///
 /// # Safety
@@ -38,7 +38,17 @@
 //!
 //! [1]: crate::kernel::cfg::KernelStatic
 //! [2]: crate#stability
-use crate::{bag::Bag, closure::Closure, kernel::raw, time::Duration, utils::PhantomInvariant};
+use crate::{
+    bag::Bag,
+    closure::Closure,
+    kernel::{
+        hook::{TaskExitHookFn, TaskSwitchHookFn},
+        raw,
+        timer::TimerContext,
+    },
+    time::Duration,
+    utils::PhantomInvariant,
+};
 
 /// The trait for all kernel-specific low-level configurator types, used by
 /// [the kernel static configuration process][2].
@@ -65,6 +75,35 @@ pub unsafe trait CfgBase {
     ///
     /// [1]: crate::kernel::hook::StartupHook
     fn startup_hook_define(&mut self, func: fn());
+
+    /// Register an [idle hook][1].
+    ///
+    /// The configuration system calls this for each registered idle hook in
+    /// the execution order. The kernel should call the idle hooks with CPU Lock
+    /// active whenever it finds no task to run.
+    ///
+    /// [1]: crate::kernel::hook::IdleHook
+    fn idle_hook_define(&mut self, start: Closure);
+
+    /// Register a [task switch hook][1].
+    ///
+    /// The configuration system calls this for each registered task switch
+    /// hook in the execution order. The kernel should call the task switch
+    /// hooks with CPU Lock active every time the running task changes,
+    /// passing the previous and next running tasks.
+    ///
+    /// [1]: crate::kernel::hook::TaskSwitchHook
+    fn task_switch_hook_define(&mut self, start: TaskSwitchHookFn<Self::System>);
+
+    /// Register a [task exit hook][1].
+    ///
+    /// The configuration system calls this for each registered task exit hook
+    /// in the execution order. The kernel should call the task exit hooks with
+    /// CPU Lock active in the context of a task exiting by
+    /// [`raw::KernelBase::raw_exit_task`], passing the task.
+    ///
+    /// [1]: crate::kernel::hook::TaskExitHook
+    fn task_exit_hook_define(&mut self, start: TaskExitHookFn<Self::System>);
 }
 
 /// A low-level configurator trait providing a method to define a
@@ -99,6 +138,9 @@ pub struct TaskDescriptor<System> {
     pub active: bool,
     pub priority: usize,
     pub stack_size: Option<usize>,
+    pub max_queued_activations: usize,
+    pub time_slice: Option<Duration>,
+    pub relative_deadline: Option<Duration>,
 }
 
 /// A low-level configurator trait providing a method to define an
@@ -197,6 +239,79 @@ pub struct SemaphoreDescriptor<System> {
     pub queue_order: raw::QueueOrder,
 }
 
+/// A low-level configurator trait providing a method to define a
+/// [message queue][2] in [the kernel static configuration process][1].
+///
+/// # Safety
+///
+/// See [the module documentation][4].
+///
+/// # Stability
+///
+/// See [the module documentation][3].
+///
+/// [1]: crate::kernel::StaticMessageQueue
+/// [2]: crate::kernel::cfg::KernelStatic
+/// [3]: self#stability
+/// [4]: self#safety
+#[const_trait]
+pub unsafe trait CfgMessageQueue: ~const CfgBase<System: raw::KernelMessageQueue> {
+    fn message_queue_define<Properties: ~const Bag>(
+        &mut self,
+        descriptor: MessageQueueDescriptor<Self::System>,
+        properties: Properties,
+    ) -> <Self::System as raw::KernelMessageQueue>::RawMessageQueueId;
+}
+
+/// The basic properties of a message queue.
+#[derive(Debug)]
+pub struct MessageQueueDescriptor<System> {
+    pub phantom: PhantomInvariant<System>,
+    /// The size of each message, measured in bytes.
+    pub message_size: usize,
+    /// The alignment of each message, measured in bytes. Must be a power of
+    /// two.
+    pub message_align: usize,
+    /// The maximum number of messages the message queue can hold.
+    pub capacity: usize,
+    /// The order in which waiting senders and receivers are woken up.
+    pub queue_order: raw::QueueOrder,
+}
+
+/// A low-level configurator trait providing a method to define a
+/// [memory pool][2] in [the kernel static configuration process][1].
+///
+/// # Safety
+///
+/// See [the module documentation][4].
+///
+/// # Stability
+///
+/// See [the module documentation][3].
+///
+/// [1]: crate::kernel::StaticMemoryPool
+/// [2]: crate::kernel::cfg::KernelStatic
+/// [3]: self#stability
+/// [4]: self#safety
+#[const_trait]
+pub unsafe trait CfgMemoryPool: ~const CfgBase<System: raw::KernelMemoryPool> {
+    fn memory_pool_define<Properties: ~const Bag>(
+        &mut self,
+        descriptor: MemoryPoolDescriptor<Self::System>,
+        properties: Properties,
+    ) -> <Self::System as raw::KernelMemoryPool>::RawMemoryPoolId;
+}
+
+/// The basic properties of a memory pool.
+#[derive(Debug)]
+pub struct MemoryPoolDescriptor<System> {
+    pub phantom: PhantomInvariant<System>,
+    /// The number of blocks in the memory pool.
+    pub num_blocks: usize,
+    /// The order in which tasks waiting for a block are woken up.
+    pub queue_order: raw::QueueOrder,
+}
+
 /// A low-level configurator trait providing a method to define a
 /// [timwer][2] in [the kernel static configuration process][1].
 ///
@@ -229,6 +344,7 @@ pub struct TimerDescriptor<System> {
     pub active: bool,
     pub delay: Option<Duration>,
     pub period: Option<Duration>,
+    pub context: TimerContext,
 }
 
 /// A low-level configurator trait providing a method to define an
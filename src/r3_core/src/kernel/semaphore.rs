@@ -6,7 +6,7 @@ use super::{
     SignalSemaphoreError, WaitSemaphoreError, WaitSemaphoreTimeoutError,
 };
 use crate::{
-    time::Duration,
+    time::{Duration, Time},
     utils::{Init, PhantomInvariant},
 };
 
@@ -124,6 +124,25 @@ pub trait SemaphoreMethods: SemaphoreHandle {
         }
     }
 
+    /// [`wait_one`](Self::wait_one) with an absolute deadline. See
+    /// [`Kernel::sleep_until`][1] for how the deadline is interpreted.
+    ///
+    /// [1]: crate::kernel::Kernel::sleep_until
+    #[inline]
+    fn wait_one_until(&self, deadline: Time) -> Result<(), WaitSemaphoreTimeoutError>
+    where
+        Self::System: raw::KernelWaitUntil,
+    {
+        // Safety: `Semaphore` represents a permission to access the
+        //         referenced object.
+        unsafe {
+            <Self::System as raw::KernelWaitUntil>::raw_semaphore_wait_one_until(
+                self.id(),
+                deadline,
+            )
+        }
+    }
+
     /// Non-blocking version of [`wait_one`](Self::wait_one). Returns
     /// immediately with [`PollSemaphoreError::Timeout`] if the unblocking
     /// condition is not satisfied.
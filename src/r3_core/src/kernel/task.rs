@@ -4,14 +4,19 @@ use core::{fmt, hash, marker::PhantomData};
 use raw::KernelBase;
 
 use super::{
-    cfg, raw, raw_cfg, ActivateTaskError, Cfg, GetCurrentTaskError, GetTaskPriorityError,
-    InterruptTaskError, SetTaskPriorityError, UnparkError, UnparkExactError,
+    cfg, raw, raw_cfg, ActivateTaskError, Cfg, GetCurrentTaskError, GetTaskCpuTimeError,
+    GetTaskPriorityError, GetTaskStackHighWaterMarkError, InterruptTaskError, NotifyTaskError,
+    ResumeTaskError, SetTaskDeadlineError, SetTaskPriorityError, SuspendTaskError,
+    TerminateTaskError, UnparkError, UnparkExactError,
 };
 use crate::{
     closure::{Closure, IntoClosureConst},
+    time::Duration,
     utils::{Init, PhantomInvariant},
 };
 
+pub use raw::{NotificationValue, NotifyAction};
+
 // ----------------------------------------------------------------------------
 
 define_object! {
@@ -66,8 +71,29 @@ define_object! {
 ///
 /// </center>
 ///
+/// If the kernel supports [`raw::KernelTaskSuspend`], a task can additionally
+/// be [suspended] by another task or an interrupt handler. Suspension is
+/// orthogonal to the states listed above:
+///
+///  - A Ready or Running task that gets suspended enters the **Suspended**
+///    state, in which the task is not eligible for scheduling.
+///
+///  - A Waiting task that gets suspended enters the **Waiting-Suspended**
+///    state. The task retains its position in the wait queue, and the ongoing
+///    wait operation can still complete, at which point the task transitions
+///    into the Suspended state.
+///
+/// A suspended task returns to the Ready or Waiting state when [resumed].
+///
+/// If the kernel supports [`raw::KernelTaskTerminate`], a task in any state
+/// other than Dormant and Running can be [terminated] by another task or an
+/// interrupt handler, which brings the task back to the Dormant state.
+///
 /// [thread]: crate#threads
 /// [activated]: TaskMethods::activate
+/// [suspended]: TaskMethods::suspend
+/// [resumed]: TaskMethods::resume
+/// [terminated]: TaskMethods::terminate
 #[doc = include_str!("../common.md")]
 pub struct Task<System: _>(System::RawTaskId);
 
@@ -192,6 +218,21 @@ impl<System: raw::KernelBase> LocalTask<System> {
 #[doc = include_str!("../common.md")]
 pub trait TaskMethods: TaskHandle {
     /// Start the execution of the task.
+    ///
+    /// If the task is not in the Dormant state, the activation request is
+    /// queued, provided that the number of queued activation requests is less
+    /// than the limit specified by [`TaskDefiner::max_queued_activations`].
+    /// Otherwise, this method will return
+    /// [`ActivateTaskError::QueueOverflow`]. Each queued activation request
+    /// causes the task to be activated again when it [exits] or is
+    /// [terminated].
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `act_tsk` (μITRON4.0)
+    ///
+    /// [exits]: crate::kernel::Kernel::exit_task
+    /// [terminated]: Self::terminate
     #[inline]
     fn activate(&self) -> Result<(), ActivateTaskError> {
         // Safety: `Task` represents a permission to access the
@@ -255,6 +296,45 @@ pub trait TaskMethods: TaskHandle {
         unsafe { <Self::System as KernelBase>::raw_task_unpark_exact(self.id()) }
     }
 
+    /// Update the task's notification value as specified by `action` and mark
+    /// it as pending, unblocking [`Kernel::wait_notification`] now or in the
+    /// future.
+    ///
+    /// Each task has a notification value, which is a lightweight alternative
+    /// to a dedicated [semaphore] or [event group] when there is only one
+    /// task to wake up. The notification value is reset to zero and the
+    /// pending flag is cleared when the task is activated.
+    ///
+    /// If `action` is [`NotifyAction::OverwriteIfEmpty`] and the task already
+    /// has a pending notification, this method will return
+    /// [`NotifyTaskError::QueueOverflow`] without updating the value. The same
+    /// error is returned if `action` is [`NotifyAction::Increment`] and the
+    /// value would overflow.
+    ///
+    /// The task shouldn't be in the Dormant state. Otherwise, this method will
+    /// return [`NotifyTaskError::BadObjectState`].
+    ///
+    /// This method can be called from an interrupt context.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `xTaskNotify` (FreeRTOS)
+    ///
+    /// [`Kernel::wait_notification`]: crate::kernel::Kernel::wait_notification
+    /// [semaphore]: crate::kernel::Semaphore
+    /// [event group]: crate::kernel::EventGroup
+    #[inline]
+    fn notify(&self, value: NotificationValue, action: NotifyAction) -> Result<(), NotifyTaskError>
+    where
+        Self::System: raw::KernelTaskNotify,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object.
+        unsafe {
+            <Self::System as raw::KernelTaskNotify>::raw_task_notify(self.id(), value, action)
+        }
+    }
+
     /// Set the task's base priority.
     ///
     /// A task's base priority is used to calculate its [effective priority].
@@ -284,6 +364,154 @@ pub trait TaskMethods: TaskHandle {
         }
     }
 
+    /// Set the task's absolute deadline to the current time plus `deadline`.
+    ///
+    /// Under an earliest-deadline-first scheduler, tasks having the same
+    /// [effective priority] execute in the order of their absolute deadlines.
+    /// The absolute deadline is reset according to
+    /// [`TaskDefiner::relative_deadline`] upon activation. A kernel might
+    /// choose to store the deadline without using it if deadline-based
+    /// scheduling is not supported.
+    ///
+    /// [effective priority]: Self::effective_priority
+    /// [`TaskDefiner::relative_deadline`]: crate::kernel::task::TaskDefiner::relative_deadline
+    ///
+    /// The duration must not be negative. Otherwise, this method will return
+    /// [`SetTaskDeadlineError::BadParam`].
+    ///
+    /// The task shouldn't be in the Dormant state. Otherwise, this method will
+    /// return [`SetTaskDeadlineError::BadObjectState`].
+    #[inline]
+    fn set_deadline(&self, deadline: Duration) -> Result<(), SetTaskDeadlineError>
+    where
+        Self::System: raw::KernelTaskSetDeadline,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object.
+        unsafe {
+            <Self::System as raw::KernelTaskSetDeadline>::raw_task_set_deadline(self.id(), deadline)
+        }
+    }
+
+    /// Suspend the task.
+    ///
+    /// A suspended task isn't scheduled until it's [resumed]. If the task is
+    /// currently in the Waiting state, the task enters the Waiting-Suspended
+    /// state, retaining its position in the wait queue. See
+    /// [Task States](Task#task-states) for details.
+    ///
+    /// If the task is the current task, this method blocks the calling task
+    /// until it's resumed by another task or an interrupt handler. In this case,
+    /// the current context must be [waitable]. Otherwise, this method will
+    /// return [`SuspendTaskError::BadContext`]. The same error is returned if
+    /// an interrupt handler attempts to suspend the interrupted task while
+    /// Priority Boost is active.
+    ///
+    /// The task shouldn't be in the Dormant state. Otherwise, this method will
+    /// return [`SuspendTaskError::BadObjectState`]. Suspension doesn't nest;
+    /// suspending an already-suspended task will fail with
+    /// [`SuspendTaskError::QueueOverflow`].
+    ///
+    /// This method can be called from an interrupt context.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `sus_tsk` (μITRON4.0),
+    /// > `vTaskSuspend` (FreeRTOS), `rtems_task_suspend` (RTEMS)
+    ///
+    /// [resumed]: Self::resume
+    /// [waitable]: crate#contexts
+    #[inline]
+    fn suspend(&self) -> Result<(), SuspendTaskError>
+    where
+        Self::System: raw::KernelTaskSuspend,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object.
+        unsafe { <Self::System as raw::KernelTaskSuspend>::raw_task_suspend(self.id()) }
+    }
+
+    /// Resume the task [suspended] previously.
+    ///
+    /// If the task is in the Waiting-Suspended state, it returns to the Waiting
+    /// state. Otherwise, it returns to the Ready state.
+    ///
+    /// The task should be suspended. Otherwise, this method will return
+    /// [`ResumeTaskError::BadObjectState`].
+    ///
+    /// This method can be called from an interrupt context.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `rsm_tsk` (μITRON4.0),
+    /// > `vTaskResume` (FreeRTOS), `rtems_task_resume` (RTEMS)
+    ///
+    /// [suspended]: Self::suspend
+    #[inline]
+    fn resume(&self) -> Result<(), ResumeTaskError>
+    where
+        Self::System: raw::KernelTaskSuspend,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object.
+        unsafe { <Self::System as raw::KernelTaskSuspend>::raw_task_resume(self.id()) }
+    }
+
+    /// Terminate the task, forcing it back to the Dormant state.
+    ///
+    /// The task is removed from any wait queue it's waiting on. The mutexes
+    /// held by the task are abandoned in the same way as [`Kernel::exit_task`]
+    /// does, and the next waiters (if any) will receive
+    /// [`LockMutexError::Abandoned`]. The task can be [activated] again
+    /// afterwards.
+    ///
+    /// If the task has a [queued activation request], the task is activated
+    /// again immediately, consuming the request.
+    ///
+    /// The task shouldn't be in the Dormant state. Otherwise, this method will
+    /// return [`TerminateTaskError::BadObjectState`]. A task can't terminate
+    /// itself, nor can an interrupt handler terminate the interrupted task;
+    /// this method will return [`TerminateTaskError::BadContext`] if the task
+    /// is in the Running state. Use [`Kernel::exit_task`] to end the current
+    /// task.
+    ///
+    /// This method can be called from an interrupt context.
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Relation to Other Specifications:** `ter_tsk` (μITRON4.0),
+    /// > `vTaskDelete` (FreeRTOS), `pthread_cancel` (POSIX)
+    ///
+    /// <div class="admonition-follows"></div>
+    ///
+    /// > **Rationale:** Unlike `vTaskDelete` and `pthread_cancel`, the
+    /// > terminated task isn't destroyed. Tasks are statically defined in R3,
+    /// > so the task simply returns to the Dormant state, in which it's
+    /// > indistinguishable from a task that has never been activated.
+    ///
+    /// # Safety
+    ///
+    /// On a successful call, this method destroys the task's stack without
+    /// running any destructors on stack-allocated objects and renders all
+    /// references pointing to such objects invalid. The caller is responsible
+    /// for taking this possibility into account and ensuring this doesn't lead
+    /// to an undefined behavior.
+    ///
+    /// [activated]: Self::activate
+    /// [queued activation request]: Self::activate
+    /// [`Kernel::exit_task`]: crate::kernel::Kernel::exit_task
+    /// [`LockMutexError::Abandoned`]: crate::kernel::LockMutexError::Abandoned
+    #[inline]
+    unsafe fn terminate(&self) -> Result<(), TerminateTaskError>
+    where
+        Self::System: raw::KernelTaskTerminate,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object. The caller is responsible for the
+        //         consequences of discarding the task's stack.
+        unsafe { <Self::System as raw::KernelTaskTerminate>::raw_task_terminate(self.id()) }
+    }
+
     /// Get the task's base priority.
     ///
     /// The task shouldn't be in the Dormant state. Otherwise, this method will
@@ -311,6 +539,42 @@ pub trait TaskMethods: TaskHandle {
         //         referenced object.
         unsafe { <Self::System as raw::KernelBase>::raw_task_effective_priority(self.id()) }
     }
+
+    /// Get the total processor time consumed by the task since boot, measured
+    /// in microseconds.
+    ///
+    /// The time spent by interrupt handlers is attributed to the task they
+    /// interrupted.
+    #[inline]
+    fn cpu_time(&self) -> Result<u64, GetTaskCpuTimeError>
+    where
+        Self::System: raw::KernelRuntimeStats,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object.
+        unsafe { <Self::System as raw::KernelRuntimeStats>::raw_task_cpu_time(self.id()) }
+    }
+
+    /// Get the task's stack high-water mark, i.e., the maximum number of bytes
+    /// of the task's stack that have ever been used since boot.
+    ///
+    /// The measurement is based on the contents of the stack region and is
+    /// inexact by nature. For example, stack bytes that were written with a
+    /// value coincidentally matching the kernel's fill pattern are counted as
+    /// unused. Add some margin when sizing stacks from this value.
+    #[inline]
+    fn stack_high_water_mark(&self) -> Result<usize, GetTaskStackHighWaterMarkError>
+    where
+        Self::System: raw::KernelTaskStackHighWaterMark,
+    {
+        // Safety: `Task` represents a permission to access the
+        //         referenced object.
+        unsafe {
+            <Self::System as raw::KernelTaskStackHighWaterMark>::raw_task_stack_high_water_mark(
+                self.id(),
+            )
+        }
+    }
 }
 
 impl<T: TaskHandle> TaskMethods for T {}
@@ -325,6 +589,9 @@ pub struct TaskDefiner<System> {
     stack_size: Option<usize>,
     priority: Option<usize>,
     active: bool,
+    max_queued_activations: usize,
+    time_slice: Option<Duration>,
+    relative_deadline: Option<Duration>,
 }
 
 impl<System: raw::KernelBase> TaskDefiner<System> {
@@ -335,6 +602,9 @@ impl<System: raw::KernelBase> TaskDefiner<System> {
             stack_size: None,
             priority: None,
             active: false,
+            max_queued_activations: 0,
+            time_slice: None,
+            relative_deadline: None,
         }
     }
 
@@ -379,6 +649,58 @@ impl<System: raw::KernelBase> TaskDefiner<System> {
         Self { active, ..self }
     }
 
+    /// Specify the maximum number of activation requests that can be queued
+    /// while the task is active. Defaults to `0` (activating an active task
+    /// always fails).
+    ///
+    /// See [`Task::activate`] for how queued activation requests are
+    /// processed.
+    ///
+    /// [`Task::activate`]: TaskMethods::activate
+    pub const fn max_queued_activations(self, max_queued_activations: usize) -> Self {
+        Self {
+            max_queued_activations,
+            ..self
+        }
+    }
+
+    /// Specify the task's time slice (quantum). When the task has been running
+    /// for the specified duration, it yields the processor to the next Ready
+    /// task having the same priority, if any. The duration must be positive.
+    ///
+    /// The quantum is reset whenever the task is scheduled again after losing
+    /// the control of the processor, e.g., by blocking or being preempted by a
+    /// higher-priority task.
+    ///
+    /// Defaults to `None` (the task keeps running until it blocks or is
+    /// preempted by a higher-priority task). A kernel might choose to ignore
+    /// this if time slicing is not supported.
+    pub const fn time_slice(self, time_slice: Duration) -> Self {
+        Self {
+            time_slice: Some(time_slice),
+            ..self
+        }
+    }
+
+    /// Specify the task's relative deadline. Each time the task is activated,
+    /// its absolute deadline is set to the activation time plus the specified
+    /// duration. The absolute deadline can be changed later by
+    /// [`Task::set_deadline`]. The duration must not be negative.
+    ///
+    /// The deadline is used to order tasks having the same effective priority
+    /// if the kernel is configured to use an earliest-deadline-first
+    /// scheduler. Defaults to `None` (the task doesn't have a deadline and is
+    /// scheduled after the tasks having one). A kernel might choose to ignore
+    /// this if deadline-based scheduling is not supported.
+    ///
+    /// [`Task::set_deadline`]: TaskMethods::set_deadline
+    pub const fn relative_deadline(self, relative_deadline: Duration) -> Self {
+        Self {
+            relative_deadline: Some(relative_deadline),
+            ..self
+        }
+    }
+
     /// Complete the definition of a task, returning a reference to the
     /// task.
     pub const fn finish<C: ~const raw_cfg::CfgTask<System = System>>(
@@ -396,6 +718,9 @@ impl<System: raw::KernelBase> TaskDefiner<System> {
                     .priority
                     .expect("`priority` (task entry point) is not specified"),
                 stack_size: self.stack_size,
+                max_queued_activations: self.max_queued_activations,
+                time_slice: self.time_slice,
+                relative_deadline: self.relative_deadline,
             },
             (),
         );
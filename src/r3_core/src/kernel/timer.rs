@@ -134,6 +134,10 @@ define_object! {
 ///  - Off-load time-consuming operations to a task, which is [activated] or
 ///    [unparked] by a timer callback function.
 ///
+///  - Alternatively, specify [`TimerContext::Task`] to have the callback
+///    function called by a service task. The timer interrupt handler only
+///    queues the calls, and the calls that don't fit in the queue are dropped.
+///
 ///  - Don't specify zero as period unless you know what you are doing.
 ///
 ///  - Keep your target platform's performance characteristics in your mind.
@@ -435,6 +439,7 @@ pub struct TimerDefiner<System> {
     delay: Option<Duration>,
     period: Option<Duration>,
     active: bool,
+    context: TimerContext,
 }
 
 impl<System: raw::KernelTimer> TimerDefiner<System> {
@@ -445,11 +450,13 @@ impl<System: raw::KernelTimer> TimerDefiner<System> {
             delay: None,
             period: None,
             active: false,
+            context: TimerContext::Interrupt,
         }
     }
 
     /// \[**Required**\] Specify the timer's entry point. It will be called
-    /// in an interrupt context.
+    /// in the context specified by [`Self::context`] (an interrupt context by
+    /// default).
     pub const fn start<C: ~const IntoClosureConst>(self, start: C) -> Self {
         Self {
             start: Some(start.into_closure_const()),
@@ -486,6 +493,12 @@ impl<System: raw::KernelTimer> TimerDefiner<System> {
         }
     }
 
+    /// Specify the context in which the timer's entry point is called.
+    /// Defaults to [`TimerContext::Interrupt`].
+    pub const fn context(self, context: TimerContext) -> Self {
+        Self { context, ..self }
+    }
+
     /// Complete the definition of a mutex, returning a reference to the
     /// mutex.
     pub const fn finish<C: ~const raw_cfg::CfgTimer<System = System>>(
@@ -501,9 +514,45 @@ impl<System: raw::KernelTimer> TimerDefiner<System> {
                 delay: self.delay,
                 period: self.period,
                 active: self.active,
+                context: self.context,
             },
             (),
         );
         unsafe { TimerRef::from_id(id) }
     }
 }
+
+/// Specifies the context in which a [timer]'s callback function is called.
+///
+/// This `enum` type is defined as `#[non_exhaustive]` to allow for potential
+/// future extensions. The behavior is implementation-defined if a specified
+/// `TimerContext` is not supported by the kernel.
+///
+/// [timer]: Timer
+#[doc = include_str!("../common.md")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum TimerContext {
+    /// The callback function is called directly by the timer interrupt
+    /// handler in an interrupt context.
+    Interrupt,
+    /// The callback function is called in a task context by a timer service
+    /// task owned by the kernel. Timer expirations are queued and processed in
+    /// the order in which they occurred. The callback function can perform
+    /// blocking operations, but doing so delays the processing of other
+    /// timers' expirations.
+    ///
+    /// Kernels may share one service task among all timers specifying this
+    /// option. If the queue is full when a timer expires, the expiration is
+    /// dropped and reported as an overrun in an implementation-defined way.
+    Task {
+        /// The priority of the service task. The value must be in range
+        /// `0..`[`num_task_priority_levels`].
+        ///
+        /// [`num_task_priority_levels`]: crate::kernel::Cfg::num_task_priority_levels
+        priority: usize,
+        /// The stack size of the service task. `None` lets the kernel choose
+        /// it.
+        stack_size: Option<usize>,
+    },
+}
@@ -0,0 +1,94 @@
+//! Waiting on multiple objects
+use core::{fmt, marker::PhantomData};
+
+use super::{
+    event_group::EventGroupHandle, raw, semaphore::SemaphoreHandle, EventGroupBits,
+    EventGroupWaitFlags, MessageQueueRef,
+};
+
+/// A wait condition passed to [`Kernel::wait_any`][1].
+///
+/// A `WaitAny` borrows the kernel object it waits on and the storage that
+/// receives the result of the wait operation (if any) for the lifetime `'a`.
+/// The result is written only if the corresponding condition is the one that
+/// completed the call.
+///
+/// <div class="admonition-follows"></div>
+///
+/// > **Relation to Other Specifications:** `WaitForMultipleObjects` (Win32),
+/// > queue sets (FreeRTOS)
+///
+/// [1]: crate::kernel::Kernel::wait_any
+#[doc = include_str!("../common.md")]
+pub struct WaitAny<'a, System: raw::KernelWaitAny> {
+    raw: raw::WaitAnySpec<System>,
+    _phantom: PhantomData<&'a mut ()>,
+}
+
+impl<System: raw::KernelWaitAny> fmt::Debug for WaitAny<'_, System> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WaitAny").field(&self.raw).finish()
+    }
+}
+
+impl<'a, System: raw::KernelWaitAny> WaitAny<'a, System> {
+    /// Construct a `WaitAny` that takes a permit from the specified semaphore
+    /// like [`Semaphore::wait_one`][1].
+    ///
+    /// [1]: crate::kernel::semaphore::SemaphoreMethods::wait_one
+    #[inline]
+    pub fn semaphore(semaphore: &'a impl SemaphoreHandle<System = System>) -> Self {
+        Self {
+            raw: raw::WaitAnySpec::Semaphore(semaphore.id()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Construct a `WaitAny` that waits for the specified bits to be set in
+    /// the specified event group like [`EventGroup::wait`][1]. The original bit
+    /// pattern is written to `orig_bits`.
+    ///
+    /// [1]: crate::kernel::event_group::EventGroupMethods::wait
+    #[inline]
+    pub fn event_group(
+        event_group: &'a impl EventGroupHandle<System = System>,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+        orig_bits: &'a mut EventGroupBits,
+    ) -> Self {
+        Self {
+            raw: raw::WaitAnySpec::EventGroup {
+                event_group: event_group.id(),
+                bits,
+                flags,
+                orig_bits,
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Construct a `WaitAny` that receives a message from the specified
+    /// message queue like [`MessageQueueRef::receive`]. The received message
+    /// is written to `message`.
+    #[inline]
+    pub fn message_queue<T: Copy + Send + 'static>(
+        message_queue: MessageQueueRef<'a, System, T>,
+        message: &'a mut T,
+    ) -> Self {
+        Self {
+            raw: raw::WaitAnySpec::MessageQueueReceive {
+                message_queue: message_queue.id(),
+                message: (message as *mut T).cast(),
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the raw [`WaitAnySpec`][1] value representing this wait condition.
+    ///
+    /// [1]: raw::WaitAnySpec
+    #[inline]
+    pub const fn raw(&self) -> raw::WaitAnySpec<System> {
+        self.raw
+    }
+}
@@ -1,5 +1,12 @@
 //! Static configuration mechanism for the kernel
-use r3_core::{kernel::Hunk, utils::ConstAllocator};
+use r3_core::{
+    closure::Closure,
+    kernel::{
+        hook::{TaskExitHookFn, TaskSwitchHookFn},
+        Hunk, StaticTask, StaticTimer,
+    },
+    utils::ConstAllocator,
+};
 
 use crate::{
     utils::{ComptimeVec, Frozen, FIXED_PRIO_BITMAP_MAX_LEN},
@@ -8,32 +15,61 @@ use crate::{
 
 mod event_group;
 mod interrupt;
+mod memory_pool;
+mod message_queue;
 mod mutex;
 mod semaphore;
 mod task;
 mod timer;
-pub use self::{event_group::*, interrupt::*, mutex::*, semaphore::*, task::*, timer::*};
+pub use self::{
+    event_group::*, interrupt::*, memory_pool::*, message_queue::*, mutex::*, semaphore::*,
+    task::*, timer::*,
+};
 
 /// Attach [a configuration function][1] to a [kernel trait type][2] by
 /// implementing [`KernelCfg2`].
 ///
+/// The task ready queue implementation can be chosen by appending
+/// `task_ready_queue = QueueName`, where `QueueName` is one of the following:
+///
+///  - `BitmapQueue` (default): Tasks having the same effective priority are
+///    scheduled in the FIFO order.
+///  - `EdfQueue`: Tasks having the same effective priority are scheduled in
+///    the earliest-deadline-first order. See [`TaskDefiner::relative_deadline`]
+///    and [`TaskMethods::set_deadline`].
+///
+/// ```rust,ignore
+/// const COTTAGE: Objects =
+///     r3_kernel::build!(SystemTraits, configure_app => Objects, task_ready_queue = EdfQueue);
+/// ```
+///
 /// [1]: r3_core#static-configuration
 /// [2]: crate#kernel-trait-type
 /// [`KernelCfg2`]: crate::KernelCfg2
+/// [`TaskDefiner::relative_deadline`]: r3_core::kernel::task::TaskDefiner::relative_deadline
+/// [`TaskMethods::set_deadline`]: r3_core::kernel::task::TaskMethods::set_deadline
 #[macro_export]
 macro_rules! build {
     // `$configure: ~const Fn(&mut Cfg<impl ~const CfgBase<System =
     // r3_kernel::System<$Traits>>) -> $IdMap`
-    ($Traits:ty, $configure:expr => $IdMap:ty) => {{
+    ($Traits:ty, $configure:expr => $IdMap:ty) => {
+        $crate::build!($Traits, $configure => $IdMap, task_ready_queue = BitmapQueue)
+    };
+
+    (
+        $Traits:ty, $configure:expr => $IdMap:ty,
+        task_ready_queue = $TaskReadyQueue:ident
+    ) => {{
         use $crate::{
             r3_core::{
                 self,
+                kernel::hook,
                 utils::ConstAllocator,
             },
             cfg::{self, CfgBuilder, MiddleCfg},
             EventGroupCb, InterruptAttr, InterruptLineInit, KernelCfg1,
             KernelCfg2, Port, State, TaskAttr, TaskCb, TimeoutRef, TimerAttr,
-            TimerCb, SemaphoreCb, MutexCb, PortThreading, readyqueue,
+            TimerCb, SemaphoreCb, MutexCb, MessageQueueCb, MemoryPoolCb, PortThreading, readyqueue,
             arrayvec::ArrayVec,
             utils::{
                 AlignedStorage, FixedPrioBitmap, Init, RawCell, UIntegerWithBound,
@@ -133,7 +169,7 @@ macro_rules! build {
 
         // Task ready queue
         type TaskReadyBitmap = FixedPrioBitmap<{ CFG.num_task_priority_levels }>;
-        type TaskReadyQueue = readyqueue::BitmapQueue<
+        type TaskReadyQueue = readyqueue::$TaskReadyQueue<
             $Traits,
             <$Traits as PortThreading>::PortTaskState,
             <$Traits as KernelCfg1>::TaskPriority,
@@ -186,6 +222,20 @@ macro_rules! build {
                     (0..CFG.semaphores.len()).map(|i| CFG.semaphores[i].get().to_state());
         }
 
+        // Instantiiate message queue structures
+        $crate::array_item_from_fn! {
+            static MESSAGE_QUEUE_CB_POOL:
+                [MessageQueueCb<$Traits>; _] =
+                    (0..CFG.message_queues.len()).map(|i| CFG.message_queues[i].get().to_state());
+        }
+
+        // Instantiiate memory pool structures
+        $crate::array_item_from_fn! {
+            static MEMORY_POOL_CB_POOL:
+                [MemoryPoolCb<$Traits>; _] =
+                    (0..CFG.memory_pools.len()).map(|i| CFG.memory_pools[i].get().to_state());
+        }
+
         // Instantiiate timer structures
         $crate::array_item_from_fn! {
             const TIMER_ATTR_POOL: [TimerAttr<$Traits>; _] =
@@ -217,10 +267,22 @@ macro_rules! build {
                     (0..CFG.interrupt_lines.len()).map(|i| CFG.interrupt_lines[i].get().to_init());
         }
 
-        // Calculate the required storage of the timeout heap
-        const TIMEOUT_HEAP_LEN: usize = CFG.tasks.len() + CFG.timers.len();
+        // Calculate the required storage of the timeout heap. Each task and
+        // timer can have one timeout at a time. The last one is for time
+        // slicing.
+        const TIMEOUT_HEAP_LEN: usize = CFG.tasks.len() + CFG.timers.len() + 1;
         type TimeoutHeap = ArrayVec<TimeoutRef<$Traits>, TIMEOUT_HEAP_LEN>;
 
+        // Construct tables of hooks
+        $crate::array_item_from_fn! {
+            const IDLE_HOOKS: [r3_core::closure::Closure; _] =
+                (0..CFG.idle_hooks.len()).map(|i| CFG.idle_hooks[i].get());
+            const TASK_SWITCH_HOOKS: [hook::TaskSwitchHookFn<System>; _] =
+                (0..CFG.task_switch_hooks.len()).map(|i| CFG.task_switch_hooks[i].get());
+            const TASK_EXIT_HOOKS: [hook::TaskExitHookFn<System>; _] =
+                (0..CFG.task_exit_hooks.len()).map(|i| CFG.task_exit_hooks[i].get());
+        }
+
         #[inline]
         unsafe fn no_startup_hook() {}
 
@@ -248,6 +310,29 @@ macro_rules! build {
                 no_startup_hook
             };
 
+            const IDLE_HOOKS: &'static [r3_core::closure::Closure] = &IDLE_HOOKS;
+
+            const TASK_SWITCH_HOOKS: &'static [hook::TaskSwitchHookFn<System>] =
+                &TASK_SWITCH_HOOKS;
+
+            const TASK_EXIT_HOOKS: &'static [hook::TaskExitHookFn<System>] = &TASK_EXIT_HOOKS;
+
+            const STACK_OVERFLOW_HOOK: Option<fn(r3_core::kernel::StaticTask<System>)> =
+                CFG.stack_overflow_hook;
+
+            const TRACE_BUFFER_CAPACITY: usize = CFG.trace_buffer_capacity;
+
+            const TRACE_BUFFER_OFFSET: usize = CFG.trace_buffer_offset;
+
+            const TIMER_SERVICE_TASK: usize = CFG.timer_service.task;
+
+            const TIMER_SERVICE_QUEUE_CAPACITY: usize = CFG.timer_service.queue_capacity();
+
+            const TIMER_SERVICE_QUEUE_OFFSET: usize = CFG.timer_service.queue_offset;
+
+            const TIMER_OVERRUN_HOOK: Option<fn(r3_core::kernel::StaticTimer<System>)> =
+                CFG.timer_service.overrun_hook;
+
             #[inline(always)]
             fn hunk_pool_ptr() -> *mut u8 {
                 HUNK_POOL.get().cast()
@@ -273,6 +358,16 @@ macro_rules! build {
                 &SEMAPHORE_CB_POOL
             }
 
+            #[inline(always)]
+            fn message_queue_cb_pool() -> &'static [MessageQueueCb<$Traits>] {
+                &MESSAGE_QUEUE_CB_POOL
+            }
+
+            #[inline(always)]
+            fn memory_pool_cb_pool() -> &'static [MemoryPoolCb<$Traits>] {
+                &MEMORY_POOL_CB_POOL
+            }
+
             #[inline(always)]
             fn timer_cb_pool() -> &'static [TimerCb<$Traits>] {
                 &TIMER_CB_POOL
@@ -317,10 +412,19 @@ pub struct CfgBuilder<Traits: KernelTraits> {
     num_task_priority_levels: usize,
     interrupt_lines: ComptimeVec<CfgBuilderInterruptLine>,
     startup_hook: Option<fn()>,
+    idle_hooks: ComptimeVec<Closure>,
+    task_switch_hooks: ComptimeVec<TaskSwitchHookFn<System<Traits>>>,
+    task_exit_hooks: ComptimeVec<TaskExitHookFn<System<Traits>>>,
+    stack_overflow_hook: Option<fn(StaticTask<System<Traits>>)>,
+    trace_buffer_capacity: usize,
+    trace_buffer_offset: usize,
     event_groups: ComptimeVec<CfgBuilderEventGroup>,
     mutexes: ComptimeVec<CfgBuilderMutex>,
     semaphores: ComptimeVec<CfgBuilderSemaphore>,
+    message_queues: ComptimeVec<CfgBuilderMessageQueue>,
+    memory_pools: ComptimeVec<CfgBuilderMemoryPool>,
     timers: ComptimeVec<CfgBuilderTimer>,
+    timer_service: CfgBuilderTimerService<Traits>,
 }
 
 /// The product of a [`CfgBuilder`]. [`build!`] will use it to define static
@@ -337,10 +441,19 @@ pub struct MiddleCfg<Traits: KernelTraits> {
     pub num_task_priority_levels: usize,
     pub interrupt_lines: &'static [Frozen<CfgBuilderInterruptLine>],
     pub startup_hook: Option<fn()>,
+    pub idle_hooks: &'static [Frozen<Closure>],
+    pub task_switch_hooks: &'static [Frozen<TaskSwitchHookFn<System<Traits>>>],
+    pub task_exit_hooks: &'static [Frozen<TaskExitHookFn<System<Traits>>>],
+    pub stack_overflow_hook: Option<fn(StaticTask<System<Traits>>)>,
+    pub trace_buffer_capacity: usize,
+    pub trace_buffer_offset: usize,
     pub event_groups: &'static [Frozen<CfgBuilderEventGroup>],
     pub mutexes: &'static [Frozen<CfgBuilderMutex>],
     pub semaphores: &'static [Frozen<CfgBuilderSemaphore>],
+    pub message_queues: &'static [Frozen<CfgBuilderMessageQueue>],
+    pub memory_pools: &'static [Frozen<CfgBuilderMemoryPool>],
     pub timers: &'static [Frozen<CfgBuilderTimer>],
+    pub timer_service: CfgBuilderTimerService<Traits>,
 }
 
 impl<Traits: KernelTraits> CfgBuilder<Traits> {
@@ -363,10 +476,19 @@ impl<Traits: KernelTraits> CfgBuilder<Traits> {
             num_task_priority_levels: 4,
             interrupt_lines: ComptimeVec::new_in(allocator.clone()),
             startup_hook: None,
+            idle_hooks: ComptimeVec::new_in(allocator.clone()),
+            task_switch_hooks: ComptimeVec::new_in(allocator.clone()),
+            task_exit_hooks: ComptimeVec::new_in(allocator.clone()),
+            stack_overflow_hook: None,
+            trace_buffer_capacity: 0,
+            trace_buffer_offset: 0,
             event_groups: ComptimeVec::new_in(allocator.clone()),
             mutexes: ComptimeVec::new_in(allocator.clone()),
             semaphores: ComptimeVec::new_in(allocator.clone()),
+            message_queues: ComptimeVec::new_in(allocator.clone()),
+            memory_pools: ComptimeVec::new_in(allocator.clone()),
             timers: ComptimeVec::new_in(allocator.clone()),
+            timer_service: CfgBuilderTimerService::new(),
         }
     }
 
@@ -380,16 +502,150 @@ impl<Traits: KernelTraits> CfgBuilder<Traits> {
             num_task_priority_levels: self.num_task_priority_levels,
             interrupt_lines: Frozen::leak_slice(&self.interrupt_lines),
             startup_hook: self.startup_hook,
+            idle_hooks: Frozen::leak_slice(&self.idle_hooks),
+            task_switch_hooks: Frozen::leak_slice(&self.task_switch_hooks),
+            task_exit_hooks: Frozen::leak_slice(&self.task_exit_hooks),
+            stack_overflow_hook: self.stack_overflow_hook,
+            trace_buffer_capacity: self.trace_buffer_capacity,
+            trace_buffer_offset: self.trace_buffer_offset,
             event_groups: Frozen::leak_slice(&self.event_groups),
             mutexes: Frozen::leak_slice(&self.mutexes),
             semaphores: Frozen::leak_slice(&self.semaphores),
+            message_queues: Frozen::leak_slice(&self.message_queues),
+            memory_pools: Frozen::leak_slice(&self.memory_pools),
             timers: Frozen::leak_slice(&self.timers),
+            timer_service: self.timer_service,
         }
     }
 
+    /// Register a hook function to be called when the kernel detects a
+    /// stack overflow. The hook receives the task whose stack has overflowed.
+    ///
+    /// The kernel checks a canary placed at the end of a task's stack every
+    /// time the task leaves the processor. It also checks the task's stack
+    /// pointer if the port reports it through
+    /// [`PortToKernel::check_stack_pointer`]. Since these are separate checks,
+    /// the hook may be called more than once for a single overflow. If no hook
+    /// is registered, a stack overflow causes a panic.
+    ///
+    /// [`PortToKernel::check_stack_pointer`]: crate::PortToKernel::check_stack_pointer
+    ///
+    /// The hook is called in the middle of a context switch with CPU Lock
+    /// active, so most kernel services are unavailable to it. The system state
+    /// may have been already corrupted by the overflow, and the hook is not
+    /// expected to return. Rebooting the system or halting it in a safe state
+    /// is recommended.
+    ///
+    /// ```rust,ignore
+    /// const fn configure_app(b: &mut r3_kernel::Cfg<SystemTraits>) -> Objects {
+    ///     b.raw().stack_overflow_hook(|task| panic!("{task:?} overflowed"));
+    ///     /* ... */
+    /// }
+    /// ```
+    #[cfg(feature = "stack_check")]
+    #[doc(cfg(feature = "stack_check"))]
+    pub const fn stack_overflow_hook(&mut self, hook: fn(StaticTask<System<Traits>>)) {
+        assert!(
+            self.stack_overflow_hook.is_none(),
+            "only one stack overflow hook can be registered"
+        );
+        self.stack_overflow_hook = Some(hook);
+    }
+
+    /// Allocate a trace buffer with the specified number of record slots and
+    /// start recording kernel events into it. See [`crate::trace`] for the
+    /// details.
+    ///
+    /// Each record slot occupies [`trace::RECORD_LEN`] bytes. When all slots
+    /// are used, the oldest record is overwritten.
+    ///
+    /// ```rust,ignore
+    /// const fn configure_app(b: &mut r3_kernel::Cfg<SystemTraits>) -> Objects {
+    ///     b.raw().trace_buffer(256);
+    ///     /* ... */
+    /// }
+    /// ```
+    ///
+    /// [`trace::RECORD_LEN`]: crate::trace::RECORD_LEN
+    #[cfg(feature = "trace")]
+    #[doc(cfg(feature = "trace"))]
+    pub const fn trace_buffer(&mut self, capacity: usize) {
+        assert!(
+            self.trace_buffer_capacity == 0,
+            "only one trace buffer can be allocated"
+        );
+        assert!(capacity > 0, "the trace buffer must have at least one slot");
+        assert!(
+            capacity <= u32::MAX as usize,
+            "the trace buffer has too many slots"
+        );
+        self.trace_buffer_capacity = capacity;
+    }
+
+    /// Specify the capacity of the queue of the timer service task, which
+    /// calls the callback functions of the timers defined with
+    /// [`TimerContext::Task`]. Defaults to the number of such timers.
+    ///
+    /// When a timer expires while the queue is full, the expiration is dropped
+    /// and reported through [`Self::timer_overrun_hook`].
+    ///
+    /// [`TimerContext::Task`]: r3_core::kernel::TimerContext::Task
+    pub const fn timer_service_queue_capacity(&mut self, capacity: usize) {
+        assert!(
+            capacity > 0,
+            "the timer service queue must have at least one slot"
+        );
+        self.timer_service.queue_capacity = Some(capacity);
+    }
+
+    /// Register a hook function to be called when an expiration of a timer
+    /// defined with [`TimerContext::Task`] is dropped because the timer
+    /// service task's queue is full. The hook receives the timer whose
+    /// expiration was dropped.
+    ///
+    /// The hook is called by the timer interrupt handler in an interrupt
+    /// context. The number of dropped expirations can also be retrieved by
+    /// [`introspect::timer`].
+    ///
+    /// ```rust,ignore
+    /// const fn configure_app(b: &mut r3_kernel::Cfg<SystemTraits>) -> Objects {
+    ///     b.raw().timer_overrun_hook(|timer| log::warn!("{timer:?} overran"));
+    ///     /* ... */
+    /// }
+    /// ```
+    ///
+    /// [`TimerContext::Task`]: r3_core::kernel::TimerContext::Task
+    /// [`introspect::timer`]: crate::introspect::timer
+    pub const fn timer_overrun_hook(&mut self, hook: fn(StaticTimer<System<Traits>>)) {
+        assert!(
+            self.timer_service.overrun_hook.is_none(),
+            "only one timer overrun hook can be registered"
+        );
+        self.timer_service.overrun_hook = Some(hook);
+    }
+
     /// Apply post-processing before [`r3_core::kernel::Cfg`] is finalized.
     #[doc(hidden)]
     pub const fn finalize_in_cfg(cfg: &mut r3_core::kernel::Cfg<Self>) {
+        // Create the timer service task and its queue. This must precede the
+        // creation of task stacks.
+        let queue_capacity = cfg.raw().timer_service.queue_capacity();
+        if queue_capacity > 0 {
+            let hunk: Hunk<System<Traits>> = Hunk::define()
+                .len(core::mem::size_of::<usize>() * queue_capacity)
+                .align(core::mem::align_of::<usize>())
+                .finish(cfg);
+
+            let raw = cfg.raw();
+            raw.timer_service.queue_offset = hunk.offset();
+            raw.timer_service.task = raw.tasks.len();
+            raw.tasks.push(CfgBuilderTask::new_kernel_task(
+                Closure::from_fn_const(crate::timer::service::task_body::<Traits>),
+                raw.timer_service.stack_size,
+                raw.timer_service.priority,
+            ));
+        }
+
         // Create hunks for task stacks.
         let mut i = 0;
         let mut tasks = &mut cfg.raw().tasks;
@@ -412,6 +668,52 @@ impl<Traits: KernelTraits> CfgBuilder<Traits> {
             }
             i += 1;
         }
+
+        // Create hunks for message queue storage.
+        let mut i = 0;
+        let mut message_queues = &mut cfg.raw().message_queues;
+        while i < message_queues.len() {
+            let len = message_queues[i].storage_len();
+            let align = message_queues[i].message_align;
+            if len > 0 {
+                let hunk: Hunk<System<Traits>> = Hunk::define().len(len).align(align).finish(cfg);
+
+                // Borrow again `message_queues`, which was unborrowed because
+                // of the call to `HunkDefiner::finish`
+                message_queues = &mut cfg.raw().message_queues;
+
+                message_queues[i].storage_offset = hunk.offset();
+            }
+            i += 1;
+        }
+
+        // Create hunks for memory pool free lists.
+        let mut i = 0;
+        let mut memory_pools = &mut cfg.raw().memory_pools;
+        while i < memory_pools.len() {
+            let num_blocks = memory_pools[i].num_blocks;
+            if num_blocks > 0 {
+                let hunk: Hunk<System<Traits>> = Hunk::define()
+                    .len(core::mem::size_of::<usize>() * num_blocks)
+                    .align(core::mem::align_of::<usize>())
+                    .finish(cfg);
+
+                // Borrow again `memory_pools`, which was unborrowed because of
+                // the call to `HunkDefiner::finish`
+                memory_pools = &mut cfg.raw().memory_pools;
+
+                memory_pools[i].free_list_offset = hunk.offset();
+            }
+            i += 1;
+        }
+
+        // Create a hunk for the trace buffer.
+        #[cfg(feature = "trace")]
+        if cfg.raw().trace_buffer_capacity > 0 {
+            let len = crate::trace::buffer_len_for_capacity(cfg.raw().trace_buffer_capacity);
+            let hunk: Hunk<System<Traits>> = Hunk::define().len(len).align(4).finish(cfg);
+            cfg.raw().trace_buffer_offset = hunk.offset();
+        }
     }
 }
 
@@ -443,4 +745,16 @@ unsafe impl<Traits: KernelTraits> const r3_core::kernel::raw_cfg::CfgBase for Cf
         );
         self.startup_hook = Some(func);
     }
+
+    fn idle_hook_define(&mut self, start: Closure) {
+        self.idle_hooks.push(start);
+    }
+
+    fn task_switch_hook_define(&mut self, start: TaskSwitchHookFn<Self::System>) {
+        self.task_switch_hooks.push(start);
+    }
+
+    fn task_exit_hook_define(&mut self, start: TaskExitHookFn<Self::System>) {
+        self.task_exit_hooks.push(start);
+    }
 }
@@ -0,0 +1,57 @@
+use core::num::NonZeroUsize;
+use r3_core::kernel::raw_cfg::{CfgMemoryPool, MemoryPoolDescriptor};
+
+use crate::{cfg::CfgBuilder, klock::CpuLockCell, memory_pool, wait, KernelTraits, Port};
+
+unsafe impl<Traits: KernelTraits> const CfgMemoryPool for CfgBuilder<Traits> {
+    fn memory_pool_define<Properties: ~const r3_core::bag::Bag>(
+        &mut self,
+        MemoryPoolDescriptor {
+            phantom: _,
+            num_blocks,
+            queue_order,
+        }: MemoryPoolDescriptor<Self::System>,
+        _properties: Properties,
+    ) -> memory_pool::MemoryPoolId {
+        self.memory_pools.push(CfgBuilderMemoryPool {
+            num_blocks,
+            queue_order: wait::QueueOrder::from(queue_order),
+            free_list_offset: 0,
+        });
+
+        unsafe { NonZeroUsize::new_unchecked(self.memory_pools.len()) }
+    }
+}
+
+#[doc(hidden)]
+pub struct CfgBuilderMemoryPool {
+    pub(super) num_blocks: usize,
+    queue_order: wait::QueueOrder,
+    /// The offset of the free list in the hunk pool. Assigned by
+    /// [`CfgBuilder::finalize_in_cfg`].
+    pub(super) free_list_offset: usize,
+}
+
+impl Clone for CfgBuilderMemoryPool {
+    fn clone(&self) -> Self {
+        Self {
+            num_blocks: self.num_blocks,
+            queue_order: self.queue_order,
+            free_list_offset: self.free_list_offset,
+        }
+    }
+}
+
+impl Copy for CfgBuilderMemoryPool {}
+
+impl CfgBuilderMemoryPool {
+    pub const fn to_state<System: Port>(&self) -> memory_pool::MemoryPoolCb<System> {
+        memory_pool::MemoryPoolCb {
+            num_blocks: self.num_blocks,
+            free_list_offset: self.free_list_offset,
+            num_used_ever: CpuLockCell::new(0),
+            num_free: CpuLockCell::new(0),
+            wait_queue: wait::WaitQueue::new(self.queue_order),
+        }
+    }
+}
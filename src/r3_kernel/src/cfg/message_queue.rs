@@ -0,0 +1,86 @@
+use core::num::NonZeroUsize;
+use r3_core::kernel::raw_cfg::{CfgMessageQueue, MessageQueueDescriptor};
+
+use crate::{cfg::CfgBuilder, klock::CpuLockCell, message_queue, wait, KernelTraits, Port};
+
+unsafe impl<Traits: KernelTraits> const CfgMessageQueue for CfgBuilder<Traits> {
+    fn message_queue_define<Properties: ~const r3_core::bag::Bag>(
+        &mut self,
+        MessageQueueDescriptor {
+            phantom: _,
+            message_size,
+            message_align,
+            capacity,
+            queue_order,
+        }: MessageQueueDescriptor<Self::System>,
+        _properties: Properties,
+    ) -> message_queue::MessageQueueId {
+        assert!(
+            message_align.is_power_of_two(),
+            "`message_align` must be a power of two"
+        );
+
+        // Round up the message size to get the distance between slots
+        let message_stride = (message_size + message_align - 1) / message_align * message_align;
+
+        self.message_queues.push(CfgBuilderMessageQueue {
+            message_size,
+            message_align,
+            message_stride,
+            capacity,
+            queue_order: wait::QueueOrder::from(queue_order),
+            storage_offset: 0,
+        });
+
+        unsafe { NonZeroUsize::new_unchecked(self.message_queues.len()) }
+    }
+}
+
+#[doc(hidden)]
+pub struct CfgBuilderMessageQueue {
+    message_size: usize,
+    pub(super) message_align: usize,
+    message_stride: usize,
+    capacity: usize,
+    queue_order: wait::QueueOrder,
+    /// The offset of the ring buffer in the hunk pool. Assigned by
+    /// [`CfgBuilder::finalize_in_cfg`].
+    pub(super) storage_offset: usize,
+}
+
+impl Clone for CfgBuilderMessageQueue {
+    fn clone(&self) -> Self {
+        Self {
+            message_size: self.message_size,
+            message_align: self.message_align,
+            message_stride: self.message_stride,
+            capacity: self.capacity,
+            queue_order: self.queue_order,
+            storage_offset: self.storage_offset,
+        }
+    }
+}
+
+impl Copy for CfgBuilderMessageQueue {}
+
+impl CfgBuilderMessageQueue {
+    /// Get the size of the ring buffer, measured in bytes.
+    pub(super) const fn storage_len(&self) -> usize {
+        self.message_stride
+            .checked_mul(self.capacity)
+            .expect("message queue storage is too large")
+    }
+
+    pub const fn to_state<System: Port>(&self) -> message_queue::MessageQueueCb<System> {
+        message_queue::MessageQueueCb {
+            storage_offset: self.storage_offset,
+            message_size: self.message_size,
+            message_stride: self.message_stride,
+            capacity: self.capacity,
+            head: CpuLockCell::new(0),
+            len: CpuLockCell::new(0),
+            send_wait_queue: wait::WaitQueue::new(self.queue_order),
+            receive_wait_queue: wait::WaitQueue::new(self.queue_order),
+        }
+    }
+}
@@ -39,6 +39,7 @@ impl CfgBuilderMutex {
                 // The default value is implementation-defined
                 _ => None,
             },
+            inherit: matches!(self.protocol, MutexProtocol::Inherit),
             inconsistent: CpuLockCell::new(false),
             wait_queue: wait::WaitQueue::new(wait::QueueOrder::TaskPriority),
             prev_mutex_held: CpuLockCell::new(None),
@@ -8,7 +8,7 @@ use r3_core::{
     utils::Init,
 };
 
-use crate::{cfg::CfgBuilder, klock::CpuLockCell, task, KernelCfg1, KernelTraits};
+use crate::{cfg::CfgBuilder, klock::CpuLockCell, task, timeout, KernelCfg1, KernelTraits};
 
 unsafe impl<Traits: KernelTraits> const CfgTask for CfgBuilder<Traits> {
     fn task_define<Properties: ~const r3_core::bag::Bag>(
@@ -19,6 +19,9 @@ unsafe impl<Traits: KernelTraits> const CfgTask for CfgBuilder<Traits> {
             active,
             priority,
             stack_size,
+            max_queued_activations,
+            time_slice,
+            relative_deadline,
         }: TaskDescriptor<Self::System>,
         properties: Properties,
     ) -> task::TaskId {
@@ -36,11 +39,35 @@ unsafe impl<Traits: KernelTraits> const CfgTask for CfgBuilder<Traits> {
             stack = task::StackHunk::from_hunk(hunk.hunk(), stack_size);
         }
 
+        let time_slice = if let Some(time_slice) = time_slice {
+            // `Result::expect` is not `const fn` yet [ref:const_result_expect]
+            match timeout::time32_from_duration(time_slice) {
+                Ok(x) if x > 0 => Some(x),
+                _ => panic!("`time_slice` must be positive"),
+            }
+        } else {
+            None
+        };
+
+        let relative_deadline = if let Some(relative_deadline) = relative_deadline {
+            // `Result::expect` is not `const fn` yet [ref:const_result_expect]
+            if let Ok(x) = timeout::time32_from_duration(relative_deadline) {
+                Some(x)
+            } else {
+                panic!("`relative_deadline` must not be negative")
+            }
+        } else {
+            None
+        };
+
         self.tasks.push(CfgBuilderTask {
             start,
             stack,
             priority,
             active,
+            max_queued_activations,
+            time_slice,
+            relative_deadline,
         });
 
         unsafe { NonZeroUsize::new_unchecked(self.tasks.len()) }
@@ -53,6 +80,9 @@ pub struct CfgBuilderTask<Traits: KernelTraits> {
     pub(super) stack: task::StackHunk<Traits>,
     priority: usize,
     active: bool,
+    max_queued_activations: usize,
+    time_slice: Option<timeout::Time32>,
+    relative_deadline: Option<timeout::Time32>,
 }
 
 impl<Traits: KernelTraits> Clone for CfgBuilderTask<Traits> {
@@ -62,6 +92,9 @@ impl<Traits: KernelTraits> Clone for CfgBuilderTask<Traits> {
             stack: self.stack,
             priority: self.priority,
             active: self.active,
+            max_queued_activations: self.max_queued_activations,
+            time_slice: self.time_slice,
+            relative_deadline: self.relative_deadline,
         }
     }
 }
@@ -69,6 +102,24 @@ impl<Traits: KernelTraits> Clone for CfgBuilderTask<Traits> {
 impl<Traits: KernelTraits> Copy for CfgBuilderTask<Traits> {}
 
 impl<Traits: KernelTraits> CfgBuilderTask<Traits> {
+    /// Construct a `CfgBuilderTask` for a task owned by the kernel. The task
+    /// is initially dormant and can have one queued activation request.
+    pub(super) const fn new_kernel_task(
+        start: Closure,
+        stack_size: usize,
+        priority: usize,
+    ) -> Self {
+        Self {
+            start,
+            stack: task::StackHunk::auto(stack_size),
+            priority,
+            active: false,
+            max_queued_activations: 1,
+            time_slice: None,
+            relative_deadline: None,
+        }
+    }
+
     pub const fn to_state(&self, attr: &'static task::TaskAttr<Traits>) -> task::TaskCb<Traits>
     where
         Traits: ~const KernelCfg1,
@@ -89,6 +140,16 @@ impl<Traits: KernelTraits> CfgBuilderTask<Traits> {
             ready_queue_data: Init::INIT,
             wait: Init::INIT,
             park_token: CpuLockCell::new(false),
+            wait_suspended: CpuLockCell::new(false),
+            num_queued_activations: CpuLockCell::new(0),
+            notification_value: CpuLockCell::new(0),
+            notification_pending: CpuLockCell::new(false),
+            // The event time starts at zero, so this is the correct absolute
+            // deadline for a task activated at boot time
+            deadline: CpuLockCell::new(self.relative_deadline),
+            #[cfg(feature = "runtime_stats")]
+            cpu_time: CpuLockCell::new(0),
+            context_stale: CpuLockCell::new(false),
             last_mutex_held: CpuLockCell::new(None),
         }
     }
@@ -104,6 +165,9 @@ impl<Traits: KernelTraits> CfgBuilderTask<Traits> {
             stack: self.stack,
             priority: Traits::to_task_priority(self.priority)
                 .expect("task's `priority` must be less than `num_task_priority_levels`"),
+            max_queued_activations: self.max_queued_activations,
+            time_slice: self.time_slice,
+            relative_deadline: self.relative_deadline,
         }
     }
 }
@@ -1,11 +1,14 @@
 use core::num::NonZeroUsize;
 use r3_core::{
     closure::Closure,
-    kernel::raw_cfg::{CfgTimer, TimerDescriptor},
+    kernel::{
+        raw_cfg::{CfgTimer, TimerDescriptor},
+        StaticTimer, TimerContext,
+    },
     utils::Init,
 };
 
-use crate::{cfg::CfgBuilder, klock::CpuLockCell, timeout, timer, KernelTraits, Port};
+use crate::{cfg::CfgBuilder, klock::CpuLockCell, timeout, timer, KernelTraits, Port, System};
 
 unsafe impl<Traits: KernelTraits> const CfgTimer for CfgBuilder<Traits> {
     fn timer_define<Properties: ~const r3_core::bag::Bag>(
@@ -16,6 +19,7 @@ unsafe impl<Traits: KernelTraits> const CfgTimer for CfgBuilder<Traits> {
             delay,
             active,
             start,
+            context,
         }: TimerDescriptor<Self::System>,
         _properties: Properties,
     ) -> timer::TimerId {
@@ -43,11 +47,24 @@ unsafe impl<Traits: KernelTraits> const CfgTimer for CfgBuilder<Traits> {
             timeout::BAD_DURATION32
         };
 
+        let deferred = match context {
+            TimerContext::Interrupt => false,
+            TimerContext::Task {
+                priority,
+                stack_size,
+            } => {
+                self.timer_service.add_timer(priority, stack_size);
+                true
+            }
+            _ => panic!("unsupported timer context"),
+        };
+
         self.timers.push(CfgBuilderTimer {
             start,
             delay,
             period,
             active,
+            deferred,
         });
 
         unsafe { NonZeroUsize::new_unchecked(self.timers.len()) }
@@ -60,6 +77,7 @@ pub struct CfgBuilderTimer {
     delay: timeout::Time32,
     period: timeout::Time32,
     active: bool,
+    deferred: bool,
 }
 
 impl Clone for CfgBuilderTimer {
@@ -69,6 +87,7 @@ impl Clone for CfgBuilderTimer {
             delay: self.delay,
             period: self.period,
             active: self.active,
+            deferred: self.deferred,
         }
     }
 }
@@ -95,6 +114,7 @@ impl CfgBuilderTimer {
             timeout,
             period: CpuLockCell::new(self.period),
             active: CpuLockCell::new(false),
+            overruns: CpuLockCell::new(0),
         }
     }
 
@@ -102,7 +122,75 @@ impl CfgBuilderTimer {
         timer::TimerAttr {
             entry_point: self.start,
             init_active: self.active,
+            deferred: self.deferred,
             _phantom: Init::INIT,
         }
     }
 }
+
+/// The configuration of the timer service task, which is shared by all timers
+/// defined with [`TimerContext::Task`].
+#[doc(hidden)]
+pub struct CfgBuilderTimerService<Traits: KernelTraits> {
+    /// The number of timers using the service task.
+    pub(super) num_timers: usize,
+    /// The highest (numerically lowest) priority requested by the timers.
+    pub(super) priority: usize,
+    /// The largest stack size requested by the timers.
+    pub(super) stack_size: usize,
+    /// The queue capacity specified by
+    /// [`CfgBuilder::timer_service_queue_capacity`].
+    pub(super) queue_capacity: Option<usize>,
+    /// The hunk offset of the queue.
+    pub queue_offset: usize,
+    /// The index of the service task in [`crate::KernelCfg2::task_cb_pool`].
+    pub task: usize,
+    pub overrun_hook: Option<fn(StaticTimer<System<Traits>>)>,
+}
+
+impl<Traits: KernelTraits> Clone for CfgBuilderTimerService<Traits> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Traits: KernelTraits> Copy for CfgBuilderTimerService<Traits> {}
+
+impl<Traits: KernelTraits> CfgBuilderTimerService<Traits> {
+    pub(super) const fn new() -> Self {
+        Self {
+            num_timers: 0,
+            priority: usize::MAX,
+            stack_size: 0,
+            queue_capacity: None,
+            queue_offset: 0,
+            task: 0,
+            overrun_hook: None,
+        }
+    }
+
+    const fn add_timer(&mut self, priority: usize, stack_size: Option<usize>) {
+        let stack_size = stack_size.unwrap_or(Traits::STACK_DEFAULT_SIZE);
+
+        self.num_timers += 1;
+        if priority < self.priority {
+            self.priority = priority;
+        }
+        if stack_size > self.stack_size {
+            self.stack_size = stack_size;
+        }
+    }
+
+    /// Get the number of elements in the queue. `0` means the service task is
+    /// not used.
+    pub const fn queue_capacity(&self) -> usize {
+        if self.num_timers == 0 {
+            0
+        } else if let Some(x) = self.queue_capacity {
+            x
+        } else {
+            // Each timer can have one outstanding expiration without overrun
+            self.num_timers
+        }
+    }
+}
@@ -72,18 +72,31 @@ define_suberror! {
     #[into(errors::ExitTaskError)]
     #[into(errors::GetCurrentTaskError)]
     #[into(errors::GetEventGroupError)]
+    #[into(errors::GetMemoryPoolError)]
+    #[into(errors::GetMemoryPoolTimeoutError)]
     #[into(errors::GetSemaphoreError)]
+    #[into(errors::GetTaskCpuTimeError)]
     #[into(errors::GetTaskPriorityError)]
+    #[into(errors::GetTaskStackHighWaterMarkError)]
     #[into(errors::InterruptTaskError)]
+    #[into(errors::LoadSnapshotError)]
     #[into(errors::LockMutexError)]
     #[into(errors::LockMutexTimeoutError)]
     #[into(errors::MarkConsistentMutexError)]
+    #[into(errors::NotifyTaskError)]
     #[into(errors::ParkError)]
     #[into(errors::ParkTimeoutError)]
     #[into(errors::PollEventGroupError)]
     #[into(errors::PollSemaphoreError)]
     #[into(errors::QueryMutexError)]
+    #[into(errors::ReceiveMessageQueueError)]
+    #[into(errors::ReceiveMessageQueueTimeoutError)]
+    #[into(errors::ReleaseMemoryPoolError)]
+    #[into(errors::ResumeTaskError)]
+    #[into(errors::SendMessageQueueError)]
+    #[into(errors::SendMessageQueueTimeoutError)]
     #[into(errors::SetInterruptLinePriorityError)]
+    #[into(errors::SetTaskDeadlineError)]
     #[into(errors::SetTaskPriorityError)]
     #[into(errors::SetTimerDelayError)]
     #[into(errors::SetTimerPeriodError)]
@@ -91,14 +104,23 @@ define_suberror! {
     #[into(errors::SleepError)]
     #[into(errors::StartTimerError)]
     #[into(errors::StopTimerError)]
+    #[into(errors::SuspendTaskError)]
+    #[into(errors::TerminateTaskError)]
     #[into(errors::TimeError)]
+    #[into(errors::TryGetMemoryPoolError)]
     #[into(errors::TryLockMutexError)]
+    #[into(errors::TryReceiveMessageQueueError)]
+    #[into(errors::TrySendMessageQueueError)]
     #[into(errors::UnlockMutexError)]
     #[into(errors::UnparkError)]
     #[into(errors::UnparkExactError)]
     #[into(errors::UpdateEventGroupError)]
+    #[into(errors::WaitAnyError)]
+    #[into(errors::WaitAnyTimeoutError)]
     #[into(errors::WaitEventGroupError)]
     #[into(errors::WaitEventGroupTimeoutError)]
+    #[into(errors::WaitNotificationError)]
+    #[into(errors::WaitNotificationTimeoutError)]
     #[into(errors::WaitSemaphoreError)]
     #[into(errors::WaitSemaphoreTimeoutError)]
     pub(super) enum BadContextError {
@@ -111,26 +133,45 @@ define_suberror! {
     #[into(errors::ActivateTaskError)]
     #[into(errors::DrainSemaphoreError)]
     #[into(errors::GetEventGroupError)]
+    #[into(errors::GetMemoryPoolError)]
+    #[into(errors::GetMemoryPoolTimeoutError)]
     #[into(errors::GetSemaphoreError)]
+    #[into(errors::GetTaskCpuTimeError)]
     #[into(errors::GetTaskPriorityError)]
+    #[into(errors::GetTaskStackHighWaterMarkError)]
     #[into(errors::InterruptTaskError)]
     #[into(errors::LockMutexError)]
     #[into(errors::LockMutexTimeoutError)]
     #[into(errors::MarkConsistentMutexError)]
+    #[into(errors::NotifyTaskError)]
     #[into(errors::PollEventGroupError)]
     #[into(errors::PollSemaphoreError)]
     #[into(errors::QueryMutexError)]
+    #[into(errors::ReceiveMessageQueueError)]
+    #[into(errors::ReceiveMessageQueueTimeoutError)]
+    #[into(errors::ReleaseMemoryPoolError)]
+    #[into(errors::ResumeTaskError)]
+    #[into(errors::SendMessageQueueError)]
+    #[into(errors::SendMessageQueueTimeoutError)]
+    #[into(errors::SetTaskDeadlineError)]
     #[into(errors::SetTaskPriorityError)]
     #[into(errors::SetTimerDelayError)]
     #[into(errors::SetTimerPeriodError)]
     #[into(errors::SignalSemaphoreError)]
     #[into(errors::StartTimerError)]
     #[into(errors::StopTimerError)]
+    #[into(errors::SuspendTaskError)]
+    #[into(errors::TerminateTaskError)]
+    #[into(errors::TryGetMemoryPoolError)]
     #[into(errors::TryLockMutexError)]
+    #[into(errors::TryReceiveMessageQueueError)]
+    #[into(errors::TrySendMessageQueueError)]
     #[into(errors::UnlockMutexError)]
     #[into(errors::UnparkError)]
     #[into(errors::UnparkExactError)]
     #[into(errors::UpdateEventGroupError)]
+    #[into(errors::WaitAnyError)]
+    #[into(errors::WaitAnyTimeoutError)]
     #[into(errors::WaitEventGroupError)]
     #[into(errors::WaitEventGroupTimeoutError)]
     #[into(errors::WaitSemaphoreError)]
@@ -142,12 +183,19 @@ define_suberror! {
 
 define_suberror! {
     /// `BadParam`
+    #[into(errors::GetMemoryPoolTimeoutError)]
     #[into(errors::LockMutexTimeoutError)]
     #[into(errors::ParkTimeoutError)]
+    #[into(errors::ReceiveMessageQueueTimeoutError)]
+    #[into(errors::SendMessageQueueTimeoutError)]
+    #[into(errors::SetTaskDeadlineError)]
     #[into(errors::SetTimerDelayError)]
     #[into(errors::SetTimerPeriodError)]
     #[into(errors::SleepError)]
+    #[into(errors::WaitAnyError)]
+    #[into(errors::WaitAnyTimeoutError)]
     #[into(errors::WaitEventGroupTimeoutError)]
+    #[into(errors::WaitNotificationTimeoutError)]
     #[into(errors::WaitSemaphoreTimeoutError)]
     pub(super) enum BadParamError {
         BadParam,
@@ -25,7 +25,7 @@ impl<Traits: KernelTraits> System<Traits> {
     ///
     /// See [`crate::bad_id`].
     #[inline]
-    unsafe fn event_group_cb(
+    pub(super) unsafe fn event_group_cb(
         this: EventGroupId,
     ) -> Result<&'static EventGroupCb<Traits>, NoAccessError> {
         Traits::get_event_group_cb(this.get() - 1).ok_or_else(|| unsafe { crate::bad_id() })
@@ -182,7 +182,7 @@ fn wait<Traits: KernelTraits>(
     }
 }
 
-fn wait_timeout<Traits: KernelTraits>(
+pub(super) fn wait_timeout<Traits: KernelTraits>(
     event_group_cb: &'static EventGroupCb<Traits>,
     mut lock: klock::CpuLockGuard<Traits>,
     bits: EventGroupBits,
@@ -219,7 +219,7 @@ fn wait_timeout<Traits: KernelTraits>(
 /// If `event_group_bits` satisfies the wait condition, this function clears
 /// some bits `event_group_bits` (if requested by `flags), and returns
 /// `Some(original_value)`. Otherwise, it returns `None`.
-fn poll_core(
+pub(super) fn poll_core(
     event_group_bits: &mut EventGroupBits,
     bits: EventGroupBits,
     flags: EventGroupWaitFlags,
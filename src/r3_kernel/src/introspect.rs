@@ -0,0 +1,468 @@
+//! Runtime kernel object introspection
+//!
+//! **This module is exempt from the API stability guarantee.**
+//!
+//! The functions in this module take snapshots of kernel objects and return
+//! them as plain structs, which can be used to implement, e.g., a shell command
+//! listing tasks or a crash dump. Each snapshot is taken with CPU Lock active,
+//! so it's internally consistent, but two snapshots taken by separate calls
+//! may not be consistent with each other.
+//!
+//! The functions fail with [`Error::BadContext`] if CPU Lock is already active.
+//!
+//! [`KernelDebugPrinter`](crate::KernelDebugPrinter) provides a `Debug`-based
+//! alternative that prints the whole kernel state.
+use core::ptr;
+use num_traits::ToPrimitive;
+use r3_core::{
+    kernel::{EventGroupBits, MutexProtocol, SemaphoreValue},
+    time::Duration,
+};
+
+use crate::{
+    error::BadContextError,
+    klock,
+    task::TaskSt,
+    timeout::{self, BAD_DURATION32},
+    wait, Id, KernelTraits,
+};
+
+/// Error type for the functions in this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// CPU Lock is active.
+    BadContext,
+    /// The specified object ID is out of range.
+    BadId,
+}
+
+impl From<BadContextError> for Error {
+    #[inline]
+    fn from(_: BadContextError) -> Self {
+        Self::BadContext
+    }
+}
+
+/// The state of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaskState {
+    Dormant,
+    Ready,
+    Running,
+    Waiting,
+    Suspended,
+    /// The task is in the Waiting state and suspended at the same time.
+    WaitingSuspended,
+}
+
+/// An object a task is waiting on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WaitObject {
+    /// The task is waiting on the event group with the specified ID.
+    EventGroup(Id),
+    /// The task is waiting on the semaphore with the specified ID.
+    Semaphore(Id),
+    /// The task is waiting to lock the mutex with the specified ID.
+    Mutex(Id),
+    /// The task is waiting to send a message to the message queue with the
+    /// specified ID.
+    MessageQueueSend(Id),
+    /// The task is waiting to receive a message from the message queue with
+    /// the specified ID.
+    MessageQueueReceive(Id),
+    /// The task is waiting to allocate a block from the memory pool with the
+    /// specified ID.
+    MemoryPool(Id),
+    /// The task is waiting for a park token.
+    Park,
+    /// The task is waiting for a notification.
+    Notification,
+    /// The task is sleeping.
+    Sleep,
+}
+
+/// A snapshot of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TaskInfo {
+    pub id: Id,
+    pub state: TaskState,
+    /// The task's base priority. Unspecified if the task is in the Dormant
+    /// state.
+    pub base_priority: usize,
+    /// The task's effective priority. Unspecified if the task is in the
+    /// Dormant state.
+    pub effective_priority: usize,
+    /// The object the task is waiting on. If the task is waiting on multiple
+    /// objects, this is the first one. Use [`task_wait_objects`] to get all of
+    /// them.
+    pub wait: Option<WaitObject>,
+    /// The remaining time until the ongoing wait operation times out.
+    pub timeout: Option<Duration>,
+}
+
+/// A snapshot of a mutex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MutexInfo {
+    pub id: Id,
+    pub protocol: MutexProtocol,
+    /// The task currently owning the mutex.
+    pub owner: Option<Id>,
+    /// `true` if the mutex is marked as inconsistent.
+    pub inconsistent: bool,
+    pub num_waiting_tasks: usize,
+}
+
+/// A snapshot of a semaphore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SemaphoreInfo {
+    pub id: Id,
+    pub value: SemaphoreValue,
+    pub max_value: SemaphoreValue,
+    pub num_waiting_tasks: usize,
+}
+
+/// A snapshot of an event group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EventGroupInfo {
+    pub id: Id,
+    pub bits: EventGroupBits,
+    pub num_waiting_tasks: usize,
+}
+
+/// A snapshot of a message queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MessageQueueInfo {
+    pub id: Id,
+    pub message_size: usize,
+    pub capacity: usize,
+    /// The number of queued messages.
+    pub len: usize,
+    pub num_waiting_senders: usize,
+    pub num_waiting_receivers: usize,
+}
+
+/// A snapshot of a memory pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MemoryPoolInfo {
+    pub id: Id,
+    pub num_blocks: usize,
+    pub num_free: usize,
+    pub num_waiting_tasks: usize,
+}
+
+/// A snapshot of a timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TimerInfo {
+    pub id: Id,
+    /// `true` if the timer is in the Active state.
+    pub active: bool,
+    /// The remaining time until the timer fires. If the timer is in the
+    /// Dormant state, this is the delay to be used when it's started.
+    pub delay: Option<Duration>,
+    pub period: Option<Duration>,
+    /// The number of expirations dropped because the timer service task's
+    /// queue was full. Always `0` for a timer whose callback function is
+    /// called in an interrupt context.
+    pub num_overruns: usize,
+}
+
+/// Take a snapshot of the task with the specified ID.
+pub fn task<Traits: KernelTraits>(id: Id) -> Result<TaskInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let task_cb = Traits::get_task_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    let state = match *task_cb.st.read(&*lock) {
+        TaskSt::Dormant => TaskState::Dormant,
+        TaskSt::Ready | TaskSt::PendingActivation => TaskState::Ready,
+        TaskSt::Running => TaskState::Running,
+        TaskSt::Waiting if task_cb.wait_suspended.get(&*lock) => TaskState::WaitingSuspended,
+        TaskSt::Waiting => TaskState::Waiting,
+        TaskSt::Suspended => TaskState::Suspended,
+    };
+
+    let wait = wait::with_current_wait_targets(lock.borrow_mut(), task_cb, |mut targets| {
+        targets.find_map(|(wait_queue, payload)| wait_object(wait_queue, payload))
+    });
+    let timeout =
+        wait::current_wait_remaining_time(lock.borrow_mut(), task_cb).map(duration_from_time32);
+
+    Ok(TaskInfo {
+        id,
+        state,
+        base_priority: task_cb.base_priority.read(&*lock).to_usize().unwrap(),
+        effective_priority: task_cb.effective_priority.read(&*lock).to_usize().unwrap(),
+        wait,
+        timeout,
+    })
+}
+
+/// Take a snapshot of each task.
+pub fn tasks<Traits: KernelTraits>() -> impl Iterator<Item = Result<TaskInfo, Error>> {
+    ids(Traits::task_cb_pool().len()).map(task::<Traits>)
+}
+
+/// Store the objects the specified task is waiting on to the beginning of
+/// `out` and return the total number of such objects, which may be greater
+/// than `out.len()`.
+pub fn task_wait_objects<Traits: KernelTraits>(
+    id: Id,
+    out: &mut [Option<WaitObject>],
+) -> Result<usize, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let task_cb = Traits::get_task_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    let len = wait::with_current_wait_targets(lock.borrow_mut(), task_cb, |targets| {
+        fill(
+            out,
+            targets.filter_map(|(wait_queue, payload)| wait_object(wait_queue, payload)),
+        )
+    });
+    Ok(len)
+}
+
+/// Store the IDs of the mutexes held by the specified task to the beginning
+/// of `out` and return the total number of such mutexes, which may be greater
+/// than `out.len()`. The mutexes are listed in the reverse locking order.
+pub fn task_held_mutexes<Traits: KernelTraits>(
+    id: Id,
+    out: &mut [Option<Id>],
+) -> Result<usize, Error> {
+    let lock = klock::lock_cpu::<Traits>()?;
+    let task_cb = Traits::get_task_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    let mutexes = core::iter::successors(task_cb.last_mutex_held.get(&*lock), |mutex_cb| {
+        mutex_cb.prev_mutex_held.get(&*lock)
+    })
+    .map(|mutex_cb| object_id(Traits::mutex_cb_pool(), mutex_cb));
+    Ok(fill(out, mutexes))
+}
+
+/// Take a snapshot of the mutex with the specified ID.
+pub fn mutex<Traits: KernelTraits>(id: Id) -> Result<MutexInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let mutex_cb = Traits::get_mutex_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    let protocol = match (mutex_cb.ceiling, mutex_cb.inherit) {
+        (Some(ceiling), _) => MutexProtocol::Ceiling(ceiling.to_usize().unwrap()),
+        (None, true) => MutexProtocol::Inherit,
+        (None, false) => MutexProtocol::None,
+    };
+
+    Ok(MutexInfo {
+        id,
+        protocol,
+        owner: mutex_cb
+            .owning_task
+            .get(&*lock)
+            .map(|task_cb| object_id(Traits::task_cb_pool(), task_cb)),
+        inconsistent: mutex_cb.inconsistent.get(&*lock),
+        num_waiting_tasks: mutex_cb.wait_queue.num_waits(lock.borrow_mut()),
+    })
+}
+
+/// Take a snapshot of each mutex.
+pub fn mutexes<Traits: KernelTraits>() -> impl Iterator<Item = Result<MutexInfo, Error>> {
+    ids(Traits::mutex_cb_pool().len()).map(mutex::<Traits>)
+}
+
+/// Take a snapshot of the semaphore with the specified ID.
+pub fn semaphore<Traits: KernelTraits>(id: Id) -> Result<SemaphoreInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let semaphore_cb = Traits::get_semaphore_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    Ok(SemaphoreInfo {
+        id,
+        value: semaphore_cb.value.get(&*lock),
+        max_value: semaphore_cb.max_value,
+        num_waiting_tasks: semaphore_cb.wait_queue.num_waits(lock.borrow_mut()),
+    })
+}
+
+/// Take a snapshot of each semaphore.
+pub fn semaphores<Traits: KernelTraits>() -> impl Iterator<Item = Result<SemaphoreInfo, Error>> {
+    ids(Traits::semaphore_cb_pool().len()).map(semaphore::<Traits>)
+}
+
+/// Take a snapshot of the event group with the specified ID.
+pub fn event_group<Traits: KernelTraits>(id: Id) -> Result<EventGroupInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let event_group_cb = Traits::get_event_group_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    Ok(EventGroupInfo {
+        id,
+        bits: event_group_cb.bits.get(&*lock),
+        num_waiting_tasks: event_group_cb.wait_queue.num_waits(lock.borrow_mut()),
+    })
+}
+
+/// Take a snapshot of each event group.
+pub fn event_groups<Traits: KernelTraits>() -> impl Iterator<Item = Result<EventGroupInfo, Error>> {
+    ids(Traits::event_group_cb_pool().len()).map(event_group::<Traits>)
+}
+
+/// Take a snapshot of the message queue with the specified ID.
+pub fn message_queue<Traits: KernelTraits>(id: Id) -> Result<MessageQueueInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let message_queue_cb = Traits::get_message_queue_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    Ok(MessageQueueInfo {
+        id,
+        message_size: message_queue_cb.message_size,
+        capacity: message_queue_cb.capacity,
+        len: message_queue_cb.len.get(&*lock),
+        num_waiting_senders: message_queue_cb
+            .send_wait_queue
+            .num_waits(lock.borrow_mut()),
+        num_waiting_receivers: message_queue_cb
+            .receive_wait_queue
+            .num_waits(lock.borrow_mut()),
+    })
+}
+
+/// Take a snapshot of each message queue.
+pub fn message_queues<Traits: KernelTraits>(
+) -> impl Iterator<Item = Result<MessageQueueInfo, Error>> {
+    ids(Traits::message_queue_cb_pool().len()).map(message_queue::<Traits>)
+}
+
+/// Take a snapshot of the memory pool with the specified ID.
+pub fn memory_pool<Traits: KernelTraits>(id: Id) -> Result<MemoryPoolInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let memory_pool_cb = Traits::get_memory_pool_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    Ok(MemoryPoolInfo {
+        id,
+        num_blocks: memory_pool_cb.num_blocks,
+        num_free: memory_pool_cb.num_free.get(&*lock),
+        num_waiting_tasks: memory_pool_cb.wait_queue.num_waits(lock.borrow_mut()),
+    })
+}
+
+/// Take a snapshot of each memory pool.
+pub fn memory_pools<Traits: KernelTraits>() -> impl Iterator<Item = Result<MemoryPoolInfo, Error>> {
+    ids(Traits::memory_pool_cb_pool().len()).map(memory_pool::<Traits>)
+}
+
+/// Take a snapshot of the timer with the specified ID.
+pub fn timer<Traits: KernelTraits>(id: Id) -> Result<TimerInfo, Error> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    let timer_cb = Traits::get_timer_cb(id.get() - 1).ok_or(Error::BadId)?;
+
+    // See the documentation of `TimerCb::timeout`
+    let delay = if timer_cb.timeout.is_linked(lock.borrow_mut()) {
+        timer_cb
+            .timeout
+            .saturating_duration_until_timeout(lock.borrow_mut())
+    } else {
+        timer_cb.timeout.at_raw(lock.borrow_mut())
+    };
+    let period = timer_cb.period.get(&*lock);
+
+    Ok(TimerInfo {
+        id,
+        active: timer_cb.active.get(&*lock),
+        delay: (delay != BAD_DURATION32).then(|| duration_from_time32(delay)),
+        period: (period != BAD_DURATION32).then(|| duration_from_time32(period)),
+        num_overruns: timer_cb.overruns.get(&*lock),
+    })
+}
+
+/// Take a snapshot of each timer.
+pub fn timers<Traits: KernelTraits>() -> impl Iterator<Item = Result<TimerInfo, Error>> {
+    ids(Traits::timer_cb_pool().len()).map(timer::<Traits>)
+}
+
+/// Get the IDs of `len` objects.
+fn ids(len: usize) -> impl Iterator<Item = Id> {
+    (1..=len).map(|i| Id::new(i).unwrap())
+}
+
+/// Get the ID of the object `cb`, which must be an element of `pool`.
+fn object_id<T>(pool: &'static [T], cb: &T) -> Id {
+    // Safety: `cb` refers to an element of `pool`
+    let offset = unsafe { <*const T>::offset_from(cb, pool.as_ptr()) };
+
+    Id::new(offset as usize + 1).unwrap()
+}
+
+/// Store the elements of `items` to the beginning of `out` and return the
+/// total number of the elements.
+fn fill<T>(out: &mut [Option<T>], items: impl Iterator<Item = T>) -> usize {
+    let mut len = 0;
+    for item in items {
+        if let Some(slot) = out.get_mut(len) {
+            *slot = Some(item);
+        }
+        len += 1;
+    }
+    len
+}
+
+/// Find the object a wait object pertains to.
+fn wait_object<Traits: KernelTraits>(
+    wait_queue: Option<&'static wait::WaitQueue<Traits>>,
+    payload: &wait::WaitPayload<Traits>,
+) -> Option<WaitObject> {
+    /// Find the object containing `wait_queue` in `pool`.
+    fn find<T, Traits: KernelTraits>(
+        pool: &'static [T],
+        wait_queue: Option<&wait::WaitQueue<Traits>>,
+        get_wait_queue: impl Fn(&T) -> &wait::WaitQueue<Traits>,
+    ) -> Option<Id> {
+        let wait_queue = wait_queue?;
+        let i = pool
+            .iter()
+            .position(|cb| ptr::eq(get_wait_queue(cb), wait_queue))?;
+        Some(Id::new(i + 1).unwrap())
+    }
+
+    use wait::WaitPayload;
+    Some(match payload {
+        WaitPayload::EventGroupBits { .. } => {
+            WaitObject::EventGroup(find(Traits::event_group_cb_pool(), wait_queue, |cb| {
+                &cb.wait_queue
+            })?)
+        }
+        WaitPayload::Semaphore => {
+            WaitObject::Semaphore(find(Traits::semaphore_cb_pool(), wait_queue, |cb| {
+                &cb.wait_queue
+            })?)
+        }
+        WaitPayload::Mutex(mutex_cb) => {
+            WaitObject::Mutex(object_id(Traits::mutex_cb_pool(), *mutex_cb))
+        }
+        WaitPayload::MessageQueueSend(_) => {
+            WaitObject::MessageQueueSend(find(Traits::message_queue_cb_pool(), wait_queue, |cb| {
+                &cb.send_wait_queue
+            })?)
+        }
+        WaitPayload::MessageQueueReceive(_) => WaitObject::MessageQueueReceive(find(
+            Traits::message_queue_cb_pool(),
+            wait_queue,
+            |cb| &cb.receive_wait_queue,
+        )?),
+        WaitPayload::MemoryPool(_) => {
+            WaitObject::MemoryPool(find(Traits::memory_pool_cb_pool(), wait_queue, |cb| {
+                &cb.wait_queue
+            })?)
+        }
+        WaitPayload::Park => WaitObject::Park,
+        WaitPayload::Notification => WaitObject::Notification,
+        WaitPayload::Sleep => WaitObject::Sleep,
+        WaitPayload::__Nonexhaustive => return None,
+    })
+}
+
+fn duration_from_time32(x: timeout::Time32) -> Duration {
+    Duration::from_micros(x.try_into().unwrap_or(i32::MAX))
+}
@@ -5,6 +5,7 @@
 #![feature(maybe_uninit_array_assume_init)]
 #![feature(const_intoiterator_identity)]
 #![feature(maybe_uninit_uninit_array)]
+#![feature(array_try_map)]
 #![feature(const_precise_live_drops)]
 #![feature(const_raw_ptr_comparison)]
 #![feature(cfg_target_has_atomic)] // `#[cfg(target_has_atomic_load_store)]`
@@ -69,9 +70,10 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use core::{fmt, marker::PhantomData, mem::forget, num::NonZeroUsize, ops::Range};
 
 use r3_core::{
+    closure::Closure,
     kernel::{
         cfg::{DelegateKernelStatic, KernelStatic},
-        raw,
+        hook, raw,
     },
     time::{Duration, Time},
     utils::Init,
@@ -84,17 +86,31 @@ pub mod cfg;
 mod error;
 mod event_group;
 mod interrupt;
+pub mod introspect;
 mod klock;
+mod memory_pool;
+mod message_queue;
 mod mutex;
+#[cfg(feature = "runtime_stats")]
+mod runtime_stats;
 mod semaphore;
 mod state;
 mod task;
 mod timeout;
 mod timer;
+#[cfg(feature = "trace")]
+#[doc(cfg(feature = "trace"))]
+pub mod trace;
 mod wait;
+mod wait_any;
+#[cfg(feature = "system_time")]
+mod wait_until;
 
 // Some of these re-exports are for our macros, the others are really public
-pub use {event_group::*, interrupt::*, mutex::*, semaphore::*, task::*, timeout::*, timer::*};
+pub use {
+    event_group::*, interrupt::*, memory_pool::*, message_queue::*, mutex::*, semaphore::*,
+    task::*, timeout::*, timer::*,
+};
 
 /// Numeric value used to identify various kinds of kernel objects.
 pub type Id = NonZeroUsize;
@@ -298,6 +314,96 @@ unsafe impl<Traits: KernelTraits> raw::KernelTaskSetPriority for System<Traits>
     }
 }
 
+unsafe impl<Traits: KernelTraits> raw::KernelTaskSetDeadline for System<Traits> {
+    #[inline]
+    unsafe fn raw_task_set_deadline(
+        this: Self::RawTaskId,
+        deadline: Duration,
+    ) -> Result<(), r3_core::kernel::SetTaskDeadlineError> {
+        Self::task_set_deadline(this, deadline)
+    }
+}
+
+#[cfg(feature = "runtime_stats")]
+unsafe impl<Traits: KernelTraits> raw::KernelRuntimeStats for System<Traits> {
+    #[inline]
+    unsafe fn raw_task_cpu_time(
+        this: Self::RawTaskId,
+    ) -> Result<u64, r3_core::kernel::GetTaskCpuTimeError> {
+        Self::task_cpu_time(this)
+    }
+
+    #[inline]
+    fn raw_load_snapshot(
+    ) -> Result<r3_core::kernel::LoadSnapshot, r3_core::kernel::LoadSnapshotError> {
+        Self::load_snapshot()
+    }
+}
+
+#[cfg(feature = "stack_check")]
+unsafe impl<Traits: KernelTraits> raw::KernelTaskStackHighWaterMark for System<Traits> {
+    #[inline]
+    unsafe fn raw_task_stack_high_water_mark(
+        this: Self::RawTaskId,
+    ) -> Result<usize, r3_core::kernel::GetTaskStackHighWaterMarkError> {
+        Self::task_stack_high_water_mark(this)
+    }
+}
+
+unsafe impl<Traits: KernelTraits> raw::KernelTaskSuspend for System<Traits> {
+    #[inline]
+    unsafe fn raw_task_suspend(
+        this: Self::RawTaskId,
+    ) -> Result<(), r3_core::kernel::SuspendTaskError> {
+        Self::task_suspend(this)
+    }
+
+    #[inline]
+    unsafe fn raw_task_resume(
+        this: Self::RawTaskId,
+    ) -> Result<(), r3_core::kernel::ResumeTaskError> {
+        Self::task_resume(this)
+    }
+}
+
+unsafe impl<Traits: KernelTraits> raw::KernelTaskTerminate for System<Traits> {
+    #[inline]
+    unsafe fn raw_task_terminate(
+        this: Self::RawTaskId,
+    ) -> Result<(), r3_core::kernel::TerminateTaskError> {
+        Self::task_terminate(this)
+    }
+}
+
+unsafe impl<Traits: KernelTraits> raw::KernelTaskNotify for System<Traits> {
+    #[inline]
+    unsafe fn raw_task_notify(
+        this: Self::RawTaskId,
+        value: r3_core::kernel::NotificationValue,
+        action: r3_core::kernel::NotifyAction,
+    ) -> Result<(), r3_core::kernel::NotifyTaskError> {
+        Self::task_notify(this, value, action)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    fn raw_wait_notification(
+        clear_on_entry: r3_core::kernel::NotificationValue,
+        clear_on_exit: r3_core::kernel::NotificationValue,
+    ) -> Result<r3_core::kernel::NotificationValue, r3_core::kernel::WaitNotificationError> {
+        task::wait_notification::<Traits>(clear_on_entry, clear_on_exit)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    fn raw_wait_notification_timeout(
+        clear_on_entry: r3_core::kernel::NotificationValue,
+        clear_on_exit: r3_core::kernel::NotificationValue,
+        timeout: Duration,
+    ) -> Result<r3_core::kernel::NotificationValue, r3_core::kernel::WaitNotificationTimeoutError>
+    {
+        task::wait_notification_timeout::<Traits>(clear_on_entry, clear_on_exit, timeout)
+    }
+}
+
 #[cfg(feature = "priority_boost")]
 #[doc(cfg(feature = "priority_boost"))]
 unsafe impl<Traits: KernelTraits> raw::KernelBoostPriority for System<Traits> {
@@ -353,6 +459,11 @@ impl<T: KernelTraits> fmt::Debug for KernelDebugPrinter<T> {
             )
             .field("mutex_cb_pool", &PoolPrinter(T::mutex_cb_pool()))
             .field("semaphore_cb_pool", &PoolPrinter(T::semaphore_cb_pool()))
+            .field(
+                "message_queue_cb_pool",
+                &PoolPrinter(T::message_queue_cb_pool()),
+            )
+            .field("memory_pool_cb_pool", &PoolPrinter(T::memory_pool_cb_pool()))
             .field("timer_cb_pool", &PoolPrinter(T::timer_cb_pool()))
             .finish()
     }
@@ -455,9 +566,14 @@ pub unsafe trait PortThreading: KernelCfg1 + KernelStatic<System<Self>> {
     /// already been removed from `*state.`[`running_task_ptr`]`()`) and proceed
     /// to the dispatcher.
     ///
+    /// The dispatcher may call [`initialize_task_state`] for `task` (this
+    /// happens when `task` has a queued activation request), so the port must
+    /// not use `task`'s stack once it has left this method.
+    ///
     /// Precondition: CPU Lock active
     ///
     /// [`running_task_ptr`]: State::running_task_ptr
+    /// [`initialize_task_state`]: Self::initialize_task_state
     unsafe fn exit_and_dispatch(task: &'static task::TaskCb<Self>) -> !;
 
     /// Disable all kernel-managed interrupts (this state is called *CPU Lock*).
@@ -674,6 +790,43 @@ pub trait PortTimer {
     unsafe fn pend_tick() {
         unsafe { Self::pend_tick_after(1) };
     }
+
+    /// The frequency of [`runtime_count`] in hertz. Must be greater than zero.
+    ///
+    /// The default value corresponds to the default implementation of
+    /// `runtime_count`.
+    ///
+    /// [`runtime_count`]: Self::runtime_count
+    const RUNTIME_COUNT_FREQUENCY: u64 = 1_000_000;
+
+    /// The maximum value that [`runtime_count`] can return.
+    ///
+    /// The default value corresponds to the default implementation of
+    /// `runtime_count`.
+    ///
+    /// [`runtime_count`]: Self::runtime_count
+    const MAX_RUNTIME_COUNT: u64 = Self::MAX_TICK_COUNT as u64;
+
+    /// Read the high-resolution counter used to measure the processor time
+    /// consumed by each task. Only used when the `runtime_stats` feature is
+    /// enabled.
+    ///
+    /// This value steadily increases over time at the rate of
+    /// [`RUNTIME_COUNT_FREQUENCY`]. When it goes past [`MAX_RUNTIME_COUNT`], it
+    /// “wraps around” to `0`. It must not wrap around more than once in
+    /// [`MAX_TIMEOUT`] microseconds.
+    ///
+    /// The default implementation returns [`tick_count`].
+    ///
+    /// Precondition: CPU Lock active
+    ///
+    /// [`RUNTIME_COUNT_FREQUENCY`]: Self::RUNTIME_COUNT_FREQUENCY
+    /// [`MAX_RUNTIME_COUNT`]: Self::MAX_RUNTIME_COUNT
+    /// [`MAX_TIMEOUT`]: Self::MAX_TIMEOUT
+    /// [`tick_count`]: Self::tick_count
+    unsafe fn runtime_count() -> u64 {
+        unsafe { Self::tick_count() as u64 }
+    }
 }
 
 /// Unsigned integer type representing a tick count used by
@@ -708,6 +861,22 @@ pub trait PortToKernel {
     /// Precondition: CPU Lock active / Postcondition: CPU Lock active
     unsafe fn choose_running_task();
 
+    /// Called by a port's dispatcher before [`choose_running_task`] with the
+    /// stack pointer saved for the running task (the task about to leave the
+    /// processor) to check it for a stack overflow.
+    ///
+    /// If the `stack_check` feature is enabled, this method calls the stack
+    /// overflow hook if `sp` is outside the running task's stack region or
+    /// inside its canary. This catches a stack frame that skipped over the
+    /// canary. Otherwise, or if there's no running task, this method does
+    /// nothing. Ports that run tasks on the stacks allocated by the kernel
+    /// are encouraged but not required to call this method.
+    ///
+    /// [`choose_running_task`]: Self::choose_running_task
+    ///
+    /// Precondition: CPU Lock active
+    unsafe fn check_stack_pointer(sp: usize);
+
     /// Called by [a port timer driver] to “announce” new ticks.
     ///
     /// This method can be called anytime, but the driver is expected to attempt
@@ -723,6 +892,24 @@ pub trait PortToKernel {
     ///
     /// Precondition: CPU Lock inactive, an interrupt context
     unsafe fn timer_tick();
+
+    /// Called by a port's first-level interrupt handler before calling a
+    /// second-level interrupt handler from [`KernelCfg2::INTERRUPT_HANDLERS`].
+    ///
+    /// This method records the event in the trace buffer if the `trace`
+    /// feature is enabled. Otherwise, it does nothing. Ports are encouraged
+    /// but not required to call this method.
+    ///
+    /// Precondition: An interrupt context
+    unsafe fn interrupt_enter(line: raw::InterruptNum);
+
+    /// Called by a port's first-level interrupt handler after a second-level
+    /// interrupt handler returns. See [`interrupt_enter`] for details.
+    ///
+    /// Precondition: An interrupt context
+    ///
+    /// [`interrupt_enter`]: Self::interrupt_enter
+    unsafe fn interrupt_exit(line: raw::InterruptNum);
 }
 
 impl<Traits: KernelTraits> PortToKernel for Traits {
@@ -730,6 +917,10 @@ impl<Traits: KernelTraits> PortToKernel for Traits {
     unsafe fn boot() -> ! {
         let mut lock = unsafe { klock::assume_cpu_lock::<Traits>() };
 
+        // Initialize the trace buffer before recording any events
+        #[cfg(feature = "trace")]
+        trace::init(lock.borrow_mut());
+
         // Initialize all tasks
         for cb in Traits::task_cb_pool() {
             task::init_task(lock.borrow_mut(), cb);
@@ -738,6 +929,10 @@ impl<Traits: KernelTraits> PortToKernel for Traits {
         // Initialize the timekeeping system
         Traits::state().timeout.init(lock.borrow_mut());
 
+        // Start the processor time accounting
+        #[cfg(feature = "runtime_stats")]
+        runtime_stats::init(lock.borrow_mut());
+
         for cb in Traits::timer_cb_pool() {
             timer::init_timer(lock.borrow_mut(), cb);
         }
@@ -775,10 +970,37 @@ impl<Traits: KernelTraits> PortToKernel for Traits {
         forget(lock);
     }
 
+    #[inline(always)]
+    unsafe fn check_stack_pointer(_sp: usize) {
+        #[cfg(feature = "stack_check")]
+        {
+            // Safety: The precondition of this method includes CPU Lock being
+            // active
+            let mut lock = unsafe { klock::assume_cpu_lock::<Traits>() };
+
+            task::check_stack_pointer(lock.borrow_mut(), _sp);
+
+            // Post-condition: CPU Lock active
+            forget(lock);
+        }
+    }
+
     #[inline(always)]
     unsafe fn timer_tick() {
         timeout::handle_tick::<Traits>();
     }
+
+    #[inline(always)]
+    unsafe fn interrupt_enter(_line: raw::InterruptNum) {
+        #[cfg(feature = "trace")]
+        trace::interrupt::<Traits>(_line, trace::EventKind::InterruptEnter);
+    }
+
+    #[inline(always)]
+    unsafe fn interrupt_exit(_line: raw::InterruptNum) {
+        #[cfg(feature = "trace")]
+        trace::interrupt::<Traits>(_line, trace::EventKind::InterruptExit);
+    }
 }
 
 /// Associates "system" types with kernel-private data. Use [`build!`] to
@@ -807,6 +1029,49 @@ pub unsafe trait KernelCfg2: Port + Sized + KernelStatic<System<Self>> {
     #[doc(hidden)]
     const STARTUP_HOOK: unsafe fn();
 
+    /// The idle hooks set through `CfgBase`, in the execution order.
+    #[doc(hidden)]
+    const IDLE_HOOKS: &'static [Closure];
+
+    /// The task switch hooks set through `CfgBase`, in the execution order.
+    #[doc(hidden)]
+    const TASK_SWITCH_HOOKS: &'static [hook::TaskSwitchHookFn<System<Self>>];
+
+    /// The task exit hooks set through `CfgBase`, in the execution order.
+    #[doc(hidden)]
+    const TASK_EXIT_HOOKS: &'static [hook::TaskExitHookFn<System<Self>>];
+
+    /// The stack overflow hook set through `CfgBuilder::stack_overflow_hook`.
+    #[doc(hidden)]
+    const STACK_OVERFLOW_HOOK: Option<fn(r3_core::kernel::StaticTask<System<Self>>)>;
+
+    /// The number of record slots in the trace buffer allocated by
+    /// `CfgBuilder::trace_buffer`. `0` if there's no trace buffer.
+    #[doc(hidden)]
+    const TRACE_BUFFER_CAPACITY: usize;
+
+    /// The hunk offset of the trace buffer.
+    #[doc(hidden)]
+    const TRACE_BUFFER_OFFSET: usize;
+
+    /// The index of the timer service task in [`Self::task_cb_pool`]. Only
+    /// meaningful if [`Self::TIMER_SERVICE_QUEUE_CAPACITY`] is non-zero.
+    #[doc(hidden)]
+    const TIMER_SERVICE_TASK: usize;
+
+    /// The number of elements in the timer service task's queue. `0` if
+    /// there's no timer service task.
+    #[doc(hidden)]
+    const TIMER_SERVICE_QUEUE_CAPACITY: usize;
+
+    /// The hunk offset of the timer service task's queue.
+    #[doc(hidden)]
+    const TIMER_SERVICE_QUEUE_OFFSET: usize;
+
+    /// The timer overrun hook set through `CfgBuilder::timer_overrun_hook`.
+    #[doc(hidden)]
+    const TIMER_OVERRUN_HOOK: Option<fn(r3_core::kernel::StaticTimer<System<Self>>)>;
+
     /// Access the kernel's global state.
     fn state() -> &'static State<Self>;
 
@@ -853,6 +1118,26 @@ pub unsafe trait KernelCfg2: Port + Sized + KernelStatic<System<Self>> {
         Self::semaphore_cb_pool().get(i)
     }
 
+    // This can't be `const` because of [ref:const_static_item_ref]
+    #[doc(hidden)]
+    fn message_queue_cb_pool() -> &'static [MessageQueueCb<Self>];
+
+    #[doc(hidden)]
+    #[inline(always)]
+    fn get_message_queue_cb(i: usize) -> Option<&'static MessageQueueCb<Self>> {
+        Self::message_queue_cb_pool().get(i)
+    }
+
+    // This can't be `const` because of [ref:const_static_item_ref]
+    #[doc(hidden)]
+    fn memory_pool_cb_pool() -> &'static [MemoryPoolCb<Self>];
+
+    #[doc(hidden)]
+    #[inline(always)]
+    fn get_memory_pool_cb(i: usize) -> Option<&'static MemoryPoolCb<Self>> {
+        Self::memory_pool_cb_pool().get(i)
+    }
+
     // This can't be `const` because of [ref:const_static_item_ref]
     #[doc(hidden)]
     fn timer_cb_pool() -> &'static [TimerCb<Self>];
@@ -880,6 +1165,12 @@ pub struct State<
     running_task:
         klock::CpuLockCell<Traits, Option<&'static TaskCb<Traits, PortTaskState, TaskPriority>>>,
 
+    /// The task that has just exited, erasing `running_task`. It's taken by
+    /// the next call to `choose_running_task` to report it to task switch
+    /// hooks as the previously running task.
+    exited_task:
+        klock::CpuLockCell<Traits, Option<&'static TaskCb<Traits, PortTaskState, TaskPriority>>>,
+
     /// The task ready queue.
     task_ready_queue: TaskReadyQueue,
 
@@ -889,10 +1180,22 @@ pub struct State<
 
     /// The global state of the timekeeping system.
     timeout: timeout::TimeoutGlobals<Traits, TimeoutHeap>,
+
+    /// The timeout object used to enforce the time slice of `running_task`.
+    /// Linked iff `running_task` is in the Running state and has a time
+    /// slice.
+    time_slice_timeout: timeout::Timeout<Traits>,
+
+    /// The global state of the processor time accounting.
+    #[cfg(feature = "runtime_stats")]
+    runtime_stats: runtime_stats::RuntimeStatsGlobals<Traits>,
+
+    /// The global state of the timer service task.
+    timer_service: timer::service::TimerServiceGlobals<Traits>,
 }
 
 impl<
-        Traits: KernelCfg2,
+        Traits: KernelTraits,
         PortTaskState: 'static,
         TaskReadyQueue: 'static + Init,
         TaskPriority: 'static,
@@ -901,10 +1204,15 @@ impl<
 {
     const INIT: Self = Self {
         running_task: klock::CpuLockCell::new(None),
+        exited_task: klock::CpuLockCell::new(None),
         task_ready_queue: Init::INIT,
         #[cfg(feature = "priority_boost")]
         priority_boost: AtomicBool::new(false),
         timeout: Init::INIT,
+        time_slice_timeout: task::time_slice::time_slice_timeout(),
+        #[cfg(feature = "runtime_stats")]
+        runtime_stats: Init::INIT,
+        timer_service: Init::INIT,
     };
 }
 
@@ -919,6 +1227,7 @@ impl<
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("State")
             .field("running_task", &self.running_task.get_and_debug_fmt())
+            .field("exited_task", &self.exited_task.get_and_debug_fmt())
             .field("task_ready_queue", &self.task_ready_queue)
             .field(
                 "priority_boost",
@@ -930,6 +1239,17 @@ impl<
                 },
             )
             .field("timeout", &self.timeout)
+            .field("time_slice_timeout", &self.time_slice_timeout)
+            .field(
+                "runtime_stats",
+                match () {
+                    #[cfg(feature = "runtime_stats")]
+                    () => &self.runtime_stats,
+                    #[cfg(not(feature = "runtime_stats"))]
+                    () => &(),
+                },
+            )
+            .field("timer_service", &self.timer_service)
             .finish()
     }
 }
@@ -0,0 +1,263 @@
+//! Memory pools
+use core::fmt;
+use r3_core::{
+    kernel::{
+        GetMemoryPoolError, GetMemoryPoolTimeoutError, Hunk, ReleaseMemoryPoolError,
+        TryGetMemoryPoolError,
+    },
+    time::Duration,
+    utils::Init,
+};
+
+use crate::{
+    error::NoAccessError,
+    klock, state, task, timeout,
+    wait::{self, WaitPayload, WaitQueue},
+    Id, KernelTraits, Port, System,
+};
+
+pub(super) type MemoryPoolId = Id;
+
+impl<Traits: KernelTraits> System<Traits> {
+    /// Get the [`MemoryPoolCb`] for the specified raw ID.
+    ///
+    /// # Safety
+    ///
+    /// See [`crate::bad_id`].
+    #[inline]
+    pub(super) unsafe fn memory_pool_cb(
+        this: MemoryPoolId,
+    ) -> Result<&'static MemoryPoolCb<Traits>, NoAccessError> {
+        Traits::get_memory_pool_cb(this.get() - 1).ok_or_else(|| unsafe { crate::bad_id() })
+    }
+}
+
+unsafe impl<Traits: KernelTraits> r3_core::kernel::raw::KernelMemoryPool for System<Traits> {
+    type RawMemoryPoolId = MemoryPoolId;
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_memory_pool_get(this: MemoryPoolId) -> Result<usize, GetMemoryPoolError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let memory_pool_cb = unsafe { Self::memory_pool_cb(this)? };
+
+        get(memory_pool_cb, lock)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_memory_pool_get_timeout(
+        this: MemoryPoolId,
+        timeout: Duration,
+    ) -> Result<usize, GetMemoryPoolTimeoutError> {
+        let time32 = timeout::time32_from_duration(timeout)?;
+        let lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let memory_pool_cb = unsafe { Self::memory_pool_cb(this)? };
+
+        get_timeout(memory_pool_cb, lock, time32)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_memory_pool_try_get(this: MemoryPoolId) -> Result<usize, TryGetMemoryPoolError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let memory_pool_cb = unsafe { Self::memory_pool_cb(this)? };
+
+        poll_core(memory_pool_cb, lock.borrow_mut()).ok_or(TryGetMemoryPoolError::Timeout)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_memory_pool_release(
+        this: MemoryPoolId,
+        block: usize,
+    ) -> Result<(), ReleaseMemoryPoolError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let memory_pool_cb = unsafe { Self::memory_pool_cb(this)? };
+
+        release(memory_pool_cb, lock, block)
+    }
+}
+
+/// *Memory pool control block* - the state data of a memory pool.
+///
+/// The blocks in a memory pool are classified into the following three
+/// categories:
+///
+///  - Blocks in range `0..num_used_ever` that have been allocated at least once
+///    and are currently in use.
+///  - Blocks in range `0..num_used_ever` that have been allocated at least once
+///    and then released. Their indices are stored in the free list.
+///  - Blocks in range `num_used_ever..num_blocks` that have never been
+///    allocated.
+///
+/// This arrangement makes it unnecessary to initialize the free list at boot
+/// time.
+#[doc(hidden)]
+pub struct MemoryPoolCb<Traits: Port> {
+    /// The number of blocks.
+    pub(super) num_blocks: usize,
+    /// The offset of the free list (`[usize; num_blocks]`) in the hunk pool.
+    pub(super) free_list_offset: usize,
+
+    /// The number of blocks that have been allocated at least once.
+    pub(super) num_used_ever: klock::CpuLockCell<Traits, usize>,
+    /// The number of elements in the free list.
+    pub(super) num_free: klock::CpuLockCell<Traits, usize>,
+
+    pub(super) wait_queue: WaitQueue<Traits>,
+}
+
+impl<Traits: Port> Init for MemoryPoolCb<Traits> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        num_blocks: 0,
+        free_list_offset: 0,
+        num_used_ever: Init::INIT,
+        num_free: Init::INIT,
+        wait_queue: Init::INIT,
+    };
+}
+
+impl<Traits: KernelTraits> fmt::Debug for MemoryPoolCb<Traits> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryPoolCb")
+            .field("self", &(self as *const _))
+            .field("num_blocks", &self.num_blocks)
+            .field("free_list", &self.free_list_ptr())
+            .field("num_used_ever", &self.num_used_ever)
+            .field("num_free", &self.num_free)
+            .field("wait_queue", &self.wait_queue)
+            .finish()
+    }
+}
+
+impl<Traits: KernelTraits> MemoryPoolCb<Traits> {
+    /// Get a pointer to the first element of the free list.
+    #[inline]
+    fn free_list_ptr(&self) -> *mut usize {
+        // FIXME: `Hunk::from_offset` is an implementation detail
+        Hunk::<System<Traits>>::from_offset(self.free_list_offset)
+            .as_ptr()
+            .cast()
+    }
+}
+
+/// Allocate a block without blocking.
+#[inline]
+fn poll_core<Traits: KernelTraits>(
+    memory_pool_cb: &'static MemoryPoolCb<Traits>,
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+) -> Option<usize> {
+    let num_free = memory_pool_cb.num_free.get(&*lock);
+    if num_free > 0 {
+        memory_pool_cb.num_free.replace(&mut *lock, num_free - 1);
+        // Safety: `num_free - 1 < num_blocks`, so it's inside the free list
+        return Some(unsafe { *memory_pool_cb.free_list_ptr().add(num_free - 1) });
+    }
+
+    let num_used_ever = memory_pool_cb.num_used_ever.get(&*lock);
+    if num_used_ever < memory_pool_cb.num_blocks {
+        memory_pool_cb
+            .num_used_ever
+            .replace(&mut *lock, num_used_ever + 1);
+        return Some(num_used_ever);
+    }
+
+    None
+}
+
+/// Implements [`StaticMemoryPool::get`].
+///
+/// [`StaticMemoryPool::get`]: r3_core::kernel::StaticMemoryPool::get
+#[inline]
+fn get<Traits: KernelTraits>(
+    memory_pool_cb: &'static MemoryPoolCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+) -> Result<usize, GetMemoryPoolError> {
+    if let Some(block) = poll_core(memory_pool_cb, lock.borrow_mut()) {
+        return Ok(block);
+    }
+
+    // The current state does not satify the wait condition. In this case,
+    // start waiting. The wake-upper is responsible for storing the index of a
+    // block to `block`.
+    let mut block = 0;
+    memory_pool_cb
+        .wait_queue
+        .wait(lock.borrow_mut(), WaitPayload::MemoryPool(&mut block))?;
+
+    Ok(block)
+}
+
+/// Implements [`StaticMemoryPool::get_timeout`].
+///
+/// [`StaticMemoryPool::get_timeout`]: r3_core::kernel::StaticMemoryPool::get_timeout
+#[inline]
+pub(super) fn get_timeout<Traits: KernelTraits>(
+    memory_pool_cb: &'static MemoryPoolCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    time32: timeout::Time32,
+) -> Result<usize, GetMemoryPoolTimeoutError> {
+    if let Some(block) = poll_core(memory_pool_cb, lock.borrow_mut()) {
+        return Ok(block);
+    }
+
+    // The current state does not satify the wait condition. In this case,
+    // start waiting. The wake-upper is responsible for storing the index of a
+    // block to `block`.
+    let mut block = 0;
+    memory_pool_cb.wait_queue.wait_timeout(
+        lock.borrow_mut(),
+        WaitPayload::MemoryPool(&mut block),
+        time32,
+    )?;
+
+    Ok(block)
+}
+
+/// Implements [`KernelMemoryPool::raw_memory_pool_release`].
+///
+/// [`KernelMemoryPool::raw_memory_pool_release`]: r3_core::kernel::raw::KernelMemoryPool::raw_memory_pool_release
+#[inline]
+fn release<Traits: KernelTraits>(
+    memory_pool_cb: &'static MemoryPoolCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    block: usize,
+) -> Result<(), ReleaseMemoryPoolError> {
+    let num_used_ever = memory_pool_cb.num_used_ever.get(&*lock);
+    let num_free = memory_pool_cb.num_free.get(&*lock);
+
+    // Reject the blocks that have never been allocated. Also, if all blocks
+    // are free, the block can't possibly be in use.
+    if block >= num_used_ever || num_free >= num_used_ever {
+        return Err(ReleaseMemoryPoolError::BadParam);
+    }
+
+    // If there's a waiting task, hand over the block directly
+    if let Some(task_cb) = memory_pool_cb
+        .wait_queue
+        .first_waiting_task(lock.borrow_mut())
+    {
+        wait::with_current_wait_payload(lock.borrow_mut(), task_cb, |payload| {
+            let Some(&WaitPayload::MemoryPool(dst)) = payload
+            else { unreachable!() };
+            // Safety: The waiting task's storage is valid while it's waiting
+            unsafe { *dst = block };
+        });
+
+        let woke_up = memory_pool_cb.wait_queue.wake_up_one(lock.borrow_mut());
+        debug_assert!(woke_up);
+
+        task::unlock_cpu_and_check_preemption(lock);
+    } else {
+        // Safety: `num_free < num_used_ever <= num_blocks`, so it's inside the
+        //         free list
+        unsafe { *memory_pool_cb.free_list_ptr().add(num_free) = block };
+        memory_pool_cb.num_free.replace(&mut *lock, num_free + 1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,473 @@
+//! Message queues
+use core::fmt;
+use r3_core::{
+    kernel::{
+        Hunk, ReceiveMessageQueueError, ReceiveMessageQueueTimeoutError, SendMessageQueueError,
+        SendMessageQueueTimeoutError, TryReceiveMessageQueueError, TrySendMessageQueueError,
+    },
+    time::Duration,
+    utils::Init,
+};
+
+use crate::{
+    error::NoAccessError,
+    klock, state, task, timeout,
+    wait::{WaitPayload, WaitQueue},
+    Id, KernelTraits, Port, System,
+};
+
+pub(super) type MessageQueueId = Id;
+
+impl<Traits: KernelTraits> System<Traits> {
+    /// Get the [`MessageQueueCb`] for the specified raw ID.
+    ///
+    /// # Safety
+    ///
+    /// See [`crate::bad_id`].
+    #[inline]
+    pub(super) unsafe fn message_queue_cb(
+        this: MessageQueueId,
+    ) -> Result<&'static MessageQueueCb<Traits>, NoAccessError> {
+        Traits::get_message_queue_cb(this.get() - 1).ok_or_else(|| unsafe { crate::bad_id() })
+    }
+}
+
+unsafe impl<Traits: KernelTraits> r3_core::kernel::raw::KernelMessageQueue for System<Traits> {
+    type RawMessageQueueId = MessageQueueId;
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_send(
+        this: MessageQueueId,
+        message: *const u8,
+    ) -> Result<(), SendMessageQueueError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { send(message_queue_cb, lock, message) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_send_timeout(
+        this: MessageQueueId,
+        message: *const u8,
+        timeout: Duration,
+    ) -> Result<(), SendMessageQueueTimeoutError> {
+        let time32 = timeout::time32_from_duration(timeout)?;
+        let lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { send_timeout(message_queue_cb, lock, message, time32) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_try_send(
+        this: MessageQueueId,
+        message: *const u8,
+    ) -> Result<(), TrySendMessageQueueError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { try_send(message_queue_cb, lock, message) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_receive(
+        this: MessageQueueId,
+        message: *mut u8,
+    ) -> Result<(), ReceiveMessageQueueError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { receive(message_queue_cb, lock, message) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_receive_timeout(
+        this: MessageQueueId,
+        message: *mut u8,
+        timeout: Duration,
+    ) -> Result<(), ReceiveMessageQueueTimeoutError> {
+        let time32 = timeout::time32_from_duration(timeout)?;
+        let lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { receive_timeout(message_queue_cb, lock, message, time32) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_try_receive(
+        this: MessageQueueId,
+        message: *mut u8,
+    ) -> Result<(), TryReceiveMessageQueueError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { try_receive(message_queue_cb, lock, message) }
+    }
+}
+
+/// *Message queue control block* - the state data of a message queue.
+///
+/// The messages are stored in a ring buffer located in the hunk pool. The
+/// following invariants hold whenever CPU Lock is inactive:
+///
+///  - If `receive_wait_queue` is not empty, the ring buffer is empty, and
+///    `send_wait_queue` is empty.
+///  - If `send_wait_queue` is not empty, the ring buffer is full.
+#[doc(hidden)]
+pub struct MessageQueueCb<Traits: Port> {
+    /// The offset of the ring buffer in the hunk pool.
+    pub(super) storage_offset: usize,
+    /// The size of each message.
+    pub(super) message_size: usize,
+    /// The distance between adjacent slots in the ring buffer.
+    pub(super) message_stride: usize,
+    /// The number of slots in the ring buffer.
+    pub(super) capacity: usize,
+
+    /// The index of the oldest message in the ring buffer.
+    pub(super) head: klock::CpuLockCell<Traits, usize>,
+    /// The number of messages in the ring buffer.
+    pub(super) len: klock::CpuLockCell<Traits, usize>,
+
+    pub(super) send_wait_queue: WaitQueue<Traits>,
+    pub(super) receive_wait_queue: WaitQueue<Traits>,
+}
+
+impl<Traits: Port> Init for MessageQueueCb<Traits> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        storage_offset: 0,
+        message_size: 0,
+        message_stride: 0,
+        capacity: 0,
+        head: Init::INIT,
+        len: Init::INIT,
+        send_wait_queue: Init::INIT,
+        receive_wait_queue: Init::INIT,
+    };
+}
+
+impl<Traits: KernelTraits> fmt::Debug for MessageQueueCb<Traits> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MessageQueueCb")
+            .field("self", &(self as *const _))
+            .field("storage", &self.slot_ptr(0))
+            .field("message_size", &self.message_size)
+            .field("capacity", &self.capacity)
+            .field("head", &self.head)
+            .field("len", &self.len)
+            .field("send_wait_queue", &self.send_wait_queue)
+            .field("receive_wait_queue", &self.receive_wait_queue)
+            .finish()
+    }
+}
+
+impl<Traits: KernelTraits> MessageQueueCb<Traits> {
+    /// Get a pointer to the `i`-th slot of the ring buffer.
+    #[inline]
+    fn slot_ptr(&self, i: usize) -> *mut u8 {
+        // FIXME: `Hunk::from_offset` is an implementation detail
+        Hunk::<System<Traits>>::from_offset(self.storage_offset)
+            .as_ptr()
+            .wrapping_add(i * self.message_stride)
+    }
+}
+
+/// Copy a message from `src` to `dst`.
+///
+/// # Safety
+///
+/// `src` and `dst` must be valid for reads and writes of `message_size` bytes,
+/// respectively, and must not overlap.
+#[inline]
+unsafe fn copy_message<Traits: KernelTraits>(
+    message_queue_cb: &MessageQueueCb<Traits>,
+    src: *const u8,
+    dst: *mut u8,
+) {
+    unsafe { core::ptr::copy_nonoverlapping(src, dst, message_queue_cb.message_size) };
+}
+
+/// Take the message from the first task waiting in `send_wait_queue` and wake
+/// up the task. Returns `false` if there's no such task.
+///
+/// This function may make a task Ready, but doesn't yield the processor.
+/// Call `unlock_cpu_and_check_preemption` as needed.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of `message_size` bytes.
+#[inline]
+unsafe fn take_message_from_waiting_sender<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    dst: *mut u8,
+) -> bool {
+    let has_sender = message_queue_cb
+        .send_wait_queue
+        .with_first_wait_payload(lock.borrow_mut(), |payload| {
+            let &WaitPayload::MessageQueueSend(src) = payload
+            else { unreachable!() };
+            // Safety: The waiting sender's message is valid while it's waiting.
+            //         The caller is responsible for ensuring `dst` is valid.
+            unsafe { copy_message(message_queue_cb, src, dst) };
+        })
+        .is_some();
+
+    if !has_sender {
+        return false;
+    }
+
+    let woke_up = message_queue_cb.send_wait_queue.wake_up_one(lock);
+    debug_assert!(woke_up);
+
+    true
+}
+
+/// Deliver a message without blocking. Returns `false` if the message queue is
+/// full and there's no task waiting to receive a message.
+///
+/// This function may make a task Ready, but doesn't yield the processor.
+/// Call `unlock_cpu_and_check_preemption` as needed.
+///
+/// # Safety
+///
+/// `message` must be valid for reads of `message_size` bytes.
+#[inline]
+unsafe fn poll_send_core<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    message: *const u8,
+) -> bool {
+    // If there's a waiting receiver, hand over the message directly
+    //
+    // The receiver might be waiting on multiple objects (`wait_any`), so access
+    // the payload through the wait queue rather than the task.
+    let has_receiver = message_queue_cb
+        .receive_wait_queue
+        .with_first_wait_payload(lock.borrow_mut(), |payload| {
+            let &WaitPayload::MessageQueueReceive(dst) = payload
+            else { unreachable!() };
+            // Safety: The waiting receiver's storage is valid while it's
+            //         waiting. The caller is responsible for ensuring
+            //         `message` is valid.
+            unsafe { copy_message(message_queue_cb, message, dst) };
+        })
+        .is_some();
+
+    if has_receiver {
+        debug_assert_eq!(message_queue_cb.len.get(&*lock), 0);
+
+        let woke_up = message_queue_cb.receive_wait_queue.wake_up_one(lock);
+        debug_assert!(woke_up);
+
+        return true;
+    }
+
+    // Otherwise, append the message to the ring buffer
+    let len = message_queue_cb.len.get(&*lock);
+    if len >= message_queue_cb.capacity {
+        return false;
+    }
+
+    let head = message_queue_cb.head.get(&*lock);
+    let tail = (head + len) % message_queue_cb.capacity;
+    // Safety: `tail < capacity`, so the slot is inside the ring buffer
+    unsafe { copy_message(message_queue_cb, message, message_queue_cb.slot_ptr(tail)) };
+    message_queue_cb.len.replace(&mut *lock, len + 1);
+
+    true
+}
+
+/// Take a message without blocking. Returns `false` if the message queue is
+/// empty and there's no task waiting to send a message.
+///
+/// This function may make a task Ready, but doesn't yield the processor.
+/// Call `unlock_cpu_and_check_preemption` as needed.
+///
+/// # Safety
+///
+/// `message` must be valid for writes of `message_size` bytes.
+#[inline]
+pub(super) unsafe fn poll_receive_core<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    message: *mut u8,
+) -> bool {
+    let len = message_queue_cb.len.get(&*lock);
+
+    if len == 0 {
+        // The ring buffer is empty. This can still succeed if the message
+        // queue has zero capacity and there's a waiting sender.
+        //
+        // Safety: The caller is responsible for ensuring `message` is valid
+        return unsafe { take_message_from_waiting_sender(message_queue_cb, lock, message) };
+    }
+
+    // Take the oldest message from the ring buffer
+    let head = message_queue_cb.head.get(&*lock);
+    // Safety: `head < capacity`, so the slot is inside the ring buffer
+    unsafe { copy_message(message_queue_cb, message_queue_cb.slot_ptr(head), message) };
+
+    let new_head = (head + 1) % message_queue_cb.capacity;
+    message_queue_cb.head.replace(&mut *lock, new_head);
+
+    // The ring buffer has a vacant slot now. If there's a waiting sender,
+    // move its message into the slot.
+    //
+    // Safety: The slot at `tail` is inside the ring buffer
+    let tail = (new_head + len - 1) % message_queue_cb.capacity;
+    let tail_ptr = message_queue_cb.slot_ptr(tail);
+    if !unsafe { take_message_from_waiting_sender(message_queue_cb, lock.borrow_mut(), tail_ptr) } {
+        message_queue_cb.len.replace(&mut *lock, len - 1);
+    }
+
+    true
+}
+
+/// Implements [`MessageQueueRef::send`].
+///
+/// [`MessageQueueRef::send`]: r3_core::kernel::MessageQueueRef::send
+#[inline]
+unsafe fn send<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    message: *const u8,
+) -> Result<(), SendMessageQueueError> {
+    if unsafe { poll_send_core(message_queue_cb, lock.borrow_mut(), message) } {
+        task::unlock_cpu_and_check_preemption(lock);
+    } else {
+        // The current state does not satify the wait condition. In this case,
+        // start waiting. The wake-upper is responsible for taking the message.
+        message_queue_cb
+            .send_wait_queue
+            .wait(lock.borrow_mut(), WaitPayload::MessageQueueSend(message))?;
+    }
+
+    Ok(())
+}
+
+/// Implements [`MessageQueueRef::send_timeout`].
+///
+/// [`MessageQueueRef::send_timeout`]: r3_core::kernel::MessageQueueRef::send_timeout
+#[inline]
+pub(super) unsafe fn send_timeout<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    message: *const u8,
+    time32: timeout::Time32,
+) -> Result<(), SendMessageQueueTimeoutError> {
+    if unsafe { poll_send_core(message_queue_cb, lock.borrow_mut(), message) } {
+        task::unlock_cpu_and_check_preemption(lock);
+    } else {
+        // The current state does not satify the wait condition. In this case,
+        // start waiting. The wake-upper is responsible for taking the message.
+        message_queue_cb.send_wait_queue.wait_timeout(
+            lock.borrow_mut(),
+            WaitPayload::MessageQueueSend(message),
+            time32,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Implements [`MessageQueueRef::try_send`].
+///
+/// [`MessageQueueRef::try_send`]: r3_core::kernel::MessageQueueRef::try_send
+#[inline]
+unsafe fn try_send<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    message: *const u8,
+) -> Result<(), TrySendMessageQueueError> {
+    if unsafe { poll_send_core(message_queue_cb, lock.borrow_mut(), message) } {
+        task::unlock_cpu_and_check_preemption(lock);
+        Ok(())
+    } else {
+        Err(TrySendMessageQueueError::Timeout)
+    }
+}
+
+/// Implements [`MessageQueueRef::receive`].
+///
+/// [`MessageQueueRef::receive`]: r3_core::kernel::MessageQueueRef::receive
+#[inline]
+unsafe fn receive<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    message: *mut u8,
+) -> Result<(), ReceiveMessageQueueError> {
+    if unsafe { poll_receive_core(message_queue_cb, lock.borrow_mut(), message) } {
+        task::unlock_cpu_and_check_preemption(lock);
+    } else {
+        // The current state does not satify the wait condition. In this case,
+        // start waiting. The wake-upper is responsible for delivering a
+        // message to `message`.
+        message_queue_cb
+            .receive_wait_queue
+            .wait(lock.borrow_mut(), WaitPayload::MessageQueueReceive(message))?;
+    }
+
+    Ok(())
+}
+
+/// Implements [`MessageQueueRef::receive_timeout`].
+///
+/// [`MessageQueueRef::receive_timeout`]: r3_core::kernel::MessageQueueRef::receive_timeout
+#[inline]
+pub(super) unsafe fn receive_timeout<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    message: *mut u8,
+    time32: timeout::Time32,
+) -> Result<(), ReceiveMessageQueueTimeoutError> {
+    if unsafe { poll_receive_core(message_queue_cb, lock.borrow_mut(), message) } {
+        task::unlock_cpu_and_check_preemption(lock);
+    } else {
+        // The current state does not satify the wait condition. In this case,
+        // start waiting. The wake-upper is responsible for delivering a
+        // message to `message`.
+        message_queue_cb.receive_wait_queue.wait_timeout(
+            lock.borrow_mut(),
+            WaitPayload::MessageQueueReceive(message),
+            time32,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Implements [`MessageQueueRef::try_receive`].
+///
+/// [`MessageQueueRef::try_receive`]: r3_core::kernel::MessageQueueRef::try_receive
+#[inline]
+unsafe fn try_receive<Traits: KernelTraits>(
+    message_queue_cb: &'static MessageQueueCb<Traits>,
+    mut lock: klock::CpuLockGuard<Traits>,
+    message: *mut u8,
+) -> Result<(), TryReceiveMessageQueueError> {
+    if unsafe { poll_receive_core(message_queue_cb, lock.borrow_mut(), message) } {
+        task::unlock_cpu_and_check_preemption(lock);
+        Ok(())
+    } else {
+        Err(TryReceiveMessageQueueError::Timeout)
+    }
+}
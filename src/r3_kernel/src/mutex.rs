@@ -1,5 +1,6 @@
 //! ~~Mutices~~ Mutexes
 use core::{assert_matches::debug_assert_matches, fmt};
+use num_traits::ToPrimitive;
 use r3_core::{
     kernel::{
         raw, LockMutexError, LockMutexTimeoutError, MarkConsistentMutexError, QueryMutexError,
@@ -11,8 +12,10 @@ use r3_core::{
 
 use crate::{
     error::{LockMutexPrecheckError, NoAccessError},
-    klock, state, task, timeout,
-    wait::{WaitPayload, WaitQueue},
+    klock, state, task,
+    task::readyqueue::Queue as _,
+    timeout,
+    wait::{self, WaitPayload, WaitQueue},
     Id, KernelCfg1, KernelTraits, PortThreading, System,
 };
 
@@ -25,7 +28,9 @@ impl<Traits: KernelTraits> System<Traits> {
     ///
     /// See [`crate::bad_id`].
     #[inline]
-    unsafe fn mutex_cb(this: MutexId) -> Result<&'static MutexCb<Traits>, NoAccessError> {
+    pub(super) unsafe fn mutex_cb(
+        this: MutexId,
+    ) -> Result<&'static MutexCb<Traits>, NoAccessError> {
         Traits::get_mutex_cb(this.get() - 1).ok_or_else(|| unsafe { crate::bad_id() })
     }
 }
@@ -36,6 +41,7 @@ unsafe impl<Traits: KernelTraits> raw::KernelMutex for System<Traits> {
     const RAW_SUPPORTED_MUTEX_PROTOCOLS: &'static [Option<raw::MutexProtocolKind>] = &[
         Some(raw::MutexProtocolKind::None),
         Some(raw::MutexProtocolKind::Ceiling),
+        Some(raw::MutexProtocolKind::Inherit),
     ];
 
     #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
@@ -112,6 +118,9 @@ pub struct MutexCb<
 > {
     pub(super) ceiling: Option<TaskPriority>,
 
+    /// `true` if the mutex uses the priority inheritance protocol.
+    pub(super) inherit: bool,
+
     pub(super) inconsistent: klock::CpuLockCell<Traits, bool>,
 
     pub(super) wait_queue: WaitQueue<Traits>,
@@ -129,6 +138,7 @@ impl<Traits: PortThreading> Init for MutexCb<Traits> {
     #[allow(clippy::declare_interior_mutable_const)]
     const INIT: Self = Self {
         ceiling: Init::INIT,
+        inherit: false,
         inconsistent: Init::INIT,
         wait_queue: Init::INIT,
         prev_mutex_held: Init::INIT,
@@ -141,6 +151,7 @@ impl<Traits: KernelTraits> fmt::Debug for MutexCb<Traits> {
         f.debug_struct("MutexCb")
             .field("self", &(self as *const _))
             .field("ceiling", &self.ceiling)
+            .field("inherit", &self.inherit)
             .field("inconsistent", &self.inconsistent)
             .field("wait_queue", &self.wait_queue)
             .field(
@@ -239,7 +250,7 @@ pub(super) fn do_held_mutexes_allow_new_task_base_priority<Traits: KernelTraits>
 /// (This method doesn't update [`task::TaskCb::effective_priority`]).
 /// The base priority is assumed to be `base_priority`.
 pub(super) fn evaluate_task_effective_priority<Traits: KernelTraits>(
-    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
     task: &'static task::TaskCb<Traits>,
     base_priority: Traits::TaskPriority,
 ) -> Traits::TaskPriority {
@@ -251,12 +262,159 @@ pub(super) fn evaluate_task_effective_priority<Traits: KernelTraits>(
             effective_priority = effective_priority.min(ceiling);
         }
 
+        if mutex_cb.inherit {
+            // The wait queue is sorted by task priority, so the first waiting
+            // task has the highest effective priority among the waiters
+            if let Some(waiting_task) = mutex_cb.wait_queue.first_waiting_task(lock.borrow_mut()) {
+                effective_priority =
+                    effective_priority.min(waiting_task.effective_priority.get(&*lock));
+            }
+        }
+
         maybe_mutex_cb = mutex_cb.prev_mutex_held.get(&*lock);
     }
 
     effective_priority
 }
 
+/// Reevaluate the effective priority of the task owning the specified mutex
+/// and propagate the change through the chain of mutex owners. This is
+/// necessary after the set of tasks waiting on a mutex or their priorities
+/// change.
+///
+/// This method may change the priority of a task, but doesn't yield the
+/// processor. Call `unlock_cpu_and_check_preemption` as needed.
+pub(super) fn reevaluate_inherited_priority<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    mut mutex_cb: &'static MutexCb<Traits>,
+) {
+    while mutex_cb.inherit {
+        let Some(task) = mutex_cb.owning_task.get(&*lock) else { return };
+
+        let base_priority = task.base_priority.get(&*lock);
+        let effective_priority =
+            evaluate_task_effective_priority(lock.borrow_mut(), task, base_priority);
+
+        match update_task_effective_priority(lock.borrow_mut(), task, effective_priority) {
+            Some(next_mutex_cb) => mutex_cb = next_mutex_cb,
+            None => return,
+        }
+    }
+}
+
+/// Raise the effective priority of the task owning the specified mutex to
+/// `priority` and propagate the change through the chain of mutex owners.
+/// This is a cheaper version of [`reevaluate_inherited_priority`] for when a
+/// new task with priority `priority` is about to wait on the mutex.
+///
+/// This method may change the priority of a task, but doesn't yield the
+/// processor. Call `unlock_cpu_and_check_preemption` as needed.
+fn raise_inherited_priority<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    mut mutex_cb: &'static MutexCb<Traits>,
+    priority: Traits::TaskPriority,
+) {
+    while mutex_cb.inherit {
+        let Some(task) = mutex_cb.owning_task.get(&*lock) else { return };
+
+        if task.effective_priority.get(&*lock) <= priority {
+            // The task already has an equal or higher priority. This also
+            // ensures the termination of this loop in case of a deadlock.
+            return;
+        }
+
+        match update_task_effective_priority(lock.borrow_mut(), task, priority) {
+            Some(next_mutex_cb) => mutex_cb = next_mutex_cb,
+            None => return,
+        }
+    }
+}
+
+/// Assign a new effective priority to the task and reposition the task in the
+/// ready queue or a wait queue accordingly.
+///
+/// Returns the mutex the task is currently waiting on if the effective
+/// priority has changed, in which case the change should be propagated to the
+/// mutex's owner.
+fn update_task_effective_priority<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task: &'static task::TaskCb<Traits>,
+    effective_priority: Traits::TaskPriority,
+) -> Option<&'static MutexCb<Traits>> {
+    let old_effective_priority = task
+        .effective_priority
+        .replace(&mut *lock, effective_priority);
+
+    if old_effective_priority == effective_priority {
+        return None;
+    }
+
+    match *task.st.read(&*lock) {
+        task::TaskSt::Ready => {
+            // Move the task within the ready queue
+            //
+            // Safety: `task` was previously inserted to the ready queue
+            // with an effective priority that is identical to
+            // `old_effective_priority`.
+            unsafe {
+                Traits::state().task_ready_queue.reorder_task(
+                    lock.borrow_mut().into(),
+                    task,
+                    effective_priority.to_usize().unwrap(),
+                    old_effective_priority.to_usize().unwrap(),
+                )
+            };
+            None
+        }
+        task::TaskSt::Waiting => {
+            // Reposition the task in the wait queue
+            wait::reorder_wait_of_task(lock.borrow_mut(), task);
+
+            wait::with_current_wait_payload(lock, task, |payload| {
+                if let Some(&WaitPayload::Mutex(mutex_cb)) = payload {
+                    Some(mutex_cb)
+                } else {
+                    None
+                }
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Check if the running task waiting on the specified mutex would cause a
+/// deadlock, i.e., if the mutex's owner is waiting, directly or through a
+/// chain of mutex owners, on a mutex held by the running task.
+#[cfg(feature = "deadlock_detection")]
+fn would_deadlock<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    mut mutex_cb: &'static MutexCb<Traits>,
+    running_task: &'static task::TaskCb<Traits>,
+) -> bool {
+    // This loop terminates because the chain can't form a cycle. Every task in
+    // the chain has passed this check before it started waiting.
+    loop {
+        let Some(owner) = mutex_cb.owning_task.get(&*lock) else { return false };
+
+        if core::ptr::eq(owner, running_task) {
+            return true;
+        }
+
+        let next_mutex_cb = wait::with_current_wait_payload(lock.borrow_mut(), owner, |payload| {
+            if let Some(&WaitPayload::Mutex(mutex_cb)) = payload {
+                Some(mutex_cb)
+            } else {
+                None
+            }
+        });
+
+        match next_mutex_cb {
+            Some(next_mutex_cb) => mutex_cb = next_mutex_cb,
+            None => return false,
+        }
+    }
+}
+
 /// Check if the current state of a mutex satisfies the wait
 /// condition.
 ///
@@ -303,6 +461,15 @@ fn lock_core<Traits: KernelTraits>(
         let effective_priority = task.effective_priority.write(&mut *lock);
         *effective_priority = (*effective_priority).min(ceiling);
     }
+
+    // If the mutex uses the priority inheritance protocol, `task` should
+    // inherit the priorities of the remaining waiters. However, `task` is
+    // either the running task or the first waiter in the wait queue (which is
+    // sorted by task priority), so none of the remaining waiters can have a
+    // higher priority than `task`.
+
+    #[cfg(feature = "trace")]
+    crate::trace::mutex_lock(lock, mutex_cb, task);
 }
 
 #[inline]
@@ -313,9 +480,20 @@ fn lock_mutex<Traits: KernelTraits>(
     let running_task = precheck_and_get_running_task(lock.borrow_mut(), mutex_cb)?;
 
     if !poll_core(mutex_cb, running_task, lock.borrow_mut()) {
+        #[cfg(feature = "deadlock_detection")]
+        if would_deadlock(lock.borrow_mut(), mutex_cb, running_task) {
+            return Err(LockMutexError::Deadlock);
+        }
+
         // The current state does not satify the wait condition. In this case,
         // start waiting. The wake-upper is responsible for using `poll_core`
         // to complete the effect of the wait operation.
+        //
+        // If the mutex uses the priority inheritance protocol, the owner
+        // inherits the current task's priority first.
+        let priority = running_task.effective_priority.get(&*lock);
+        raise_inherited_priority(lock.borrow_mut(), mutex_cb, priority);
+
         mutex_cb
             .wait_queue
             .wait(lock.borrow_mut(), WaitPayload::Mutex(mutex_cb))?;
@@ -347,7 +525,7 @@ fn try_lock_mutex<Traits: KernelTraits>(
 }
 
 #[inline]
-fn lock_mutex_timeout<Traits: KernelTraits>(
+pub(super) fn lock_mutex_timeout<Traits: KernelTraits>(
     mutex_cb: &'static MutexCb<Traits>,
     mut lock: klock::CpuLockGuard<Traits>,
     time32: timeout::Time32,
@@ -355,9 +533,20 @@ fn lock_mutex_timeout<Traits: KernelTraits>(
     let running_task = precheck_and_get_running_task(lock.borrow_mut(), mutex_cb)?;
 
     if !poll_core(mutex_cb, running_task, lock.borrow_mut()) {
+        #[cfg(feature = "deadlock_detection")]
+        if would_deadlock(lock.borrow_mut(), mutex_cb, running_task) {
+            return Err(LockMutexTimeoutError::Deadlock);
+        }
+
         // The current state does not satify the wait condition. In this case,
         // start waiting. The wake-upper is responsible for using `poll_core`
         // to complete the effect of the wait operation.
+        //
+        // If the mutex uses the priority inheritance protocol, the owner
+        // inherits the current task's priority first.
+        let priority = running_task.effective_priority.get(&*lock);
+        raise_inherited_priority(lock.borrow_mut(), mutex_cb, priority);
+
         mutex_cb.wait_queue.wait_timeout(
             lock.borrow_mut(),
             WaitPayload::Mutex(mutex_cb),
@@ -437,6 +626,9 @@ fn unlock_mutex_unchecked<Traits: KernelTraits>(
     mutex_cb: &'static MutexCb<Traits>,
     mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
 ) {
+    #[cfg(feature = "trace")]
+    crate::trace::mutex_unlock(lock.borrow_mut(), mutex_cb);
+
     // Check if there's any other tasks waiting on the mutex
     if let Some(next_task) = mutex_cb.wait_queue.first_waiting_task(lock.borrow_mut()) {
         // Give the ownership of the mutex to `next_task`
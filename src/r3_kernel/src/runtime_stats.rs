@@ -0,0 +1,138 @@
+//! Processor time accounting
+//!
+//! The elapsed time is measured by [`PortTimer::runtime_count`] and attributed
+//! to the running task (or the idle state if there's none) whenever the
+//! running task changes. It's also sampled on every timer tick so that the
+//! counter never wraps around more than once between two samples.
+use core::fmt;
+use r3_core::kernel::{GetTaskCpuTimeError, LoadSnapshot, LoadSnapshotError};
+
+use crate::{
+    klock::{self, CpuLockCell},
+    task,
+    utils::Init,
+    KernelTraits, PortTimer, System,
+};
+
+/// A kernel-global state for processor time accounting.
+pub(super) struct RuntimeStatsGlobals<Traits> {
+    /// The value of [`PortTimer::runtime_count`] at the last sample.
+    last_count: CpuLockCell<Traits, u64>,
+
+    /// The total processor time spent by tasks, measured in
+    /// [`PortTimer::runtime_count`] units.
+    busy_time: CpuLockCell<Traits, u64>,
+
+    /// The total processor time spent while no task was running, measured in
+    /// [`PortTimer::runtime_count`] units.
+    idle_time: CpuLockCell<Traits, u64>,
+}
+
+impl<Traits> Init for RuntimeStatsGlobals<Traits> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        last_count: Init::INIT,
+        busy_time: Init::INIT,
+        idle_time: Init::INIT,
+    };
+}
+
+impl<Traits: KernelTraits> fmt::Debug for RuntimeStatsGlobals<Traits> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RuntimeStatsGlobals")
+            .field("last_count", &self.last_count)
+            .field("busy_time", &self.busy_time)
+            .field("idle_time", &self.idle_time)
+            .finish()
+    }
+}
+
+/// These associate functions implement the processor time accounting portion
+/// of [`r3_core::kernel::raw::KernelRuntimeStats`].
+impl<Traits: KernelTraits> System<Traits> {
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_cpu_time(this: task::TaskId) -> Result<u64, GetTaskCpuTimeError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::task_cb(this)? };
+
+        // Account for the time spent by the running task so far
+        sample(lock.borrow_mut());
+
+        Ok(counts_to_micros::<Traits>(task_cb.cpu_time.get(&*lock)))
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn load_snapshot() -> Result<LoadSnapshot, LoadSnapshotError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+
+        sample(lock.borrow_mut());
+
+        let g_runtime_stats = &Traits::state().runtime_stats;
+        Ok(LoadSnapshot {
+            busy_time: counts_to_micros::<Traits>(g_runtime_stats.busy_time.get(&*lock)),
+            idle_time: counts_to_micros::<Traits>(g_runtime_stats.idle_time.get(&*lock)),
+        })
+    }
+}
+
+/// Start the measurement at boot time.
+pub(super) fn init<Traits: KernelTraits>(mut lock: klock::CpuLockTokenRefMut<'_, Traits>) {
+    // Safety: CPU Lock active
+    let now = unsafe { Traits::runtime_count() };
+    Traits::state()
+        .runtime_stats
+        .last_count
+        .replace(&mut *lock, now);
+}
+
+/// Attribute the time elapsed since the last sample to the running task (or
+/// the idle state if there's none).
+///
+/// This must be called before `running_task` is updated.
+pub(super) fn sample<Traits: KernelTraits>(mut lock: klock::CpuLockTokenRefMut<'_, Traits>) {
+    let g_runtime_stats = &Traits::state().runtime_stats;
+
+    // Safety: CPU Lock active
+    let now = unsafe { Traits::runtime_count() };
+    let last = g_runtime_stats.last_count.replace(&mut *lock, now);
+    let elapsed = elapsed_counts::<Traits>(last, now);
+
+    if let Some(running_task) = Traits::state().running_task(lock.borrow_mut()) {
+        add_to_cell(lock.borrow_mut(), &running_task.cpu_time, elapsed);
+        add_to_cell(lock, &g_runtime_stats.busy_time, elapsed);
+    } else {
+        add_to_cell(lock, &g_runtime_stats.idle_time, elapsed);
+    }
+}
+
+#[inline]
+fn add_to_cell<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    cell: &CpuLockCell<Traits, u64>,
+    x: u64,
+) {
+    let value = cell.get(&*lock).wrapping_add(x);
+    cell.replace(&mut *lock, value);
+}
+
+/// Calculate the number of counts elapsed between two readings of
+/// [`PortTimer::runtime_count`], taking a wrap-around into account.
+#[inline]
+fn elapsed_counts<Traits: PortTimer>(last: u64, now: u64) -> u64 {
+    if now >= last {
+        now - last
+    } else {
+        now + (Traits::MAX_RUNTIME_COUNT - last) + 1
+    }
+}
+
+/// Convert [`PortTimer::runtime_count`] units to microseconds.
+#[inline]
+fn counts_to_micros<Traits: PortTimer>(counts: u64) -> u64 {
+    if Traits::RUNTIME_COUNT_FREQUENCY == 1_000_000 {
+        counts
+    } else {
+        (counts as u128 * 1_000_000 / Traits::RUNTIME_COUNT_FREQUENCY as u128) as u64
+    }
+}
@@ -25,7 +25,7 @@ impl<Traits: KernelTraits> System<Traits> {
     ///
     /// See [`crate::bad_id`].
     #[inline]
-    unsafe fn semaphore_cb(
+    pub(super) unsafe fn semaphore_cb(
         this: SemaphoreId,
     ) -> Result<&'static SemaphoreCb<Traits>, NoAccessError> {
         Traits::get_semaphore_cb(this.get() - 1).ok_or_else(|| unsafe { crate::bad_id() })
@@ -135,6 +135,9 @@ fn poll_one<Traits: KernelTraits>(
     semaphore_cb: &'static SemaphoreCb<Traits>,
     mut lock: klock::CpuLockGuard<Traits>,
 ) -> Result<(), PollSemaphoreError> {
+    #[cfg(feature = "trace")]
+    crate::trace::semaphore_wait(lock.borrow_mut(), semaphore_cb);
+
     if poll_core(semaphore_cb.value.write(&mut *lock)) {
         Ok(())
     } else {
@@ -147,6 +150,9 @@ fn wait_one<Traits: KernelTraits>(
     semaphore_cb: &'static SemaphoreCb<Traits>,
     mut lock: klock::CpuLockGuard<Traits>,
 ) -> Result<(), WaitSemaphoreError> {
+    #[cfg(feature = "trace")]
+    crate::trace::semaphore_wait(lock.borrow_mut(), semaphore_cb);
+
     if poll_core(semaphore_cb.value.write(&mut *lock)) {
         Ok(())
     } else {
@@ -162,11 +168,14 @@ fn wait_one<Traits: KernelTraits>(
 }
 
 #[inline]
-fn wait_one_timeout<Traits: KernelTraits>(
+pub(super) fn wait_one_timeout<Traits: KernelTraits>(
     semaphore_cb: &'static SemaphoreCb<Traits>,
     mut lock: klock::CpuLockGuard<Traits>,
     time32: timeout::Time32,
 ) -> Result<(), WaitSemaphoreTimeoutError> {
+    #[cfg(feature = "trace")]
+    crate::trace::semaphore_wait(lock.borrow_mut(), semaphore_cb);
+
     if poll_core(semaphore_cb.value.write(&mut *lock)) {
         Ok(())
     } else {
@@ -187,7 +196,7 @@ fn wait_one_timeout<Traits: KernelTraits>(
 /// If `value` satisfies the wait condition, this function updates `value` and
 /// returns `true`. Otherwise, it returns `false`.
 #[inline]
-fn poll_core(value: &mut SemaphoreValue) -> bool {
+pub(super) fn poll_core(value: &mut SemaphoreValue) -> bool {
     if *value > 0 {
         *value -= 1;
         true
@@ -208,6 +217,9 @@ fn signal<Traits: KernelTraits>(
         return Err(SignalSemaphoreError::QueueOverflow);
     }
 
+    #[cfg(feature = "trace")]
+    crate::trace::semaphore_signal(lock.borrow_mut(), semaphore_cb, count);
+
     let orig_count = count;
 
     // This is equivalent to using `wake_up_all_conditional` and calling
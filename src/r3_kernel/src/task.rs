@@ -6,13 +6,17 @@ use num_traits::ToPrimitive;
 use r3_core::{
     closure::ClosureEnv,
     kernel::{
-        raw::KernelBase, ActivateTaskError, ExitTaskError, GetCurrentTaskError,
-        GetTaskPriorityError, Hunk, InterruptTaskError, ParkError, ParkTimeoutError,
-        SetTaskPriorityError, SleepError, UnparkExactError, WaitTimeoutError,
+        raw::KernelBase, task::TaskHandle, ActivateTaskError, ExitTaskError, GetCurrentTaskError,
+        GetTaskPriorityError, Hunk, InterruptTaskError, NotificationValue, NotifyAction,
+        NotifyTaskError, ParkError, ParkTimeoutError, ResumeTaskError, SetTaskDeadlineError,
+        SetTaskPriorityError, SleepError, StaticTask, SuspendTaskError, TerminateTaskError,
+        UnparkExactError, WaitNotificationError, WaitNotificationTimeoutError, WaitTimeoutError,
     },
     time::Duration,
     utils::Init,
 };
+#[cfg(feature = "system_time")]
+use r3_core::time::Time;
 
 use crate::{
     error::NoAccessError, klock, mutex, state, timeout, wait, Id, KernelCfg1, KernelTraits,
@@ -21,6 +25,9 @@ use crate::{
 
 #[doc(hidden)]
 pub mod readyqueue;
+#[cfg(feature = "stack_check")]
+mod stack_check;
+pub(super) mod time_slice;
 use self::readyqueue::Queue as _;
 
 pub(super) type TaskId = Id;
@@ -34,10 +41,27 @@ impl<Traits: KernelTraits> System<Traits> {
     ///
     /// See [`crate::bad_id`].
     #[inline]
-    unsafe fn task_cb(this: TaskId) -> Result<&'static TaskCb<Traits>, NoAccessError> {
+    pub(super) unsafe fn task_cb(this: TaskId) -> Result<&'static TaskCb<Traits>, NoAccessError> {
         Traits::get_task_cb(this.get() - 1).ok_or_else(|| unsafe { crate::bad_id() })
     }
 
+    /// Like [`Self::task_cb`], but fails with `NoAccess` if the task is owned
+    /// by the kernel. This is used by the operations that control a task's
+    /// lifecycle, which only the kernel may do on its own tasks.
+    ///
+    /// # Safety
+    ///
+    /// See [`crate::bad_id`].
+    #[inline]
+    unsafe fn app_task_cb(this: TaskId) -> Result<&'static TaskCb<Traits>, NoAccessError> {
+        // Safety: Upheld by the caller
+        let task_cb = unsafe { Self::task_cb(this)? };
+        if crate::timer::service::is_service_task(task_cb) {
+            return Err(NoAccessError::NoAccess);
+        }
+        Ok(task_cb)
+    }
+
     #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
     pub(super) fn task_current() -> Result<TaskId, GetCurrentTaskError> {
         if !Traits::is_task_context() {
@@ -47,20 +71,14 @@ impl<Traits: KernelTraits> System<Traits> {
         let mut lock = klock::lock_cpu::<Traits>()?;
         let task_cb = Traits::state().running_task(lock.borrow_mut()).unwrap();
 
-        // Calculate an `Id` from the task CB pointer
-        // Safety: `task_cb` refers to an element of `Traits::task_cb_pool()`
-        let offset = unsafe { <*const _>::offset_from(task_cb, Traits::task_cb_pool().as_ptr()) };
-
-        let task = Id::new(offset as usize + 1).unwrap();
-
-        Ok(task)
+        Ok(task_id_from_cb(task_cb))
     }
 
     #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
     pub(super) fn task_activate(this: TaskId) -> Result<(), ActivateTaskError> {
         let lock = klock::lock_cpu::<Traits>()?;
         // Safety: The caller is responsible for providing a valid object ID
-        let task_cb = unsafe { Self::task_cb(this)? };
+        let task_cb = unsafe { Self::app_task_cb(this)? };
         activate(lock, task_cb)
     }
 
@@ -100,6 +118,69 @@ impl<Traits: KernelTraits> System<Traits> {
         set_task_base_priority(lock, task_cb, priority)
     }
 
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_set_deadline(
+        this: TaskId,
+        deadline: Duration,
+    ) -> Result<(), SetTaskDeadlineError> {
+        let time32 = timeout::time32_from_duration(deadline)?;
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::task_cb(this)? };
+        set_task_deadline(lock, task_cb, time32)
+    }
+
+    #[cfg(feature = "stack_check")]
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_stack_high_water_mark(
+        this: TaskId,
+    ) -> Result<usize, r3_core::kernel::GetTaskStackHighWaterMarkError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::task_cb(this)? };
+
+        // Keep CPU Lock active while scanning the stack. Otherwise, the task
+        // might be dispatched and start using the stack in the middle of the
+        // scan.
+        Ok(stack_check::high_water_mark(lock.borrow_mut(), task_cb))
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_suspend(this: TaskId) -> Result<(), SuspendTaskError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::app_task_cb(this)? };
+        suspend(lock, task_cb)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_resume(this: TaskId) -> Result<(), ResumeTaskError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::task_cb(this)? };
+        resume(lock, task_cb)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_terminate(this: TaskId) -> Result<(), TerminateTaskError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::app_task_cb(this)? };
+        terminate(lock, task_cb)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    pub(super) fn task_notify(
+        this: TaskId,
+        value: NotificationValue,
+        action: NotifyAction,
+    ) -> Result<(), NotifyTaskError> {
+        let lock = klock::lock_cpu::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let task_cb = unsafe { Self::task_cb(this)? };
+        notify(lock, task_cb, value, action)
+    }
+
     #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
     pub(super) fn task_priority(this: TaskId) -> Result<usize, GetTaskPriorityError> {
         let lock = klock::lock_cpu::<Traits>()?;
@@ -272,6 +353,42 @@ pub struct TaskCb<
     /// A flag indicating whether the task has a park token or not.
     pub(super) park_token: klock::CpuLockCell<Traits, bool>,
 
+    /// A flag indicating whether the task is in the Waiting-Suspended state.
+    /// Only meaningful when the task is in the Waiting state.
+    pub(super) wait_suspended: klock::CpuLockCell<Traits, bool>,
+
+    /// The number of queued activation requests. Must be less than or equal to
+    /// [`TaskAttr::max_queued_activations`].
+    pub(super) num_queued_activations: klock::CpuLockCell<Traits, usize>,
+
+    /// A flag indicating that the task was reactivated by `exit_task` and its
+    /// execution context hasn't been initialized yet. `exit_task` can't
+    /// initialize the context because it's still running on the task's stack,
+    /// so this is deferred until the task is chosen to run.
+    pub(super) context_stale: klock::CpuLockCell<Traits, bool>,
+
+    /// The task's notification value.
+    pub(super) notification_value: klock::CpuLockCell<Traits, NotificationValue>,
+
+    /// A flag indicating whether the task has a pending notification.
+    pub(super) notification_pending: klock::CpuLockCell<Traits, bool>,
+
+    /// The task's absolute deadline, which is an event time. `None` means the
+    /// task doesn't have a deadline.
+    ///
+    /// The deadline determines the task's position within the task ready
+    /// queue if the queue is deadline-aware. You must call
+    /// `TaskReadyQueue::reorder_task_by_deadline` after updating `deadline` of
+    /// a task which is in Ready state.
+    pub(super) deadline: klock::CpuLockCell<Traits, Option<timeout::Time32>>,
+
+    /// The processor time consumed by the task, measured in
+    /// [`PortTimer::runtime_count`] units.
+    ///
+    /// [`PortTimer::runtime_count`]: crate::PortTimer::runtime_count
+    #[cfg(feature = "runtime_stats")]
+    pub(super) cpu_time: klock::CpuLockCell<Traits, u64>,
+
     /// Allows `TaskCb` to participate in one of linked lists.
     ///
     ///  - In a `Ready` state, this forms the linked list headed by
@@ -313,6 +430,21 @@ impl<
                     .debug_fmt_with(|x, f| x.map(|x| x as *const _).fmt(f)),
             )
             .field("park_token", &self.park_token)
+            .field("wait_suspended", &self.wait_suspended)
+            .field("num_queued_activations", &self.num_queued_activations)
+            .field("context_stale", &self.context_stale)
+            .field("notification_value", &self.notification_value)
+            .field("notification_pending", &self.notification_pending)
+            .field("deadline", &self.deadline)
+            .field(
+                "cpu_time",
+                match () {
+                    #[cfg(feature = "runtime_stats")]
+                    () => &self.cpu_time,
+                    #[cfg(not(feature = "runtime_stats"))]
+                    () => &(),
+                },
+            )
             .finish()
     }
 }
@@ -341,6 +473,17 @@ pub struct TaskAttr<
 
     /// The initial base priority of the task.
     pub priority: TaskPriority,
+
+    /// The maximum number of queued activation requests.
+    pub max_queued_activations: usize,
+
+    /// The time slice (quantum) of the task, measured in microseconds. `None`
+    /// disables time slicing for the task.
+    pub time_slice: Option<timeout::Time32>,
+
+    /// The relative deadline of the task, measured in microseconds. `None`
+    /// means the task doesn't have a deadline.
+    pub relative_deadline: Option<timeout::Time32>,
 }
 
 impl<Traits: KernelTraits, TaskPriority: fmt::Debug> fmt::Debug for TaskAttr<Traits, TaskPriority> {
@@ -350,6 +493,9 @@ impl<Traits: KernelTraits, TaskPriority: fmt::Debug> fmt::Debug for TaskAttr<Tra
             .field("entry_param", &self.entry_param)
             .field("stack", &self.stack)
             .field("priority", &self.priority)
+            .field("max_queued_activations", &self.max_queued_activations)
+            .field("time_slice", &self.time_slice)
+            .field("relative_deadline", &self.relative_deadline)
             .finish()
     }
 }
@@ -366,9 +512,13 @@ pub enum TaskSt {
     /// The task is in the Running state.
     Running,
 
-    /// The task is in the Waiting state.
+    /// The task is in the Waiting state. If `TaskCb::wait_suspended` is set,
+    /// the task is in the Waiting-Suspended state.
     Waiting,
 
+    /// The task is in the Suspended state.
+    Suspended,
+
     /// The task should be activated at startup. This will transition into
     /// `Ready` or `Running` before the first task is scheduled.
     PendingActivation,
@@ -405,16 +555,52 @@ pub(super) unsafe fn exit_current_task<Traits: KernelTraits>() -> Result<!, Exit
 
     let running_task = Traits::state().running_task(lock.borrow_mut()).unwrap();
 
+    call_task_exit_hooks(running_task);
+
     // Abandon mutexes, waking up the next waiters of the mutexes (if any)
     mutex::abandon_held_mutexes(lock.borrow_mut(), running_task);
     debug_assert!(running_task.last_mutex_held.read(&*lock).is_none());
 
-    // Transition the current task to Dormant
     assert_eq!(*running_task.st.read(&*lock), TaskSt::Running);
-    running_task.st.replace(&mut *lock, TaskSt::Dormant);
 
-    // Erase `running_task`
+    if let Some(num_queued_activations) = running_task
+        .num_queued_activations
+        .read(&*lock)
+        .checked_sub(1)
+    {
+        // Consume a queued activation request and make the current task Ready
+        // again. We are still running on the task's stack, so we can't
+        // initialize the task's execution context here.
+        // `choose_next_running_task` will do this when the task is chosen to
+        // run.
+        running_task
+            .num_queued_activations
+            .replace(&mut *lock, num_queued_activations);
+        running_task.context_stale.replace(&mut *lock, true);
+
+        // Safety: The task's execution context will be initialized before
+        // the task runs
+        unsafe { make_active(lock.borrow_mut(), running_task) };
+    } else {
+        // Transition the current task to Dormant
+        set_task_st(lock.borrow_mut(), running_task, TaskSt::Dormant);
+    }
+
+    // Account for the processor time consumed by the exiting task
+    #[cfg(feature = "runtime_stats")]
+    crate::runtime_stats::sample(lock.borrow_mut());
+
+    // Erase `running_task`. Remember the exiting task so that
+    // `choose_next_running_task` can pass it to task switch hooks.
     Traits::state().running_task.replace(&mut *lock, None);
+    Traits::state()
+        .exited_task
+        .replace(&mut *lock, Some(running_task));
+
+    // Check the stack of the exiting task. `choose_next_running_task` won't do
+    // this because we just erased `running_task`.
+    #[cfg(feature = "stack_check")]
+    stack_check::check(running_task);
 
     core::mem::forget(lock);
 
@@ -426,11 +612,49 @@ pub(super) unsafe fn exit_current_task<Traits: KernelTraits>() -> Result<!, Exit
     }
 }
 
+/// Call the task exit hooks for the specified task, which is exiting or being
+/// terminated and still holds its mutexes.
+#[inline]
+fn call_task_exit_hooks<Traits: KernelTraits>(task_cb: &'static TaskCb<Traits>) {
+    for hook in Traits::TASK_EXIT_HOOKS {
+        // Safety: `task_id_from_cb` returns a valid task ID
+        hook(unsafe { StaticTask::from_id(task_id_from_cb(task_cb)) });
+    }
+}
+
+/// Update the state of the specified task, recording the transition in the
+/// trace buffer if the `trace` feature is enabled.
+#[inline]
+pub(super) fn set_task_st<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+    st: TaskSt,
+) {
+    task_cb.st.replace(&mut *lock, st);
+
+    #[cfg(feature = "trace")]
+    crate::trace::task_state(lock, task_cb, st);
+}
+
+/// Calculate the [`TaskId`] of the specified task.
+fn task_id_from_cb<Traits: KernelTraits>(task_cb: &'static TaskCb<Traits>) -> TaskId {
+    // Safety: `task_cb` refers to an element of `Traits::task_cb_pool()`
+    let offset = unsafe { <*const _>::offset_from(task_cb, Traits::task_cb_pool().as_ptr()) };
+
+    Id::new(offset as usize + 1).unwrap()
+}
+
 /// Initialize a task at boot time.
 pub(super) fn init_task<Traits: KernelTraits>(
     lock: klock::CpuLockTokenRefMut<'_, Traits>,
     task_cb: &'static TaskCb<Traits>,
 ) {
+    // Safety: Nothing is running on task stacks at boot time
+    #[cfg(feature = "stack_check")]
+    unsafe {
+        stack_check::paint(task_cb);
+    }
+
     if let TaskSt::PendingActivation = task_cb.st.read(&*lock) {
         // `PendingActivation` is equivalent to `Dormant` but serves as a marker
         // indicating tasks that should be activated by `init_task`.
@@ -449,15 +673,62 @@ fn activate<Traits: KernelTraits>(
     mut lock: klock::CpuLockGuard<Traits>,
     task_cb: &'static TaskCb<Traits>,
 ) -> Result<(), ActivateTaskError> {
+    if activate_core(lock.borrow_mut(), task_cb)? {
+        // If `task_cb` has a higher priority, perform a context switch.
+        unlock_cpu_and_check_preemption(lock);
+    }
+
+    Ok(())
+}
+
+/// The core portion of [`activate`]. Returns `Ok(true)` if the task was
+/// transitioned into the Ready state, in which case the caller is responsible
+/// for checking dispatch.
+pub(super) fn activate_core<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+) -> Result<bool, ActivateTaskError> {
     if *task_cb.st.read(&*lock) != TaskSt::Dormant {
-        return Err(ActivateTaskError::QueueOverflow);
+        // The task is already active. Queue the activation request if
+        // possible.
+        let num_queued_activations = task_cb.num_queued_activations.get(&*lock);
+        if num_queued_activations >= task_cb.attr.max_queued_activations {
+            return Err(ActivateTaskError::QueueOverflow);
+        }
+        task_cb
+            .num_queued_activations
+            .replace(&mut *lock, num_queued_activations + 1);
+        return Ok(false);
     }
 
+    // Safety: CPU Lock active, the task is in the Dormant state
+    unsafe { Traits::initialize_task_state(task_cb) };
+
+    // Safety: We just initialized the task state
+    unsafe { make_active(lock, task_cb) };
+
+    Ok(true)
+}
+
+/// Reset the task's properties and transition it into the Ready state, starting
+/// a new activation of the task.
+///
+/// # Safety
+///
+/// The task must be in the Dormant state or the Running state, and in the
+/// latter case the task must be leaving the processor (i.e., it's exiting).
+/// The caller must initialize the task state first by calling
+/// `initialize_task_state` unless [`TaskCb::context_stale`] is set.
+unsafe fn make_active<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+) {
     // Discard a park token if the task has one
     task_cb.park_token.replace(&mut *lock, false);
 
-    // Safety: CPU Lock active, the task is in the Dormant state
-    unsafe { Traits::initialize_task_state(task_cb) };
+    // Reset the notification state
+    task_cb.notification_value.replace(&mut *lock, 0);
+    task_cb.notification_pending.replace(&mut *lock, false);
 
     // Reset the task priority
     task_cb
@@ -467,14 +738,15 @@ fn activate<Traits: KernelTraits>(
         .effective_priority
         .replace(&mut *lock, task_cb.attr.priority);
 
-    // Safety: The previous state is Dormant, and we just initialized the task
-    // state, so this is safe
-    unsafe { make_ready(lock.borrow_mut(), task_cb) };
-
-    // If `task_cb` has a higher priority, perform a context switch.
-    unlock_cpu_and_check_preemption(lock);
+    // Reset the task deadline
+    let deadline = task_cb
+        .attr
+        .relative_deadline
+        .map(|d| timeout::current_time(lock.borrow_mut()).wrapping_add(d));
+    task_cb.deadline.replace(&mut *lock, deadline);
 
-    Ok(())
+    // Safety: Upheld by the caller
+    unsafe { make_ready(lock, task_cb) };
 }
 
 /// Transition the task into the Ready state. This function doesn't do any
@@ -486,7 +758,7 @@ pub(super) unsafe fn make_ready<Traits: KernelTraits>(
     task_cb: &'static TaskCb<Traits>,
 ) {
     // Make the task Ready
-    task_cb.st.replace(&mut *lock, TaskSt::Ready);
+    set_task_st(lock.borrow_mut(), task_cb, TaskSt::Ready);
 
     // Insert the task to the ready queue.
     //
@@ -520,24 +792,14 @@ pub(super) fn unlock_cpu_and_check_preemption<Traits: KernelTraits>(
         return;
     }
 
-    let prev_task_priority =
-        if let Some(running_task) = Traits::state().running_task(lock.borrow_mut()) {
-            if *running_task.st.read(&*lock) == TaskSt::Running {
-                running_task
-                    .effective_priority
-                    .read(&*lock)
-                    .to_usize()
-                    .unwrap()
-            } else {
-                usize::MAX
-            }
-        } else {
-            usize::MAX
-        };
+    // The running task, unless it's leaving the Running state
+    let prev_task = Traits::state()
+        .running_task(lock.borrow_mut())
+        .filter(|running_task| *running_task.st.read(&*lock) == TaskSt::Running);
 
     let has_preempting_task = Traits::state()
         .task_ready_queue
-        .has_ready_task_in_priority_range(lock.borrow_mut().into(), ..prev_task_priority);
+        .has_preempting_task(lock.borrow_mut().into(), prev_task);
 
     // Relinquish CPU Lock
     drop(lock);
@@ -568,34 +830,23 @@ pub(super) fn choose_next_running_task<Traits: KernelTraits>(
         return;
     }
 
-    // The priority of `running_task`
+    // `running_task`, unless it's not running anymore
     let prev_running_task = Traits::state().running_task(lock.borrow_mut());
-    let prev_task_priority = if let Some(running_task) = prev_running_task {
-        if *running_task.st.read(&*lock) == TaskSt::Running {
-            running_task
-                .effective_priority
-                .read(&*lock)
-                .to_usize()
-                .unwrap()
-        } else {
-            usize::MAX // (2) see the discussion below
-        }
-    } else {
-        usize::MAX // (1) see the discussion below
-    };
+    let prev_task =
+        prev_running_task.filter(|running_task| *running_task.st.read(&*lock) == TaskSt::Running);
 
     // Decide the next task to run
     //
-    // The special value `prev_task_priority == usize::MAX` indicates that
-    // (1) there is no running task, or (2) there was one but it is not running
-    // anymore, and we need to elect a new task to run. In case (2), we would
-    // want to update `running_task` regardless of whether there exists a
-    // schedulable task or not. That is, even if there was not such a task, we
-    // would still want to assign `None` to `running_task`. Therefore,
-    // `pop_front_task` is designed to return `SwitchTo(None)` in this case.
+    // `prev_task == None` indicates that (1) there is no running task, or (2)
+    // there was one but it is not running anymore, and we need to elect a new
+    // task to run. In case (2), we would want to update `running_task`
+    // regardless of whether there exists a schedulable task or not. That is,
+    // even if there was not such a task, we would still want to assign `None`
+    // to `running_task`. Therefore, `pop_front_task` is designed to return
+    // `SwitchTo(None)` in this case.
     let decision = Traits::state()
         .task_ready_queue
-        .pop_front_task(lock.borrow_mut().into(), prev_task_priority);
+        .pop_front_task(lock.borrow_mut().into(), prev_task);
 
     let next_running_task = match decision {
         readyqueue::ScheduleDecision::SwitchTo(task) => task,
@@ -603,16 +854,32 @@ pub(super) fn choose_next_running_task<Traits: KernelTraits>(
         // Return if there's no task willing to take over the current one, and
         // the current one can still run.
         readyqueue::ScheduleDecision::Keep => {
-            // If `prev_task_priority == usize::MAX`, `pop_front_task` must
-            // return `SwitchTo(_)`.
-            debug_assert_ne!(prev_task_priority, usize::MAX);
+            // If `prev_task == None`, `pop_front_task` must return
+            // `SwitchTo(_)`.
+            debug_assert!(prev_task.is_some());
             return;
         }
     };
 
+    // Timestamp the dispatch. The time elapsed since the last sample is
+    // attributed to `prev_running_task`.
+    #[cfg(feature = "runtime_stats")]
+    crate::runtime_stats::sample(lock.borrow_mut());
+
+    // `next_running_task` is given a fresh time slice
+    time_slice::restart(lock.borrow_mut(), next_running_task);
+
     if let Some(task) = next_running_task {
+        if task.context_stale.replace(&mut *lock, false) {
+            // The task was reactivated by `exit_task`. Initialize its
+            // execution context now that nothing is running on its stack.
+            //
+            // Safety: CPU Lock active, the task is not running
+            unsafe { Traits::initialize_task_state(task) };
+        }
+
         // Transition `next_running_task` into the Running state
-        task.st.replace(&mut *lock, TaskSt::Running);
+        set_task_st(lock.borrow_mut(), task, TaskSt::Running);
 
         if ptr_from_option_ref(prev_running_task) == task {
             // Skip the remaining steps if `task == prev_running_task`
@@ -626,6 +893,12 @@ pub(super) fn choose_next_running_task<Traits: KernelTraits>(
             ptr_from_option_ref(prev_running_task),
             ptr_from_option_ref(next_running_task),
         );
+
+        // Every port calls `choose_running_task` on a context switch, so this
+        // is where we check the stack of the task leaving the processor
+        #[cfg(feature = "stack_check")]
+        stack_check::check(running_task);
+
         match running_task.st.read(&*lock) {
             TaskSt::Running => {
                 // Transition `prev_running_task` into Ready state.
@@ -638,6 +911,9 @@ pub(super) fn choose_next_running_task<Traits: KernelTraits>(
             TaskSt::Ready => {
                 // `prev_running_task` stays in Ready state.
             }
+            TaskSt::Suspended => {
+                // `prev_running_task` stays in Suspended state.
+            }
             _ => unreachable!(),
         }
     }
@@ -645,6 +921,47 @@ pub(super) fn choose_next_running_task<Traits: KernelTraits>(
     Traits::state()
         .running_task
         .replace(&mut *lock, next_running_task);
+
+    #[cfg(feature = "trace")]
+    crate::trace::task_switch(lock.borrow_mut(), next_running_task);
+
+    // If the previously running task has exited, `exit_current_task` has
+    // already erased `running_task`. Report the exited task instead.
+    let exited_task = Traits::state().exited_task.replace(&mut *lock, None);
+    let prev_running_task = prev_running_task.or(exited_task);
+
+    // We only get here with `prev_running_task == next_running_task` if both
+    // are `None` or the exited task is starting again
+    if prev_running_task.is_some() || next_running_task.is_some() {
+        let prev_task = prev_running_task.map(task_id_from_cb);
+        let next_task = next_running_task.map(task_id_from_cb);
+        for hook in Traits::TASK_SWITCH_HOOKS {
+            // Safety: `task_id_from_cb` returns a valid task ID
+            hook(
+                prev_task.map(|id| unsafe { StaticTask::from_id(id) }),
+                next_task.map(|id| unsafe { StaticTask::from_id(id) }),
+            );
+        }
+    }
+
+    // The port will put the processor into an idle state when we return
+    if next_running_task.is_none() {
+        for hook in Traits::IDLE_HOOKS {
+            hook.call();
+        }
+    }
+}
+
+/// Implements `PortToKernel::check_stack_pointer`.
+#[cfg(feature = "stack_check")]
+#[inline]
+pub(super) fn check_stack_pointer<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<Traits>,
+    sp: usize,
+) {
+    if let Some(running_task) = Traits::state().running_task(lock.borrow_mut()) {
+        stack_check::check_stack_pointer(running_task, sp);
+    }
 }
 
 #[inline]
@@ -671,7 +988,7 @@ pub(super) fn wait_until_woken_up<Traits: KernelTraits>(
     // Transition the current task to Waiting
     let running_task = Traits::state().running_task(lock.borrow_mut()).unwrap();
     assert_eq!(*running_task.st.read(&*lock), TaskSt::Running);
-    running_task.st.replace(&mut *lock, TaskSt::Waiting);
+    set_task_st(lock.borrow_mut(), running_task, TaskSt::Waiting);
 
     loop {
         // Temporarily release the CPU Lock before calling `yield_cpu`
@@ -691,10 +1008,176 @@ pub(super) fn wait_until_woken_up<Traits: KernelTraits>(
             break;
         }
 
-        assert_eq!(*running_task.st.read(&*lock), TaskSt::Waiting);
+        // The task might have been suspended after the wait operation
+        // completed
+        assert!(matches!(
+            *running_task.st.read(&*lock),
+            TaskSt::Waiting | TaskSt::Suspended
+        ));
+    }
+}
+
+/// Implements [`Task::suspend`].
+fn suspend<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    task_cb: &'static TaskCb<Traits>,
+) -> Result<(), SuspendTaskError> {
+    match *task_cb.st.read(&*lock) {
+        TaskSt::Dormant => Err(SuspendTaskError::BadObjectState),
+        TaskSt::Suspended => Err(SuspendTaskError::QueueOverflow),
+        TaskSt::Waiting => {
+            // Transition the task into the Waiting-Suspended state. The task
+            // stays in the wait queue.
+            if task_cb.wait_suspended.replace(&mut *lock, true) {
+                Err(SuspendTaskError::QueueOverflow)
+            } else {
+                Ok(())
+            }
+        }
+        TaskSt::Ready => {
+            // Remove the task from the ready queue
+            //
+            // Safety: The task is in the Ready state, so it's in the ready
+            // queue with its current effective priority.
+            unsafe {
+                Traits::state()
+                    .task_ready_queue
+                    .remove_task(lock.borrow_mut().into(), task_cb)
+            };
+            set_task_st(lock.borrow_mut(), task_cb, TaskSt::Suspended);
+            Ok(())
+        }
+        TaskSt::Running => {
+            // If Priority Boost is active, the current task must keep running
+            if System::<Traits>::raw_is_priority_boost_active() {
+                return Err(SuspendTaskError::BadContext);
+            }
+
+            set_task_st(lock.borrow_mut(), task_cb, TaskSt::Suspended);
+
+            if !Traits::is_task_context() {
+                // An interrupt handler suspended the interrupted task. Make
+                // sure a new task is chosen when the interrupt handler returns.
+                drop(lock);
+                // Safety: CPU Lock inactive
+                unsafe { Traits::yield_cpu() };
+                return Ok(());
+            }
+
+            // The current task suspended itself. Wait until resumed.
+            loop {
+                // Temporarily release the CPU Lock before calling `yield_cpu`
+                // Safety: (1) We don't access rseources protected by CPU Lock.
+                //         (2) We currently have CPU Lock.
+                //         (3) We will re-acquire a CPU Lock before returning
+                //             from this function.
+                unsafe { Traits::leave_cpu_lock() };
+
+                // Safety: CPU Lock inactive
+                unsafe { Traits::yield_cpu() };
+
+                // Re-acquire a CPU Lock
+                unsafe { Traits::enter_cpu_lock() };
+
+                if *task_cb.st.read(&*lock) == TaskSt::Running {
+                    break;
+                }
+
+                assert_eq!(*task_cb.st.read(&*lock), TaskSt::Suspended);
+            }
+
+            Ok(())
+        }
+        TaskSt::PendingActivation => unreachable!(),
+    }
+}
+
+/// Implements [`Task::resume`].
+fn resume<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    task_cb: &'static TaskCb<Traits>,
+) -> Result<(), ResumeTaskError> {
+    match *task_cb.st.read(&*lock) {
+        TaskSt::Suspended => {
+            // Safety: The task is in the Suspended state, meaning the task
+            // state is valid and ready to resume from the point where it was
+            // previously interrupted.
+            unsafe { make_ready(lock.borrow_mut(), task_cb) };
+
+            // If `task_cb` has a higher priority, perform a context switch.
+            unlock_cpu_and_check_preemption(lock);
+
+            Ok(())
+        }
+        TaskSt::Waiting if task_cb.wait_suspended.get(&*lock) => {
+            // Return to the Waiting state
+            task_cb.wait_suspended.replace(&mut *lock, false);
+            Ok(())
+        }
+        _ => Err(ResumeTaskError::BadObjectState),
     }
 }
 
+/// Implements [`Task::terminate`].
+fn terminate<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    task_cb: &'static TaskCb<Traits>,
+) -> Result<(), TerminateTaskError> {
+    match *task_cb.st.read(&*lock) {
+        TaskSt::Dormant => return Err(TerminateTaskError::BadObjectState),
+        TaskSt::Running => return Err(TerminateTaskError::BadContext),
+        TaskSt::Ready => {
+            // Remove the task from the ready queue
+            //
+            // Safety: The task is in the Ready state, so it's in the ready
+            // queue with its current effective priority.
+            unsafe {
+                Traits::state()
+                    .task_ready_queue
+                    .remove_task(lock.borrow_mut().into(), task_cb)
+            };
+        }
+        TaskSt::Waiting | TaskSt::Suspended => {}
+        TaskSt::PendingActivation => unreachable!(),
+    }
+
+    // Remove the task from the wait queue and the timeout heap. These objects
+    // are about to be discarded along with the task's stack.
+    wait::abandon_wait_of_task(lock.borrow_mut(), task_cb);
+    task_cb.wait_suspended.replace(&mut *lock, false);
+
+    call_task_exit_hooks(task_cb);
+
+    // Abandon mutexes, waking up the next waiters of the mutexes (if any)
+    mutex::abandon_held_mutexes(lock.borrow_mut(), task_cb);
+    debug_assert!(task_cb.last_mutex_held.read(&*lock).is_none());
+
+    // Transition the task to Dormant
+    set_task_st(lock.borrow_mut(), task_cb, TaskSt::Dormant);
+
+    // Discard the task's execution context
+    // Safety: CPU Lock active, the task is in the Dormant state
+    unsafe { Traits::initialize_task_state(task_cb) };
+    task_cb.context_stale.replace(&mut *lock, false);
+
+    if let Some(num_queued_activations) = task_cb.num_queued_activations.read(&*lock).checked_sub(1)
+    {
+        // Consume a queued activation request and activate the task again
+        task_cb
+            .num_queued_activations
+            .replace(&mut *lock, num_queued_activations);
+
+        // Safety: The task is in the Dormant state, and we just initialized
+        // the task state
+        unsafe { make_active(lock.borrow_mut(), task_cb) };
+    }
+
+    // Abandoning mutexes might have woken up a task with a higher priority
+    unlock_cpu_and_check_preemption(lock);
+
+    Ok(())
+}
+
 /// Implements `KernelBase::park`.
 #[inline]
 pub(super) fn park_current_task<Traits: KernelTraits>() -> Result<(), ParkError> {
@@ -720,9 +1203,30 @@ pub(super) fn park_current_task_timeout<Traits: KernelTraits>(
     timeout: Duration,
 ) -> Result<(), ParkTimeoutError> {
     let time32 = timeout::time32_from_duration(timeout)?;
+    let lock = klock::lock_cpu::<Traits>()?;
+    state::expect_waitable_context::<Traits>()?;
+
+    park_current_task_timeout_core(lock, time32)
+}
+
+/// Implements `KernelWaitUntil::park_until`.
+#[cfg(feature = "system_time")]
+#[inline]
+pub(super) fn park_current_task_until<Traits: KernelTraits>(
+    deadline: Time,
+) -> Result<(), ParkTimeoutError> {
     let mut lock = klock::lock_cpu::<Traits>()?;
     state::expect_waitable_context::<Traits>()?;
+    let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
 
+    park_current_task_timeout_core(lock, time32)
+}
+
+#[inline]
+fn park_current_task_timeout_core<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    time32: timeout::Time32,
+) -> Result<(), ParkTimeoutError> {
     let running_task = Traits::state().running_task(lock.borrow_mut()).unwrap();
 
     // If the task already has a park token, return immediately
@@ -771,15 +1275,163 @@ fn unpark_exact<Traits: KernelTraits>(
     }
 }
 
+/// Implements [`Task::notify`].
+#[inline]
+fn notify<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    task_cb: &'static TaskCb<Traits>,
+    value: NotificationValue,
+    action: NotifyAction,
+) -> Result<(), NotifyTaskError> {
+    if *task_cb.st.read(&*lock) == TaskSt::Dormant {
+        return Err(NotifyTaskError::BadObjectState);
+    }
+
+    let old_value = *task_cb.notification_value.read(&*lock);
+    let new_value = match action {
+        NotifyAction::SetBits => old_value | value,
+        NotifyAction::Increment => old_value
+            .checked_add(1)
+            .ok_or(NotifyTaskError::QueueOverflow)?,
+        NotifyAction::Overwrite => value,
+        NotifyAction::OverwriteIfEmpty => {
+            if *task_cb.notification_pending.read(&*lock) {
+                return Err(NotifyTaskError::QueueOverflow);
+            }
+            value
+        }
+    };
+    task_cb.notification_value.replace(&mut *lock, new_value);
+    task_cb.notification_pending.replace(&mut *lock, true);
+
+    // Is the task currently waiting for a notification?
+    let is_waiting = *task_cb.st.read(&*lock) == TaskSt::Waiting
+        && wait::with_current_wait_payload(lock.borrow_mut(), task_cb, |payload| {
+            matches!(payload, Some(wait::WaitPayload::Notification))
+        });
+
+    if is_waiting {
+        // Unblock the task. We confirmed that the task is in the Waiting state,
+        // so `interrupt_task` should succeed. The task will consume the
+        // notification when it resumes execution.
+        wait::interrupt_task(lock.borrow_mut(), task_cb, Ok(())).unwrap();
+
+        // The task is now awake, check dispatch
+        unlock_cpu_and_check_preemption(lock);
+    }
+
+    Ok(())
+}
+
+/// Implements `KernelTaskNotify::wait_notification`.
+#[inline]
+pub(super) fn wait_notification<Traits: KernelTraits>(
+    clear_on_entry: NotificationValue,
+    clear_on_exit: NotificationValue,
+) -> Result<NotificationValue, WaitNotificationError> {
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    state::expect_waitable_context::<Traits>()?;
+
+    let running_task = Traits::state().running_task(lock.borrow_mut()).unwrap();
+
+    if !*running_task.notification_pending.read(&*lock) {
+        clear_notification_value(lock.borrow_mut(), running_task, clear_on_entry);
+
+        // Wait until woken up by `notify`
+        wait::wait_no_queue(lock.borrow_mut(), wait::WaitPayload::Notification)?;
+    }
+
+    Ok(take_notification(
+        lock.borrow_mut(),
+        running_task,
+        clear_on_exit,
+    ))
+}
+
+/// Implements `KernelTaskNotify::wait_notification_timeout`.
+#[inline]
+pub(super) fn wait_notification_timeout<Traits: KernelTraits>(
+    clear_on_entry: NotificationValue,
+    clear_on_exit: NotificationValue,
+    timeout: Duration,
+) -> Result<NotificationValue, WaitNotificationTimeoutError> {
+    let time32 = timeout::time32_from_duration(timeout)?;
+    let mut lock = klock::lock_cpu::<Traits>()?;
+    state::expect_waitable_context::<Traits>()?;
+
+    let running_task = Traits::state().running_task(lock.borrow_mut()).unwrap();
+
+    if !*running_task.notification_pending.read(&*lock) {
+        clear_notification_value(lock.borrow_mut(), running_task, clear_on_entry);
+
+        // Wait until woken up by `notify`
+        wait::wait_no_queue_timeout(lock.borrow_mut(), wait::WaitPayload::Notification, time32)?;
+    }
+
+    Ok(take_notification(
+        lock.borrow_mut(),
+        running_task,
+        clear_on_exit,
+    ))
+}
+
+/// Clear the specified bits of the task's notification value.
+fn clear_notification_value<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+    bits: NotificationValue,
+) {
+    let value = *task_cb.notification_value.read(&*lock);
+    task_cb
+        .notification_value
+        .replace(&mut *lock, value & !bits);
+}
+
+/// Consume the task's pending notification and return the notification value
+/// before clearing `clear_on_exit`.
+fn take_notification<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+    clear_on_exit: NotificationValue,
+) -> NotificationValue {
+    debug_assert!(*task_cb.notification_pending.read(&*lock));
+    task_cb.notification_pending.replace(&mut *lock, false);
+
+    let value = *task_cb.notification_value.read(&*lock);
+    clear_notification_value(lock, task_cb, clear_on_exit);
+    value
+}
+
 /// Implements `KernelBase::sleep`.
 #[inline]
 pub(super) fn put_current_task_on_sleep_timeout<Traits: KernelTraits>(
     timeout: Duration,
 ) -> Result<(), SleepError> {
     let time32 = timeout::time32_from_duration(timeout)?;
+    let lock = klock::lock_cpu::<Traits>()?;
+    state::expect_waitable_context::<Traits>()?;
+
+    put_current_task_on_sleep_timeout_core(lock, time32)
+}
+
+/// Implements `KernelWaitUntil::sleep_until`.
+#[cfg(feature = "system_time")]
+#[inline]
+pub(super) fn put_current_task_on_sleep_until<Traits: KernelTraits>(
+    deadline: Time,
+) -> Result<(), SleepError> {
     let mut lock = klock::lock_cpu::<Traits>()?;
     state::expect_waitable_context::<Traits>()?;
+    let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+    put_current_task_on_sleep_timeout_core(lock, time32)
+}
 
+#[inline]
+fn put_current_task_on_sleep_timeout_core<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    time32: timeout::Time32,
+) -> Result<(), SleepError> {
     // Wait until woken up by timeout
     match wait::wait_no_queue_timeout(lock.borrow_mut(), wait::WaitPayload::Sleep, time32) {
         Ok(_) => unreachable!(),
@@ -877,22 +1529,73 @@ fn set_task_base_priority<Traits: KernelTraits>(
                 old_effective_priority,
             );
         },
-        TaskSt::Running => {}
+        TaskSt::Running | TaskSt::Suspended => {}
         TaskSt::Waiting => {
             // Reposition the task in a wait queue if the task is currently waiting
             wait::reorder_wait_of_task(lock.borrow_mut(), task_cb);
+
+            // If the task is waiting on a mutex, the mutex's owner might be
+            // inheriting the task's priority
+            let waited_mutex =
+                wait::with_current_wait_payload(lock.borrow_mut(), task_cb, |payload| {
+                    if let Some(&wait::WaitPayload::Mutex(mutex_cb)) = payload {
+                        Some(mutex_cb)
+                    } else {
+                        None
+                    }
+                });
+            if let Some(waited_mutex) = waited_mutex {
+                mutex::reevaluate_inherited_priority(lock.borrow_mut(), waited_mutex);
+            }
         }
         TaskSt::Dormant | TaskSt::PendingActivation => unreachable!(),
     }
 
-    if let TaskSt::Running | TaskSt::Ready = st {
-        // - If `st == TaskSt::Running`, `task_cb` is the currently running
-        //   task. If the priority was lowered, it could be preempted by
-        //   a task in the Ready state.
-        // - If `st == TaskSt::Ready` and the priority was raised, it could
-        //   preempt the currently running task.
-        unlock_cpu_and_check_preemption(lock);
+    // - If `st == TaskSt::Running`, `task_cb` is the currently running
+    //   task. If the priority was lowered, it could be preempted by
+    //   a task in the Ready state.
+    // - If `st == TaskSt::Ready` and the priority was raised, it could
+    //   preempt the currently running task.
+    // - If `st == TaskSt::Waiting`, the priority change might have been
+    //   propagated to another task by priority inheritance.
+    unlock_cpu_and_check_preemption(lock);
+
+    Ok(())
+}
+
+/// Implements [`Task::set_deadline`].
+#[inline]
+fn set_task_deadline<Traits: KernelTraits>(
+    mut lock: klock::CpuLockGuard<Traits>,
+    task_cb: &'static TaskCb<Traits>,
+    duration_time32: timeout::Time32,
+) -> Result<(), SetTaskDeadlineError> {
+    let st = *task_cb.st.read(&*lock);
+
+    if st == TaskSt::Dormant {
+        return Err(SetTaskDeadlineError::BadObjectState);
     }
 
+    let deadline = timeout::current_time(lock.borrow_mut()).wrapping_add(duration_time32);
+    task_cb.deadline.replace(&mut *lock, Some(deadline));
+
+    if st == TaskSt::Ready {
+        // Move the task within the ready queue
+        //
+        // Safety: `task_cb` is in the ready queue
+        unsafe {
+            Traits::state()
+                .task_ready_queue
+                .reorder_task_by_deadline(lock.borrow_mut().into(), task_cb);
+        }
+    }
+
+    // - If `st == TaskSt::Running`, `task_cb` is the currently running
+    //   task. If the deadline was postponed, it could be preempted by
+    //   a task in the Ready state.
+    // - If `st == TaskSt::Ready` and the deadline was brought forward, it
+    //   could preempt the currently running task.
+    unlock_cpu_and_check_preemption(lock);
+
     Ok(())
 }
@@ -41,23 +41,43 @@ pub trait Queue<Traits>: Send + Sync + fmt::Debug + Init + 'static + private::Se
     where
         Traits: KernelTraits;
 
-    /// Choose the next task to schedule based on `prev_task_priority`, the
-    /// priority of the current task (more precisely, the task that would run
-    /// after the ongoing scheduling decision if preemption was not requested by
-    /// this decision). If there's no such current task, `prev_task_priority`
-    /// should be `usize::MAX`, in which case this method will return
-    /// `SwitchTo(_)`.
+    /// Remove the specified task `task_cb` from the ready queue.
+    ///
+    /// # Safety
+    ///
+    /// This method will cause an undefined behavior if `task_cb` is not
+    /// included in the queue or was lastly inserted to the queue with an
+    /// effective priority that is not identical to its current effective
+    /// priority.
+    unsafe fn remove_task(&self, ctx: Ctx<'_, Traits>, task_cb: &'static TaskCb<Traits>)
+    where
+        Traits: KernelTraits;
+
+    /// Return a flag indicating whether there's a task in Ready state that
+    /// would be chosen over `prev_task` by [`Self::pop_front_task`].
+    fn has_preempting_task(
+        &self,
+        ctx: Ctx<'_, Traits>,
+        prev_task: Option<&'static TaskCb<Traits>>,
+    ) -> bool
+    where
+        Traits: KernelTraits;
+
+    /// Choose the next task to schedule based on `prev_task`, the current task
+    /// (more precisely, the task that would run after the ongoing scheduling
+    /// decision if preemption was not requested by this decision). If there's
+    /// no such current task, `prev_task` should be `None`, in which case this
+    /// method will return `SwitchTo(_)`.
     ///
     /// If this method returns `SwitchTo(Some(task))`, `task` is removed from
     /// the queue.
     ///
     /// This method performs the following abstract steps:
     ///
-    ///  1. If `prev_task_priority` does not equal to `usize::MAX`, insert
-    ///     an imaginary task with that effective priority into the ready queue
-    ///     as close to the front as possible without violating the priority
-    ///     ordering. This imaginary task only exists during the duration of
-    ///     the current method call.
+    ///  1. If `prev_task` is `Some(_)`, insert an imaginary task identical to
+    ///     `prev_task` into the ready queue as close to the front as possible
+    ///     without violating the ordering. This imaginary task only exists
+    ///     during the duration of the current method call.
     ///
     ///  2. If the ready queue is empty, return `SwitchTo(None)`.
     ///
@@ -66,20 +86,20 @@ pub trait Queue<Traits>: Send + Sync + fmt::Debug + Init + 'static + private::Se
     ///  4. If the popped task `t` is the imaginary task inserted in step 1,
     ///     return `Keep`. Otherwise, return `SwitchTo(t)`.
     ///
-    /// | Has current task? | Is it blocked? | `prev_task_priority` | Has next task? |        Returns      |
-    /// | ----------------- | -------------- | -------------------- | -------------- | ------------------- |
-    /// |        no         |       no       |   `== usize::MAX`    |       no       |  `SwitchTo(None)`   |
-    /// |        no         |       no       |   `== usize::MAX`    |       yes      | `SwitchTo(Some(_))` |
-    /// |        yes        |       yes      |   `== usize::MAX`    |       no       |  `SwitchTo(None)`   |
-    /// |        yes        |       yes      |   `== usize::MAX`    |       yes      | `SwitchTo(Some(_))` |
-    /// |        yes        |       no       |   `!= usize::MAX`    |       no       |       `Keep`        |
-    /// |        yes        |       no       |   `!= usize::MAX`    |       yes      | `SwitchTo(Some(_))` |
+    /// | Has current task? | Is it blocked? | `prev_task` | Has next task? |        Returns      |
+    /// | ----------------- | -------------- | ----------- | -------------- | ------------------- |
+    /// |        no         |       no       |   `None`    |       no       |  `SwitchTo(None)`   |
+    /// |        no         |       no       |   `None`    |       yes      | `SwitchTo(Some(_))` |
+    /// |        yes        |       yes      |   `None`    |       no       |  `SwitchTo(None)`   |
+    /// |        yes        |       yes      |   `None`    |       yes      | `SwitchTo(Some(_))` |
+    /// |        yes        |       no       |  `Some(_)`  |       no       |       `Keep`        |
+    /// |        yes        |       no       |  `Some(_)`  |       yes      | `SwitchTo(Some(_))` |
     ///
     ///  - *Has current task?* and *Is it blocked?* columns are contexts in
     ///    which this method is called but are not directly observable by this
     ///    method's implementation.
     ///
-    ///  - `prev_task_priority` is the value passed to this method.
+    ///  - `prev_task` is the value passed to this method.
     ///
     ///  - *Has next task?* column is a possible outcome of the scheduling
     ///    decision made by this method.
@@ -90,7 +110,7 @@ pub trait Queue<Traits>: Send + Sync + fmt::Debug + Init + 'static + private::Se
     fn pop_front_task(
         &self,
         ctx: Ctx<'_, Traits>,
-        prev_task_priority: usize,
+        prev_task: Option<&'static TaskCb<Traits>>,
     ) -> ScheduleDecision<&'static TaskCb<Traits>>
     where
         Traits: KernelTraits;
@@ -120,6 +140,23 @@ pub trait Queue<Traits>: Send + Sync + fmt::Debug + Init + 'static + private::Se
         old_effective_priority: usize,
     ) where
         Traits: KernelTraits;
+
+    /// Reposition the specified task within the ready queue after a change in
+    /// its deadline ([`TaskCb::deadline`]). This is a no-op if the queue
+    /// doesn't take deadlines into account.
+    ///
+    /// # Safety
+    ///
+    /// This method will cause an undefined behavior if `task_cb` is not
+    /// included in the queue or was lastly inserted to the queue with an
+    /// effective priority that is not identical to its current effective
+    /// priority.
+    unsafe fn reorder_task_by_deadline(
+        &self,
+        ctx: Ctx<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+    ) where
+        Traits: KernelTraits;
 }
 
 /// Implements [the sealed trait pattern], which prevents [`Queue`] against
@@ -241,6 +278,9 @@ macro_rules! list_accessor {
     }};
 }
 
+mod edf;
+pub use self::edf::EdfQueue;
+
 impl<Traits: KernelTraits, Bitmap: PrioBitmap, const LEN: usize> Queue<Traits>
     for BitmapQueue<
         Traits,
@@ -268,6 +308,16 @@ where
         highest_task_priority < range.end
     }
 
+    #[inline]
+    fn has_preempting_task(
+        &self,
+        Ctx { lock }: Ctx<'_, Traits>,
+        prev_task: Option<&'static TaskCb<Traits>>,
+    ) -> bool {
+        let prev_task_priority = task_priority_or_max(&lock, prev_task);
+        self.has_ready_task_in_priority_range(Ctx { lock }, ..prev_task_priority)
+    }
+
     #[inline]
     unsafe fn push_back_task(
         &self,
@@ -289,12 +339,36 @@ where
         self.bitmap.write(&mut *lock).set(pri);
     }
 
+    #[inline]
+    unsafe fn remove_task(
+        &self,
+        Ctx { mut lock }: Ctx<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+    ) {
+        // Remove the task from a ready queue
+        let pri = task_cb.effective_priority.read(&*lock).to_usize().unwrap();
+        let pri_empty = {
+            let mut accessor = list_accessor!(&self.queues[pri], lock.borrow_mut());
+            // Safety:  `task_cb` is definitely linked to this list, so `remove`
+            //          shouldn't return `ItemError::NotLinked`.
+            unsafe { accessor.remove(Ident(task_cb)).unwrap_unchecked() };
+            accessor.is_empty()
+        };
+
+        // Update `bitmap` accordingly
+        if pri_empty {
+            self.bitmap.write(&mut *lock).clear(pri);
+        }
+    }
+
     #[inline]
     fn pop_front_task(
         &self,
         Ctx { mut lock }: Ctx<'_, Traits>,
-        prev_task_priority: usize,
+        prev_task: Option<&'static TaskCb<Traits>>,
     ) -> ScheduleDecision<&'static TaskCb<Traits>> {
+        let prev_task_priority = task_priority_or_max(&lock, prev_task);
+
         // The priority of the next task to run
         //
         // Consider the case where `prev_task_priority == usize::MAX`, i.e.,
@@ -377,6 +451,22 @@ where
             task_ready_bitmap.clear(old_effective_priority);
         }
     }
+
+    #[inline]
+    unsafe fn reorder_task_by_deadline(&self, _: Ctx<'_, Traits>, _: &'static TaskCb<Traits>) {
+        // `BitmapQueue` doesn't take deadlines into account
+    }
+}
+
+/// Get the effective priority of `task_cb`, or `usize::MAX` if it's `None`.
+#[inline]
+fn task_priority_or_max<Traits: KernelTraits>(
+    lock: &CpuLockTokenRefMut<'_, Traits>,
+    task_cb: Option<&'static TaskCb<Traits>>,
+) -> usize {
+    task_cb.map_or(usize::MAX, |task_cb| {
+        task_cb.effective_priority.read(&**lock).to_usize().unwrap()
+    })
 }
 
 impl<
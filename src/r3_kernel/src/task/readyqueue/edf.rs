@@ -0,0 +1,410 @@
+//! The earliest-deadline-first task ready queue
+use core::{cmp::Ordering, fmt, ops::RangeTo};
+use num_traits::ToPrimitive;
+
+use super::{
+    private, task_priority_or_max, BitmapQueuePerTaskData, BitmapQueueTaskCb, Ctx, Queue,
+    ScheduleDecision,
+};
+use crate::{
+    klock::{CpuLockCell, CpuLockTokenRefMut},
+    task::TaskCb,
+    timeout::Time32,
+    utils::{
+        intrusive_list::{Ident, ListAccessorCell, Static, StaticListHead},
+        Init, PrioBitmap,
+    },
+    KernelCfg1, KernelTraits, PortThreading,
+};
+
+/// The ready queue implementation that orders tasks having the same effective
+/// priority by their absolute deadlines ([`TaskCb::deadline`]).
+///
+/// Tasks are primarily ordered by effective priority, so a priority elevated
+/// by a mutex locking protocol takes precedence over deadlines. Within each
+/// priority level, tasks are ordered by absolute deadline in the ascending
+/// order, and tasks without a deadline come last. Tasks having identical
+/// priorities and deadlines are scheduled in the FIFO order.
+///
+/// Deadlines are compared by the wrap-around difference of event times, so
+/// the result is meaningful only if they are no more than `i32::MAX`
+/// microseconds apart from each other. This holds for all deadlines set in
+/// the past `i32::MAX` microseconds.
+pub struct EdfQueue<
+    Traits: PortThreading,
+    PortTaskState: 'static,
+    TaskPriority: 'static,
+    Bitmap: 'static,
+    const LEN: usize,
+> {
+    /// The set of segregated task ready queues, in which each queue stores
+    /// the list of Ready tasks at the corresponding priority, sorted by
+    /// deadline.
+    ///
+    /// Invariant: `queues[i].first.is_some() == bitmap.get(i)`
+    queues: [EdfQueueListHead<Traits, PortTaskState, TaskPriority>; LEN],
+
+    /// The task ready bitmap, in which each bit indicates whether the
+    /// segregated queue corresponding to that bit contains a task or not.
+    bitmap: CpuLockCell<Traits, Bitmap>,
+}
+
+type EdfQueueListHead<Traits, PortTaskState, TaskPriority> =
+    CpuLockCell<Traits, StaticListHead<BitmapQueueTaskCb<Traits, PortTaskState, TaskPriority>>>;
+
+impl<
+        Traits: PortThreading,
+        PortTaskState: 'static,
+        TaskPriority: 'static,
+        Bitmap: 'static + Init,
+        const LEN: usize,
+    > Init for EdfQueue<Traits, PortTaskState, TaskPriority, Bitmap, LEN>
+{
+    const INIT: Self = Self {
+        queues: Init::INIT,
+        bitmap: Init::INIT,
+    };
+}
+
+/// Return a flag indicating whether the deadline `a` is earlier than `b`.
+/// `None` represents the lack of a deadline and is later than any deadline.
+#[inline]
+fn is_deadline_earlier(a: Option<Time32>, b: Option<Time32>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a.wrapping_sub(b) as i32) < 0,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+impl<Traits: KernelTraits, Bitmap: PrioBitmap, const LEN: usize>
+    EdfQueue<
+        Traits,
+        <Traits as PortThreading>::PortTaskState,
+        <Traits as KernelCfg1>::TaskPriority,
+        Bitmap,
+        LEN,
+    >
+where
+    Traits: KernelCfg1<TaskReadyQueue = Self>,
+{
+    /// Insert `task_cb` to `self.queues[pri]` as close to the back as
+    /// possible without violating the deadline ordering.
+    ///
+    /// # Safety
+    ///
+    /// `task_cb` must not be included in any of `self.queues[..]`.
+    #[inline]
+    unsafe fn insert_task(
+        &self,
+        mut lock: CpuLockTokenRefMut<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+        pri: usize,
+    ) {
+        let deadline = task_cb.deadline.get(&*lock);
+
+        let mut accessor = list_accessor!(&self.queues[pri], lock.borrow_mut());
+
+        // Find the insertion position, starting from the back. This makes the
+        // insertion of a task without a deadline a constant-time operation.
+        let mut insert_at = None;
+        let Ok(mut cursor) = accessor.back();
+        while let Some(next_cursor) = cursor {
+            // Should `task_cb` be inserted at this or an earlier position?
+            let next_cursor_deadline = next_cursor.0.deadline.get(&**accessor.cell_key());
+            if is_deadline_earlier(deadline, next_cursor_deadline) {
+                // If so, update `insert_at`. Continue searching because there
+                // might be a viable position that is even earlier.
+                insert_at = Some(next_cursor);
+                // Safety: `next_cursor` is linked, so `prev` shouldn't return
+                //         `ItemError::Unlinked`.
+                cursor = unsafe { accessor.prev(next_cursor).unwrap_unchecked() };
+            } else {
+                break;
+            }
+        }
+
+        // Safety: `task_cb` is unlinked, so it shouldn't return
+        //         `InsertError::AlreadyLinked`.
+        unsafe {
+            accessor
+                .insert(Ident(task_cb), insert_at)
+                .unwrap_unchecked()
+        };
+    }
+
+    /// Remove `task_cb` from `self.queues[pri]`. Returns a flag indicating
+    /// whether `self.queues[pri]` became empty.
+    ///
+    /// # Safety
+    ///
+    /// `task_cb` must be included in `self.queues[pri]`.
+    #[inline]
+    unsafe fn unlink_task(
+        &self,
+        mut lock: CpuLockTokenRefMut<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+        pri: usize,
+    ) -> bool {
+        let mut accessor = list_accessor!(&self.queues[pri], lock.borrow_mut());
+        // Safety:  `task_cb` is definitely linked to this list, so `remove`
+        //          shouldn't return `ItemError::NotLinked`.
+        unsafe { accessor.remove(Ident(task_cb)).unwrap_unchecked() };
+        accessor.is_empty()
+    }
+
+    /// Make the scheduling decision described by [`Queue::pop_front_task`]
+    /// without modifying the queue. `SwitchTo(Some(pri))` indicates that the
+    /// front task of `self.queues[pri]` should be chosen.
+    #[inline]
+    fn decide(
+        &self,
+        mut lock: CpuLockTokenRefMut<'_, Traits>,
+        prev_task: Option<&'static TaskCb<Traits>>,
+    ) -> ScheduleDecision<usize> {
+        let prev_task_priority = task_priority_or_max(&lock, prev_task);
+
+        // See `BitmapQueue::pop_front_task` for why the default value is
+        // `usize::MAX - 1`
+        let next_task_priority = self
+            .bitmap
+            .read(&*lock)
+            .find_set()
+            .unwrap_or(usize::MAX - 1);
+
+        match prev_task_priority.cmp(&next_task_priority) {
+            Ordering::Less => ScheduleDecision::Keep,
+            Ordering::Equal => {
+                // `prev_task` is inserted before the tasks having the same
+                // deadline, so it's preempted only by an earlier deadline
+                let accessor = list_accessor!(&self.queues[next_task_priority], lock.borrow_mut());
+                let Ok(next_task) = accessor.front();
+                // There must be at least one element, because the bitmap
+                // indicated so
+                let next_task = next_task.unwrap().0;
+
+                let key = &**accessor.cell_key();
+                if is_deadline_earlier(
+                    next_task.deadline.get(key),
+                    prev_task.unwrap().deadline.get(key),
+                ) {
+                    ScheduleDecision::SwitchTo(Some(next_task_priority))
+                } else {
+                    ScheduleDecision::Keep
+                }
+            }
+            Ordering::Greater if next_task_priority < LEN => {
+                ScheduleDecision::SwitchTo(Some(next_task_priority))
+            }
+            Ordering::Greater => ScheduleDecision::SwitchTo(None),
+        }
+    }
+}
+
+impl<Traits: KernelTraits, Bitmap: PrioBitmap, const LEN: usize> Queue<Traits>
+    for EdfQueue<
+        Traits,
+        <Traits as PortThreading>::PortTaskState,
+        <Traits as KernelCfg1>::TaskPriority,
+        Bitmap,
+        LEN,
+    >
+where
+    Traits: KernelCfg1<TaskReadyQueue = Self>,
+{
+    // `EdfQueue` stores tasks in the same way as `BitmapQueue` does
+    type PerTaskData = BitmapQueuePerTaskData<
+        Traits,
+        <Traits as PortThreading>::PortTaskState,
+        <Traits as KernelCfg1>::TaskPriority,
+    >;
+
+    #[inline]
+    fn has_ready_task_in_priority_range(
+        &self,
+        Ctx { lock }: Ctx<'_, Traits>,
+        range: RangeTo<usize>,
+    ) -> bool {
+        let highest_task_priority = self.bitmap.read(&*lock).find_set().unwrap_or(usize::MAX);
+        highest_task_priority < range.end
+    }
+
+    #[inline]
+    fn has_preempting_task(
+        &self,
+        Ctx { lock }: Ctx<'_, Traits>,
+        prev_task: Option<&'static TaskCb<Traits>>,
+    ) -> bool {
+        matches!(
+            self.decide(lock, prev_task),
+            ScheduleDecision::SwitchTo(Some(_))
+        )
+    }
+
+    #[inline]
+    unsafe fn push_back_task(
+        &self,
+        Ctx { mut lock }: Ctx<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+    ) {
+        let pri = task_cb.effective_priority.read(&*lock).to_usize().unwrap();
+
+        // Safety: `task_cb` is unlinked (upheld by the caller)
+        unsafe { self.insert_task(lock.borrow_mut(), task_cb, pri) };
+
+        // Update `bitmap` accordingly
+        self.bitmap.write(&mut *lock).set(pri);
+    }
+
+    #[inline]
+    unsafe fn remove_task(
+        &self,
+        Ctx { mut lock }: Ctx<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+    ) {
+        let pri = task_cb.effective_priority.read(&*lock).to_usize().unwrap();
+
+        // Safety: `task_cb` is linked to `self.queues[pri]` (upheld by the
+        //         caller)
+        let pri_empty = unsafe { self.unlink_task(lock.borrow_mut(), task_cb, pri) };
+
+        // Update `bitmap` accordingly
+        if pri_empty {
+            self.bitmap.write(&mut *lock).clear(pri);
+        }
+    }
+
+    #[inline]
+    fn pop_front_task(
+        &self,
+        Ctx { mut lock }: Ctx<'_, Traits>,
+        prev_task: Option<&'static TaskCb<Traits>>,
+    ) -> ScheduleDecision<&'static TaskCb<Traits>> {
+        match self.decide(lock.borrow_mut(), prev_task) {
+            ScheduleDecision::Keep => ScheduleDecision::Keep,
+            ScheduleDecision::SwitchTo(Some(next_task_priority)) => {
+                // Take the first task from the ready queue corresponding to
+                // `next_task_priority`
+                let mut accessor =
+                    list_accessor!(&self.queues[next_task_priority], lock.borrow_mut());
+                let Ok(task) = accessor.pop_front();
+                // There must be at least one element, because the bitmap
+                // indicated so
+                let task = task.unwrap().0;
+
+                // Update `bitmap` accordingly
+                if accessor.is_empty() {
+                    self.bitmap.write(&mut *lock).clear(next_task_priority);
+                }
+
+                ScheduleDecision::SwitchTo(Some(task))
+            }
+            ScheduleDecision::SwitchTo(None) => ScheduleDecision::SwitchTo(None),
+        }
+    }
+
+    #[inline]
+    unsafe fn reorder_task(
+        &self,
+        Ctx { mut lock }: Ctx<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+        effective_priority: usize,
+        old_effective_priority: usize,
+    ) {
+        debug_assert_ne!(effective_priority, old_effective_priority);
+
+        // Move the task between ready queues
+        //
+        // Safety: `task_cb` is linked to `self.queues[old_effective_priority]`
+        //         (upheld by the caller)
+        let old_pri_empty =
+            unsafe { self.unlink_task(lock.borrow_mut(), task_cb, old_effective_priority) };
+
+        // Safety: `task_cb` is not affiliated to any of `self.queues[..]` at
+        //         this point
+        unsafe { self.insert_task(lock.borrow_mut(), task_cb, effective_priority) };
+
+        // Update `bitmap` accordingly
+        // (This code assumes `effective_priority != old_effective_priority`.)
+        let task_ready_bitmap = self.bitmap.write(&mut *lock);
+        task_ready_bitmap.set(effective_priority);
+        if old_pri_empty {
+            task_ready_bitmap.clear(old_effective_priority);
+        }
+    }
+
+    #[inline]
+    unsafe fn reorder_task_by_deadline(
+        &self,
+        Ctx { mut lock }: Ctx<'_, Traits>,
+        task_cb: &'static TaskCb<Traits>,
+    ) {
+        let pri = task_cb.effective_priority.read(&*lock).to_usize().unwrap();
+
+        // Re-insert the task to the same ready queue. `bitmap` doesn't change
+        // because the queue is not empty before and after this operation.
+        //
+        // Safety: `task_cb` is linked to `self.queues[pri]` (upheld by the
+        //         caller)
+        unsafe {
+            self.unlink_task(lock.borrow_mut(), task_cb, pri);
+            self.insert_task(lock.borrow_mut(), task_cb, pri);
+        }
+    }
+}
+
+impl<
+        Traits: KernelTraits,
+        PortTaskState: 'static,
+        TaskPriority: 'static,
+        Bitmap: 'static + fmt::Debug,
+        const LEN: usize,
+    > fmt::Debug for EdfQueue<Traits, PortTaskState, TaskPriority, Bitmap, LEN>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Ok(lock) = crate::klock::lock_cpu() {
+            let lock = core::cell::RefCell::new(lock);
+            let lock = &lock; // capture-by-reference in the closure below
+
+            struct DebugFn<F>(F);
+            impl<F: Fn(&mut fmt::Formatter) -> fmt::Result> fmt::Debug for DebugFn<F> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    (self.0)(f)
+                }
+            }
+
+            f.write_str("EdfQueue ")?;
+            f.debug_map()
+                .entries(self.queues.iter().enumerate().map(|(i, head_cell)| {
+                    (
+                        // key = priority
+                        i,
+                        // value = list of tasks
+                        DebugFn(move |f: &mut fmt::Formatter| {
+                            let mut lock = lock.borrow_mut();
+                            let accessor = list_accessor!(head_cell, lock.borrow_mut());
+                            f.debug_list()
+                                .entries(accessor.iter().map(|x| x.unwrap().0))
+                                .finish()
+                        }),
+                    )
+                }))
+                .finish()
+        } else {
+            f.write_str("EdfQueue { < locked > }")
+        }
+    }
+}
+
+impl<Traits: KernelTraits, Bitmap: PrioBitmap, const LEN: usize> private::Sealed
+    for EdfQueue<
+        Traits,
+        <Traits as PortThreading>::PortTaskState,
+        <Traits as KernelCfg1>::TaskPriority,
+        Bitmap,
+        LEN,
+    >
+where
+    Traits: KernelCfg1<TaskReadyQueue = Self>,
+{
+}
@@ -0,0 +1,134 @@
+//! Stack overflow detection and stack usage measurement
+//!
+//! Every task stack is filled with [`STACK_PAINT`] at boot time. Since all
+//! supported architectures have a full-descending stack, a task's stack usage
+//! grows toward the lowest address of its stack region. The first
+//! [`CANARY_LEN`] bytes of the region are used as a canary, which is checked
+//! whenever the task leaves the processor.
+//!
+//! A stack frame larger than the canary can skip over it. To catch such
+//! overflows, ports additionally report the stack pointer saved for the task
+//! leaving the processor through [`PortToKernel::check_stack_pointer`], which
+//! is checked by [`check_stack_pointer`].
+//!
+//! [`PortToKernel::check_stack_pointer`]: crate::PortToKernel::check_stack_pointer
+use r3_core::kernel::{task::TaskHandle, StaticTask};
+
+use super::{task_id_from_cb, TaskCb};
+use crate::{klock, KernelTraits};
+
+/// The value used to fill unused portions of task stacks.
+const STACK_PAINT: u8 = 0xa5;
+
+/// The size of the canary at the far end of a task stack.
+const CANARY_LEN: usize = 16;
+
+/// Fill the task's stack with [`STACK_PAINT`].
+///
+/// # Safety
+///
+/// Nothing must be running on the task's stack.
+pub(super) unsafe fn paint<Traits: KernelTraits>(task_cb: &TaskCb<Traits>) {
+    let stack = &task_cb.attr.stack;
+
+    // Safety: The stack region is valid for writes, and the caller guarantees
+    // that no one is using it
+    unsafe { stack.hunk().as_ptr().write_bytes(STACK_PAINT, stack.len) };
+}
+
+/// Check the canary of the task's stack, calling the stack overflow hook if
+/// it has been overwritten.
+///
+/// This must be called when `task_cb` has just left the processor.
+#[inline]
+pub(super) fn check<Traits: KernelTraits>(task_cb: &'static TaskCb<Traits>) {
+    let stack = &task_cb.attr.stack;
+    let canary_len = CANARY_LEN.min(stack.len);
+
+    // Safety: The stack region is valid for reads
+    if unsafe { painted_len(stack.hunk().as_ptr(), canary_len) } < canary_len {
+        report_overflow(task_cb);
+    }
+}
+
+/// Check that `sp`, the stack pointer saved for the task leaving the
+/// processor, points to the task's stack region outside the canary, calling
+/// the stack overflow hook if it doesn't.
+#[inline]
+pub(super) fn check_stack_pointer<Traits: KernelTraits>(
+    task_cb: &'static TaskCb<Traits>,
+    sp: usize,
+) {
+    let stack = &task_cb.attr.stack;
+    let start = stack.hunk().as_ptr() as usize;
+    let canary_len = CANARY_LEN.min(stack.len);
+
+    // A full-descending stack pointer points to one past the end of the region
+    // when the stack is empty
+    if !(start + canary_len..=start + stack.len).contains(&sp) {
+        report_overflow(task_cb);
+    }
+}
+
+/// Get the task's stack high-water mark.
+///
+/// This requires CPU Lock so that the task doesn't run on the stack while
+/// it's being scanned.
+pub(super) fn high_water_mark<Traits: KernelTraits>(
+    _lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &TaskCb<Traits>,
+) -> usize {
+    let stack = &task_cb.attr.stack;
+
+    // Safety: The stack region is valid for reads
+    stack.len - unsafe { painted_len(stack.hunk().as_ptr(), stack.len) }
+}
+
+/// Count the bytes still having [`STACK_PAINT`] from the start of the given
+/// memory region, stopping at the first byte having a different value.
+///
+/// The region might be concurrently modified by the task owning it, hence the
+/// use of volatile reads.
+///
+/// # Safety
+///
+/// `ptr[0..len]` must be valid for reads.
+unsafe fn painted_len(ptr: *const u8, len: usize) -> usize {
+    (0..len)
+        // Safety: Upheld by the caller
+        .take_while(|&i| unsafe { ptr.add(i).read_volatile() } == STACK_PAINT)
+        .count()
+}
+
+#[cold]
+fn report_overflow<Traits: KernelTraits>(task_cb: &'static TaskCb<Traits>) {
+    // Safety: `task_id_from_cb` returns a valid task ID
+    let task = unsafe { StaticTask::from_id(task_id_from_cb(task_cb)) };
+
+    if let Some(hook) = Traits::STACK_OVERFLOW_HOOK {
+        hook(task);
+    } else {
+        panic!("stack overflow detected in {task:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn painted_len_counts_leading_paint() {
+        let mut region = [STACK_PAINT; 64];
+        assert_eq!(unsafe { painted_len(region.as_ptr(), 64) }, 64);
+        assert_eq!(unsafe { painted_len(region.as_ptr(), 16) }, 16);
+
+        region[40] = 0;
+        assert_eq!(unsafe { painted_len(region.as_ptr(), 64) }, 40);
+
+        // A paint-colored byte past a used byte doesn't count
+        region[8] = !STACK_PAINT;
+        region[40] = STACK_PAINT;
+        assert_eq!(unsafe { painted_len(region.as_ptr(), 64) }, 8);
+        assert_eq!(unsafe { painted_len(region.as_ptr(), 0) }, 0);
+    }
+}
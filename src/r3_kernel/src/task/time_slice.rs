@@ -0,0 +1,79 @@
+//! Round-robin time slicing
+use core::pin::Pin;
+use num_traits::ToPrimitive;
+use r3_core::kernel::raw::KernelBase;
+
+use super::{make_ready, readyqueue::Queue as _, TaskCb, TaskSt};
+use crate::{klock, timeout, KernelTraits, System};
+
+/// Construct the [`timeout::Timeout`] used to enforce the time slice of the
+/// running task.
+pub(crate) const fn time_slice_timeout<Traits: KernelTraits>() -> timeout::Timeout<Traits> {
+    timeout::Timeout::new(time_slice_timeout_handler::<Traits>, 0)
+}
+
+/// Start a new time slice for `task`, which has just been chosen as the next
+/// running task. Stops the current time slice if `task` is `None` or doesn't
+/// have a time slice.
+#[inline]
+pub(super) fn restart<Traits: KernelTraits>(
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task: Option<&'static TaskCb<Traits>>,
+) {
+    let timeout = &Traits::state().time_slice_timeout;
+
+    if timeout.is_linked(lock.borrow_mut()) {
+        timeout::remove_timeout(lock.borrow_mut(), timeout);
+    }
+
+    if let Some(time_slice) = task.and_then(|task| task.attr.time_slice) {
+        timeout.set_expiration_after(lock.borrow_mut(), time_slice);
+
+        // Safety: `timeout` is in a `static` item
+        timeout::insert_timeout(lock, unsafe { Pin::new_unchecked(timeout) });
+    }
+}
+
+/// The timeout callback function called when the running task exhausts its
+/// time slice.
+fn time_slice_timeout_handler<Traits: KernelTraits>(
+    _: usize,
+    mut lock: klock::CpuLockGuard<Traits>,
+) -> klock::CpuLockGuard<Traits> {
+    let Some(running_task) = Traits::state().running_task(lock.borrow_mut()) else {
+        return lock;
+    };
+
+    if *running_task.st.read(&*lock) != TaskSt::Running {
+        // The dispatcher is about to choose the next task to run, which will
+        // start a new time slice
+        return lock;
+    }
+
+    let priority = running_task
+        .effective_priority
+        .read(&*lock)
+        .to_usize()
+        .unwrap();
+
+    // Don't rotate if Priority Boost is active or there are no other tasks at
+    // the same priority. Start a new time slice instead so that the task will
+    // be checked again later.
+    if System::<Traits>::raw_is_priority_boost_active()
+        || !Traits::state()
+            .task_ready_queue
+            .has_ready_task_in_priority_range(lock.borrow_mut().into(), ..priority + 1)
+    {
+        restart(lock.borrow_mut(), Some(running_task));
+        return lock;
+    }
+
+    // Move the running task to the back of the ready queue. `handle_tick`
+    // will call `unlock_cpu_and_check_preemption`, which will find the
+    // running task not in the Running state anymore and call `yield_cpu`.
+    //
+    // Safety: The previous state is Running, so this is safe
+    unsafe { make_ready(lock.borrow_mut(), running_task) };
+
+    lock
+}
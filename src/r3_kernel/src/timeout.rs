@@ -315,6 +315,35 @@ pub(super) const fn time32_from_duration(duration: Duration) -> Result<Time32, B
     }
 }
 
+/// Convert an absolute system time `deadline` to the duration from the current
+/// system time, saturating to zero if `deadline` is in the past. Fails with
+/// `BadParam` if `deadline` is further in the future than [`Duration::MAX`].
+///
+/// The result is meant to be used as a relative timeout. Consequently, a
+/// timeout created in this way is affected by [`raw_adjust_time`] (which moves
+/// the system time and event times together) but not by [`raw_set_time`]
+/// (which only moves the system time) that happens after the conversion.
+///
+/// [`raw_adjust_time`]: r3_core::kernel::raw::KernelAdjustTime::raw_adjust_time
+/// [`raw_set_time`]: r3_core::kernel::raw::KernelBase::raw_set_time
+#[inline]
+#[cfg(feature = "system_time")]
+pub(super) fn time32_from_deadline<Traits: KernelTraits>(
+    lock: CpuLockTokenRefMut<'_, Traits>,
+    deadline: Time,
+) -> Result<Time32, BadParamError> {
+    let cur_sys_time = current_system_time(lock);
+    let delta = time64_from_sys_time(deadline).wrapping_sub(cur_sys_time) as i64;
+
+    if delta <= 0 {
+        Ok(0)
+    } else if let Ok(delta) = i32::try_from(delta) {
+        Ok(delta as Time32)
+    } else {
+        Err(BadParamError::BadParam)
+    }
+}
+
 /// Convert the negation of `duration` to `Time32`.
 #[inline]
 pub(super) fn time32_from_neg_duration(duration: Duration) -> Result<Time32, BadParamError> {
@@ -661,14 +690,20 @@ pub(super) fn system_time<Traits: KernelTraits>() -> Result<Time, TimeError> {
     expect_task_context::<Traits>()?;
     let mut lock = lock_cpu::<Traits>()?;
 
+    let cur_sys_time = current_system_time(lock.borrow_mut());
+
+    // Convert `Time64` to a public type
+    Ok(sys_time_from_time64(cur_sys_time))
+}
+
+/// Get the current system time.
+#[cfg(feature = "system_time")]
+fn current_system_time<Traits: KernelTraits>(mut lock: CpuLockTokenRefMut<'_, Traits>) -> Time64 {
     let (duration_since_last_tick, _) = duration_since_last_tick(lock.borrow_mut());
     let last_tick_sys_time = Traits::g_timeout()
         .last_tick_sys_time
         .get(&*lock.borrow_mut());
-    let cur_sys_time = last_tick_sys_time.wrapping_add(duration_since_last_tick as Time64);
-
-    // Convert `Time64` to a public type
-    Ok(sys_time_from_time64(cur_sys_time))
+    last_tick_sys_time.wrapping_add(duration_since_last_tick as Time64)
 }
 
 /// Implements [`Kernel::set_time`].
@@ -875,6 +910,11 @@ pub(super) fn handle_tick<Traits: KernelTraits>() {
 
     mark_tick(lock.borrow_mut());
 
+    // Sample the runtime counter regularly so that it doesn't wrap around
+    // more than once between samples
+    #[cfg(feature = "runtime_stats")]
+    crate::runtime_stats::sample(lock.borrow_mut());
+
     let g_timeout = Traits::g_timeout();
     let current_time = g_timeout.last_tick_time.get(&*lock);
     let critical_point = critical_point(current_time);
@@ -937,7 +977,9 @@ pub(super) fn handle_tick<Traits: KernelTraits>() {
 }
 
 /// Get the current event time.
-fn current_time<Traits: KernelTraits>(mut lock: CpuLockTokenRefMut<'_, Traits>) -> Time32 {
+pub(super) fn current_time<Traits: KernelTraits>(
+    mut lock: CpuLockTokenRefMut<'_, Traits>,
+) -> Time32 {
     let (duration_since_last_tick, _) = duration_since_last_tick::<Traits>(lock.borrow_mut());
 
     let g_timeout = Traits::g_timeout();
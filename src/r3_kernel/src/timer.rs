@@ -15,6 +15,8 @@ use crate::{
     Id, KernelCfg2, KernelTraits, System,
 };
 
+pub(super) mod service;
+
 pub(super) type TimerId = Id;
 
 impl<Traits: KernelTraits> System<Traits> {
@@ -47,6 +49,11 @@ unsafe impl<Traits: KernelTraits> traits::KernelTimer for System<Traits> {
         // Safety: The caller is responsible for providing a valid object ID
         let timer_cb = unsafe { Self::timer_cb(this)? };
         stop_timer(lock.borrow_mut(), timer_cb);
+        if timer_cb.attr.deferred {
+            // Don't let the timer service task call the callback function for
+            // the expirations that occurred before this
+            service::purge(lock.borrow_mut(), this.get() - 1);
+        }
         Ok(())
     }
 
@@ -110,6 +117,10 @@ pub struct TimerCb<Traits: KernelCfg2> {
     pub(super) active: CpuLockCell<Traits, bool>,
 
     pub(super) period: CpuLockCell<Traits, timeout::Time32>,
+
+    /// The number of expirations dropped because the timer service task's
+    /// queue was full. Only used if [`TimerAttr::deferred`] is `true`.
+    pub(super) overruns: CpuLockCell<Traits, usize>,
 }
 
 impl<Traits: KernelTraits> Init for TimerCb<Traits> {
@@ -119,6 +130,7 @@ impl<Traits: KernelTraits> Init for TimerCb<Traits> {
         timeout: Init::INIT,
         active: Init::INIT,
         period: Init::INIT,
+        overruns: Init::INIT,
     };
 }
 
@@ -130,6 +142,7 @@ impl<Traits: KernelTraits> fmt::Debug for TimerCb<Traits> {
             .field("timeout", &self.timeout)
             .field("active", &self.active)
             .field("period", &self.period)
+            .field("overruns", &self.overruns)
             .finish()
     }
 }
@@ -152,6 +165,12 @@ pub struct TimerAttr<Traits> {
     /// The initial state of the timer.
     pub(super) init_active: bool,
 
+    /// `true` if the entry point is called by the timer service task
+    /// ([`TimerContext::Task`]).
+    ///
+    /// [`TimerContext::Task`]: r3_core::kernel::TimerContext::Task
+    pub(super) deferred: bool,
+
     pub(super) _phantom: PhantomData<Traits>,
 }
 
@@ -159,6 +178,7 @@ impl<Traits> Init for TimerAttr<Traits> {
     const INIT: Self = Self {
         entry_point: Closure::INIT,
         init_active: false,
+        deferred: false,
         _phantom: PhantomData,
     };
 }
@@ -167,6 +187,7 @@ impl<Traits: KernelTraits> fmt::Debug for TimerAttr<Traits> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("TimerAttr")
             .field("entry_point", &self.entry_point)
+            .field("deferred", &self.deferred)
             .finish()
     }
 }
@@ -297,6 +318,14 @@ pub(super) fn timer_timeout_handler<Traits: KernelTraits>(
         timeout::insert_timeout(lock.borrow_mut(), static_pin(&timer_cb.timeout));
     }
 
+    #[cfg(feature = "trace")]
+    crate::trace::timer_fire(lock.borrow_mut(), i);
+
+    if timer_cb.attr.deferred {
+        // Let the timer service task call the callback function
+        return service::enqueue(i, lock);
+    }
+
     // Release CPU Lock before calling the application-provided callback
     // function
     drop(lock);
@@ -0,0 +1,183 @@
+//! The timer service task, which calls the callback functions of the timers
+//! defined with [`TimerContext::Task`] in a task context
+//!
+//! The timer interrupt handler pushes the indices of expired timers to a
+//! fixed-capacity FIFO queue and activates the service task. The service task
+//! pops them in order, calls the callback functions with CPU Lock inactive,
+//! and exits when the queue is empty. When the queue is full, the expiration is
+//! dropped and counted as an overrun of the timer. Stopping a timer removes its
+//! expirations from the queue.
+//!
+//! [`TimerContext::Task`]: r3_core::kernel::TimerContext::Task
+use core::fmt;
+use r3_core::kernel::{timer::TimerHandle, Hunk, StaticTimer};
+
+use crate::{
+    klock::{self, CpuLockCell, CpuLockGuard, CpuLockTokenRefMut},
+    task,
+    utils::Init,
+    KernelTraits, System,
+};
+
+/// A kernel-global state for the timer service task.
+pub(crate) struct TimerServiceGlobals<Traits> {
+    /// The position of the oldest element in the queue.
+    head: CpuLockCell<Traits, usize>,
+
+    /// The number of elements in the queue.
+    len: CpuLockCell<Traits, usize>,
+}
+
+impl<Traits> Init for TimerServiceGlobals<Traits> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        head: Init::INIT,
+        len: Init::INIT,
+    };
+}
+
+impl<Traits: KernelTraits> fmt::Debug for TimerServiceGlobals<Traits> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TimerServiceGlobals")
+            .field("head", &self.head)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Get a pointer to the first element of the queue (`[usize;
+/// TIMER_SERVICE_QUEUE_CAPACITY]`).
+#[inline]
+fn queue_ptr<Traits: KernelTraits>() -> *mut usize {
+    // FIXME: `Hunk::from_offset` is an implementation detail
+    Hunk::<System<Traits>>::from_offset(Traits::TIMER_SERVICE_QUEUE_OFFSET)
+        .as_ptr()
+        .cast()
+}
+
+/// Queue an expiration of the timer `i` and activate the service task.
+///
+/// If the queue is full, this function drops the expiration, counts it as an
+/// overrun, and calls the overrun hook with CPU Lock temporarily released.
+///
+/// `i` is an index into [`crate::KernelCfg2::timer_cb_pool`].
+pub(super) fn enqueue<Traits: KernelTraits>(
+    i: usize,
+    mut lock: CpuLockGuard<Traits>,
+) -> CpuLockGuard<Traits> {
+    let g_service = &Traits::state().timer_service;
+    let capacity = Traits::TIMER_SERVICE_QUEUE_CAPACITY;
+    let len = g_service.len.get(&*lock);
+
+    if len < capacity {
+        let head = g_service.head.get(&*lock);
+        let tail = (head + len) % capacity;
+        // Safety: `tail < capacity`, and the queue is only accessed with CPU
+        // Lock active
+        unsafe { *queue_ptr::<Traits>().add(tail) = i };
+        g_service.len.replace(&mut *lock, len + 1);
+
+        activate(lock.borrow_mut());
+        return lock;
+    }
+
+    let timer_cb = Traits::get_timer_cb(i).unwrap();
+    timer_cb
+        .overruns
+        .replace_with(&mut *lock, |x| x.wrapping_add(1));
+
+    if let Some(hook) = Traits::TIMER_OVERRUN_HOOK {
+        // Release CPU Lock before calling the application-provided hook
+        // function
+        drop(lock);
+
+        // Safety: `i + 1` is a valid timer ID
+        hook(unsafe { StaticTimer::from_id(crate::Id::new(i + 1).unwrap()) });
+
+        lock = klock::lock_cpu().unwrap_or_else(|_| unsafe { klock::assume_cpu_lock() });
+    }
+
+    lock
+}
+
+/// Discard the queued expirations of the timer `i`. This is called when the
+/// timer is stopped so that its callback function isn't called afterward.
+///
+/// `i` is an index into [`crate::KernelCfg2::timer_cb_pool`].
+pub(super) fn purge<Traits: KernelTraits>(mut lock: CpuLockTokenRefMut<'_, Traits>, i: usize) {
+    let g_service = &Traits::state().timer_service;
+    let capacity = Traits::TIMER_SERVICE_QUEUE_CAPACITY;
+    let head = g_service.head.get(&*lock);
+    let len = g_service.len.get(&*lock);
+    let queue = queue_ptr::<Traits>();
+
+    // Move the remaining elements toward the head, preserving their order
+    let mut new_len = 0;
+    for k in 0..len {
+        // Safety: The indices are in range `0..capacity`, and the queue is
+        // only accessed with CPU Lock active
+        unsafe {
+            let x = *queue.add((head + k) % capacity);
+            if x != i {
+                *queue.add((head + new_len) % capacity) = x;
+                new_len += 1;
+            }
+        }
+    }
+    g_service.len.replace(&mut *lock, new_len);
+}
+
+/// Check if `task_cb` is the service task.
+#[inline]
+pub(crate) fn is_service_task<Traits: KernelTraits>(task_cb: &'static task::TaskCb<Traits>) -> bool {
+    Traits::TIMER_SERVICE_QUEUE_CAPACITY > 0
+        && core::ptr::eq(
+            task_cb,
+            Traits::get_task_cb(Traits::TIMER_SERVICE_TASK).unwrap(),
+        )
+}
+
+/// Activate the service task. The timer interrupt handler is responsible for
+/// checking dispatch.
+fn activate<Traits: KernelTraits>(lock: CpuLockTokenRefMut<'_, Traits>) {
+    let task_cb = Traits::get_task_cb(Traits::TIMER_SERVICE_TASK).unwrap();
+
+    // If the service task is still running, this queues an activation request
+    // so that it checks the queue again before going back to sleep.
+    // `QueueOverflow` means there's already a pending activation request.
+    let _ = task::activate_core(lock, task_cb);
+}
+
+/// Dequeue the oldest expiration. Returns an index into
+/// [`crate::KernelCfg2::timer_cb_pool`].
+fn dequeue<Traits: KernelTraits>(mut lock: CpuLockTokenRefMut<'_, Traits>) -> Option<usize> {
+    let g_service = &Traits::state().timer_service;
+    let len = g_service.len.get(&*lock);
+    if len == 0 {
+        return None;
+    }
+
+    let head = g_service.head.get(&*lock);
+    // Safety: `head < TIMER_SERVICE_QUEUE_CAPACITY`, and the queue is only
+    // accessed with CPU Lock active
+    let i = unsafe { *queue_ptr::<Traits>().add(head) };
+    g_service.head.replace(
+        &mut *lock,
+        (head + 1) % Traits::TIMER_SERVICE_QUEUE_CAPACITY,
+    );
+    g_service.len.replace(&mut *lock, len - 1);
+    Some(i)
+}
+
+/// The entry point of the service task. Calls the callback functions of the
+/// queued expirations and exits when the queue is empty.
+pub(crate) fn task_body<Traits: KernelTraits>() {
+    loop {
+        let mut lock =
+            klock::lock_cpu::<Traits>().unwrap_or_else(|_| unsafe { klock::assume_cpu_lock() });
+        let Some(i) = dequeue(lock.borrow_mut()) else { break };
+        drop(lock);
+
+        Traits::get_timer_cb(i).unwrap().attr.entry_point.call();
+    }
+}
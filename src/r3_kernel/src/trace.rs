@@ -0,0 +1,406 @@
+//! Kernel event tracing
+//!
+//! When a trace buffer is configured by [`CfgBuilder::trace_buffer`], the
+//! kernel records task switches, task state transitions, interrupt handler
+//! activations, and some object operations into it. The trace buffer is a
+//! ring buffer placed in a [hunk]. When it's full, the oldest record is
+//! overwritten.
+//!
+//! Writing a record doesn't take any locks. A writer reserves a slot by
+//! advancing the write position with a compare-and-swap loop and then fills
+//! the slot, so every execution context (a task or an interrupt handler,
+//! managed or not) can record events without blocking the others. On targets
+//! without compare-and-swap (e.g., Armv6-M), the write position is advanced
+//! with CPU Lock active instead; CPU Lock masks all interrupts on such targets.
+//! Most recording points are inside the kernel's CPU Lock sections and record
+//! the current [`PortTimer::tick_count`]. [`PortToKernel::interrupt_enter`] and
+//! [`PortToKernel::interrupt_exit`] can't read it because it requires CPU Lock,
+//! so they leave the timestamp out and set [`FLAG_NO_TICK_COUNT`] instead.
+//! Since all timestamps are taken with CPU Lock active, they never go back in
+//! the order of the slots.
+//!
+//! If the buffer wraps around while an interrupt handler is preempting a
+//! writer, both of them can write the same slot, leaving a corrupted record
+//! in it. This takes as many events as there are slots, so it doesn't happen
+//! with a reasonably sized buffer.
+//!
+//! Readers don't need any synchronization at all: a debugger can read the
+//! trace buffer (located by [`buffer_ptr`]) whenever the target is halted. The
+//! application can also take a copy by [`dump`]. Either way,
+//! `r3_trace_decoder` turns the contents into a timeline.
+//!
+//! # Format
+//!
+//! <!-- [tag:trace_format] `r3_trace_decoder` must be kept in sync with this
+//! definition. -->
+//!
+//! All multi-byte fields are little-endian. A trace buffer starts with a
+//! 16-byte header:
+//!
+//! | Offset | Type      | Description                                |
+//! | ------ | --------- | ------------------------------------------ |
+//! | 0      | `[u8; 4]` | The magic number [`MAGIC`]                 |
+//! | 4      | `u32`     | The number of record slots                 |
+//! | 8      | `u32`     | The index of the slot to be written next   |
+//! | 12     | `u32`     | [`PortTimer::MAX_TICK_COUNT`]              |
+//!
+//! The header is followed by 12-byte record slots:
+//!
+//! | Offset | Type      | Description                                         |
+//! | ------ | --------- | --------------------------------------------------- |
+//! | 0      | `u32`     | [`PortTimer::tick_count`] at the time of the event  |
+//! | 4      | `u16`     | The subject's ID, saturated to `u16::MAX`           |
+//! | 6      | `u16`     | An event-specific argument, saturated to `u16::MAX` |
+//! | 8      | `u8`      | Flags ([`FLAG_NO_TICK_COUNT`])                      |
+//! | 9      | `[u8; 2]` | Reserved (zero)                                     |
+//! | 11     | `u8`      | [`EventKind`], or `0` if the slot is empty          |
+//!
+//! The kind byte of a slot is cleared before the slot is overwritten and is
+//! written last, so a torn record appears as an empty slot.
+//!
+//! [`CfgBuilder::trace_buffer`]: crate::cfg::CfgBuilder::trace_buffer
+//! [`PortTimer::MAX_TICK_COUNT`]: crate::PortTimer::MAX_TICK_COUNT
+//! [`PortTimer::tick_count`]: crate::PortTimer::tick_count
+//! [`PortToKernel::interrupt_enter`]: crate::PortToKernel::interrupt_enter
+//! [`PortToKernel::interrupt_exit`]: crate::PortToKernel::interrupt_exit
+//! [hunk]: r3_core::kernel::Hunk
+use core::{
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+};
+use r3_core::kernel::{raw::InterruptNum, Hunk};
+
+use crate::{klock, mutex, semaphore, task, KernelTraits, System, UTicks};
+
+/// The magic number at the beginning of a trace buffer.
+pub const MAGIC: [u8; 4] = *b"R3TR";
+
+/// The size of a trace buffer's header, measured in bytes.
+pub const HEADER_LEN: usize = 16;
+
+/// The size of a record slot, measured in bytes.
+pub const RECORD_LEN: usize = 12;
+
+/// A record flag indicating that the record doesn't have a timestamp. The
+/// tick count field is zero.
+pub const FLAG_NO_TICK_COUNT: u8 = 1;
+
+const HEADER_CAPACITY: usize = 4;
+const HEADER_HEAD: usize = 8;
+const HEADER_MAX_TICK_COUNT: usize = 12;
+
+const RECORD_ID: usize = 4;
+const RECORD_ARG: usize = 6;
+const RECORD_FLAGS: usize = 8;
+const RECORD_KIND: usize = 11;
+
+/// The kind of an event recorded in a trace buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EventKind {
+    /// The running task has changed. The ID is that of the new running task,
+    /// or `0` if there's none.
+    TaskSwitch = 1,
+    /// A task has transitioned into another state. The argument is the new
+    /// state (`0`: Dormant, `1`: Ready, `2`: Running, `3`: Waiting, `4`:
+    /// Suspended).
+    TaskState = 2,
+    /// A port is about to call an interrupt handler. The ID is the interrupt
+    /// number.
+    InterruptEnter = 3,
+    /// An interrupt handler has returned. The ID is the interrupt number.
+    InterruptExit = 4,
+    /// A semaphore is being signaled. The argument is the number of permits,
+    /// saturated to `u16::MAX`.
+    SemaphoreSignal = 5,
+    /// A task has started a wait or poll operation on a semaphore.
+    SemaphoreWait = 6,
+    /// A mutex has been given to a task. The argument is the task's ID,
+    /// saturated to `u16::MAX`.
+    MutexLock = 7,
+    /// A mutex is being released by its owner.
+    MutexUnlock = 8,
+    /// A timer has fired. The callback function is about to be called.
+    TimerFire = 9,
+}
+
+/// Get the size of a trace buffer having the specified number of record
+/// slots.
+///
+/// # Panics
+///
+/// This function panics if the size overflows `usize`.
+#[inline]
+pub const fn buffer_len_for_capacity(capacity: usize) -> usize {
+    if capacity == 0 {
+        return 0;
+    }
+    // `Option::and_then` is not `const fn` [ref:const_result_map]
+    if let Some(records_len) = capacity.checked_mul(RECORD_LEN) {
+        if let Some(len) = records_len.checked_add(HEADER_LEN) {
+            return len;
+        }
+    }
+    panic!("the trace buffer is too large");
+}
+
+/// Get a pointer to the trace buffer, including the header.
+///
+/// The returned slice is empty if no trace buffer is configured.
+#[inline]
+pub fn buffer_ptr<Traits: KernelTraits>() -> *const [u8] {
+    ptr::slice_from_raw_parts(
+        buffer_base::<Traits>(),
+        buffer_len_for_capacity(Traits::TRACE_BUFFER_CAPACITY),
+    )
+}
+
+/// Copy the contents of the trace buffer to the beginning of `out` and return
+/// the number of copied bytes.
+///
+/// Returns `None` if CPU Lock is active or `out` is too short to hold the
+/// whole buffer.
+///
+/// CPU Lock is held during the copy, so the copy doesn't contain torn records
+/// except for those written by unmanaged interrupt handlers.
+pub fn dump<Traits: KernelTraits>(out: &mut [u8]) -> Option<usize> {
+    let len = buffer_len_for_capacity(Traits::TRACE_BUFFER_CAPACITY);
+    let out = out.get_mut(..len)?;
+    let _lock = klock::lock_cpu::<Traits>().ok()?;
+
+    // Safety: The buffer is `len` bytes long. Concurrent writers only use
+    // volatile and atomic accesses.
+    unsafe { read_volatile_bytes(buffer_base::<Traits>(), out) };
+
+    Some(len)
+}
+
+#[inline]
+fn buffer_base<Traits: KernelTraits>() -> *mut u8 {
+    Hunk::<System<Traits>>::from_offset(Traits::TRACE_BUFFER_OFFSET).as_ptr()
+}
+
+/// Initialize the trace buffer at boot time.
+pub(super) fn init<Traits: KernelTraits>(_lock: klock::CpuLockTokenRefMut<'_, Traits>) {
+    let capacity = Traits::TRACE_BUFFER_CAPACITY;
+    if capacity == 0 {
+        return;
+    }
+
+    let base = buffer_base::<Traits>();
+    // Safety: CPU Lock active, and the hunk is large enough to hold the buffer
+    unsafe {
+        write_volatile_bytes(base, &MAGIC);
+        write_volatile_bytes(base.add(HEADER_CAPACITY), &(capacity as u32).to_le_bytes());
+        write_volatile_bytes(base.add(HEADER_HEAD), &0u32.to_le_bytes());
+        write_volatile_bytes(
+            base.add(HEADER_MAX_TICK_COUNT),
+            &Traits::MAX_TICK_COUNT.to_le_bytes(),
+        );
+        for i in 0..capacity {
+            write_volatile_bytes(base.add(HEADER_LEN + i * RECORD_LEN), &[0; RECORD_LEN]);
+        }
+    }
+}
+
+/// Append a record with the current tick count to the trace buffer.
+fn record<Traits: KernelTraits>(
+    _lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    kind: EventKind,
+    id: usize,
+    arg: usize,
+) {
+    if Traits::TRACE_BUFFER_CAPACITY == 0 {
+        return;
+    }
+
+    // Safety: CPU Lock active
+    let tick_count = unsafe { Traits::tick_count() };
+    write_record::<Traits>(Some(tick_count), kind, id, arg);
+}
+
+/// Append a record to the trace buffer.
+///
+/// `tick_count` is `None` if the caller can't read the tick count because
+/// CPU Lock may be inactive.
+fn write_record<Traits: KernelTraits>(
+    tick_count: Option<UTicks>,
+    kind: EventKind,
+    id: usize,
+    arg: usize,
+) {
+    let capacity = Traits::TRACE_BUFFER_CAPACITY;
+    if capacity == 0 {
+        return;
+    }
+
+    let slot_index = reserve_slot::<Traits>(capacity);
+
+    let base = buffer_base::<Traits>();
+    let id = u16::try_from(id).unwrap_or(u16::MAX);
+    let arg = u16::try_from(arg).unwrap_or(u16::MAX);
+    let flags = if tick_count.is_some() {
+        0
+    } else {
+        FLAG_NO_TICK_COUNT
+    };
+
+    // Safety: `slot_index` is in range `0..capacity`, and no one else is
+    // writing the slot unless the buffer wraps around during this function
+    unsafe {
+        let slot = base.add(HEADER_LEN + slot_index * RECORD_LEN);
+
+        // Empty the slot first so that a reader never sees a torn record
+        slot.add(RECORD_KIND).write_volatile(0);
+        write_volatile_bytes(slot, &tick_count.unwrap_or(0).to_le_bytes());
+        write_volatile_bytes(slot.add(RECORD_ID), &id.to_le_bytes());
+        write_volatile_bytes(slot.add(RECORD_ARG), &arg.to_le_bytes());
+        slot.add(RECORD_FLAGS).write_volatile(flags);
+        slot.add(RECORD_KIND).write_volatile(kind as u8);
+    }
+}
+
+/// Get the header field holding the index of the slot to be written next.
+#[inline]
+fn head<Traits: KernelTraits>() -> &'static AtomicU32 {
+    // Safety: The field is in the buffer, which is 4-byte aligned and lives
+    // forever. It's only accessed atomically after `init`.
+    unsafe { &*buffer_base::<Traits>().add(HEADER_HEAD).cast::<AtomicU32>() }
+}
+
+/// Advance the write position and return the index of the reserved slot.
+#[cfg(target_has_atomic = "32")]
+fn reserve_slot<Traits: KernelTraits>(capacity: usize) -> usize {
+    let next = |head: u32| {
+        let head = u32::from_le(head);
+        let next = if head as usize + 1 == capacity {
+            0
+        } else {
+            head + 1
+        };
+        Some(next.to_le())
+    };
+    let head = head::<Traits>()
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, next)
+        .unwrap();
+    u32::from_le(head) as usize
+}
+
+/// Advance the write position and return the index of the reserved slot.
+#[cfg(not(target_has_atomic = "32"))]
+fn reserve_slot<Traits: KernelTraits>(capacity: usize) -> usize {
+    // Without compare-and-swap, make the read-modify-write operation atomic
+    // with CPU Lock. If CPU Lock is already active, we are already in a
+    // critical section because CPU Lock masks all interrupts on such targets.
+    let _lock = klock::lock_cpu::<Traits>().ok();
+
+    let head = head::<Traits>();
+    let slot_index = u32::from_le(head.load(Ordering::Relaxed));
+    let next = if slot_index as usize + 1 == capacity {
+        0
+    } else {
+        slot_index + 1
+    };
+    head.store(next.to_le(), Ordering::Relaxed);
+    slot_index as usize
+}
+
+#[inline]
+unsafe fn write_volatile_bytes(dst: *mut u8, bytes: &[u8]) {
+    for (i, &b) in bytes.iter().enumerate() {
+        // Safety: Upheld by the caller
+        unsafe { dst.add(i).write_volatile(b) };
+    }
+}
+
+#[inline]
+unsafe fn read_volatile_bytes(src: *const u8, bytes: &mut [u8]) {
+    for (i, b) in bytes.iter_mut().enumerate() {
+        // Safety: Upheld by the caller
+        *b = unsafe { src.add(i).read_volatile() };
+    }
+}
+
+/// Get the one-based position of `x` in `pool`, which is the same as the
+/// object ID of `x`.
+#[inline]
+fn id_in_pool<T>(pool: &'static [T], x: &'static T) -> usize {
+    // Safety: `x` refers to an element of `pool`
+    unsafe { <*const T>::offset_from(x, pool.as_ptr()) as usize + 1 }
+}
+
+pub(super) fn task_switch<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: Option<&'static task::TaskCb<Traits>>,
+) {
+    let id = task_cb.map_or(0, |task_cb| id_in_pool(Traits::task_cb_pool(), task_cb));
+    record(lock, EventKind::TaskSwitch, id, 0);
+}
+
+pub(super) fn task_state<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static task::TaskCb<Traits>,
+    st: task::TaskSt,
+) {
+    let st = match st {
+        task::TaskSt::Dormant => 0,
+        task::TaskSt::Ready => 1,
+        task::TaskSt::Running => 2,
+        task::TaskSt::Waiting => 3,
+        task::TaskSt::Suspended => 4,
+        task::TaskSt::PendingActivation => unreachable!(),
+    };
+    let id = id_in_pool(Traits::task_cb_pool(), task_cb);
+    record(lock, EventKind::TaskState, id, st);
+}
+
+/// Implements [`crate::PortToKernel::interrupt_enter`] and
+/// [`crate::PortToKernel::interrupt_exit`].
+pub(super) fn interrupt<Traits: KernelTraits>(line: InterruptNum, kind: EventKind) {
+    // This may be an unmanaged interrupt handler, so we can't take CPU Lock
+    // to read the tick count
+    write_record::<Traits>(None, kind, line, 0);
+}
+
+pub(super) fn semaphore_signal<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    semaphore_cb: &'static semaphore::SemaphoreCb<Traits>,
+    count: usize,
+) {
+    let id = id_in_pool(Traits::semaphore_cb_pool(), semaphore_cb);
+    record(lock, EventKind::SemaphoreSignal, id, count);
+}
+
+pub(super) fn semaphore_wait<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    semaphore_cb: &'static semaphore::SemaphoreCb<Traits>,
+) {
+    let id = id_in_pool(Traits::semaphore_cb_pool(), semaphore_cb);
+    record(lock, EventKind::SemaphoreWait, id, 0);
+}
+
+pub(super) fn mutex_lock<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    mutex_cb: &'static mutex::MutexCb<Traits>,
+    task_cb: &'static task::TaskCb<Traits>,
+) {
+    let id = id_in_pool(Traits::mutex_cb_pool(), mutex_cb);
+    let task_id = id_in_pool(Traits::task_cb_pool(), task_cb);
+    record(lock, EventKind::MutexLock, id, task_id);
+}
+
+pub(super) fn mutex_unlock<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    mutex_cb: &'static mutex::MutexCb<Traits>,
+) {
+    let id = id_in_pool(Traits::mutex_cb_pool(), mutex_cb);
+    record(lock, EventKind::MutexUnlock, id, 0);
+}
+
+/// `i` is an index into [`crate::KernelCfg2::timer_cb_pool`].
+pub(super) fn timer_fire<Traits: KernelTraits>(
+    lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    i: usize,
+) {
+    record(lock, EventKind::TimerFire, i + 1, 0);
+}
@@ -1,4 +1,4 @@
-use core::{cell::Cell, fmt, ops, pin::pin, ptr::NonNull};
+use core::{cell::Cell, fmt, ops, pin::pin, ptr::NonNull, slice};
 use r3_core::{
     kernel::{EventGroupBits, EventGroupWaitFlags, WaitError, WaitTimeoutError},
     utils::Init,
@@ -46,6 +46,17 @@ impl<Traits: PortThreading> PartialEq for WaitRef<Traits> {
 
 impl<Traits: PortThreading> Eq for WaitRef<Traits> {}
 
+/// A reference to a [`timeout::Timeout`]`<Traits>` registered by a blocking
+/// operation with timeout. The pointee type is erased because
+/// [`timeout::Timeout`] requires `Traits: KernelTraits`, which is not available
+/// in [`TaskWait`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TaskTimeoutRef(NonNull<()>);
+
+// Safety: `Timeout` is `Send + Sync`
+unsafe impl Send for TaskTimeoutRef {}
+unsafe impl Sync for TaskTimeoutRef {}
+
 use self::unsafe_static::UnsafeStatic;
 mod unsafe_static {
     use super::*;
@@ -128,7 +139,14 @@ pub(super) enum WaitPayload<Traits: PortThreading> {
     },
     Semaphore,
     Mutex(&'static mutex::MutexCb<Traits>),
+    /// The pointer to the message to be sent.
+    MessageQueueSend(*const u8),
+    /// The pointer to the storage to receive a message.
+    MessageQueueReceive(*mut u8),
+    /// The pointer to the storage to receive the index of an allocated block.
+    MemoryPool(*mut usize),
     Park,
+    Notification,
     Sleep,
     __Nonexhaustive,
 }
@@ -156,7 +174,11 @@ impl<T: PortThreading> WaitPayload<T> {
             },
             Self::Semaphore => Self::Semaphore,
             Self::Mutex(x) => Self::Mutex(x),
+            Self::MessageQueueSend(x) => Self::MessageQueueSend(x),
+            Self::MessageQueueReceive(x) => Self::MessageQueueReceive(x),
+            Self::MemoryPool(x) => Self::MemoryPool(x),
             Self::Park => Self::Park,
+            Self::Notification => Self::Notification,
             Self::Sleep => Self::Sleep,
             Self::__Nonexhaustive => Self::__Nonexhaustive,
         }
@@ -212,16 +234,37 @@ pub(crate) struct TaskWait<Traits: PortThreading> {
     /// The pointee must be valid.
     current_wait: CpuLockCell<Traits, Option<WaitRef<Traits>>>,
 
+    /// The number of wait objects comprising the ongoing wait operation. The
+    /// wait objects are stored contiguously, starting at `current_wait`. This
+    /// is greater than one only during [`wait_any`] and is reset to one when
+    /// the wait operation completes.
+    current_wait_len: CpuLockCell<Traits, usize>,
+
+    /// The index of the wait object that completed the last wait operation.
+    /// Set by a wake-upper. Returned by [`wait_any`].
+    completed_wait_index: CpuLockCell<Traits, usize>,
+
     /// The result of the last wait operation. Set by a wake-upper. Returned by
     /// [`WaitQueue::wait`].
     wait_result: CpuLockCell<Traits, Result<(), WaitTimeoutError>>,
+
+    /// The timeout object registered by the ongoing blocking operation with
+    /// timeout. This may remain `Some(_)` for a while after the task leaves the
+    /// Waiting state. Used by [`abandon_wait_of_task`] to unregister the timeout
+    /// object before discarding the task's stack.
+    ///
+    /// The pointee must be valid.
+    current_timeout: CpuLockCell<Traits, Option<TaskTimeoutRef>>,
 }
 
 impl<Traits: PortThreading> Init for TaskWait<Traits> {
     #[allow(clippy::declare_interior_mutable_const)]
     const INIT: Self = Self {
         current_wait: Init::INIT,
+        current_wait_len: CpuLockCell::new(1),
+        completed_wait_index: Init::INIT,
         wait_result: CpuLockCell::new(Ok(())),
+        current_timeout: Init::INIT,
     };
 }
 
@@ -239,19 +282,48 @@ macro_rules! setup_timeout_wait {
             $duration_time32
         ));
 
-        // Use `TimeoutGuard` to automatically unregister the timeout when
+        // Use `TaskTimeoutGuard` to automatically unregister the timeout when
         // leaving the current lexical scope.
-        let mut timeout_guard = timeout::TimeoutGuard {
-            timeout: timeout.as_ref(),
-            lock: $lock,
+        let mut timeout_guard = TaskTimeoutGuard {
+            inner: timeout::TimeoutGuard {
+                timeout: timeout.as_ref(),
+                lock: $lock,
+            },
+            task_cb: $task_cb,
         };
-        let mut $lock = timeout_guard.lock.borrow_mut();
+        let mut $lock = timeout_guard.inner.lock.borrow_mut();
 
         // Register the timeout object
-        timeout::insert_timeout($lock.borrow_mut(), timeout_guard.timeout);
+        timeout::insert_timeout($lock.borrow_mut(), timeout_guard.inner.timeout);
+
+        // Remember the timeout object in case the task is terminated
+        $task_cb.wait.current_timeout.replace(
+            &mut *$lock,
+            Some(TaskTimeoutRef(
+                NonNull::from(&*timeout_guard.inner.timeout).cast(),
+            )),
+        );
     };
 }
 
+/// RAII guard that automatically unregisters a timeout object set up by
+/// [`setup_timeout_wait!`] when dropped.
+struct TaskTimeoutGuard<'a, 'b, Traits: KernelTraits> {
+    inner: timeout::TimeoutGuard<'a, 'b, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+}
+
+impl<Traits: KernelTraits> Drop for TaskTimeoutGuard<'_, '_, Traits> {
+    #[inline]
+    fn drop(&mut self) {
+        // `self.inner` will unregister the timeout object
+        self.task_cb
+            .wait
+            .current_timeout
+            .replace(&mut *self.inner.lock, None);
+    }
+}
+
 impl<Traits: PortThreading> WaitQueue<Traits> {
     /// Construct a `WaitQueue`.
     pub(super) const fn new(order: QueueOrder) -> Self {
@@ -340,6 +412,27 @@ impl<Traits: KernelTraits> WaitQueue<Traits> {
         debug_assert!(core::ptr::eq(wait.wait_queue.unwrap(), self));
 
         // Insert `wait_ref` into `self.waits`
+        self.insert_wait(lock.borrow_mut(), wait);
+
+        // Set `task.current_wait`
+        task.wait.current_wait.replace(&mut *lock, Some(wait_ref));
+
+        // Transition the task into Waiting. This statement will complete when
+        // the task is woken up.
+        task::wait_until_woken_up(lock.borrow_mut());
+
+        // `wait_ref` should have been removed from a wait queue by a wake-upper
+        assert!(wait.link.read(&*lock).is_none());
+        assert!(task.wait.current_wait.get(&*lock).is_none());
+
+        // Return the wait result (`Ok(())` or `Err(Interrupted)`)
+        task.wait.wait_result.get(&*lock)
+    }
+
+    /// Insert `wait` into `self.waits` according to `self.order`.
+    fn insert_wait(&'static self, mut lock: CpuLockTokenRefMut<'_, Traits>, wait: &Wait<Traits>) {
+        let wait_ref = WaitRef(wait.into());
+
         // Safety: All elements of `self.waits` are extant.
         let mut accessor = wait_queue_accessor!(&self.waits, lock.borrow_mut());
         let insert_at = match self.order {
@@ -348,7 +441,7 @@ impl<Traits: KernelTraits> WaitQueue<Traits> {
                 None
             }
             QueueOrder::TaskPriority => {
-                let cur_task_pri = *task.effective_priority.read(&**accessor.cell_key());
+                let cur_task_pri = *wait.task.effective_priority.read(&**accessor.cell_key());
                 // TODO: It's unfortunate that we need to pass
                 //       `&ListAccessorCell`, which incurs a runtime cost because
                 //       `&T` is always pointer-sized. Find a way to eliminate
@@ -360,20 +453,6 @@ impl<Traits: KernelTraits> WaitQueue<Traits> {
         // Safety: `wait_ref` is not linked, so it shouldn't return
         //     `InsertError::AlreadyLinked`.
         unsafe { accessor.insert(wait_ref, insert_at).unwrap_unchecked() };
-
-        // Set `task.current_wait`
-        task.wait.current_wait.replace(&mut *lock, Some(wait_ref));
-
-        // Transition the task into Waiting. This statement will complete when
-        // the task is woken up.
-        task::wait_until_woken_up(lock.borrow_mut());
-
-        // `wait_ref` should have been removed from a wait queue by a wake-upper
-        assert!(wait.link.read(&*lock).is_none());
-        assert!(task.wait.current_wait.get(&*lock).is_none());
-
-        // Return the wait result (`Ok(())` or `Err(Interrupted)`)
-        task.wait.wait_result.get(&*lock)
     }
 
     /// Find the insertion position for a wait object owned by a task whose
@@ -465,6 +544,24 @@ impl<Traits: KernelTraits> WaitQueue<Traits> {
         unsafe { accessor.front_data().unwrap_unchecked() }.map(|wait| wait.task)
     }
 
+    /// Access the payload of the next wait object to be woken up in the
+    /// supplied closure. Returns `None` if there's no waiting task.
+    ///
+    /// Use this instead of [`with_current_wait_payload`] on
+    /// [`Self::first_waiting_task`] if the waiting task might be waiting on
+    /// multiple wait queues at once ([`wait_any`]), in which case the task's
+    /// current wait object might belong to another wait queue.
+    pub(super) fn with_first_wait_payload<R>(
+        &self,
+        mut lock: CpuLockTokenRefMut<'_, Traits>,
+        f: impl FnOnce(&WaitPayload<Traits>) -> R,
+    ) -> Option<R> {
+        // Safety: This linked list is structurally sound, so it shouldn't
+        //         return `Err(InconsistentError)`
+        let accessor = wait_queue_accessor!(&self.waits, lock.borrow_mut());
+        unsafe { accessor.front_data().unwrap_unchecked() }.map(|wait| f(&wait.payload))
+    }
+
     /// Wake up up to one waiting task. Returns `true` if it has successfully
     /// woken up a task.
     ///
@@ -568,13 +665,25 @@ impl<Traits: KernelTraits> fmt::Debug for WaitPayload<Traits> {
                 .finish(),
             Self::Semaphore => f.write_str("Semaphore"),
             Self::Mutex(mutex) => write!(f, "Mutex({mutex:p})"),
+            Self::MessageQueueSend(message) => write!(f, "MessageQueueSend({message:p})"),
+            Self::MessageQueueReceive(message) => write!(f, "MessageQueueReceive({message:p})"),
+            Self::MemoryPool(block) => write!(f, "MemoryPool({block:p})"),
             Self::Park => f.write_str("Park"),
+            Self::Notification => f.write_str("Notification"),
             Self::Sleep => f.write_str("Sleep"),
             Self::__Nonexhaustive => unreachable!(),
         }
     }
 }
 
+impl<Traits: KernelTraits> WaitQueue<Traits> {
+    /// Get the number of wait objects in the wait queue.
+    pub(super) fn num_waits(&self, lock: CpuLockTokenRefMut<'_, Traits>) -> usize {
+        let accessor = wait_queue_accessor!(&self.waits, lock);
+        accessor.iter().count()
+    }
+}
+
 impl<Traits: KernelTraits> fmt::Debug for WaitQueue<Traits> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         struct WaitQueuePrinter<'a, Traits: KernelTraits> {
@@ -614,7 +723,10 @@ impl<Traits: KernelTraits> fmt::Debug for TaskWait<Traits> {
                     wait.fmt(f)
                 }),
             )
+            .field("current_wait_len", &self.current_wait_len)
+            .field("completed_wait_index", &self.completed_wait_index)
             .field("wait_result", &self.wait_result)
+            .field("current_timeout", &self.current_timeout)
             .finish()
     }
 }
@@ -649,14 +761,36 @@ pub(super) fn reorder_wait_of_task<Traits: KernelTraits>(
     lock: CpuLockTokenRefMut<'_, Traits>,
     task_cb: &TaskCb<Traits>,
 ) {
-    let Some(wait_ref) = task_cb.wait.current_wait.get(&*lock) else { return };
+    with_current_waits(lock, task_cb, |mut lock, waits| {
+        for wait in waits.unwrap_or(&[]) {
+            if let Some(wait_queue) = wait.wait_queue {
+                wait_queue.reorder_wait(lock.borrow_mut(), wait);
+            }
+        }
+    });
+}
 
-    // Safety: `wait_ref` must point to an existing `Wait`
-    let wait = unsafe { &*wait_ref.0.as_ptr() };
+/// Call the given closure with the wait objects comprising the specified
+/// task's ongoing wait operation (`None` if the task is not in the Waiting
+/// state) and the CPU Lock token.
+///
+/// The wait objects reside in the waiting task's stack, which stays intact
+/// until the task resumes execution. This can't happen while CPU Lock is
+/// active, so the slice remains valid during the call even if the closure
+/// ends the wait operation.
+fn with_current_waits<Traits: KernelTraits, R>(
+    lock: CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &TaskCb<Traits>,
+    f: impl FnOnce(CpuLockTokenRefMut<'_, Traits>, Option<&[Wait<Traits>]>) -> R,
+) -> R {
+    let wait_ref = task_cb.wait.current_wait.get(&*lock);
+    let len = task_cb.wait.current_wait_len.get(&*lock);
 
-    if let Some(wait_queue) = wait.wait_queue {
-        wait_queue.reorder_wait(lock, wait);
-    }
+    // Safety: `wait_ref` must point to the first of `len` existing `Wait`s
+    // stored contiguously
+    let waits = wait_ref.map(|r| unsafe { slice::from_raw_parts(r.0.as_ptr(), len) });
+
+    f(lock, waits)
 }
 
 /// Create a wait object pertaining to the currently running task but
@@ -753,6 +887,106 @@ fn wait_no_queue_inner<Traits: KernelTraits>(
     task.wait.wait_result.get(&*lock)
 }
 
+/// Create wait objects pertaining to the currently running task, one for each
+/// of the specified wait queues, and insert them to the respective wait
+/// queues. Transition the task into the Waiting state.
+///
+/// The wait operation completes when any of the wait objects is woken up, at
+/// which point the other wait objects are removed from their wait queues.
+/// Returns the index of the completing wait object and its payload.
+///
+/// The wait queues must be distinct from each other.
+///
+/// The current context must be [waitable] (This function doesn't check
+/// that). The caller should use `expect_waitable_context` to do that.
+///
+/// [waitable]: crate#contexts
+#[inline]
+pub(super) fn wait_any<Traits: KernelTraits, const N: usize>(
+    mut lock: CpuLockTokenRefMut<'_, Traits>,
+    waits: [(&'static WaitQueue<Traits>, WaitPayload<Traits>); N],
+) -> Result<(usize, WaitPayload<Traits>), WaitError> {
+    let task = Traits::state().running_task(lock.borrow_mut()).unwrap();
+    let waits = waits.map(|(wait_queue, payload)| Wait {
+        task,
+        link: CpuLockCell::new(None),
+        wait_queue: Some(wait_queue),
+        payload: payload.r#move(),
+    });
+
+    let index = wait_any_inner(lock, &waits).map_err(expect_not_timeout)?;
+
+    Ok((index, waits.into_iter().nth(index).unwrap().payload))
+}
+
+/// [`wait_any`] with timeout.
+#[inline]
+pub(super) fn wait_any_timeout<Traits: KernelTraits, const N: usize>(
+    mut lock: CpuLockTokenRefMut<'_, Traits>,
+    waits: [(&'static WaitQueue<Traits>, WaitPayload<Traits>); N],
+    duration_time32: timeout::Time32,
+) -> Result<(usize, WaitPayload<Traits>), WaitTimeoutError> {
+    let task = Traits::state().running_task(lock.borrow_mut()).unwrap();
+    let waits = waits.map(|(wait_queue, payload)| Wait {
+        task,
+        link: CpuLockCell::new(None),
+        wait_queue: Some(wait_queue),
+        payload: payload.r#move(),
+    });
+
+    // Configure a timeout
+    setup_timeout_wait!(lock, task, duration_time32);
+
+    let index = wait_any_inner(lock, &waits)?;
+
+    Ok((index, waits.into_iter().nth(index).unwrap().payload))
+}
+
+/// The core portion of [`wait_any`].
+fn wait_any_inner<Traits: KernelTraits>(
+    mut lock: CpuLockTokenRefMut<'_, Traits>,
+    waits: &[Wait<Traits>],
+) -> Result<usize, WaitTimeoutError> {
+    let task = waits[0].task;
+
+    debug_assert!(core::ptr::eq(
+        task,
+        Traits::state().running_task(lock.borrow_mut()).unwrap()
+    ));
+
+    // Insert the wait objects into their respective wait queues
+    for wait in waits {
+        wait.wait_queue
+            .unwrap()
+            .insert_wait(lock.borrow_mut(), wait);
+    }
+
+    // Set `task.current_wait` and `task.current_wait_len`
+    task.wait
+        .current_wait
+        .replace(&mut *lock, Some(WaitRef((&waits[0]).into())));
+    task.wait.current_wait_len.replace(&mut *lock, waits.len());
+
+    // Transition the task into Waiting. This statement will complete when
+    // the task is woken up.
+    task::wait_until_woken_up(lock.borrow_mut());
+
+    // All wait objects should have been removed from the wait queues by a
+    // wake-upper
+    assert!(waits.iter().all(|wait| wait.link.read(&*lock).is_none()));
+    assert!(task.wait.current_wait.get(&*lock).is_none());
+
+    // Return the wait result (`Ok(())` or `Err(Interrupted)`)
+    task.wait.wait_result.get(&*lock)?;
+
+    // `complete_wait` only records the index for multiple wait objects
+    if waits.len() == 1 {
+        Ok(0)
+    } else {
+        Ok(task.wait.completed_wait_index.get(&*lock))
+    }
+}
+
 /// Deassociate the specified wait object from its waiting task (`wait.task`)
 /// and wake up the task.
 ///
@@ -769,11 +1003,35 @@ fn complete_wait<Traits: KernelTraits>(
 ) {
     let task_cb = wait.task;
 
+    if task_cb.wait.current_wait_len.get(&*lock) > 1 {
+        // The task is waiting on multiple wait queues. Remove the other wait
+        // objects from their respective wait queues.
+        let index = with_current_waits(lock.borrow_mut(), task_cb, |mut lock, waits| {
+            let waits = waits.unwrap();
+            let index = waits
+                .iter()
+                .position(|w| core::ptr::eq(w, wait))
+                .expect("the wait object does not belong to the task's current wait operation");
+
+            for (i, other_wait) in waits.iter().enumerate() {
+                if i != index {
+                    dequeue_wait(lock.borrow_mut(), other_wait);
+                }
+            }
+
+            index
+        });
+
+        task_cb.wait.current_wait_len.replace(&mut *lock, 1);
+        task_cb.wait.completed_wait_index.replace(&mut *lock, index);
+    } else {
+        assert_eq!(
+            *task_cb.wait.current_wait.read(&*lock),
+            Some(WaitRef(wait.into()))
+        );
+    }
+
     // Clear `TaskWait::current_wait`
-    assert_eq!(
-        *task_cb.wait.current_wait.read(&*lock),
-        Some(WaitRef(wait.into()))
-    );
     task_cb.wait.current_wait.replace(&mut *lock, None);
 
     // Set a wait result
@@ -781,6 +1039,13 @@ fn complete_wait<Traits: KernelTraits>(
 
     assert_eq!(*task_cb.st.read(&*lock), task::TaskSt::Waiting);
 
+    if task_cb.wait_suspended.replace(&mut *lock, false) {
+        // The task is in the Waiting-Suspended state. Make the task Suspended.
+        // It will be made Ready when it's resumed.
+        task::set_task_st(lock.borrow_mut(), task_cb, task::TaskSt::Suspended);
+        return;
+    }
+
     // Make the task Ready
     //
     // Safety: The task is in the Waiting state, meaning the task state is valid
@@ -818,13 +1083,7 @@ pub(super) fn interrupt_task<Traits: KernelTraits>(
             // Safety: ... and `wait_ref` must point to an existing `Wait`
             let wait = unsafe { wait_ref.0.as_ref() };
 
-            // Remove `wait` from the wait queue it belongs to
-            if let Some(wait_queue) = wait.wait_queue {
-                let mut accessor = wait_queue_accessor!(&wait_queue.waits, lock.borrow_mut());
-                // Safety: `wait_ref` is linked, so it shouldn't return
-                //         `ItemError::Unlinked`.
-                unsafe { accessor.remove(wait_ref).unwrap_unchecked() };
-            }
+            dequeue_wait(lock.borrow_mut(), wait);
 
             // Wake up the task
             complete_wait(lock.borrow_mut(), wait, wait_result);
@@ -835,6 +1094,98 @@ pub(super) fn interrupt_task<Traits: KernelTraits>(
     }
 }
 
+/// Remove the specified wait object from the wait queue it belongs to (if
+/// any).
+///
+/// This method doesn't wake up the waiting task.
+fn dequeue_wait<Traits: KernelTraits>(
+    mut lock: CpuLockTokenRefMut<'_, Traits>,
+    wait: &Wait<Traits>,
+) {
+    if let Some(wait_queue) = wait.wait_queue {
+        let mut accessor = wait_queue_accessor!(&wait_queue.waits, lock.borrow_mut());
+        // Safety: `wait` is linked, so it shouldn't return
+        //         `ItemError::Unlinked`.
+        unsafe { accessor.remove(WaitRef(wait.into())).unwrap_unchecked() };
+    }
+
+    // If the task was waiting on a mutex, the mutex's owner might
+    // have been inheriting the task's priority
+    if let WaitPayload::Mutex(mutex_cb) = wait.payload {
+        mutex::reevaluate_inherited_priority(lock, mutex_cb);
+    }
+}
+
+/// Discard any ongoing wait operation of the specified task and unregister the
+/// timeout object set up by the task (if any) without waking up the task.
+///
+/// This is used to terminate a task. The wait object and the timeout object
+/// reside in the task's stack, so they must be unlinked before the stack is
+/// discarded. The caller is responsible for transitioning the task out of the
+/// Waiting state.
+pub(super) fn abandon_wait_of_task<Traits: KernelTraits>(
+    mut lock: CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &'static TaskCb<Traits>,
+) {
+    with_current_waits(lock.borrow_mut(), task_cb, |mut lock, waits| {
+        if let Some(waits) = waits {
+            for wait in waits {
+                dequeue_wait(lock.borrow_mut(), wait);
+            }
+
+            task_cb.wait.current_wait.replace(&mut *lock, None);
+            task_cb.wait.current_wait_len.replace(&mut *lock, 1);
+        }
+    });
+
+    if let Some(timeout_ref) = task_cb.wait.current_timeout.replace(&mut *lock, None) {
+        // Safety: `timeout_ref` must point to an existing `Timeout`
+        let timeout = unsafe { timeout_ref.0.cast::<timeout::Timeout<Traits>>().as_ref() };
+
+        timeout::remove_timeout(lock, timeout);
+    }
+}
+
+/// Call the given closure with an iterator over the wait queue and payload of
+/// each wait object comprising the ongoing wait operation of the specified
+/// task. Used by [`crate::introspect`].
+pub(super) fn with_current_wait_targets<Traits: KernelTraits, R>(
+    lock: CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &TaskCb<Traits>,
+    f: impl FnOnce(WaitTargets<'_, Traits>) -> R,
+) -> R {
+    with_current_waits(lock, task_cb, |_, waits| {
+        f(WaitTargets(waits.unwrap_or(&[]).iter()))
+    })
+}
+
+/// The iterator passed to the closure by [`with_current_wait_targets`].
+pub(super) struct WaitTargets<'a, Traits: PortThreading>(slice::Iter<'a, Wait<Traits>>);
+
+impl<'a, Traits: PortThreading> Iterator for WaitTargets<'a, Traits> {
+    type Item = (Option<&'static WaitQueue<Traits>>, &'a WaitPayload<Traits>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|wait| (wait.wait_queue, &wait.payload))
+    }
+}
+
+/// Get the remaining time until the ongoing wait operation of the specified
+/// task times out. Returns `None` if the task is not waiting or the wait
+/// operation has no timeout. Used by [`crate::introspect`].
+pub(super) fn current_wait_remaining_time<Traits: KernelTraits>(
+    lock: CpuLockTokenRefMut<'_, Traits>,
+    task_cb: &TaskCb<Traits>,
+) -> Option<timeout::Time32> {
+    task_cb.wait.current_wait.get(&*lock)?;
+    let timeout_ref = task_cb.wait.current_timeout.get(&*lock)?;
+
+    // Safety: `timeout_ref` must point to an existing `Timeout`
+    let timeout = unsafe { timeout_ref.0.cast::<timeout::Timeout<Traits>>().as_ref() };
+
+    Some(timeout.saturating_duration_until_timeout(lock))
+}
+
 /// Construct [`timeout::Timeout`] to interrupt the specified task with
 /// [`WaitTimeoutError::Timeout`] after a certain period of time.
 fn new_timeout_object_for_task<Traits: KernelTraits>(
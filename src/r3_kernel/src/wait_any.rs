@@ -0,0 +1,257 @@
+//! Waiting on multiple objects
+use r3_core::{
+    kernel::{
+        raw::{self, WaitAnySpec},
+        EventGroupBits, EventGroupWaitFlags, WaitAnyError, WaitAnyTimeoutError,
+    },
+    time::Duration,
+    utils::Init,
+};
+
+use crate::{
+    error::{BadParamError, NoAccessError},
+    event_group::{self, EventGroupCb},
+    klock,
+    message_queue::{self, MessageQueueCb},
+    semaphore::{self, SemaphoreCb},
+    state, task, timeout,
+    wait::{self, WaitPayload, WaitQueue},
+    KernelTraits, System,
+};
+
+unsafe impl<Traits: KernelTraits> raw::KernelWaitAny for System<Traits> {
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_wait_any<const N: usize>(
+        specs: &[WaitAnySpec<Self>; N],
+    ) -> Result<usize, WaitAnyError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing valid object IDs and
+        //         pointers
+        let targets = unsafe { resolve_targets(specs)? };
+        check_targets(&targets)?;
+
+        // Safety: The caller is responsible for providing valid pointers
+        if let Some(i) = unsafe { poll(&targets, lock.borrow_mut()) } {
+            task::unlock_cpu_and_check_preemption(lock);
+            return Ok(i);
+        }
+
+        // None of the conditions are satisfied. In this case, start waiting on
+        // all of them. The wake-upper is responsible for completing the wait
+        // operation described by the wait object it wakes up.
+        let (i, payload) = wait::wait_any(lock.borrow_mut(), targets.map(Target::wait_entry))?;
+
+        // Safety: The caller is responsible for providing valid pointers
+        unsafe { targets[i].finish_wait(payload, lock.borrow_mut()) };
+
+        Ok(i)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_wait_any_timeout<const N: usize>(
+        specs: &[WaitAnySpec<Self>; N],
+        timeout: Duration,
+    ) -> Result<usize, WaitAnyTimeoutError> {
+        let time32 = timeout::time32_from_duration(timeout)?;
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing valid object IDs and
+        //         pointers
+        let targets = unsafe { resolve_targets(specs)? };
+        check_targets(&targets)?;
+
+        // Safety: The caller is responsible for providing valid pointers
+        if let Some(i) = unsafe { poll(&targets, lock.borrow_mut()) } {
+            task::unlock_cpu_and_check_preemption(lock);
+            return Ok(i);
+        }
+
+        let (i, payload) =
+            wait::wait_any_timeout(lock.borrow_mut(), targets.map(Target::wait_entry), time32)?;
+
+        // Safety: The caller is responsible for providing valid pointers
+        unsafe { targets[i].finish_wait(payload, lock.borrow_mut()) };
+
+        Ok(i)
+    }
+}
+
+/// A resolved [`WaitAnySpec`].
+enum Target<Traits: KernelTraits> {
+    Semaphore(&'static SemaphoreCb<Traits>),
+    EventGroup {
+        event_group_cb: &'static EventGroupCb<Traits>,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+        orig_bits: *mut EventGroupBits,
+    },
+    MessageQueueReceive {
+        message_queue_cb: &'static MessageQueueCb<Traits>,
+        message: *mut u8,
+    },
+}
+
+/// Resolve the object IDs in `specs`.
+///
+/// # Safety
+///
+/// See [`crate::bad_id`].
+unsafe fn resolve_targets<Traits: KernelTraits, const N: usize>(
+    specs: &[WaitAnySpec<System<Traits>>; N],
+) -> Result<[Target<Traits>; N], NoAccessError> {
+    specs.try_map(|spec| {
+        // Safety: The caller is responsible for providing valid object IDs
+        Ok(unsafe {
+            match spec {
+                WaitAnySpec::Semaphore(id) => Target::Semaphore(System::semaphore_cb(id)?),
+                WaitAnySpec::EventGroup {
+                    event_group,
+                    bits,
+                    flags,
+                    orig_bits,
+                } => Target::EventGroup {
+                    event_group_cb: System::event_group_cb(event_group)?,
+                    bits,
+                    flags,
+                    orig_bits,
+                },
+                WaitAnySpec::MessageQueueReceive {
+                    message_queue,
+                    message,
+                } => Target::MessageQueueReceive {
+                    message_queue_cb: System::message_queue_cb(message_queue)?,
+                    message,
+                },
+            }
+        })
+    })
+}
+
+/// Fail with `BadParam` if `targets` is empty or refers to the same object
+/// more than once.
+fn check_targets<Traits: KernelTraits>(targets: &[Target<Traits>]) -> Result<(), BadParamError> {
+    if targets.is_empty() {
+        return Err(BadParamError::BadParam);
+    }
+
+    // Each object can be waited on only once because a wait object for one
+    // task can't be linked into the same wait queue twice
+    for (i, target) in targets.iter().enumerate() {
+        let wait_queue = target.wait_queue();
+        if targets[..i]
+            .iter()
+            .any(|other| core::ptr::eq(other.wait_queue(), wait_queue))
+        {
+            return Err(BadParamError::BadParam);
+        }
+    }
+
+    Ok(())
+}
+
+/// Try to complete any of the wait operations without blocking. Returns the
+/// index of the first target that succeeded.
+///
+/// This function may make a task Ready, but doesn't yield the processor.
+/// Call `unlock_cpu_and_check_preemption` as needed.
+///
+/// # Safety
+///
+/// The pointers in `targets` must be valid for writes.
+unsafe fn poll<Traits: KernelTraits>(
+    targets: &[Target<Traits>],
+    mut lock: klock::CpuLockTokenRefMut<'_, Traits>,
+) -> Option<usize> {
+    targets.iter().position(|target| match *target {
+        Target::Semaphore(semaphore_cb) => {
+            semaphore::poll_core(semaphore_cb.value.write(&mut *lock))
+        }
+        Target::EventGroup {
+            event_group_cb,
+            bits,
+            flags,
+            orig_bits,
+        } => {
+            if let Some(original_value) =
+                event_group::poll_core(event_group_cb.bits.write(&mut *lock), bits, flags)
+            {
+                // Safety: The caller is responsible for ensuring `orig_bits`
+                //         is valid
+                unsafe { orig_bits.write(original_value) };
+                true
+            } else {
+                false
+            }
+        }
+        Target::MessageQueueReceive {
+            message_queue_cb,
+            message,
+        } => {
+            // Safety: The caller is responsible for ensuring `message` is
+            //         valid
+            unsafe {
+                message_queue::poll_receive_core(message_queue_cb, lock.borrow_mut(), message)
+            }
+        }
+    })
+}
+
+impl<Traits: KernelTraits> Clone for Target<Traits> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Traits: KernelTraits> Copy for Target<Traits> {}
+
+impl<Traits: KernelTraits> Target<Traits> {
+    fn wait_queue(&self) -> &'static WaitQueue<Traits> {
+        match *self {
+            Self::Semaphore(semaphore_cb) => &semaphore_cb.wait_queue,
+            Self::EventGroup { event_group_cb, .. } => &event_group_cb.wait_queue,
+            Self::MessageQueueReceive {
+                message_queue_cb, ..
+            } => &message_queue_cb.receive_wait_queue,
+        }
+    }
+
+    /// Get the wait queue and the wait payload to wait on this target.
+    fn wait_entry(self) -> (&'static WaitQueue<Traits>, WaitPayload<Traits>) {
+        let payload = match self {
+            // The wake-upper is responsible for giving a permit
+            Self::Semaphore(_) => WaitPayload::Semaphore,
+            // The wake-upper is responsible for using `poll_core`
+            Self::EventGroup { bits, flags, .. } => WaitPayload::EventGroupBits {
+                bits,
+                flags,
+                orig_bits: Init::INIT,
+            },
+            // The wake-upper is responsible for delivering a message to
+            // `message`
+            Self::MessageQueueReceive { message, .. } => WaitPayload::MessageQueueReceive(message),
+        };
+        (self.wait_queue(), payload)
+    }
+
+    /// Process the payload of the completed wait operation on this target.
+    ///
+    /// # Safety
+    ///
+    /// The pointers in `self` must be valid for writes.
+    unsafe fn finish_wait(
+        &self,
+        payload: WaitPayload<Traits>,
+        lock: klock::CpuLockTokenRefMut<'_, Traits>,
+    ) {
+        if let Self::EventGroup { orig_bits, .. } = *self {
+            // The original value has been copied to `orig_bits`
+            let WaitPayload::EventGroupBits { orig_bits: value, .. } = payload
+            else { unreachable!() };
+            // Safety: The caller is responsible for ensuring `orig_bits` is
+            //         valid
+            unsafe { orig_bits.write(value.read(&*lock).get()) };
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Blocking operations with absolute deadlines
+use r3_core::{
+    kernel::{
+        raw, EventGroupBits, EventGroupWaitFlags, GetMemoryPoolTimeoutError, LockMutexTimeoutError,
+        ParkTimeoutError, ReceiveMessageQueueTimeoutError, SendMessageQueueTimeoutError,
+        SleepError, WaitEventGroupTimeoutError, WaitSemaphoreTimeoutError,
+    },
+    time::Time,
+};
+
+use crate::{
+    event_group, klock, memory_pool, message_queue, mutex, semaphore, state, task, timeout,
+    KernelTraits, System,
+};
+
+// A deadline is converted to a relative timeout after the object ID is
+// validated, so that the time spent for the validation doesn't count toward
+// the timeout.
+unsafe impl<Traits: KernelTraits> raw::KernelWaitUntil for System<Traits> {
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    fn raw_sleep_until(deadline: Time) -> Result<(), SleepError> {
+        task::put_current_task_on_sleep_until::<Traits>(deadline)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    fn raw_park_until(deadline: Time) -> Result<(), ParkTimeoutError> {
+        task::park_current_task_until::<Traits>(deadline)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_semaphore_wait_one_until(
+        this: Self::RawSemaphoreId,
+        deadline: Time,
+    ) -> Result<(), WaitSemaphoreTimeoutError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let semaphore_cb = unsafe { Self::semaphore_cb(this)? };
+        let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+        semaphore::wait_one_timeout(semaphore_cb, lock, time32)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_event_group_wait_until(
+        this: Self::RawEventGroupId,
+        bits: EventGroupBits,
+        flags: EventGroupWaitFlags,
+        deadline: Time,
+    ) -> Result<EventGroupBits, WaitEventGroupTimeoutError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let event_group_cb = unsafe { Self::event_group_cb(this)? };
+        let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+        event_group::wait_timeout(event_group_cb, lock, bits, flags, time32)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_mutex_lock_until(
+        this: Self::RawMutexId,
+        deadline: Time,
+    ) -> Result<(), LockMutexTimeoutError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let mutex_cb = unsafe { Self::mutex_cb(this)? };
+        let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+        mutex::lock_mutex_timeout(mutex_cb, lock, time32)
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_send_until(
+        this: Self::RawMessageQueueId,
+        message: *const u8,
+        deadline: Time,
+    ) -> Result<(), SendMessageQueueTimeoutError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+        let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { message_queue::send_timeout(message_queue_cb, lock, message, time32) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_message_queue_receive_until(
+        this: Self::RawMessageQueueId,
+        message: *mut u8,
+        deadline: Time,
+    ) -> Result<(), ReceiveMessageQueueTimeoutError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let message_queue_cb = unsafe { Self::message_queue_cb(this)? };
+        let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+        // Safety: The caller is responsible for providing a valid pointer
+        unsafe { message_queue::receive_timeout(message_queue_cb, lock, message, time32) }
+    }
+
+    #[cfg_attr(not(feature = "inline_syscall"), inline(never))]
+    unsafe fn raw_memory_pool_get_until(
+        this: Self::RawMemoryPoolId,
+        deadline: Time,
+    ) -> Result<usize, GetMemoryPoolTimeoutError> {
+        let mut lock = klock::lock_cpu::<Traits>()?;
+        state::expect_waitable_context::<Traits>()?;
+        // Safety: The caller is responsible for providing a valid object ID
+        let memory_pool_cb = unsafe { Self::memory_pool_cb(this)? };
+        let time32 = timeout::time32_from_deadline(lock.borrow_mut(), deadline)?;
+
+        memory_pool::get_timeout(memory_pool_cb, lock, time32)
+    }
+}
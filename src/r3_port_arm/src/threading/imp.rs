@@ -172,6 +172,15 @@ impl State {
     unsafe extern "C" fn push_second_level_state_and_dispatch<Traits: PortInstance>() -> ! {
         extern "C" fn choose_and_get_next_task<Traits: PortInstance>(
         ) -> Option<&'static TaskCb<Traits>> {
+            // If we came here from a task, check the stack pointer we have
+            // just saved for it
+            // Safety: `running_task` is only modified by `choose_running_task`,
+            //         so there's no data race. CPU Lock active.
+            if let Some(task) = unsafe { *Traits::state().running_task_ptr() } {
+                let sp = unsafe { *task.port_task_state.sp.get() };
+                unsafe { Traits::check_stack_pointer(sp as usize) };
+            }
+
             // Safety: CPU Lock active
             unsafe { Traits::choose_running_task() };
 
@@ -354,10 +363,19 @@ impl State {
             asm!(
                 "
                 cpsid i
+
+                # Borrow the main stack. The kernel might reinitialize the
+                # exiting task's stack in `choose_and_get_next_task` (if the
+                # task has a queued activation request), so we can't stay on
+                # it.
+                ldr sp, [{state}, #{OFFSET_MAIN_STACK}]
+
                 b {push_second_level_state_and_dispatch}.dispatch
                 ",
+                state = in(reg) self,
                 push_second_level_state_and_dispatch =
                     sym Self::push_second_level_state_and_dispatch::<Traits>,
+                OFFSET_MAIN_STACK = const Self::OFFSET_MAIN_STACK,
                 options(noreturn),
             );
         }
@@ -626,8 +644,12 @@ impl State {
 
         if let Some(handler) = Traits::INTERRUPT_HANDLERS.get(line) {
             // Safety: The first-level interrupt handler is the only code
-            //         allowed to call this
-            unsafe { handler() };
+            //         allowed to call these
+            unsafe {
+                <Traits as PortToKernel>::interrupt_enter(line);
+                handler();
+                <Traits as PortToKernel>::interrupt_exit(line);
+            }
         }
 
         // Safety: We are the port, so it's okay to call this
@@ -16,7 +16,9 @@ macro_rules! use_rt {
     (unsafe $Traits:ty) => {
         const _: () = {
             use $crate::{
-                r3_kernel::KernelCfg2, rt::imp::ExceptionTrampoline, EntryPoint, INTERRUPT_SYSTICK,
+                r3_kernel::{KernelCfg2, PortToKernel},
+                rt::imp::ExceptionTrampoline,
+                EntryPoint, INTERRUPT_SYSTICK,
             };
 
             #[link_section = ".vector_table.interrupts"]
@@ -67,7 +69,11 @@ macro_rules! use_rt {
                 if let Some(x) = <$Traits as KernelCfg2>::INTERRUPT_HANDLERS.get(INTERRUPT_SYSTICK)
                 {
                     // Safety: It's a first-level interrupt handler here. CPU Lock inactive
-                    unsafe { x() };
+                    unsafe {
+                        <$Traits as PortToKernel>::interrupt_enter(INTERRUPT_SYSTICK);
+                        x();
+                        <$Traits as PortToKernel>::interrupt_exit(INTERRUPT_SYSTICK);
+                    }
                 }
             }
         };
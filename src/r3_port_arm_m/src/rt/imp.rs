@@ -1,5 +1,6 @@
 use crate::INTERRUPT_SYSTICK;
-use r3_kernel::KernelTraits;
+use r3_kernel::{KernelTraits, PortToKernel};
+use r3_portkit::pptext::pp_asm;
 
 /// Used by `use_port!`
 #[derive(Clone, Copy)]
@@ -20,7 +21,15 @@ pub const fn make_interrupt_handler_table<Traits: KernelTraits>() -> InterruptHa
     // `core::array::from_fn` is not `const fn` [ref:const_array_from_fn]
     for i in 0..table.len() {
         table[i] = if let Some(x) = Traits::INTERRUPT_HANDLERS.get(i + 16) {
-            InterruptHandler { defined: x }
+            if Traits::TRACE_BUFFER_CAPACITY != 0 {
+                // Go through `handle_interrupt_traced` to record the
+                // interrupt handler activation
+                InterruptHandler {
+                    defined: handle_interrupt_traced::<Traits>,
+                }
+            } else {
+                InterruptHandler { defined: x }
+            }
         } else {
             InterruptHandler { undefined: 0 }
         };
@@ -42,6 +51,33 @@ pub const fn make_interrupt_handler_table<Traits: KernelTraits>() -> InterruptHa
     table
 }
 
+/// The first-level interrupt handler used in place of second-level interrupt
+/// handlers when a trace buffer is configured. Calls the second-level
+/// interrupt handler for the current exception, reporting its activation to
+/// the kernel.
+unsafe extern "C" fn handle_interrupt_traced<Traits: KernelTraits>() {
+    // `IPSR.Exception` is the interrupt number
+    let line = unsafe {
+        let ipsr: u32;
+        pp_asm!(
+            "mrs {}, ipsr",
+            out(reg) ipsr,
+            options(nomem, preserves_flags, nostack),
+        );
+        (ipsr & ((1u32 << 9) - 1)) as usize
+    };
+
+    if let Some(handler) = Traits::INTERRUPT_HANDLERS.get(line) {
+        // Safety: The first-level interrupt handler is the only code
+        //         allowed to call these
+        unsafe {
+            <Traits as PortToKernel>::interrupt_enter(line);
+            handler();
+            <Traits as PortToKernel>::interrupt_exit(line);
+        }
+    }
+}
+
 #[repr(C, align(4))]
 pub struct ExceptionTrampoline {
     _inst: u32,
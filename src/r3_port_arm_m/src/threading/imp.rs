@@ -246,6 +246,15 @@ impl State {
             // Choose the next task to run
             unsafe { State::enter_cpu_lock_inner::<Traits>() };
 
+            // If we came here from a task, check the stack pointer we have
+            // just saved for it
+            // Safety: `running_task` is only modified by `choose_running_task`,
+            //         so there's no data race. CPU Lock active.
+            if let Some(task) = unsafe { *Traits::state().running_task_ptr() } {
+                let sp = unsafe { *task.port_task_state.sp.get() };
+                unsafe { Traits::check_stack_pointer(sp as usize) };
+            }
+
             // Safety: CPU Lock active
             unsafe { Traits::choose_running_task() };
 
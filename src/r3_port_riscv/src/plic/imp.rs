@@ -3,7 +3,7 @@ use r3_core::kernel::{
     traits, Cfg, EnableInterruptLineError, InterruptNum, InterruptPriority,
     QueryInterruptLineError, SetInterruptLinePriorityError, StaticInterruptHandler,
 };
-use r3_kernel::{KernelTraits, System};
+use r3_kernel::{KernelTraits, PortToKernel, System};
 use tock_registers::interfaces::{Readable, Writeable};
 
 use crate::{InterruptControllerToPort, Plic, INTERRUPT_EXTERNAL, INTERRUPT_PLATFORM_START};
@@ -46,7 +46,11 @@ fn interrupt_handler<Traits: Plic + KernelTraits + InterruptControllerToPort>()
             // Safety: The interrupt controller driver is responsible for
             //         dispatching the appropriate interrupt handler for
             //         a platform interrupt
-            unsafe { handler() };
+            unsafe {
+                <Traits as PortToKernel>::interrupt_enter(num);
+                handler();
+                <Traits as PortToKernel>::interrupt_exit(num);
+            }
 
             if Traits::USE_NESTING {
                 unsafe { Traits::disable_external_interrupts() };
@@ -458,6 +458,15 @@ impl State {
 
         extern "C" fn choose_and_get_next_task<Traits: PortInstance>(
         ) -> A0A1<MaybeUninit<usize>, Option<&'static TaskCb<Traits>>> {
+            // If we came here from a task, check the stack pointer we have
+            // just saved for it
+            // Safety: `running_task` is only modified by `choose_running_task`,
+            //         so there's no data race. CPU Lock active.
+            if let Some(task) = unsafe { *Traits::state().running_task_ptr() } {
+                let sp = unsafe { *task.port_task_state.sp.get() };
+                unsafe { Traits::check_stack_pointer(sp) };
+            }
+
             // Safety: CPU Lock active
             unsafe { Traits::choose_running_task() };
 
@@ -835,9 +844,16 @@ impl State {
                 csrci " crate::threading::imp::csr::csrexpr!(XSTATUS) ",            "
                     crate::threading::imp::csr::csrexpr!(XSTATUS_XIE)               "
 
+                # Borrow the main stack. The kernel might reinitialize the
+                # exiting task's stack in `choose_and_get_next_task` (if the
+                # task has a queued activation request), so we can't stay on
+                # it.
+                LOAD sp, ({MAIN_STACK})
+
                 j {push_second_level_state_and_dispatch}.dispatch
                 ",
                 PRIV = sym <<Traits as PortInstance>::Priv as csr::Num>::value,
+                MAIN_STACK = sym MAIN_STACK,
                 push_second_level_state_and_dispatch =
                     sym Self::push_second_level_state_and_dispatch::<Traits>,
                 options(noreturn, nostack),
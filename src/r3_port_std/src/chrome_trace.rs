@@ -0,0 +1,277 @@
+//! Records the simulated system's activity in [the Trace Event Format][1],
+//! which can be opened by `chrome://tracing`, [Perfetto][2], and other trace
+//! viewers.
+//!
+//! [1]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+//! [2]: https://ui.perfetto.dev/
+use r3_core::kernel::InterruptNum;
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{INTERRUPT_LINE_DISPATCH, INTERRUPT_LINE_TIMER};
+
+/// The environment variable specifying the output file path.
+pub const ENV_VAR: &str = "R3_PORT_STD_CHROME_TRACE";
+
+/// The track showing the periods when no task is running.
+const TID_IDLE: u64 = 0;
+/// The track showing timer ticks.
+const TID_TIMER: u64 = 1;
+/// The track of the task with ID `1`. The rest follows.
+const TID_TASK_BASE: u64 = 1000;
+/// The track of the last possible task.
+const TID_TASK_LAST: u64 = TID_INTERRUPT_BASE - 1;
+/// The track of the interrupt line `0`. The rest follows.
+const TID_INTERRUPT_BASE: u64 = 100000;
+
+/// Collects events in memory until [`Self::write_to_file`] is called.
+pub struct Recorder {
+    origin: Instant,
+    events: Vec<Event>,
+    /// The one-based ID of the running task, or `Some(None)` if idle. `None`
+    /// if no task switch has been recorded yet.
+    running_task: Option<Option<usize>>,
+    /// Interrupt lines whose handlers are running.
+    active_lines: BTreeSet<InterruptNum>,
+    /// Tracks that have appeared in `events`.
+    tids: BTreeSet<u64>,
+}
+
+struct Event {
+    ts: Duration,
+    tid: u64,
+    ph: Phase,
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    /// The beginning of a slice
+    Begin,
+    /// The end of a slice
+    End,
+    /// An instant event with the specified name
+    Instant(&'static str),
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            events: Vec::new(),
+            running_task: None,
+            active_lines: BTreeSet::new(),
+            tids: BTreeSet::new(),
+        }
+    }
+
+    /// Get the output file path specified by [`ENV_VAR`]. Returns `None` if
+    /// the tracing is disabled.
+    pub fn path_from_env() -> Option<PathBuf> {
+        env::var_os(ENV_VAR)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    fn push(&mut self, tid: u64, ph: Phase) {
+        self.tids.insert(tid);
+        self.events.push(Event {
+            ts: self.origin.elapsed(),
+            tid,
+            ph,
+        });
+    }
+
+    /// Record a task switch. `task` is the new running task's one-based ID.
+    pub fn task_switch(&mut self, task: Option<usize>) {
+        if self.running_task == Some(task) {
+            return;
+        }
+        if let Some(old_task) = self.running_task {
+            self.push(task_tid(old_task), Phase::End);
+        }
+        self.push(task_tid(task), Phase::Begin);
+        self.running_task = Some(task);
+    }
+
+    /// Record the start of an interrupt handler.
+    pub fn interrupt_start(&mut self, line: InterruptNum) {
+        self.active_lines.insert(line);
+        self.push(interrupt_tid(line), Phase::Begin);
+    }
+
+    /// Record the completion of an interrupt handler.
+    pub fn interrupt_end(&mut self, line: InterruptNum) {
+        if self.active_lines.remove(&line) {
+            self.push(interrupt_tid(line), Phase::End);
+        }
+    }
+
+    /// Record the pending of an interrupt line.
+    pub fn interrupt_pend(&mut self, line: InterruptNum) {
+        self.push(interrupt_tid(line), Phase::Instant("pend"));
+    }
+
+    /// Record a timer tick.
+    pub fn timer_tick(&mut self) {
+        self.push(TID_TIMER, Phase::Instant("tick"));
+    }
+
+    /// Write the recorded events to the file at `path`.
+    pub fn write_to_file(&mut self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(fs::File::create(path)?);
+        self.write(&mut out)?;
+        out.flush()
+    }
+
+    /// Write the recorded events in the JSON Object Format. Slices that are
+    /// still open are closed at the current time.
+    pub fn write(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if let Some(task) = self.running_task.take() {
+            self.push(task_tid(task), Phase::End);
+        }
+        for line in std::mem::take(&mut self.active_lines) {
+            self.push(interrupt_tid(line), Phase::End);
+        }
+
+        writeln!(out, "{{\"traceEvents\":[")?;
+        let mut first = true;
+        let mut sep = |out: &mut dyn Write| {
+            let sep = if first { "" } else { ",\n" };
+            first = false;
+            write!(out, "{sep}")
+        };
+
+        // Name the tracks
+        for &tid in self.tids.iter() {
+            sep(out)?;
+            write!(
+                out,
+                "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{tid},\
+                \"args\":{{\"name\":\"{}\"}}}}",
+                track_name(tid)
+            )?;
+            sep(out)?;
+            write!(
+                out,
+                "{{\"name\":\"thread_sort_index\",\"ph\":\"M\",\"pid\":1,\"tid\":{tid},\
+                \"args\":{{\"sort_index\":{tid}}}}}",
+            )?;
+        }
+
+        for event in self.events.iter() {
+            let Event { ts, tid, ph } = event;
+            let ts = ts.as_nanos() as f64 / 1000.0;
+            sep(out)?;
+            match ph {
+                Phase::Begin => write!(
+                    out,
+                    "{{\"name\":\"{}\",\"ph\":\"B\",\"ts\":{ts:.3},\"pid\":1,\"tid\":{tid}}}",
+                    slice_name(*tid)
+                )?,
+                Phase::End => write!(
+                    out,
+                    "{{\"ph\":\"E\",\"ts\":{ts:.3},\"pid\":1,\"tid\":{tid}}}"
+                )?,
+                Phase::Instant(name) => write!(
+                    out,
+                    "{{\"name\":\"{name}\",\"ph\":\"i\",\"s\":\"t\",\"ts\":{ts:.3},\
+                    \"pid\":1,\"tid\":{tid}}}"
+                )?,
+            }
+        }
+
+        writeln!(out, "\n],\"displayTimeUnit\":\"ns\"}}")
+    }
+}
+
+fn task_tid(task: Option<usize>) -> u64 {
+    match task {
+        Some(task) => TID_TASK_BASE + task as u64 - 1,
+        None => TID_IDLE,
+    }
+}
+
+fn interrupt_tid(line: InterruptNum) -> u64 {
+    TID_INTERRUPT_BASE + line as u64
+}
+
+fn track_name(tid: u64) -> String {
+    match tid {
+        TID_IDLE => "Idle".to_owned(),
+        TID_TIMER => "Timer".to_owned(),
+        TID_TASK_BASE..=TID_TASK_LAST => format!("Task {}", tid - TID_TASK_BASE + 1),
+        _ => match (tid - TID_INTERRUPT_BASE) as InterruptNum {
+            INTERRUPT_LINE_DISPATCH => format!("Interrupt {INTERRUPT_LINE_DISPATCH} (dispatch)"),
+            INTERRUPT_LINE_TIMER => format!("Interrupt {INTERRUPT_LINE_TIMER} (timer)"),
+            line => format!("Interrupt {line}"),
+        },
+    }
+}
+
+fn slice_name(tid: u64) -> &'static str {
+    match tid {
+        TID_IDLE => "idle",
+        TID_TASK_BASE..=TID_TASK_LAST => "running",
+        _ => "handler",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phases(json: &str) -> Vec<&str> {
+        json.match_indices("\"ph\":\"")
+            .map(|(i, _)| &json[i + 6..i + 7])
+            .collect()
+    }
+
+    #[test]
+    fn unclosed_slices() {
+        let mut recorder = Recorder::new();
+        recorder.task_switch(Some(1));
+        recorder.interrupt_pend(3);
+        recorder.interrupt_start(3);
+        recorder.timer_tick();
+
+        let mut out = Vec::new();
+        recorder.write(&mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+        log::debug!("{json}");
+
+        assert!(json.starts_with("{\"traceEvents\":["));
+        assert!(json.contains("\"args\":{\"name\":\"Task 1\"}"));
+        assert!(json.contains("\"args\":{\"name\":\"Interrupt 3\"}"));
+        assert!(json.contains("\"args\":{\"name\":\"Timer\"}"));
+
+        // Three tracks (`M` × 2 each), then the recorded events, then the
+        // slices closed by `write`
+        assert_eq!(
+            phases(&json),
+            ["M", "M", "M", "M", "M", "M", "B", "i", "B", "i", "E", "E"]
+        );
+    }
+
+    #[test]
+    fn task_switch() {
+        let mut recorder = Recorder::new();
+        recorder.task_switch(Some(1));
+        recorder.task_switch(Some(1));
+        recorder.task_switch(None);
+        recorder.task_switch(Some(2));
+
+        let mut out = Vec::new();
+        recorder.write(&mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+        log::debug!("{json}");
+
+        assert!(json.contains("\"args\":{\"name\":\"Idle\"}"));
+        assert!(json.contains("\"args\":{\"name\":\"Task 2\"}"));
+        assert_eq!(phases(&json)[6..], ["B", "E", "B", "E", "B", "E"]);
+    }
+}
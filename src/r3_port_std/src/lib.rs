@@ -42,6 +42,7 @@ mod threading;
 #[cfg(test)]
 mod threading_test;
 
+mod chrome_trace;
 mod sched;
 mod ums;
 mod utils;
@@ -279,6 +280,17 @@ impl State {
         // Wait until the thread group shuts down
         let result = join_handle.join();
 
+        // Write the trace file if requested
+        if let Some(path) = chrome_trace::Recorder::path_from_env() {
+            let mut lock = self.thread_group.get().unwrap().lock();
+            if let Some(recorder) = &mut lock.scheduler().chrome_trace {
+                log::debug!("writing a trace file to {path:?}");
+                if let Err(e) = recorder.write_to_file(&path) {
+                    log::warn!("failed to write a trace file to {path:?}: {e}");
+                }
+            }
+        }
+
         // Stop the timer thread.
         // `timer_cmd_recv.recv` will return `Err(_)` when we drop the
         // corresponding sender (`timer_cmd_send`).
@@ -383,6 +395,16 @@ impl State {
         } else {
             None
         };
+
+        if let Some(recorder) = &mut lock.scheduler().chrome_trace {
+            recorder.task_switch(running_task.map(|task| {
+                Traits::task_cb_pool()
+                    .iter()
+                    .position(|cb| std::ptr::eq(cb, task))
+                    .unwrap()
+                    + 1
+            }));
+        }
     }
 
     pub unsafe fn yield_cpu<Traits: PortInstance>(&'static self) {
@@ -438,12 +460,24 @@ impl State {
         expect_worker_thread();
         assert!(self.is_cpu_lock_active::<Traits>());
 
+        let mut lock = self.thread_group.get().unwrap().lock();
+
         let pts = &task.port_task_state;
         let mut tsm = pts.tsm.lock();
         match &*tsm {
             Tsm::Dormant => {}
-            Tsm::Running(_) => {
-                todo!("terminating a thread is not implemented yet");
+            &Tsm::Running(thread_id) => {
+                // The task was terminated by another context. Dissociate the
+                // thread from the task and get rid of it.
+                log::trace!("terminating thread {thread_id:?} of the task {task:p}");
+                if lock.scheduler().task_thread == Some(thread_id) {
+                    lock.scheduler().task_thread = None;
+                }
+
+                // Safety: The kernel is allowed to discard the task's stack
+                unsafe { lock.terminate(thread_id) };
+
+                *tsm = Tsm::Dormant;
             }
             Tsm::Uninit => {
                 *tsm = Tsm::Dormant;
@@ -595,10 +629,10 @@ impl State {
     pub const MAX_TICK_COUNT: UTicks = UTicks::MAX;
     pub const MAX_TIMEOUT: UTicks = UTicks::MAX / 2;
 
-    pub fn tick_count<Traits: PortInstance>(&self) -> UTicks {
-        expect_worker_thread();
-
-        let origin = if let Some(x) = self.origin.load(Ordering::Acquire) {
+    /// Get the origin point of [`Self::tick_count`] and
+    /// [`Self::runtime_count`], establishing one if it hasn't been done yet.
+    fn origin(&self) -> &'static Instant {
+        if let Some(x) = self.origin.load(Ordering::Acquire) {
             x
         } else {
             // Establish an origin point.
@@ -629,9 +663,13 @@ impl State {
                 Ok(_) => origin,      // case 2
                 Err(x) => x.unwrap(), // case 1
             }
-        };
+        }
+    }
+
+    pub fn tick_count<Traits: PortInstance>(&self) -> UTicks {
+        expect_worker_thread();
 
-        let micros = Instant::now().duration_since(*origin).as_micros();
+        let micros = Instant::now().duration_since(*self.origin()).as_micros();
 
         /// Implementation of <https://xkcd.com/221/> with a different magic
         /// number
@@ -644,6 +682,16 @@ impl State {
         (micros as UTicks).wrapping_add(get_random_number())
     }
 
+    pub const RUNTIME_COUNT_FREQUENCY: u64 = 1_000_000_000;
+    pub const MAX_RUNTIME_COUNT: u64 = u64::MAX;
+
+    pub fn runtime_count<Traits: PortInstance>(&self) -> u64 {
+        expect_worker_thread();
+
+        // Nanoseconds. This won't overflow for 500 years.
+        Instant::now().duration_since(*self.origin()).as_nanos() as u64
+    }
+
     pub fn pend_tick_after<Traits: PortInstance>(&self, tick_count_delta: UTicks) {
         expect_worker_thread();
         log::trace!("pend_tick_after({tick_count_delta:?})");
@@ -674,6 +722,12 @@ impl State {
         assert_eq!(expect_worker_thread(), ThreadRole::Interrupt);
         log::trace!("timer_handler");
 
+        let mut lock = Traits::port_state().thread_group.get().unwrap().lock();
+        if let Some(recorder) = &mut lock.scheduler().chrome_trace {
+            recorder.timer_tick();
+        }
+        drop(lock);
+
         // Safety: CPU Lock inactive, an interrupt context
         unsafe { <Traits as PortToKernel>::timer_tick() };
     }
@@ -864,6 +918,13 @@ macro_rules! use_port {
                 unsafe fn pend_tick() {
                     PORT_STATE.pend_tick::<Self>()
                 }
+
+                const RUNTIME_COUNT_FREQUENCY: u64 = State::RUNTIME_COUNT_FREQUENCY;
+                const MAX_RUNTIME_COUNT: u64 = State::MAX_RUNTIME_COUNT;
+
+                unsafe fn runtime_count() -> u64 {
+                    PORT_STATE.runtime_count::<Self>()
+                }
             }
         }
 
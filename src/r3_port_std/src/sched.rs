@@ -3,10 +3,10 @@ use r3_core::{
     kernel::interrupt::{InterruptHandlerFn, InterruptNum, InterruptPriority},
     utils::Init,
 };
-use r3_kernel::KernelTraits;
+use r3_kernel::{KernelTraits, PortToKernel};
 use std::collections::{BTreeSet, HashMap};
 
-use crate::{ums, ThreadRole, NUM_INTERRUPT_LINES, THREAD_ROLE};
+use crate::{chrome_trace, ums, ThreadRole, NUM_INTERRUPT_LINES, THREAD_ROLE};
 
 /// The state of the simulated hardware scheduler.
 pub struct SchedState {
@@ -23,6 +23,15 @@ pub struct SchedState {
 
     /// Garbage can
     zombies: Vec<ums::ThreadId>,
+
+    /// [`PortToKernel::interrupt_enter`] and [`PortToKernel::interrupt_exit`]
+    /// of the kernel instance using this scheduler.
+    interrupt_enter: unsafe fn(InterruptNum),
+    interrupt_exit: unsafe fn(InterruptNum),
+
+    /// Records the activity in the Trace Event Format if enabled by
+    /// [`chrome_trace::ENV_VAR`].
+    pub chrome_trace: Option<chrome_trace::Recorder>,
 }
 
 /// The configuration of an interrupt line.
@@ -54,6 +63,10 @@ impl SchedState {
             cpu_lock: true,
             task_thread: None,
             zombies: Vec::new(),
+            interrupt_enter: <Traits as PortToKernel>::interrupt_enter,
+            interrupt_exit: <Traits as PortToKernel>::interrupt_exit,
+            chrome_trace: chrome_trace::Recorder::path_from_env()
+                .map(|_| chrome_trace::Recorder::new()),
         };
 
         for i in 0..NUM_INTERRUPT_LINES {
@@ -81,7 +94,13 @@ impl SchedState {
         }
         let line = self.int_lines.entry(i).or_insert_with(|| IntLine::INIT);
         self.pended_lines.remove(&(line.priority, i));
+        let was_pended = line.pended;
         f(line);
+        if line.pended && !was_pended {
+            if let Some(recorder) = &mut self.chrome_trace {
+                recorder.interrupt_pend(i);
+            }
+        }
         if line.enable && line.pended {
             self.pended_lines.insert((line.priority, i));
         }
@@ -180,6 +199,10 @@ pub fn check_preemption_by_interrupt(
         // Take the interrupt
         sched_state.pended_lines.remove(&(pri, num));
 
+        if let Some(recorder) = &mut sched_state.chrome_trace {
+            recorder.interrupt_start(num);
+        }
+
         // Find the interrupt handler for `num`. Return
         // `default_interrupt_handler` if there's none.
         let start = sched_state
@@ -187,12 +210,18 @@ pub fn check_preemption_by_interrupt(
             .get(&num)
             .and_then(|line| line.start)
             .unwrap_or(default_interrupt_handler);
+        let (interrupt_enter, interrupt_exit) =
+            (sched_state.interrupt_enter, sched_state.interrupt_exit);
 
         let thread_id = lock.spawn(move |thread_id| {
             THREAD_ROLE.with(|role| role.set(ThreadRole::Interrupt));
 
             // Safety: The port can call an interrupt handler
-            unsafe { start() }
+            unsafe {
+                interrupt_enter(num);
+                start();
+                interrupt_exit(num);
+            }
 
             let mut lock = thread_group.lock();
 
@@ -201,6 +230,10 @@ pub fn check_preemption_by_interrupt(
             assert_eq!(thread_id, popped_thread_id);
             log::trace!("an interrupt handler for an interrupt {num} (priority = {pri}) exited");
 
+            if let Some(recorder) = &mut lock.scheduler().chrome_trace {
+                recorder.interrupt_end(num);
+            }
+
             // Make sure this thread will run to completion
             lock.scheduler().zombies.push(thread_id);
 
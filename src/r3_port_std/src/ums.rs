@@ -3,7 +3,10 @@ use slab::Slab;
 use std::{
     panic::{catch_unwind, AssertUnwindSafe},
     sync::OnceLock,
-    sync::{mpsc, Arc},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
     thread::Result,
 };
 
@@ -72,6 +75,11 @@ struct State<Sched: ?Sized> {
 #[derive(Debug)]
 struct WorkerThread {
     join_handle: Option<threading::JoinHandle<()>>,
+    /// Set by [`ThreadGroupLockGuard::terminate`].
+    terminated: Arc<AtomicBool>,
+    /// `true` if the thread was stopped by [`ThreadGroupLockGuard::preempt`]
+    /// and hasn't been resumed since then.
+    preempted: bool,
 }
 
 thread_local! {
@@ -83,6 +91,8 @@ struct ThreadLocalBlock {
     thread_id: ThreadId,
     /// The thread group the current worker thread belongs to.
     state: Arc<threading::Mutex<State<dyn Scheduler>>>,
+    /// Set by [`ThreadGroupLockGuard::terminate`].
+    terminated: Arc<AtomicBool>,
 }
 
 impl<Sched: Scheduler> ThreadGroup<Sched> {
@@ -142,25 +152,32 @@ impl<'a, Sched: Scheduler> ThreadGroupLockGuard<'a, Sched> {
         }
 
         let state = Arc::clone(self.state_ref);
+        let terminated = Arc::new(AtomicBool::new(false));
 
         // Allocate a `ThreadId`
-        let ptr: SlabPtr = self
-            .guard
-            .threads
-            .insert(WorkerThread { join_handle: None });
+        let ptr: SlabPtr = self.guard.threads.insert(WorkerThread {
+            join_handle: None,
+            terminated: Arc::clone(&terminated),
+            preempted: false,
+        });
         let thread_id = ThreadId(ptr);
         self.guard.num_threads += 1;
 
         let join_handle = threading::spawn(move || {
             let state2 = Arc::clone(&state);
             TLB.with(|cell| {
-                cell.set(ThreadLocalBlock { thread_id, state })
-                    .ok()
-                    .unwrap()
+                cell.set(ThreadLocalBlock {
+                    thread_id,
+                    state,
+                    terminated,
+                })
+                .ok()
+                .unwrap()
             });
 
             // Block thw spawned thread until scheduled to run
             threading::park();
+            exit_if_terminated();
 
             // Call the thread entry point
             let result = catch_unwind(AssertUnwindSafe(move || {
@@ -192,13 +209,59 @@ impl<'a, Sched: Scheduler> ThreadGroupLockGuard<'a, Sched> {
         let guard = &mut *self.guard;
         log::trace!("preempting {:?}", guard.cur_thread_id);
         if let Some(thread_id) = guard.cur_thread_id {
-            let join_handle = guard.threads[thread_id.0].join_handle.as_ref().unwrap();
-            join_handle.thread().park();
+            let thread = &mut guard.threads[thread_id.0];
+            thread.join_handle.as_ref().unwrap().thread().park();
+            thread.preempted = true;
         }
 
         guard.unpark_next_thread();
     }
 
+    /// Remove the specified worker thread from the thread group. The thread
+    /// will never be scheduled again. [`Scheduler::thread_exited`] is not
+    /// called for the thread.
+    ///
+    /// If the thread is blocked in [`yield_now`], the underlying OS thread
+    /// exits as soon as possible. If the thread was stopped by
+    /// [`Self::preempt`], it's impossible to tell if it's safe to exit, so the
+    /// underlying OS thread is left stopped forever.
+    ///
+    /// The current thread cannot be terminated by this method. Use
+    /// [`exit_thread`] instead.
+    ///
+    /// # Safety
+    ///
+    /// It comes with all the unsafety of terminating a thread, such as that it
+    /// could unpin pinned local variables.
+    pub unsafe fn terminate(&mut self, thread_id: ThreadId) {
+        let guard = &mut *self.guard;
+        assert_ne!(
+            guard.cur_thread_id,
+            Some(thread_id),
+            "the current thread cannot be terminated"
+        );
+
+        log::trace!("terminating {thread_id:?}");
+
+        let thread = guard.threads.remove(thread_id.0);
+        guard.num_threads -= 1;
+
+        if thread.preempted {
+            log::warn!(
+                "{thread_id:?} was terminated while preempted; leaking the \
+                underlying OS thread"
+            );
+        } else {
+            // Wake up the thread and let it exit
+            thread.terminated.store(true, Ordering::Relaxed);
+            thread.join_handle.as_ref().unwrap().thread().unpark();
+        }
+
+        if guard.num_threads == 0 && guard.shutting_down {
+            guard.complete_shutdown();
+        }
+    }
+
     /// Initiate graceful shutdown for the thread group.
     ///
     /// The shutdown completes when all threads complete execution. After this
@@ -245,8 +308,9 @@ impl State<dyn Scheduler> {
         self.cur_thread_id = self.sched.choose_next_thread();
         log::trace!("scheduling {:?}", self.cur_thread_id);
         if let Some(thread_id) = self.cur_thread_id {
-            let join_handle = self.threads[thread_id.0].join_handle.as_ref().unwrap();
-            join_handle.thread().unpark();
+            let thread = &mut self.threads[thread_id.0];
+            thread.preempted = false;
+            thread.join_handle.as_ref().unwrap().thread().unpark();
         }
     }
 
@@ -277,6 +341,24 @@ pub fn yield_now() {
     // Block thw thread until scheduled to run. This might end immediately if
     // the current thread is the next thread to run.
     threading::park();
+    exit_if_terminated();
+}
+
+/// Exit the current worker thread if it has been terminated by
+/// [`ThreadGroupLockGuard::terminate`].
+fn exit_if_terminated() {
+    let terminated = TLB.with(|cell| {
+        cell.get()
+            .map_or(false, |tlb| tlb.terminated.load(Ordering::Relaxed))
+    });
+
+    if terminated {
+        log::trace!("{:?} exiting due to termination", current_thread());
+
+        // Safety: The caller of `ThreadGroupLockGuard::terminate` is
+        //         responsible for this
+        unsafe { threading::exit_thread() };
+    }
 }
 
 /// Terminate the current worker thread.
@@ -0,0 +1,206 @@
+//! Checks the snapshots taken by `r3_kernel::introspect`.
+//!
+//! 1. (`seq`: 0 → 1) `task2` locks `mutex` and waits on `sem` with a timeout.
+//! 2. (`seq`: 1 → 2) `task3` waits to lock `mutex`.
+//! 3. (`seq`: 2 → 3) `task1` inspects the kernel objects and signals `sem`.
+//! 4. (`seq`: 3 → 4) `task2` unlocks `mutex` and exits.
+//! 5. (`seq`: 4 → 5) `task3` locks and unlocks `mutex` and exits.
+//! 6. (`seq`: 5 → 6) `task1` checks that the other tasks are Dormant.
+use r3_core::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, MutexProtocol, StaticMutex, StaticSemaphore, StaticTask,
+        StaticTimer,
+    },
+    time::Duration,
+};
+use r3_kernel::{
+    introspect::{self, TaskState, WaitObject},
+    Id, System,
+};
+use r3_test_suite::kernel_tests::Driver;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use r3_port_std::PortInstance;
+
+pub trait SupportedSystemTraits: PortInstance {}
+impl<T: PortInstance> SupportedSystemTraits for T {}
+
+pub struct App<
+    System: traits::KernelBase
+        + traits::KernelMutex
+        + traits::KernelSemaphore
+        + traits::KernelTimer
+        + traits::KernelStatic,
+> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    task3: StaticTask<System>,
+    mutex: StaticMutex<System>,
+    sem: StaticSemaphore<System>,
+    timer: StaticTimer<System>,
+    seq: Hunk<System, AtomicUsize>,
+}
+
+impl<Traits: SupportedSystemTraits> App<System<Traits>> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System<Traits>>
+            + ~const traits::CfgMutex
+            + ~const traits::CfgSemaphore
+            + ~const traits::CfgTimer,
+    {
+        let task1 = StaticTask::define()
+            .start(task1_body::<Traits, D>)
+            .priority(3)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<Traits, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+        let task3 = StaticTask::define()
+            .start(task3_body::<Traits, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let mutex = StaticMutex::define().finish(b);
+        let sem = StaticSemaphore::define().initial(0).maximum(1).finish(b);
+        let timer = StaticTimer::define()
+            .start(|| unreachable!())
+            .delay(Duration::from_millis(500))
+            .finish(b);
+
+        let seq = Hunk::<_, AtomicUsize>::define().finish(b);
+
+        App {
+            task1,
+            task2,
+            task3,
+            mutex,
+            sem,
+            timer,
+            seq,
+        }
+    }
+}
+
+fn task1_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(3, Ordering::Relaxed), 2);
+
+    let info = introspect::task::<Traits>(app.task1.id()).unwrap();
+    assert_eq!(info.state, TaskState::Running);
+    assert_eq!(info.base_priority, 3);
+    assert_eq!(info.wait, None);
+    assert_eq!(info.timeout, None);
+
+    // `task2` is waiting on `sem` with a timeout
+    let info = introspect::task::<Traits>(app.task2.id()).unwrap();
+    log::debug!("task2 = {info:?}");
+    assert_eq!(info.state, TaskState::Waiting);
+    assert_eq!(info.wait, Some(WaitObject::Semaphore(app.sem.id())));
+    let timeout = info.timeout.unwrap();
+    assert!(timeout > Duration::ZERO && timeout <= Duration::from_secs(1));
+
+    let mut held = [None; 2];
+    assert_eq!(
+        introspect::task_held_mutexes::<Traits>(app.task2.id(), &mut held),
+        Ok(1)
+    );
+    assert_eq!(held, [Some(app.mutex.id()), None]);
+
+    // `task3` is waiting on `mutex` without a timeout
+    let info = introspect::task::<Traits>(app.task3.id()).unwrap();
+    log::debug!("task3 = {info:?}");
+    assert_eq!(info.state, TaskState::Waiting);
+    assert_eq!(info.wait, Some(WaitObject::Mutex(app.mutex.id())));
+    assert_eq!(info.timeout, None);
+
+    let mut waits = [None; 2];
+    assert_eq!(
+        introspect::task_wait_objects::<Traits>(app.task3.id(), &mut waits),
+        Ok(1)
+    );
+    assert_eq!(waits, [Some(WaitObject::Mutex(app.mutex.id())), None]);
+
+    let info = introspect::mutex::<Traits>(app.mutex.id()).unwrap();
+    log::debug!("mutex = {info:?}");
+    assert_eq!(info.protocol, MutexProtocol::None);
+    assert_eq!(info.owner, Some(app.task2.id()));
+    assert!(!info.inconsistent);
+    assert_eq!(info.num_waiting_tasks, 1);
+
+    let info = introspect::semaphore::<Traits>(app.sem.id()).unwrap();
+    log::debug!("sem = {info:?}");
+    assert_eq!((info.value, info.max_value), (0, 1));
+    assert_eq!(info.num_waiting_tasks, 1);
+
+    let info = introspect::timer::<Traits>(app.timer.id()).unwrap();
+    log::debug!("timer = {info:?}");
+    assert!(!info.active);
+    assert_eq!(info.delay, Some(Duration::from_millis(500)));
+    assert_eq!(info.period, None);
+
+    // Enumeration
+    let tasks: Vec<_> = introspect::tasks::<Traits>()
+        .map(|info| info.unwrap().id)
+        .collect();
+    assert_eq!(tasks, [app.task1.id(), app.task2.id(), app.task3.id()]);
+    assert_eq!(introspect::mutexes::<Traits>().count(), 1);
+    assert_eq!(introspect::event_groups::<Traits>().count(), 0);
+
+    // Error cases
+    assert_eq!(
+        introspect::task::<Traits>(Id::new(4).unwrap()),
+        Err(introspect::Error::BadId)
+    );
+    System::<Traits>::acquire_cpu_lock().unwrap();
+    assert_eq!(
+        introspect::task::<Traits>(app.task1.id()),
+        Err(introspect::Error::BadContext)
+    );
+    unsafe { System::<Traits>::release_cpu_lock().unwrap() };
+
+    app.sem.signal_one().unwrap();
+
+    assert_eq!(app.seq.swap(6, Ordering::Relaxed), 5);
+
+    for task in [app.task2, app.task3] {
+        let info = introspect::task::<Traits>(task.id()).unwrap();
+        assert_eq!(info.state, TaskState::Dormant);
+        assert_eq!(info.wait, None);
+    }
+    assert_eq!(
+        introspect::task_held_mutexes::<Traits>(app.task2.id(), &mut held),
+        Ok(0)
+    );
+    let info = introspect::mutex::<Traits>(app.mutex.id()).unwrap();
+    assert_eq!(info.owner, None);
+    assert_eq!(info.num_waiting_tasks, 0);
+
+    D::success();
+}
+
+fn task2_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(1, Ordering::Relaxed), 0);
+
+    app.mutex.lock().unwrap();
+    app.sem.wait_one_timeout(Duration::from_secs(1)).unwrap();
+
+    assert_eq!(app.seq.swap(4, Ordering::Relaxed), 3);
+    app.mutex.unlock().unwrap();
+}
+
+fn task3_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(2, Ordering::Relaxed), 1);
+
+    app.mutex.lock().unwrap();
+
+    assert_eq!(app.seq.swap(5, Ordering::Relaxed), 4);
+    app.mutex.unlock().unwrap();
+}
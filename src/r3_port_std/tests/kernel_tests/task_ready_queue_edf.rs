@@ -0,0 +1,165 @@
+//! Checks that `EdfQueue` schedules tasks having the same effective priority
+//! in the earliest-deadline-first order.
+//!
+//! 1. (`seq`: 0 → 1) `task0` checks the error codes of `set_deadline` and
+//!    activates all other tasks.
+//! 2. (`seq`: 1 → 2) `task2` (deadline = 100ms) runs first. It brings forward
+//!    `task3`'s deadline to 10ms, which causes `task3` to preempt `task2`.
+//! 3. (`seq`: 2 → 3) `task3` runs and exits.
+//! 4. (`seq`: 3 → 4) `task2` resumes and exits.
+//! 5. (`seq`: 4 → 5) `task1` (deadline = 300ms) locks `mutex`, raising its
+//!    effective priority. It brings forward `task4`'s deadline to 1ms, which
+//!    doesn't cause preemption because `task4` has a lower effective priority.
+//! 6. (`seq`: 5 → 6) `task1` unlocks `mutex` and is preempted by `task4`.
+//! 7. (`seq`: 6 → 7) `task4` runs and exits.
+//! 8. (`seq`: 7 → 8) `task1` resumes and exits.
+//! 9. (`seq`: 8 → 9) `task5` has the earliest deadline but the lowest
+//!    priority, so it runs last.
+//!
+use r3_core::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, MutexProtocol, SetTaskDeadlineError, StaticMutex, StaticTask,
+    },
+    time::Duration,
+};
+use r3_kernel::System;
+use r3_test_suite::kernel_tests::Driver;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use r3_port_std::PortInstance;
+
+pub trait SupportedSystemTraits: PortInstance {}
+impl<T: PortInstance> SupportedSystemTraits for T {}
+
+pub struct App<System: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    task3: StaticTask<System>,
+    task4: StaticTask<System>,
+    task5: StaticTask<System>,
+    mutex: StaticMutex<System>,
+    seq: Hunk<System, AtomicUsize>,
+}
+
+impl<Traits: SupportedSystemTraits> App<System<Traits>> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System<Traits>> + ~const traits::CfgMutex,
+    {
+        StaticTask::define()
+            .start(task0_body::<Traits, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<Traits, D>)
+            .priority(2)
+            .relative_deadline(Duration::from_millis(300))
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<Traits, D>)
+            .priority(2)
+            .relative_deadline(Duration::from_millis(100))
+            .finish(b);
+        let task3 = StaticTask::define()
+            .start(task3_body::<Traits, D>)
+            .priority(2)
+            .relative_deadline(Duration::from_millis(200))
+            .finish(b);
+        let task4 = StaticTask::define()
+            .start(task4_body::<Traits, D>)
+            .priority(2)
+            .finish(b);
+        let task5 = StaticTask::define()
+            .start(task5_body::<Traits, D>)
+            .priority(3)
+            .relative_deadline(Duration::from_millis(1))
+            .finish(b);
+
+        let mutex = StaticMutex::define()
+            .protocol(MutexProtocol::Ceiling(1))
+            .finish(b);
+        let seq = Hunk::<_, AtomicUsize>::define().finish(b);
+
+        App {
+            task1,
+            task2,
+            task3,
+            task4,
+            task5,
+            mutex,
+            seq,
+        }
+    }
+}
+
+fn expect_and_replace<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>(
+    old: usize,
+    new: usize,
+) {
+    let got = D::app().seq.swap(new, Ordering::Relaxed);
+    log::debug!("{got} (expected: {old}) → {new}");
+    assert_eq!(got, old, "expected {old}, got {got}");
+}
+
+fn task0_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    expect_and_replace::<Traits, D>(0, 1);
+
+    // The task is dormant
+    assert_eq!(
+        app.task5.set_deadline(Duration::from_millis(1)),
+        Err(SetTaskDeadlineError::BadObjectState)
+    );
+
+    // Activate the tasks in the order that doesn't match their deadlines
+    app.task1.activate().unwrap();
+    app.task4.activate().unwrap();
+    app.task5.activate().unwrap();
+    app.task2.activate().unwrap();
+    app.task3.activate().unwrap();
+
+    // The duration is negative
+    assert_eq!(
+        app.task1.set_deadline(Duration::from_millis(-1)),
+        Err(SetTaskDeadlineError::BadParam)
+    );
+}
+
+fn task1_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    expect_and_replace::<Traits, D>(4, 5);
+
+    // The priority ceiling takes precedence over the deadline
+    app.mutex.lock().unwrap();
+    app.task4.set_deadline(Duration::from_millis(1)).unwrap();
+
+    expect_and_replace::<Traits, D>(5, 6);
+    app.mutex.unlock().unwrap();
+
+    expect_and_replace::<Traits, D>(7, 8);
+}
+
+fn task2_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    expect_and_replace::<Traits, D>(1, 2);
+
+    // `task3` preempts `task2`
+    app.task3.set_deadline(Duration::from_millis(10)).unwrap();
+
+    expect_and_replace::<Traits, D>(3, 4);
+}
+
+fn task3_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    expect_and_replace::<Traits, D>(2, 3);
+}
+
+fn task4_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    expect_and_replace::<Traits, D>(6, 7);
+}
+
+fn task5_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    expect_and_replace::<Traits, D>(8, 9);
+    D::success();
+}
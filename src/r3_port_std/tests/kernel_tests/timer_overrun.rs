@@ -0,0 +1,105 @@
+//! Checks that the expirations of a timer defined with `TimerContext::Task` are
+//! dropped and counted as overruns when the timer service task's queue is
+//! full.
+//!
+//! 1. (`seq`: 0 → 1) `task1` occupies the processor, preventing the timer
+//!    service task from running, until `timer` overruns twice. `task1` sets
+//!    `timer`'s delay to infinity and exits. (Stopping `timer` would discard
+//!    the queued expiration.)
+//! 2. (`seq`: 1 → 2) The timer service task calls `timer`'s callback function
+//!    for the only expiration that fit in the queue.
+//! 3. (`seq`: 2 → 3) `task2` checks the number of overruns and completes the
+//!    test.
+use r3_core::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask, StaticTimer, TimerContext},
+    time::Duration,
+};
+use r3_kernel::{introspect, System};
+use r3_test_suite::kernel_tests::Driver;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use r3_port_std::PortInstance;
+
+pub trait SupportedSystemTraits: PortInstance {}
+impl<T: PortInstance> SupportedSystemTraits for T {}
+
+pub struct App<System: traits::KernelBase + traits::KernelTimer + traits::KernelStatic> {
+    timer: StaticTimer<System>,
+    seq: Hunk<System, AtomicUsize>,
+    num_overruns: Hunk<System, AtomicUsize>,
+}
+
+impl<Traits: SupportedSystemTraits> App<System<Traits>> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System<Traits>> + ~const traits::CfgTimer,
+    {
+        StaticTask::define()
+            .start(task1_body::<Traits, D>)
+            .priority(0)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task2_body::<Traits, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        // The queue has one slot because there's only one timer using it
+        let timer = StaticTimer::define()
+            .delay(Duration::from_millis(10))
+            .period(Duration::from_millis(10))
+            .active(true)
+            .context(TimerContext::Task {
+                priority: 1,
+                stack_size: None,
+            })
+            .start(timer_body::<Traits, D>)
+            .finish(b);
+
+        let seq = Hunk::<_, AtomicUsize>::define().finish(b);
+        let num_overruns = Hunk::<_, AtomicUsize>::define().finish(b);
+
+        App {
+            timer,
+            seq,
+            num_overruns,
+        }
+    }
+}
+
+fn task1_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(1, Ordering::Relaxed), 0);
+
+    let num_overruns = loop {
+        let info = introspect::timer::<Traits>(app.timer.id()).unwrap();
+        if info.num_overruns >= 2 {
+            break info.num_overruns;
+        }
+        core::hint::spin_loop();
+    };
+    app.timer.set_delay(None).unwrap();
+    log::debug!("num_overruns = {num_overruns}");
+
+    app.num_overruns.store(num_overruns, Ordering::Relaxed);
+}
+
+fn task2_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(3, Ordering::Relaxed), 2);
+
+    let info = introspect::timer::<Traits>(app.timer.id()).unwrap();
+    assert!(info.active);
+    assert_eq!(info.delay, None);
+    assert_eq!(info.num_overruns, app.num_overruns.load(Ordering::Relaxed));
+
+    D::success();
+}
+
+fn timer_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert!(System::<Traits>::is_task_context());
+    assert_eq!(app.seq.swap(2, Ordering::Relaxed), 1);
+}
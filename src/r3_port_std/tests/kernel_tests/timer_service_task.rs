@@ -0,0 +1,105 @@
+//! Checks that the application can't control the lifecycle of the timer
+//! service task.
+//!
+//! 1. (`seq`: 0 → 1) `task` starts sleeping.
+//! 2. (`seq`: 1 → 2) `timer` fires, and its callback function is called by the
+//!    timer service task. It tries to activate, suspend, and terminate the
+//!    current task, which fail with `NoAccess`. It records the current task's
+//!    ID.
+//! 3. (`seq`: 2 → 3) `task` wakes up and tries the same operations on the
+//!    timer service task, which is now in the Dormant state. They fail with
+//!    `NoAccess`. `task` completes the test.
+use r3_core::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, ActivateTaskError, Cfg, LocalTask, StaticTask, StaticTimer,
+        SuspendTaskError, TerminateTaskError, TimerContext,
+    },
+    time::Duration,
+};
+use r3_kernel::{Id, System};
+use r3_test_suite::kernel_tests::Driver;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use r3_port_std::PortInstance;
+
+pub trait SupportedSystemTraits: PortInstance {}
+impl<T: PortInstance> SupportedSystemTraits for T {}
+
+pub struct App<System: traits::KernelBase + traits::KernelTimer + traits::KernelStatic> {
+    task: StaticTask<System>,
+    seq: Hunk<System, AtomicUsize>,
+    service_task_id: Hunk<System, AtomicUsize>,
+}
+
+impl<Traits: SupportedSystemTraits> App<System<Traits>> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System<Traits>> + ~const traits::CfgTimer,
+    {
+        let task = StaticTask::define()
+            .start(task_body::<Traits, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        StaticTimer::define()
+            .delay(Duration::from_millis(20))
+            .active(true)
+            .context(TimerContext::Task {
+                priority: 1,
+                stack_size: None,
+            })
+            .start(timer_body::<Traits, D>)
+            .finish(b);
+
+        let seq = Hunk::<_, AtomicUsize>::define().finish(b);
+        let service_task_id = Hunk::<_, AtomicUsize>::define().finish(b);
+
+        App {
+            task,
+            seq,
+            service_task_id,
+        }
+    }
+}
+
+fn task_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(1, Ordering::Relaxed), 0);
+
+    System::<Traits>::sleep(Duration::from_millis(100)).unwrap();
+    assert_eq!(app.seq.swap(3, Ordering::Relaxed), 2);
+
+    // The service task's ID follows the application's tasks
+    let id = Id::new(app.service_task_id.load(Ordering::Relaxed)).unwrap();
+    assert_eq!(id.get(), app.task.id().get() + 1);
+
+    // Safety: The ID is valid, and we don't violate the object safety because
+    // the kernel rejects the following operations
+    let service_task = unsafe { StaticTask::<System<Traits>>::from_id(id) };
+    assert_eq!(service_task.activate(), Err(ActivateTaskError::NoAccess));
+    assert_eq!(service_task.suspend(), Err(SuspendTaskError::NoAccess));
+    assert_eq!(
+        unsafe { service_task.terminate() },
+        Err(TerminateTaskError::NoAccess)
+    );
+
+    D::success();
+}
+
+fn timer_body<Traits: SupportedSystemTraits, D: Driver<App<System<Traits>>>>() {
+    let app = D::app();
+    assert_eq!(app.seq.swap(2, Ordering::Relaxed), 1);
+
+    let current = LocalTask::<System<Traits>>::current().unwrap();
+    assert_eq!(current.activate(), Err(ActivateTaskError::NoAccess));
+    assert_eq!(current.suspend(), Err(SuspendTaskError::NoAccess));
+    assert_eq!(
+        unsafe { current.terminate() },
+        Err(TerminateTaskError::NoAccess)
+    );
+
+    app.service_task_id
+        .store(current.id().get(), Ordering::Relaxed);
+}
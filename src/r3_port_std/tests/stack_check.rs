@@ -0,0 +1,168 @@
+//! Checks the kernel's stack overflow detection and stack high-water mark
+//! measurement (the `stack_check` feature).
+//!
+//! Tasks don't run on the stacks allocated by the kernel in this port, so
+//! `task1` writes to `task2`'s stack region to simulate stack usage.
+//!
+//! 1. `task1` writes to the top of `task2`'s stack and checks the high-water
+//!    mark.
+//! 2. `task1` overwrites the canary of `task2`'s stack and activates `task2`.
+//! 3. `task2` parks itself, and the kernel calls the stack overflow hook as
+//!    `task2` leaves the processor.
+//! 4. `task1` unparks `task2`. `task2` exits, and the kernel calls the stack
+//!    overflow hook again.
+//! 5. `task1` reports stack pointers through
+//!    [`PortToKernel::check_stack_pointer`] like the dispatchers of the ports
+//!    running tasks on the kernel's stacks do. The kernel calls the stack
+//!    overflow hook for the ones outside `task1`'s stack or inside its canary.
+//! 6. `task1` checks the hook calls and shuts down the system.
+#![feature(const_refs_to_cell)]
+#![feature(const_trait_impl)]
+#![feature(const_mut_refs)]
+#![feature(slice_ptr_get)]
+#![feature(slice_ptr_len)]
+
+extern crate r3_core_ks as r3_core;
+
+use r3_core::kernel::{prelude::*, StaticTask};
+use r3_kernel::{Id, KernelCfg2, PortToKernel};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+type System = r3_kernel::System<SystemTraits>;
+r3_port_std::use_port!(unsafe struct SystemTraits);
+
+struct Objects {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+}
+
+const STACK_SIZE: usize = 1024;
+const USED_LEN: usize = 256;
+/// The canary size used by the kernel
+const CANARY_LEN: usize = 16;
+
+static COTTAGE: Objects = r3_kernel::build!(SystemTraits, configure_app => Objects);
+
+const fn configure_app(b: &mut r3_kernel::Cfg<'_, SystemTraits>) -> Objects {
+    b.num_task_priority_levels(4);
+    b.raw().stack_overflow_hook(stack_overflow_hook);
+
+    let task1 = StaticTask::define()
+        .start(task1_body)
+        .priority(2)
+        .stack_size(STACK_SIZE)
+        .active(true)
+        .finish(b);
+    let task2 = StaticTask::define()
+        .start(task2_body)
+        .priority(1)
+        .stack_size(STACK_SIZE)
+        .finish(b);
+
+    Objects { task1, task2 }
+}
+
+/// The tasks reported by [`stack_overflow_hook`]
+static OVERFLOWED_TASKS: Mutex<Vec<Id>> = Mutex::new(Vec::new());
+
+static DONE: AtomicBool = AtomicBool::new(false);
+
+fn stack_overflow_hook(task: StaticTask<System>) {
+    OVERFLOWED_TASKS.lock().unwrap().push(task.id());
+}
+
+fn task1_body() {
+    let stack = SystemTraits::get_task_cb(COTTAGE.task2.id().get() - 1)
+        .unwrap()
+        .attr
+        .stack
+        .as_ptr();
+    assert_eq!(stack.len(), STACK_SIZE);
+    let stack = stack.as_mut_ptr();
+
+    // `task2`'s stack is intact. `task1` runs on a host thread's stack, so
+    // its high-water mark is zero even though it's running.
+    assert_eq!(COTTAGE.task2.stack_high_water_mark(), Ok(0));
+    assert_eq!(COTTAGE.task1.stack_high_water_mark(), Ok(0));
+
+    // The stack grows downward from the end of the region
+    // Safety: `task2` hasn't run, so no one is using its stack
+    unsafe { stack.add(STACK_SIZE - USED_LEN).write_bytes(0, USED_LEN) };
+    assert_eq!(COTTAGE.task2.stack_high_water_mark(), Ok(USED_LEN));
+
+    // Overwrite the canary at the far end
+    // Safety: ditto
+    unsafe { stack.write(0) };
+    assert_eq!(COTTAGE.task2.stack_high_water_mark(), Ok(STACK_SIZE));
+    assert!(OVERFLOWED_TASKS.lock().unwrap().is_empty());
+
+    // `task2` preempts `task1` and parks itself
+    COTTAGE.task2.activate().unwrap();
+    assert_eq!(*OVERFLOWED_TASKS.lock().unwrap(), [COTTAGE.task2.id()]);
+
+    // `task2` preempts `task1` and exits
+    COTTAGE.task2.unpark_exact().unwrap();
+    assert_eq!(
+        *OVERFLOWED_TASKS.lock().unwrap(),
+        [COTTAGE.task2.id(), COTTAGE.task2.id()]
+    );
+
+    OVERFLOWED_TASKS.lock().unwrap().clear();
+    check_stack_pointers();
+
+    DONE.store(true, Ordering::Relaxed);
+    r3_port_std::shutdown::<SystemTraits>();
+}
+
+fn check_stack_pointers() {
+    let stack = SystemTraits::get_task_cb(COTTAGE.task1.id().get() - 1)
+        .unwrap()
+        .attr
+        .stack
+        .as_ptr();
+    let start = stack.as_mut_ptr() as usize;
+    let end = start + stack.len();
+
+    let check = |sp: usize| {
+        System::acquire_cpu_lock().unwrap();
+        // Safety: CPU Lock active
+        unsafe { SystemTraits::check_stack_pointer(sp) };
+        unsafe { System::release_cpu_lock().unwrap() };
+        OVERFLOWED_TASKS.lock().unwrap().len()
+    };
+
+    // Inside the stack
+    assert_eq!(check(end), 0);
+    assert_eq!(check(end - USED_LEN), 0);
+    assert_eq!(check(start + CANARY_LEN), 0);
+
+    // Inside the canary
+    assert_eq!(check(start + CANARY_LEN - 1), 1);
+    assert_eq!(check(start), 2);
+
+    // Outside the stack
+    assert_eq!(check(start - 64), 3);
+    assert_eq!(check(end + 4), 4);
+
+    assert!(OVERFLOWED_TASKS
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|&id| id == COTTAGE.task1.id()));
+}
+
+fn task2_body() {
+    System::park().unwrap();
+}
+
+#[test]
+fn stack_check() {
+    let _ = env_logger::try_init();
+
+    port_std_impl::PORT_STATE.port_boot::<SystemTraits>();
+
+    assert!(DONE.load(Ordering::Relaxed));
+}
@@ -49,25 +49,33 @@ impl KernelTestUtil {
 mod kernel_tests {
     pub mod external_interrupt;
     pub mod interrupt_table_sparsity;
+    pub mod introspect;
     pub mod stack_align;
+    pub mod task_ready_queue_edf;
+    pub mod timer_overrun;
+    pub mod timer_service_task;
 }
 
 macro_rules! instantiate_kernel_tests {
-    ( $( { $($tt:tt)* }, )* ) => {
+    ( $( { path: $path:path, name_ident: $name_ident:ident, $($rest:tt)* }, )* ) => {
         instantiate_kernel_tests!(
             @inner
 
-            $( { $($tt)* }, )*
+            $( { path: $path, name_ident: $name_ident, task_ready_queue: BitmapQueue, }, )*
 
             // Port-specific tests, which cover `r3` and `r3_kernel` as
             // well as `r3_port_std`
-            { path: crate::kernel_tests::external_interrupt, name_ident: external_interrupt, },
-            { path: crate::kernel_tests::interrupt_table_sparsity, name_ident: interrupt_table_sparsity, },
-            { path: crate::kernel_tests::stack_align, name_ident: stack_align, },
+            { path: crate::kernel_tests::external_interrupt, name_ident: external_interrupt, task_ready_queue: BitmapQueue, },
+            { path: crate::kernel_tests::interrupt_table_sparsity, name_ident: interrupt_table_sparsity, task_ready_queue: BitmapQueue, },
+            { path: crate::kernel_tests::introspect, name_ident: introspect, task_ready_queue: BitmapQueue, },
+            { path: crate::kernel_tests::stack_align, name_ident: stack_align, task_ready_queue: BitmapQueue, },
+            { path: crate::kernel_tests::task_ready_queue_edf, name_ident: task_ready_queue_edf, task_ready_queue: EdfQueue, },
+            { path: crate::kernel_tests::timer_overrun, name_ident: timer_overrun, task_ready_queue: BitmapQueue, },
+            { path: crate::kernel_tests::timer_service_task, name_ident: timer_service_task, task_ready_queue: BitmapQueue, },
         );
     };
     ( @inner $(
-        { path: $path:path, name_ident: $name_ident:ident, $($rest:tt)* },
+        { path: $path:path, name_ident: $name_ident:ident, task_ready_queue: $TaskReadyQueue:ident, },
     )*) => {$(
         mod $name_ident {
             use r3_core::kernel::{InterruptNum, InterruptPriority};
@@ -98,10 +106,14 @@ macro_rules! instantiate_kernel_tests {
                 const INTERRUPT_LINES: &'static [InterruptNum] = &[0, 1, 2, 3];
                 const INTERRUPT_PRIORITIES: &'static [InterruptPriority] = &[0, 4];
                 const TIME_USER_HEADROOM_IS_EXACT: bool = true;
+                const TASKS_USE_KERNEL_STACKS: bool = false;
             }
 
             static COTTAGE: test_case::App<System> =
-                r3_kernel::build!(SystemTraits, test_case::App::new::<_, Driver> => test_case::App<System>);
+                r3_kernel::build!(
+                    SystemTraits, test_case::App::new::<_, Driver> => test_case::App<System>,
+                    task_ready_queue = $TaskReadyQueue
+                );
 
             #[test]
             fn run() {
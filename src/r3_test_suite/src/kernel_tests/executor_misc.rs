@@ -0,0 +1,211 @@
+//! Checks miscellaneous properties of [`r3::executor`].
+//!
+//! 1. (`seq`: 0 → 1 → 2) `task1` spawns `worker`, which starts waiting for
+//!    `sem`.
+//! 2. (`seq`: 2 → 3 → 4) `task1` signals `sem`. `worker` starts waiting for
+//!    `eg`.
+//! 3. (`seq`: 4 → 5 → 6) `task1` sets the bits of `eg` in two steps. `worker`
+//!    starts waiting for `signal`.
+//! 4. (`seq`: 6 → 7 → 8) An interrupt handler sends `signal`. `worker`
+//!    spawns `helper` and starts sleeping.
+//! 5. (`seq`: 8 → 9) `helper` finds the timer busy and completes.
+//! 6. (`seq`: 9 → 10) `task1` exits.
+//! 7. (`seq`: 10 → 11) `worker` wakes up and completes.
+use assert_matches::assert_matches;
+use r3::{
+    executor::{
+        SleepError, SpawnError, StaticAsyncEventGroup, StaticAsyncSemaphore, StaticAsyncTask,
+        StaticAsyncTimer, StaticExecutor, StaticSignal,
+    },
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, EventGroupWaitFlags, InterruptLine, StaticInterruptHandler,
+        StaticTask,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase
+    + traits::KernelEventGroup
+    + traits::KernelInterruptLine
+    + traits::KernelSemaphore
+    + traits::KernelTimer
+    + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelEventGroup
+            + traits::KernelInterruptLine
+            + traits::KernelSemaphore
+            + traits::KernelTimer
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    worker: StaticAsyncTask<System>,
+    helper: StaticAsyncTask<System>,
+    sem: StaticAsyncSemaphore<System>,
+    eg: StaticAsyncEventGroup<System>,
+    timer: StaticAsyncTimer<System>,
+    signal: StaticSignal<System>,
+    int: Option<InterruptLine<System>>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>
+            + ~const traits::CfgEventGroup
+            + ~const traits::CfgInterruptLine
+            + ~const traits::CfgSemaphore
+            + ~const traits::CfgTimer,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let executor = StaticExecutor::define().priority(1).finish(b);
+        let worker = StaticAsyncTask::define()
+            .executor(executor)
+            .start(worker_body::<System, D>)
+            .finish(b);
+        let helper = StaticAsyncTask::define()
+            .executor(executor)
+            .start(helper_body::<System, D>)
+            .finish(b);
+
+        let sem = StaticAsyncSemaphore::define()
+            .initial(0)
+            .maximum(1)
+            .finish(b);
+        let eg = StaticAsyncEventGroup::define().finish(b);
+        let timer = StaticAsyncTimer::define().finish(b);
+        let signal = StaticSignal::define().finish(b);
+
+        let int = if let (&[int_line, ..], &[int_pri, ..]) =
+            (D::INTERRUPT_LINES, D::INTERRUPT_PRIORITIES)
+        {
+            StaticInterruptHandler::define()
+                .line(int_line)
+                .start(isr::<System, D>)
+                .finish(b);
+
+            Some(
+                InterruptLine::define()
+                    .line(int_line)
+                    .enabled(true)
+                    .priority(int_pri)
+                    .finish(b),
+            )
+        } else {
+            None
+        };
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            worker,
+            helper,
+            sem,
+            eg,
+            timer,
+            signal,
+            int,
+            seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    // CPU Lock is not allowed
+    System::acquire_cpu_lock().unwrap();
+    assert_matches!(app.worker.spawn(), Err(SpawnError::BadContext));
+    assert!(!app.worker.is_running());
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // The executor task preempts this task
+    app.worker.spawn().unwrap();
+    assert!(app.worker.is_running());
+    assert_matches!(app.worker.spawn(), Err(SpawnError::AlreadySpawned));
+
+    app.seq.expect_and_replace(2, 3);
+    app.sem.signal_one().unwrap();
+
+    // `worker` waits for both bits
+    app.eg.set(0b001).unwrap();
+    app.seq.expect_and_replace(4, 5);
+    app.eg.set(0b110).unwrap();
+
+    if let Some(int) = app.int {
+        int.pend().unwrap();
+    } else {
+        log::warn!("No interrupt lines defined, skipping a portion of the test");
+        app.seq.expect_and_replace(6, 7);
+        app.signal.signal().unwrap();
+    }
+
+    app.seq.expect_and_replace(9, 10);
+}
+
+async fn worker_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+
+    app.sem.wait_one().await.unwrap();
+    app.seq.expect_and_replace(3, 4);
+
+    let bits = app
+        .eg
+        .wait(0b011, EventGroupWaitFlags::ALL | EventGroupWaitFlags::CLEAR)
+        .await
+        .unwrap();
+    assert_eq!(bits, 0b111);
+    assert_eq!(app.eg.event_group().get().unwrap(), 0b100);
+    app.seq.expect_and_replace(5, 6);
+
+    app.signal.wait().await;
+    app.seq.expect_and_replace(7, 8);
+
+    app.helper.spawn().unwrap();
+    assert_matches!(
+        app.timer.sleep(Duration::from_millis(-1)).await,
+        Err(SleepError::BadParam)
+    );
+    app.timer.sleep(Duration::from_millis(100)).await.unwrap();
+    app.seq.expect_and_replace(10, 11);
+
+    assert!(!app.helper.is_running());
+
+    D::success();
+}
+
+async fn helper_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(8, 9);
+
+    // `worker` is sleeping on the timer
+    assert_matches!(
+        app.timer.sleep(Duration::from_millis(10)).await,
+        Err(SleepError::Busy)
+    );
+}
+
+fn isr<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(6, 7);
+
+    app.signal.signal().unwrap();
+}
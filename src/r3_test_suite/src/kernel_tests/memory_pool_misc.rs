@@ -0,0 +1,154 @@
+//! Validates error codes returned by memory pool manipulation methods. Also,
+//! checks miscellaneous properties of `StaticMemoryPool`.
+use r3::{
+    kernel::{prelude::*, raw, traits, Cfg, MemoryPoolBlock, StaticMemoryPool, StaticTask},
+    time::Duration,
+};
+use wyhash::WyHash;
+
+use super::Driver;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMemoryPool + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMemoryPool + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mp1: StaticMemoryPool<System, [u32; 4], 3>,
+    mp2: StaticMemoryPool<System, [u32; 4], 3>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMemoryPool,
+    {
+        StaticTask::define()
+            .start(task_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let mp1 = StaticMemoryPool::define().finish(b);
+        let mp2 = StaticMemoryPool::define().finish(b);
+
+        App { mp1, mp2 }
+    }
+}
+
+fn task_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    // `PartialEq`
+    let app = D::app();
+    assert_ne!(app.mp1, app.mp2);
+    assert_eq!(app.mp1, app.mp1);
+    assert_eq!(app.mp2, app.mp2);
+
+    // `Hash`
+    let hash = |x: StaticMemoryPool<System, [u32; 4], 3>| {
+        use core::hash::{Hash, Hasher};
+        let mut hasher = WyHash::with_seed(42);
+        x.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(app.mp1), hash(app.mp1));
+    assert_eq!(hash(app.mp2), hash(app.mp2));
+
+    // Invalid memory pool ID
+    if let Some(bad_id) = D::bad_raw_memory_pool_id() {
+        assert_eq!(
+            unsafe { <System as raw::KernelMemoryPool>::raw_memory_pool_try_get(bad_id) },
+            Err(r3::kernel::TryGetMemoryPoolError::NoAccess)
+        );
+    }
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(
+        app.mp1.get().err(),
+        Some(r3::kernel::GetMemoryPoolError::BadContext)
+    );
+    assert_eq!(
+        app.mp1.get_timeout(Duration::ZERO).err(),
+        Some(r3::kernel::GetMemoryPoolTimeoutError::BadContext)
+    );
+    assert_eq!(
+        app.mp1.try_get().err(),
+        Some(r3::kernel::TryGetMemoryPoolError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // Negative timeout
+    assert_eq!(
+        app.mp1.get_timeout(Duration::from_micros(-1)).err(),
+        Some(r3::kernel::GetMemoryPoolTimeoutError::BadParam)
+    );
+
+    // Get all blocks. The blocks are initialized with `Init::INIT`.
+    let mut block0 = app.mp1.get().unwrap();
+    let mut block1 = app.mp1.get_timeout(Duration::ZERO).unwrap();
+    let mut block2 = app.mp1.try_get().unwrap();
+    assert_eq!(*block0, [0; 4]);
+    assert_eq!(*block1, [0; 4]);
+    assert_eq!(*block2, [0; 4]);
+    *block0 = [1; 4];
+    *block1 = [2; 4];
+    *block2 = [3; 4];
+
+    let mut indices = [
+        MemoryPoolBlock::index(&block0),
+        MemoryPoolBlock::index(&block1),
+        MemoryPoolBlock::index(&block2),
+    ];
+    indices.sort();
+    assert_eq!(indices, [0, 1, 2]);
+    assert_eq!(MemoryPoolBlock::pool(&block0), app.mp1);
+
+    // All blocks are in use
+    assert_eq!(
+        app.mp1.try_get().err(),
+        Some(r3::kernel::TryGetMemoryPoolError::Timeout)
+    );
+    assert_eq!(
+        app.mp1.get_timeout(Duration::ZERO).err(),
+        Some(r3::kernel::GetMemoryPoolTimeoutError::Timeout)
+    );
+
+    // The memory pools are independent of each other
+    let block3 = app.mp2.try_get().unwrap();
+    assert_eq!(*block3, [0; 4]);
+    drop(block3);
+
+    // Return a block. The next allocation reuses it, and the block retains the
+    // value left by the previous owner.
+    let index1 = MemoryPoolBlock::index(&block1);
+    drop(block1);
+    let block1 = app.mp1.try_get().unwrap();
+    assert_eq!(MemoryPoolBlock::index(&block1), index1);
+    assert_eq!(*block1, [2; 4]);
+
+    // `into_raw` and `from_raw`
+    let index2 = MemoryPoolBlock::into_raw(block2);
+    assert_eq!(
+        app.mp1.try_get().err(),
+        Some(r3::kernel::TryGetMemoryPoolError::Timeout)
+    );
+    let block2 = unsafe { MemoryPoolBlock::from_raw(app.mp1, index2) };
+    assert_eq!(*block2, [3; 4]);
+
+    drop((block0, block1, block2));
+
+    // Returning a block that is not in use
+    assert_eq!(
+        unsafe { <System as raw::KernelMemoryPool>::raw_memory_pool_release(app.mp1.id(), 0) },
+        Err(r3::kernel::ReleaseMemoryPoolError::BadParam)
+    );
+    assert_eq!(
+        unsafe { <System as raw::KernelMemoryPool>::raw_memory_pool_release(app.mp1.id(), 3) },
+        Err(r3::kernel::ReleaseMemoryPoolError::BadParam)
+    );
+
+    D::success();
+}
@@ -0,0 +1,87 @@
+//! Returns a block to a memory pool, waking up a task waiting for a block.
+//!
+//! 1. (`seq`: 0 → 1) `task0` gets all blocks in the memory pool and activates
+//!    `task1`.
+//! 2. (`seq`: 1 → 2) `task1` starts waiting for a block.
+//! 3. (`seq`: 2 → 3) `task0` writes a value to a block and returns it, waking
+//!    up `task1`.
+//! 4. (`seq`: 3 → 4) `task1` receives the block containing the value. `task1`
+//!    exits, returning the block.
+//! 5. (`seq`: 4 → 5) `task0` gets the block again.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, MemoryPoolBlock, StaticMemoryPool, StaticTask},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMemoryPool + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMemoryPool + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mp: StaticMemoryPool<System, u64, 2>,
+    task1: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMemoryPool,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let mp = StaticMemoryPool::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { mp, task1, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mp, task1, seq } = D::app();
+
+    seq.expect_and_replace(0, 1);
+    let _block0 = mp.get().unwrap();
+    let mut block1 = mp.get().unwrap();
+    let index1 = MemoryPoolBlock::index(&block1);
+
+    task1.activate().unwrap(); // switching to `task1`
+
+    seq.expect_and_replace(2, 3);
+    *block1 = 0x1234_5678_9abc_def0;
+    drop(block1); // wakes up `task1`
+
+    seq.expect_and_replace(4, 5);
+    let block1 = mp.try_get().unwrap();
+    assert_eq!(MemoryPoolBlock::index(&block1), index1);
+    assert_eq!(*block1, 0x1234_5678_9abc_def0);
+
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mp, seq, .. } = D::app();
+
+    seq.expect_and_replace(1, 2);
+    let block = mp.get().unwrap(); // start waiting, switching to `task0`
+
+    seq.expect_and_replace(3, 4);
+    assert_eq!(*block, 0x1234_5678_9abc_def0);
+}
@@ -0,0 +1,99 @@
+//! A task waits for a block with timeout.
+//!
+//! 1. (`seq`: 0 → 1, 0ms) `task0` gets the only block in the memory pool and
+//!    activates `task1`.
+//! 2. (`seq`: 1 → 2, 0ms) `task1` starts waiting for a block.
+//! 3. (`seq`: 2 → 3, 0ms) `task0` starts sleeping, which will last for 300
+//!    milliseconds.
+//! 4. (`seq`: 3 → 4, 200ms) `task1` wakes up, seeing that the wait operation
+//!    timed out. `task1` again starts waiting for a block.
+//! 5. (`seq`: 4 → 5, 300ms) `task0` wakes up and returns the block.
+//! 6. (`seq`: 5 → 6, 300ms) `task1` wakes up and preempts `task0`, seeing that
+//!    the wait operation was successful. `task1` exits.
+//! 7. (`seq`: 6 → 7, 300ms) `task0` starts running.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, GetMemoryPoolTimeoutError, StaticMemoryPool, StaticTask},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMemoryPool + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMemoryPool + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mp: StaticMemoryPool<System, u32, 1>,
+    task1: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMemoryPool,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let mp = StaticMemoryPool::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { mp, task1, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mp, task1, seq } = D::app();
+
+    seq.expect_and_replace(0, 1);
+    let block = mp.get().unwrap();
+    task1.activate().unwrap(); // switching to `task1`
+
+    seq.expect_and_replace(2, 3);
+    System::sleep(Duration::from_millis(300)).unwrap();
+    // `task0` goes into sleep. `task1` wakes up first.
+    // `task0` follows:
+    seq.expect_and_replace(4, 5);
+    drop(block);
+    // preempted by `task1`, which we just woke up
+
+    // back from `task1`
+    seq.expect_and_replace(6, 7);
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mp, seq, .. } = D::app();
+
+    seq.expect_and_replace(1, 2);
+
+    assert_eq!(
+        // start waiting, switching to `task0`
+        mp.get_timeout(Duration::from_millis(200)).err(),
+        // ... the control is returned on timeout
+        Some(GetMemoryPoolTimeoutError::Timeout),
+    );
+
+    seq.expect_and_replace(3, 4);
+
+    // start waiting. wakes up when `task0` returns the block
+    let _block = mp.get_timeout(Duration::from_millis(200)).unwrap();
+
+    seq.expect_and_replace(5, 6);
+}
@@ -0,0 +1,125 @@
+//! Sends a message to a message queue in an interrupt handler, waking up a
+//! task.
+use r3::{
+    hunk::Hunk,
+    kernel::{traits, Cfg, InterruptLine, StaticInterruptHandler, StaticMessageQueue, StaticTask},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMessageQueue + traits::KernelInterruptLine + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelMessageQueue
+            + traits::KernelInterruptLine
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    int: Option<InterruptLine<System>>,
+    mq: StaticMessageQueue<System, u32>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>
+            + ~const traits::CfgMessageQueue
+            + ~const traits::CfgInterruptLine,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+
+        let mq = StaticMessageQueue::define().capacity(1).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        let int = if let (&[int_line, ..], &[int_pri, ..]) =
+            (D::INTERRUPT_LINES, D::INTERRUPT_PRIORITIES)
+        {
+            StaticInterruptHandler::define()
+                .line(int_line)
+                .start(isr::<System, D>)
+                .finish(b);
+
+            Some(
+                InterruptLine::define()
+                    .line(int_line)
+                    .enabled(true)
+                    .priority(int_pri)
+                    .finish(b),
+            )
+        } else {
+            None
+        };
+
+        App { mq, seq, int }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(1, 2);
+
+    let Some(int) = D::app().int
+    else {
+        log::warn!("No interrupt lines defined, skipping the test");
+        D::success();
+        return;
+    };
+
+    int.pend().unwrap();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(0, 1);
+
+    // start waiting, switching to `task1`
+    assert_eq!(D::app().mq.receive(), Ok(42));
+
+    D::app().seq.expect_and_replace(3, 4);
+
+    // The message sent after the first one is stored in the message queue
+    assert_eq!(D::app().mq.try_receive(), Ok(43));
+
+    D::success();
+}
+
+fn isr<System: SupportedSystem, D: Driver<App<System>>>() {
+    let mq = D::app().mq;
+
+    D::app().seq.expect_and_replace(2, 3);
+
+    assert_eq!(
+        mq.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::Timeout)
+    );
+    assert_eq!(
+        mq.receive(),
+        Err(r3::kernel::ReceiveMessageQueueError::BadContext)
+    );
+    assert_eq!(
+        mq.send(0),
+        Err(r3::kernel::SendMessageQueueError::BadContext)
+    );
+
+    mq.try_send(42).unwrap(); // wakes up `task2`
+    mq.try_send(43).unwrap();
+    assert_eq!(
+        mq.try_send(44),
+        Err(r3::kernel::TrySendMessageQueueError::Timeout)
+    );
+}
@@ -0,0 +1,157 @@
+//! Validates error codes returned by message queue manipulation methods. Also,
+//! checks miscellaneous properties of `MessageQueue`.
+use r3::{
+    kernel::{prelude::*, traits, Cfg, MessageQueueRef, StaticMessageQueue, StaticTask},
+    time::Duration,
+};
+use wyhash::WyHash;
+
+use super::Driver;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMessageQueue {}
+impl<T: traits::KernelBase + traits::KernelMessageQueue> SupportedSystem for T {}
+
+type Message = (u16, u64);
+
+pub struct App<System: SupportedSystem> {
+    mq1: StaticMessageQueue<System, Message>,
+    mq2: StaticMessageQueue<System, Message>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMessageQueue,
+    {
+        StaticTask::define()
+            .start(task_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let mq1 = StaticMessageQueue::define().capacity(1).finish(b);
+        let mq2 = StaticMessageQueue::define().capacity(3).finish(b);
+
+        App { mq1, mq2 }
+    }
+}
+
+fn task_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    // `PartialEq`
+    let app = D::app();
+    assert_ne!(app.mq1, app.mq2);
+    assert_eq!(app.mq1, app.mq1);
+    assert_eq!(app.mq2, app.mq2);
+
+    // `Hash`
+    let hash = |x: MessageQueueRef<'_, System, Message>| {
+        use core::hash::{Hash, Hasher};
+        let mut hasher = WyHash::with_seed(42);
+        x.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(app.mq1), hash(app.mq1));
+    assert_eq!(hash(app.mq2), hash(app.mq2));
+
+    // Invalid message queue ID
+    if let Some(bad_id) = D::bad_raw_message_queue_id() {
+        let bad_mq: MessageQueueRef<'_, System, Message> =
+            unsafe { MessageQueueRef::from_id(bad_id) };
+        assert_eq!(
+            bad_mq.try_send((0, 0)),
+            Err(r3::kernel::TrySendMessageQueueError::NoAccess)
+        );
+        assert_eq!(
+            bad_mq.try_receive(),
+            Err(r3::kernel::TryReceiveMessageQueueError::NoAccess)
+        );
+    }
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(
+        app.mq1.send((0, 0)),
+        Err(r3::kernel::SendMessageQueueError::BadContext)
+    );
+    assert_eq!(
+        app.mq1.send_timeout((0, 0), Duration::ZERO),
+        Err(r3::kernel::SendMessageQueueTimeoutError::BadContext)
+    );
+    assert_eq!(
+        app.mq1.try_send((0, 0)),
+        Err(r3::kernel::TrySendMessageQueueError::BadContext)
+    );
+    assert_eq!(
+        app.mq1.receive(),
+        Err(r3::kernel::ReceiveMessageQueueError::BadContext)
+    );
+    assert_eq!(
+        app.mq1.receive_timeout(Duration::ZERO),
+        Err(r3::kernel::ReceiveMessageQueueTimeoutError::BadContext)
+    );
+    assert_eq!(
+        app.mq1.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // Negative timeout
+    assert_eq!(
+        app.mq1.send_timeout((0, 0), Duration::from_micros(-1)),
+        Err(r3::kernel::SendMessageQueueTimeoutError::BadParam)
+    );
+    assert_eq!(
+        app.mq1.receive_timeout(Duration::from_micros(-1)),
+        Err(r3::kernel::ReceiveMessageQueueTimeoutError::BadParam)
+    );
+
+    // Empty
+    assert_eq!(
+        app.mq2.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::Timeout)
+    );
+    assert_eq!(
+        app.mq2.receive_timeout(Duration::ZERO),
+        Err(r3::kernel::ReceiveMessageQueueTimeoutError::Timeout)
+    );
+
+    // Fill the message queue, wrapping around the ring buffer
+    app.mq2.try_send((1, 0x1111_1111_1111_1111)).unwrap();
+    app.mq2.send((2, 0x2222_2222_2222_2222)).unwrap();
+    assert_eq!(app.mq2.try_receive(), Ok((1, 0x1111_1111_1111_1111)));
+    app.mq2
+        .send_timeout((3, 0x3333_3333_3333_3333), Duration::ZERO)
+        .unwrap();
+    app.mq2.try_send((4, 0x4444_4444_4444_4444)).unwrap();
+
+    // Full
+    assert_eq!(
+        app.mq2.try_send((5, 0)),
+        Err(r3::kernel::TrySendMessageQueueError::Timeout)
+    );
+    assert_eq!(
+        app.mq2.send_timeout((5, 0), Duration::ZERO),
+        Err(r3::kernel::SendMessageQueueTimeoutError::Timeout)
+    );
+
+    // The messages are received in a FIFO order
+    assert_eq!(app.mq2.receive(), Ok((2, 0x2222_2222_2222_2222)));
+    assert_eq!(
+        app.mq2.receive_timeout(Duration::ZERO),
+        Ok((3, 0x3333_3333_3333_3333))
+    );
+    assert_eq!(app.mq2.try_receive(), Ok((4, 0x4444_4444_4444_4444)));
+    assert_eq!(
+        app.mq2.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::Timeout)
+    );
+
+    // The message queues are independent of each other
+    app.mq1.try_send((6, 6)).unwrap();
+    assert_eq!(
+        app.mq2.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::Timeout)
+    );
+    assert_eq!(app.mq1.try_receive(), Ok((6, 6)));
+
+    D::success();
+}
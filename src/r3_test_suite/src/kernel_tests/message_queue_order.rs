@@ -0,0 +1,138 @@
+//! Receives messages from a full message queue, waking up multiple blocked
+//! senders in a task priority order.
+//!
+//! 1. (`seq`: 0 → 1) `task0` fills the message queue and activates `task[1-4]`
+//!    in a particular order.
+//! 2. (`seq`: 1 → 5) `task[1-4]` start waiting for the message queue to have a
+//!    vacant slot.
+//! 3. (`seq`: 5 → 9) `task0` receives a message for four times. `task[1-4]`
+//!    should be unblocked in a task priority order, and their messages should
+//!    be enqueued in the same order.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, QueueOrder, StaticMessageQueue, StaticTask},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mq: StaticMessageQueue<System, usize>,
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    task3: StaticTask<System>,
+    task4: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMessageQueue,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(3)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task3 = StaticTask::define()
+            .start(task3_body::<System, D>)
+            .priority(2)
+            .finish(b);
+        let task4 = StaticTask::define()
+            .start(task4_body::<System, D>)
+            .priority(2)
+            .finish(b);
+
+        let mq = StaticMessageQueue::define()
+            .capacity(1)
+            .queue_order(QueueOrder::TaskPriority)
+            .finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            mq,
+            task1,
+            task2,
+            task3,
+            task4,
+            seq,
+        }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mq, seq, .. } = D::app();
+
+    seq.expect_and_replace(0, 1);
+
+    mq.try_send(0).unwrap();
+
+    D::app().task3.activate().unwrap(); // [3]
+    D::app().task1.activate().unwrap(); // [1, 3]
+    D::app().task2.activate().unwrap(); // [1, 2, 3]
+    D::app().task4.activate().unwrap(); // [1, 2, 3, 4]
+
+    assert_eq!(mq.receive(), Ok(0)); // unblocks `task1`
+    assert_eq!(mq.receive(), Ok(1)); // unblocks `task2`
+    assert_eq!(mq.receive(), Ok(2)); // unblocks `task3`
+    assert_eq!(mq.receive(), Ok(3)); // unblocks `task4`
+    assert_eq!(mq.receive(), Ok(4));
+
+    seq.expect_and_replace(9, 10);
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(2, 3);
+
+    D::app().mq.send(1).unwrap(); // start waiting, switching to `task0`
+
+    D::app().seq.expect_and_replace(5, 6);
+    // return the control to `task0`
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(3, 4);
+
+    D::app().mq.send(2).unwrap(); // start waiting, switching to `task0`
+
+    D::app().seq.expect_and_replace(6, 7);
+    // return the control to `task0`
+}
+
+fn task3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(1, 2);
+
+    D::app().mq.send(3).unwrap(); // start waiting, switching to `task0`
+
+    D::app().seq.expect_and_replace(7, 8);
+    // return the control to `task0`
+}
+
+fn task4_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(4, 5);
+
+    D::app().mq.send(4).unwrap(); // start waiting, switching to `task0`
+
+    D::app().seq.expect_and_replace(8, 9);
+    // return the control to `task0`
+}
@@ -0,0 +1,92 @@
+//! Sends and receives messages through a message queue, waking up blocked
+//! senders and receivers.
+//!
+//! 1. (`seq`: 0 → 1) `task1` starts waiting for a message.
+//! 2. (`seq`: 1 → 2) `task0` sends a message, waking up `task1`.
+//! 3. (`seq`: 2 → 3) `task1` receives the message. `task1` sends three
+//!    messages. The third one blocks `task1` because the message queue is
+//!    full.
+//! 4. (`seq`: 3 → 4) `task0` receives a message, waking up `task1`.
+//! 5. (`seq`: 4 → 5) `task1` exits.
+//! 6. (`seq`: 5 → 6) `task0` receives the remaining messages.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{traits, Cfg, StaticMessageQueue, StaticTask},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mq: StaticMessageQueue<System, u32>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMessageQueue,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+
+        let mq = StaticMessageQueue::define().capacity(2).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { mq, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mq, seq } = D::app();
+
+    seq.expect_and_replace(1, 2);
+    mq.send(42).unwrap(); // wakes up `task1`
+
+    seq.expect_and_replace(3, 4);
+    // The message queue contains `[1, 2]`, and `task1` is blocked in
+    // `send(3)`. Receiving a message makes room for `3` and wakes up `task1`.
+    assert_eq!(mq.receive(), Ok(1));
+
+    seq.expect_and_replace(5, 6);
+    assert_eq!(mq.try_receive(), Ok(2));
+    assert_eq!(mq.try_receive(), Ok(3));
+    assert_eq!(
+        mq.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::Timeout)
+    );
+
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mq, seq } = D::app();
+
+    seq.expect_and_replace(0, 1);
+    assert_eq!(mq.receive(), Ok(42)); // start waiting, switching to `task0`
+
+    seq.expect_and_replace(2, 3);
+    mq.send(1).unwrap();
+    mq.send(2).unwrap();
+    mq.send(3).unwrap(); // start waiting, switching to `task0`
+
+    seq.expect_and_replace(4, 5);
+}
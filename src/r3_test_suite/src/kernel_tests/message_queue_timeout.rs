@@ -0,0 +1,116 @@
+//! A task sends and receives messages with timeout.
+//!
+//! 1. (`seq`: 0 → 1, 0ms) `task1` starts waiting for a message.
+//! 2. (`seq`: 1 → 2, 0ms) `task0` starts sleeping, which will last for 300
+//!    milliseconds.
+//! 3. (`seq`: 2 → 3, 200ms) `task1` wakes up, seeing that the wait operation
+//!    timed out. `task1` fills the message queue and starts waiting for the
+//!    message queue to have a vacant slot.
+//! 4. (`seq`: 3 → 4, 300ms) `task0` wakes up and receives a message.
+//! 5. (`seq`: 4 → 5, 300ms) `task1` wakes up and preempts `task0`, seeing that
+//!    the send operation was successful. `task1` again starts waiting for the
+//!    message queue to have a vacant slot. `task0` starts sleeping, which
+//!    will last for 300 milliseconds.
+//! 6. (`seq`: 5 → 6, 500ms) `task1` wakes up, seeing that the wait operation
+//!    timed out. `task1` exits.
+//! 7. (`seq`: 6 → 7, 600ms) `task0` wakes up and receives a message.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, ReceiveMessageQueueTimeoutError, SendMessageQueueTimeoutError,
+        StaticMessageQueue, StaticTask,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mq: StaticMessageQueue<System, u16>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMessageQueue,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+
+        let mq = StaticMessageQueue::define().capacity(1).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { mq, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { seq, mq } = D::app();
+
+    seq.expect_and_replace(1, 2);
+    System::sleep(Duration::from_millis(300)).unwrap();
+    // `task0` goes into sleep. `task1` wakes up first.
+    // `task0` follows:
+    seq.expect_and_replace(3, 4);
+    // wakes up `task1`, which preempts `task0`
+    assert_eq!(mq.try_receive(), Ok(1));
+
+    // back from `task1`, which started waiting again
+    System::sleep(Duration::from_millis(300)).unwrap();
+
+    seq.expect_and_replace(6, 7);
+    assert_eq!(mq.try_receive(), Ok(2));
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { seq, mq } = D::app();
+
+    seq.expect_and_replace(0, 1);
+
+    assert_eq!(
+        // start waiting, switching to `task0`
+        mq.receive_timeout(Duration::from_millis(200)),
+        // ... the control is returned on timeout
+        Err(ReceiveMessageQueueTimeoutError::Timeout),
+    );
+
+    seq.expect_and_replace(2, 3);
+
+    // this doesn't block
+    mq.send_timeout(1, Duration::from_millis(200)).unwrap();
+
+    // start waiting. wakes up when `task0` receives a message
+    mq.send_timeout(2, Duration::from_millis(200)).unwrap();
+
+    seq.expect_and_replace(4, 5);
+
+    assert_eq!(
+        // start waiting, switching to `task0`
+        mq.send_timeout(3, Duration::from_millis(200)),
+        // ... the control is returned on timeout
+        Err(SendMessageQueueTimeoutError::Timeout),
+    );
+
+    seq.expect_and_replace(5, 6);
+}
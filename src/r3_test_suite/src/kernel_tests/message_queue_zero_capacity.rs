@@ -0,0 +1,89 @@
+//! Passes messages through a message queue with zero capacity.
+//!
+//! 1. (`seq`: 0 → 1) `task1` starts waiting for a message.
+//! 2. (`seq`: 1 → 2) `task0` sends a message, which is handed over directly to
+//!    `task1`, waking it up.
+//! 3. (`seq`: 2 → 3) `task1` starts waiting for a receiver to take a message.
+//! 4. (`seq`: 3 → 4) `task0` receives the message, waking up `task1`.
+//! 5. (`seq`: 4 → 5) `task1` exits.
+//! 6. (`seq`: 5 → 6) `task0` checks that the message queue can't buffer a
+//!    message.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{traits, Cfg, StaticMessageQueue, StaticTask},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelMessageQueue + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    mq: StaticMessageQueue<System, [u8; 5]>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMessageQueue,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+
+        let mq = StaticMessageQueue::define().capacity(0).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { mq, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mq, seq } = D::app();
+
+    seq.expect_and_replace(1, 2);
+    mq.try_send(*b"hello").unwrap(); // wakes up `task1`
+
+    seq.expect_and_replace(3, 4);
+    assert_eq!(mq.try_receive(), Ok(*b"world")); // wakes up `task1`
+
+    seq.expect_and_replace(5, 6);
+    assert_eq!(
+        mq.try_send(*b"-----"),
+        Err(r3::kernel::TrySendMessageQueueError::Timeout)
+    );
+    assert_eq!(
+        mq.try_receive(),
+        Err(r3::kernel::TryReceiveMessageQueueError::Timeout)
+    );
+
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let App { mq, seq } = D::app();
+
+    seq.expect_and_replace(0, 1);
+    assert_eq!(mq.receive(), Ok(*b"hello")); // start waiting, switching to `task0`
+
+    seq.expect_and_replace(2, 3);
+    mq.send(*b"world").unwrap(); // start waiting, switching to `task0`
+
+    seq.expect_and_replace(4, 5);
+}
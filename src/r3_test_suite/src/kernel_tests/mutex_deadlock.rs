@@ -0,0 +1,116 @@
+//! Checks that locking a mutex fails with `Deadlock` if it would cause a
+//! deadlock.
+//!
+//! 1. (`seq`: 0 → 1) `task3` locks `m0`.
+//! 2. (`seq`: 1 → 2) `task3` activates `task2`. `task2` locks `m1` and starts
+//!    waiting on `m0`.
+//! 3. (`seq`: 2 → 3) `task3` activates `task1`. `task1` locks `m2` and starts
+//!    waiting on `m1`.
+//! 4. (`seq`: 3 → 4) `task3` tries to lock `m2`, which would cause a deadlock
+//!    through `task1` and `task2`. `task3` unlocks `m0`.
+//! 5. (`seq`: 4 → 5) `task2` acquires `m0` and unlocks `m0` and `m1`.
+//! 6. (`seq`: 5 → 6) `task1` acquires `m1` and unlocks `m1` and `m2`.
+//! 7. (`seq`: 6 → 7) `task3` locks `m2` successfully.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, LockMutexError, LockMutexTimeoutError, StaticMutex, StaticTask,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMutex + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    m0: StaticMutex<System>,
+    m1: StaticMutex<System>,
+    m2: StaticMutex<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMutex,
+    {
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(2)
+            .finish(b);
+        StaticTask::define()
+            .start(task3_body::<System, D>)
+            .priority(3)
+            .active(true)
+            .finish(b);
+
+        let m0 = StaticMutex::define().finish(b);
+        let m1 = StaticMutex::define().finish(b);
+        let m2 = StaticMutex::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task1,
+            task2,
+            m0,
+            m1,
+            m2,
+            seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(2, 3);
+    app.m2.lock().unwrap();
+    app.m1.lock().unwrap();
+
+    app.seq.expect_and_replace(5, 6);
+    app.m1.unlock().unwrap();
+    app.m2.unlock().unwrap();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+    app.m1.lock().unwrap();
+    app.m0.lock().unwrap();
+
+    app.seq.expect_and_replace(4, 5);
+    app.m0.unlock().unwrap();
+    app.m1.unlock().unwrap();
+}
+
+fn task3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+    app.m0.lock().unwrap();
+    app.task2.activate().unwrap();
+    app.task1.activate().unwrap();
+
+    // `m2` → `task1` → `m1` → `task2` → `m0` → `task3`
+    app.seq.expect_and_replace(3, 4);
+    assert_eq!(app.m2.lock(), Err(LockMutexError::Deadlock));
+    assert_eq!(
+        app.m2.lock_timeout(Duration::from_millis(100)),
+        Err(LockMutexTimeoutError::Deadlock)
+    );
+    app.m0.unlock().unwrap();
+
+    app.seq.expect_and_replace(6, 7);
+    app.m2.lock().unwrap();
+    app.m2.unlock().unwrap();
+
+    D::success();
+}
@@ -0,0 +1,160 @@
+//! Checks that the priority inheritance protocol propagates inherited
+//! priorities through a chain of mutex owners and recalculates them when a
+//! waiting task stops waiting by timeout or interruption.
+//!
+//! 1. (`seq`: 0 → 1) `task3` locks `m1`.
+//! 2. (`seq`: 1 → 2) `task3` activates `task2`. `task2` locks `m0` and starts
+//!    waiting on `m1`, raising `task3` to priority 2.
+//! 3. (`seq`: 2 → 4) `task3` activates `task1`. `task1` starts waiting on `m0`
+//!    with timeout, raising `task2` and `task3` to priority 1.
+//! 4. (`seq`: 4 → 6) `task3` activates `task0`. `task0` starts waiting on `m0`,
+//!    raising `task2` and `task3` to priority 0.
+//! 5. (`seq`: 6 → 8) `task3` interrupts `task0`. `task0` exits, and `task2` and
+//!    `task3` return to priority 1.
+//! 6. (`seq`: 8 → 10) `task3` sleeps. `task1` times out and exits, and `task2`
+//!    and `task3` return to priority 2.
+//! 7. (`seq`: 10 → 12) `task3` unlocks `m1` and returns to priority 3. `task2`
+//!    acquires `m1`.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, LockMutexError, LockMutexTimeoutError, MutexProtocol, StaticMutex,
+        StaticTask,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMutex + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task0: StaticTask<System>,
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    task3: StaticTask<System>,
+    m0: StaticMutex<System>,
+    m1: StaticMutex<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMutex,
+    {
+        let task0 = StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(0)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(2)
+            .finish(b);
+        let task3 = StaticTask::define()
+            .start(task3_body::<System, D>)
+            .priority(3)
+            .active(true)
+            .finish(b);
+
+        let m0 = StaticMutex::define()
+            .protocol(MutexProtocol::Inherit)
+            .finish(b);
+        let m1 = StaticMutex::define()
+            .protocol(MutexProtocol::Inherit)
+            .finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task0,
+            task1,
+            task2,
+            task3,
+            m0,
+            m1,
+            seq,
+        }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(4, 5);
+    assert_eq!(D::app().m0.lock(), Err(LockMutexError::Interrupted));
+    D::app().seq.expect_and_replace(6, 7);
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(2, 3);
+    assert_eq!(
+        D::app().m0.lock_timeout(Duration::from_millis(100)),
+        Err(LockMutexTimeoutError::Timeout)
+    );
+    D::app().seq.expect_and_replace(8, 9);
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(1, 2);
+    D::app().m0.lock().unwrap();
+    D::app().m1.lock().unwrap();
+
+    D::app().seq.expect_and_replace(11, 12);
+    assert_eq!(D::app().task2.effective_priority().unwrap(), 2);
+    assert_eq!(D::app().task3.effective_priority().unwrap(), 3);
+
+    D::app().m1.unlock().unwrap();
+    D::app().m0.unlock().unwrap();
+
+    D::success();
+}
+
+fn task3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    if !MutexProtocol::Inherit.is_supported::<System>() {
+        log::warn!("priority inheritance is not supported, skipping the test");
+        D::success();
+        return;
+    }
+
+    let app = D::app();
+
+    app.seq.expect_and_replace(0, 1);
+    app.m1.lock().unwrap();
+
+    // `task2` waits on `m1`
+    app.task2.activate().unwrap();
+    assert_eq!(app.task3.effective_priority().unwrap(), 2);
+
+    // `task1` waits on `m0`, which is owned by `task2`
+    app.task1.activate().unwrap();
+    app.seq.expect_and_replace(3, 4);
+    assert_eq!(app.task2.effective_priority().unwrap(), 1);
+    assert_eq!(app.task3.effective_priority().unwrap(), 1);
+
+    // `task0` waits on `m0`, which is owned by `task2`
+    app.task0.activate().unwrap();
+    app.seq.expect_and_replace(5, 6);
+    assert_eq!(app.task2.effective_priority().unwrap(), 0);
+    assert_eq!(app.task3.effective_priority().unwrap(), 0);
+
+    // Interrupt `task0`'s wait operation
+    app.task0.interrupt().unwrap();
+    app.seq.expect_and_replace(7, 8);
+    assert_eq!(app.task2.effective_priority().unwrap(), 1);
+    assert_eq!(app.task3.effective_priority().unwrap(), 1);
+
+    // Let `task1`'s wait operation time out
+    System::sleep(Duration::from_millis(200)).unwrap();
+    app.seq.expect_and_replace(9, 10);
+    assert_eq!(app.task2.effective_priority().unwrap(), 2);
+    assert_eq!(app.task3.effective_priority().unwrap(), 2);
+
+    // `task2` acquires `m1` and preempts `task3`
+    app.seq.expect_and_replace(10, 11);
+    app.m1.unlock().unwrap();
+}
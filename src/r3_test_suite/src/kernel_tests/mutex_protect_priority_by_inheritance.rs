@@ -0,0 +1,112 @@
+//! A text-book example where a mutex adhereing to the priority inheritance
+//! protocol successfully prevents unbounded priority inversion.
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, MutexProtocol, StaticMutex, StaticTask},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMutex + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task0: StaticTask<System>,
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    mtx: StaticMutex<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMutex,
+    {
+        let task0 = StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(0)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let mtx = StaticMutex::define()
+            .protocol(MutexProtocol::Inherit)
+            .finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task0,
+            task1,
+            task2,
+            mtx,
+            seq,
+        }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(1, 2);
+
+    // Activate `task1`. `task0` has a higher priority, so this won't cause
+    // dispatching.
+    D::app().task1.activate().unwrap();
+
+    // Start waiting for `task2` to release `mtx`. `task2` inherits `task0`'s
+    // priority, so `task2` resumes execution instead of `task1`.
+    //
+    // If it weren't for the locking protocol, the following code would dispatch
+    // `task1`, preventing `task2` from completing the critical section
+    // indefinitely.
+    D::app().seq.expect_and_replace(2, 3);
+    D::app().mtx.lock().unwrap();
+
+    D::app().seq.expect_and_replace(4, 5);
+    D::app().mtx.unlock().unwrap();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(5, 6);
+
+    // `task2`'s priority has been restored
+    assert_eq!(D::app().task2.effective_priority().unwrap(), 2);
+    assert_eq!(D::app().task2.priority().unwrap(), 2);
+
+    D::success();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    if !MutexProtocol::Inherit.is_supported::<System>() {
+        log::warn!("priority inheritance is not supported, skipping the test");
+        D::success();
+        return;
+    }
+
+    D::app().seq.expect_and_replace(0, 1);
+    D::app().mtx.lock().unwrap();
+
+    // Priority inheritance doesn't take place until another task starts
+    // waiting on the mutex
+    assert_eq!(D::app().task2.effective_priority().unwrap(), 2);
+    assert_eq!(D::app().task2.priority().unwrap(), 2);
+
+    // Activate `task0`, which preempts `task2` and then blocks on `mtx`
+    D::app().task0.activate().unwrap();
+
+    // `task2` has inherited `task0`'s priority
+    D::app().seq.expect_and_replace(3, 4);
+    assert_eq!(D::app().task2.effective_priority().unwrap(), 0);
+    assert_eq!(D::app().task2.priority().unwrap(), 2);
+
+    // After unlocking the mutex, `task2`'s priority is restored, and `task0`
+    // acquires a mutex lock.
+    D::app().mtx.unlock().unwrap();
+}
@@ -0,0 +1,168 @@
+//! Checks miscellaneous properties of [`r3::sync::StaticChannel`].
+//!
+//! 1. (`seq`: 0 → 1) `task1` activates `task2`.
+//! 2. (`seq`: 1 → 2 → 3) `task2` fills up the channel, times out trying to
+//!    send another value, and starts waiting for an empty slot.
+//! 3. (`seq`: 3 → 4 → 5) `task1` receives a value. `task2` sends the
+//!    remaining value and exits.
+//! 4. (`seq`: 5 → 6 → 7) `task1` receives the remaining values and pends an
+//!    interrupt, whose handler sends a value by `try_send`.
+//! 5. (`seq`: 7 → 8) `task1` receives the value.
+use assert_matches::assert_matches;
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, InterruptLine, StaticInterruptHandler, StaticTask},
+    prelude::*,
+    sync::channel::{
+        Receiver, RecvTimeoutError, SendTimeoutError, Sender, StaticChannel, TryRecvError,
+        TrySendError,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelInterruptLine + traits::KernelSemaphore + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelInterruptLine
+            + traits::KernelSemaphore
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task2: StaticTask<System>,
+    int: Option<InterruptLine<System>>,
+    tx: Sender<System, u32, 2>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>
+            + ~const traits::CfgInterruptLine
+            + ~const traits::CfgSemaphore,
+    {
+        let (tx, rx) = StaticChannel::define().finish(b);
+
+        StaticTask::define()
+            .start_with_bind((rx.borrow_mut(),), task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let int = if let (&[int_line, ..], &[int_pri, ..]) =
+            (D::INTERRUPT_LINES, D::INTERRUPT_PRIORITIES)
+        {
+            StaticInterruptHandler::define()
+                .line(int_line)
+                .start(isr::<System, D>)
+                .finish(b);
+
+            Some(
+                InterruptLine::define()
+                    .line(int_line)
+                    .enabled(true)
+                    .priority(int_pri)
+                    .finish(b),
+            )
+        } else {
+            None
+        };
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task2,
+            int,
+            tx,
+            seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>(rx: &mut Receiver<System, u32, 2>) {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    assert_matches!(rx.try_recv(), Err(TryRecvError::Empty));
+    assert_matches!(
+        rx.recv_timeout(Duration::from_millis(-1)),
+        Err(RecvTimeoutError::BadParam)
+    );
+    assert_matches!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+
+    // CPU Lock is not allowed
+    System::acquire_cpu_lock().unwrap();
+    assert_matches!(rx.try_recv(), Err(TryRecvError::BadContext));
+    assert_matches!(app.tx.try_send(42), Err(TrySendError::BadContext(42)));
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    app.task2.activate().unwrap();
+
+    // Wait until `task2` times out and starts waiting again
+    System::sleep(Duration::from_millis(100)).unwrap();
+    app.seq.expect_and_replace(3, 4);
+
+    assert_eq!(rx.recv().unwrap(), 1);
+    app.seq.expect_and_replace(5, 6);
+    assert_eq!(rx.recv().unwrap(), 2);
+    assert_eq!(rx.try_recv().unwrap(), 3);
+
+    if let Some(int) = app.int {
+        int.pend().unwrap();
+        assert_eq!(rx.recv_timeout(Duration::from_millis(100)).unwrap(), 4);
+    } else {
+        log::warn!("No interrupt lines defined, skipping a portion of the test");
+        app.seq.expect_and_replace(6, 7);
+    }
+    app.seq.expect_and_replace(7, 8);
+
+    D::success();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+
+    app.tx.try_send(1).unwrap();
+    app.tx.send(2).unwrap();
+    assert_matches!(app.tx.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(
+        app.tx
+            .send_timeout(3, Duration::from_millis(10))
+            .unwrap_err()
+            .into_inner(),
+        3
+    );
+    assert_matches!(
+        app.tx.send_timeout(3, Duration::from_millis(-1)),
+        Err(SendTimeoutError::BadParam(3))
+    );
+    app.seq.expect_and_replace(2, 3);
+
+    // `task1` receives a value
+    app.tx.send(3).unwrap();
+    app.seq.expect_and_replace(4, 5);
+}
+
+fn isr<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(6, 7);
+
+    app.tx.try_send(4).unwrap();
+}
@@ -0,0 +1,165 @@
+//! Checks miscellaneous properties of [`r3::sync::StaticCondvar`].
+//!
+//! 1. (`seq`: 0 → 1) `task1` starts waiting on `condvar` for `mutex` to have
+//!    a non-zero value.
+//! 2. (`seq`: 1 → 2) `task2` does the same.
+//! 3. (`seq`: 2 → 3) `task3` updates `mutex` and notifies all waiting tasks.
+//! 4. (`seq`: 3 → 4) `task1` wakes up and exits.
+//! 5. (`seq`: 4 → 5) `task2` wakes up and exits.
+//! 6. (`seq`: 5 → 6 → 7) `task3` waits on `condvar` with a timeout, holding
+//!    `cmutex`, which uses the priority ceiling protocol.
+//! 7. (`seq`: 7 → 8) `task3` waits on `condvar` with a timeout, holding
+//!    `mutex`. Meanwhile, `task4` locks `mutex` and exits without unlocking
+//!    it.
+//! 8. (`seq`: 8 → 9) `task3` wakes up and finds `mutex` abandoned.
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, MutexProtocol, StaticTask},
+    sync::{
+        condvar::{NotifyError, WaitTimeoutError},
+        mutex::{LockError, StaticMutex},
+        StaticCondvar,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMutex + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task4: StaticTask<System>,
+    mutex: StaticMutex<System, u32>,
+    cmutex: StaticMutex<System, u32>,
+    condvar: StaticCondvar<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMutex,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(0)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .active(true)
+            .finish(b);
+        StaticTask::define()
+            .start(task3_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task4 = StaticTask::define()
+            .start(task4_body::<System, D>)
+            .priority(3)
+            .finish(b);
+
+        let mutex = StaticMutex::define().finish(b);
+        let cmutex = StaticMutex::define()
+            .protocol(MutexProtocol::Ceiling(1))
+            .finish(b);
+        let condvar = StaticCondvar::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task4,
+            mutex,
+            cmutex,
+            condvar,
+            seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    // Notifying a condition variable with no waiting tasks does nothing
+    app.condvar.notify_one().unwrap();
+    app.condvar.notify_all().unwrap();
+
+    let guard = app.mutex.lock().unwrap();
+    let guard = app.condvar.wait_while(guard, |x| *x == 0).unwrap();
+    assert_eq!(*guard, 1);
+    app.seq.expect_and_replace(3, 4);
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+
+    let guard = app.mutex.lock().unwrap();
+    let guard = app.condvar.wait_while(guard, |x| *x == 0).unwrap();
+    assert_eq!(*guard, 1);
+    app.seq.expect_and_replace(4, 5);
+}
+
+fn task3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(2, 3);
+
+    *app.mutex.lock().unwrap() = 1;
+    app.condvar.notify_all().unwrap();
+    app.seq.expect_and_replace(5, 6);
+
+    // CPU Lock is not allowed
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(app.condvar.notify_one(), Err(NotifyError::BadContext));
+    assert_eq!(app.condvar.notify_all(), Err(NotifyError::BadContext));
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // Time out while holding a priority ceiling mutex. The mutex is released
+    // while waiting and re-acquired after that.
+    let mut guard = app.cmutex.lock().unwrap();
+    *guard = 42;
+    let (guard, result) = app
+        .condvar
+        .wait_timeout(guard, Duration::from_millis(20))
+        .unwrap();
+    assert!(result.timed_out());
+    assert_eq!(*guard, 42);
+    drop(guard);
+
+    // A negative timeout is rejected without releasing the mutex
+    let guard = app.cmutex.lock().unwrap();
+    let guard = match app.condvar.wait_timeout(guard, Duration::from_millis(-1)) {
+        Err(WaitTimeoutError::BadParam(guard)) => guard,
+        other => panic!("unexpected result: {other:?}"),
+    };
+    assert_eq!(*guard, 42);
+    drop(guard);
+    app.seq.expect_and_replace(6, 7);
+
+    // `task4` abandons `mutex` while `task3` is waiting
+    let guard = app.mutex.lock().unwrap();
+    app.task4.activate().unwrap();
+    match app.condvar.wait_timeout(guard, Duration::from_millis(50)) {
+        Err(WaitTimeoutError::Lock(LockError::Abandoned((guard, result)))) => {
+            assert!(result.timed_out());
+            assert_eq!(*guard, 2);
+            app.mutex.mark_consistent().unwrap();
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+    app.seq.expect_and_replace(8, 9);
+
+    D::success();
+}
+
+fn task4_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(7, 8);
+
+    let mut guard = app.mutex.lock().unwrap();
+    *guard = 2;
+    core::mem::forget(guard);
+}
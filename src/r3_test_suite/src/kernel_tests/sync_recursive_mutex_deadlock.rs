@@ -0,0 +1,94 @@
+//! Checks that locking a [`r3::sync::StaticRecursiveMutex`] fails with
+//! `Deadlock` if it would cause a deadlock.
+//!
+//! 1. (`seq`: 0 → 1) `task2` locks `eg0`.
+//! 2. (`seq`: 1 → 2) `task2` activates `task1`. `task1` locks `eg1` and starts
+//!    waiting on `eg0`.
+//! 3. (`seq`: 2 → 3) `task2` tries to lock `eg1`, which would cause a deadlock
+//!    through `task1`. `task2` unlocks `eg0`.
+//! 4. (`seq`: 3 → 4) `task1` acquires `eg0` and unlocks `eg0` and `eg1`.
+//! 5. (`seq`: 4 → 5) `task2` locks `eg1` successfully.
+//!
+use assert_matches::assert_matches;
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask},
+    sync::recursive_mutex::{self, StaticRecursiveMutex},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMutex + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    eg0: StaticRecursiveMutex<System, ()>,
+    eg1: StaticRecursiveMutex<System, ()>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMutex,
+    {
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let eg0 = StaticRecursiveMutex::define().finish(b);
+        let eg1 = StaticRecursiveMutex::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task1,
+            eg0,
+            eg1,
+            seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+    let guard1 = app.eg1.lock().unwrap();
+    let guard0 = app.eg0.lock().unwrap();
+
+    app.seq.expect_and_replace(3, 4);
+    drop(guard0);
+    drop(guard1);
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+    let guard0 = app.eg0.lock().unwrap();
+    app.task1.activate().unwrap();
+
+    // `eg1` → `task1` → `eg0` → `task2`. This must not be mistaken for a
+    // recursive lock.
+    app.seq.expect_and_replace(2, 3);
+    assert_matches!(app.eg1.lock(), Err(recursive_mutex::LockError::Deadlock));
+    assert_matches!(
+        app.eg1.lock_timeout(Duration::from_millis(100)),
+        Err(recursive_mutex::LockError::Deadlock)
+    );
+    // Recursive locking is still allowed
+    drop(app.eg0.lock().unwrap());
+    drop(guard0);
+
+    app.seq.expect_and_replace(4, 5);
+    drop(app.eg1.lock().unwrap());
+
+    D::success();
+}
@@ -0,0 +1,125 @@
+//! Checks the behavior of
+//! [`r3::sync::recursive_mutex::GenericRecursiveMutex::lock_timeout`].
+//!
+//! 1. (`seq`: 0 → 1) `task1` locks `mutex` twice and activates `task2`.
+//! 2. (`seq`: 1 → 2 → 3) `task2` times out waiting for `mutex` and starts
+//!    waiting for it again.
+//! 3. (`seq`: 3 → 4) `task1` interrupts `task2`.
+//! 4. (`seq`: 4 → 5) `task2` starts waiting for `mutex` again.
+//! 5. (`seq`: 5 → 6) `task1` exits without unlocking `mutex`.
+//! 6. (`seq`: 6 → 7) `task2` wakes up and finds `mutex` abandoned.
+use assert_matches::assert_matches;
+use core::cell::Cell;
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask},
+    sync::recursive_mutex::{LockError, StaticRecursiveMutex},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelMutex + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelMutex + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task2: StaticTask<System>,
+    mutex: StaticRecursiveMutex<System, Cell<u32>>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgMutex,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let mutex = StaticRecursiveMutex::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { task2, mutex, seq }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    // CPU Lock is not allowed
+    System::acquire_cpu_lock().unwrap();
+    assert_matches!(
+        app.mutex.lock_timeout(Duration::from_millis(10)),
+        Err(LockError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // Recursive locking doesn't block
+    let guard1 = app.mutex.lock_timeout(Duration::from_millis(10)).unwrap();
+    let guard2 = app.mutex.lock_timeout(Duration::from_millis(10)).unwrap();
+
+    app.task2.activate().unwrap();
+
+    // Wait until `task2` times out and starts waiting again
+    System::sleep(Duration::from_millis(100)).unwrap();
+    app.seq.expect_and_replace(3, 4);
+
+    app.task2.interrupt().unwrap();
+    app.seq.expect_and_replace(5, 6);
+
+    // Exit while holding the lock
+    guard2.set(42);
+    core::mem::forget(guard2);
+    core::mem::forget(guard1);
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+
+    assert_matches!(
+        app.mutex.lock_timeout(Duration::from_millis(-1)),
+        Err(LockError::BadParam)
+    );
+    assert_matches!(
+        app.mutex.lock_timeout(Duration::from_millis(10)),
+        Err(LockError::Timeout)
+    );
+    app.seq.expect_and_replace(2, 3);
+
+    // `task1` interrupts the wait
+    assert_matches!(
+        app.mutex.lock_timeout(Duration::from_millis(1000)),
+        Err(LockError::Interrupted)
+    );
+    app.seq.expect_and_replace(4, 5);
+
+    // `task1` abandons the lock
+    match app.mutex.lock_timeout(Duration::from_millis(1000)) {
+        Err(LockError::Abandoned(guard)) => {
+            assert_eq!(guard.get(), 42);
+            app.mutex.mark_consistent().unwrap();
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+    app.seq.expect_and_replace(6, 7);
+
+    assert_eq!(
+        app.mutex
+            .lock_timeout(Duration::from_millis(10))
+            .unwrap()
+            .get(),
+        42
+    );
+
+    D::success();
+}
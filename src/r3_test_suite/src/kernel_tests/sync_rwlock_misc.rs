@@ -0,0 +1,157 @@
+//! Checks miscellaneous properties of [`r3::sync::StaticRwLock`].
+//!
+//! 1. (`seq`: 0 → 1 → 2) `task1` acquires a read lock of `rwlock` and
+//!    activates `task2`, which starts waiting for a write lock.
+//! 2. (`seq`: 2 → 3) `task1` can't acquire another read lock because a writer
+//!    is waiting. It activates `task3`, which starts waiting for a read lock.
+//! 3. (`seq`: 3 → 4 → 5) `task1` releases the read lock. `task2` acquires the
+//!    write lock and updates the data.
+//! 4. (`seq`: 5 → 6 → 7) `task2` releases the write lock. `task3` acquires
+//!    the read lock and exits.
+//! 5. (`seq`: 7 → 8) `task2` exits.
+//! 6. (`seq`: 8 → 9 → 10) `task1` acquires a read lock of `rrwlock` (which
+//!    prefers readers) and activates `task4`, which starts waiting for a write
+//!    lock.
+//! 7. (`seq`: 10 → 11) `task1` can still acquire another read lock. It
+//!    releases both of the read locks.
+//! 8. (`seq`: 11 → 12) `task4` acquires the write lock and exits.
+//! 9. (`seq`: 12 → 13) `task1` exits.
+use assert_matches::assert_matches;
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask},
+    sync::{
+        rwlock::{LockError, RwLockPolicy, TryLockError},
+        StaticRwLock,
+    },
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task2: StaticTask<System>,
+    task3: StaticTask<System>,
+    task4: StaticTask<System>,
+    rwlock: StaticRwLock<System, u32>,
+    rrwlock: StaticRwLock<System, u32>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task3 = StaticTask::define()
+            .start(task3_body::<System, D>)
+            .priority(0)
+            .finish(b);
+        let task4 = StaticTask::define()
+            .start(task4_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let rwlock = StaticRwLock::define().finish(b);
+        let rrwlock = StaticRwLock::define()
+            .policy(RwLockPolicy::ReaderPreferred)
+            .init(|| 42)
+            .finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task2,
+            task3,
+            task4,
+            rwlock,
+            rrwlock,
+            seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    let guard = app.rwlock.try_read().unwrap();
+    assert_eq!(*guard, 0);
+    assert_matches!(app.rwlock.try_write(), Err(TryLockError::WouldBlock));
+
+    // CPU Lock is not allowed
+    System::acquire_cpu_lock().unwrap();
+    assert_matches!(app.rwlock.try_read(), Err(TryLockError::BadContext));
+    assert_matches!(app.rwlock.read(), Err(LockError::BadContext));
+    assert_matches!(app.rwlock.write(), Err(LockError::BadContext));
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    app.task2.activate().unwrap();
+    app.seq.expect_and_replace(2, 3);
+
+    // A writer is waiting, so new readers have to wait
+    assert_matches!(app.rwlock.try_read(), Err(TryLockError::WouldBlock));
+
+    app.task3.activate().unwrap();
+    app.seq.expect_and_replace(4, 5);
+
+    drop(guard);
+    app.seq.expect_and_replace(8, 9);
+
+    // `rrwlock` lets readers in even if a writer is waiting
+    let guard = app.rrwlock.read().unwrap();
+    app.task4.activate().unwrap();
+    app.seq.expect_and_replace(10, 11);
+
+    let guard2 = app.rrwlock.try_read().unwrap();
+    assert_eq!(*guard2, 42);
+    drop(guard2);
+    drop(guard);
+    app.seq.expect_and_replace(12, 13);
+
+    assert_eq!(*app.rrwlock.read().unwrap(), 43);
+
+    D::success();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+
+    let mut guard = app.rwlock.write().unwrap();
+    app.seq.expect_and_replace(5, 6);
+    *guard = 1;
+    drop(guard);
+
+    app.seq.expect_and_replace(7, 8);
+}
+
+fn task3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(3, 4);
+
+    let guard = app.rwlock.read().unwrap();
+    app.seq.expect_and_replace(6, 7);
+    assert_eq!(*guard, 1);
+    assert_matches!(app.rwlock.try_write(), Err(TryLockError::WouldBlock));
+}
+
+fn task4_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(9, 10);
+
+    let mut guard = app.rrwlock.write().unwrap();
+    app.seq.expect_and_replace(11, 12);
+    *guard += 1;
+}
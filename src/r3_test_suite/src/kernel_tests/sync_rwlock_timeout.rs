@@ -0,0 +1,105 @@
+//! Checks the timed operations of [`r3::sync::StaticRwLock`].
+//!
+//! 1. (`seq`: 0 → 1) `task1` checks the error codes, acquires a write lock of
+//!    `rwlock`, and activates `task2`.
+//! 2. (`seq`: 1 → 2) `task2` starts waiting for a read lock with a timeout of
+//!    100ms.
+//! 3. (`seq`: 2 → 3) `task1` unparks `task2` every 30ms for 300ms. These
+//!    spurious wakeups don't extend the timeout, so `task2` times out at
+//!    around 100ms.
+//! 4. (`seq`: 3 → 4) `task1` releases the write lock and can acquire a read
+//!    lock with a timeout.
+use assert_matches::assert_matches;
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask},
+    sync::{rwlock::LockTimeoutError, StaticRwLock},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelTime + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelTime + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task2: StaticTask<System>,
+    rwlock: StaticRwLock<System, u32>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let rwlock = StaticRwLock::define().finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { task2, rwlock, seq }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    // CPU Lock is not allowed
+    System::acquire_cpu_lock().unwrap();
+    assert_matches!(
+        app.rwlock.write_timeout(Duration::from_millis(10)),
+        Err(LockTimeoutError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    assert_matches!(
+        app.rwlock.write_timeout(Duration::from_millis(-1)),
+        Err(LockTimeoutError::BadParam)
+    );
+
+    let guard = app.rwlock.write_timeout(Duration::ZERO).unwrap();
+    app.task2.activate().unwrap();
+    assert_eq!(app.seq.get(), 2);
+
+    for _ in 0..10 {
+        System::sleep(Duration::from_millis(30)).unwrap();
+        // `task2` might have exited already
+        let _ = app.task2.unpark();
+    }
+
+    app.seq.expect_and_replace(3, 4);
+    drop(guard);
+    drop(app.rwlock.read_timeout(Duration::ZERO).unwrap());
+
+    D::success();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(1, 2);
+
+    let start = System::time().unwrap();
+    assert_matches!(
+        app.rwlock.read_timeout(Duration::from_millis(100)),
+        Err(LockTimeoutError::Timeout)
+    );
+    let elapsed = System::time().unwrap().duration_since(start).unwrap();
+    log::trace!("elapsed = {elapsed:?}");
+    assert!(
+        (Duration::from_millis(100)..Duration::from_millis(250)).contains(&elapsed),
+        "elapsed = {elapsed:?}"
+    );
+
+    app.seq.expect_and_replace(2, 3);
+}
@@ -0,0 +1,97 @@
+//! Checks that activation requests for an active task are queued up to the
+//! limit specified by `TaskDefiner::max_queued_activations` and that the task
+//! restarts after `exit_task` for each queued request.
+//!
+//! 1. (`seq`: 0 → 1) `task0` activates `task1` three times and `task2` once.
+//!    Further activation requests fail with `QueueOverflow`.
+//! 2. (`seq`: 1 → 5) `task0` sleeps. `task1` runs, exits, and goes to the back
+//!    of the ready queue, so `task2` runs next. `task1` runs two more times.
+//! 3. (`seq`: 5 → 6) `task0` wakes up and activates `task1` again. It's now
+//!    Dormant, so the request is accepted.
+//! 4. (`seq`: 6 → 7) `task0` sleeps. `task1` runs once and completes the test.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, ActivateTaskError, Cfg, StaticTask},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(3)
+            .max_queued_activations(2)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(3)
+            .finish(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { task1, task2, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    // `task1` and `task2` have lower priorities, so they don't run in the
+    // meantime
+    app.seq.expect_and_replace(0, 1);
+    app.task1.activate().unwrap();
+    app.task1.activate().unwrap();
+    app.task1.activate().unwrap();
+    assert_eq!(app.task1.activate(), Err(ActivateTaskError::QueueOverflow));
+
+    // `task2` doesn't queue activation requests
+    app.task2.activate().unwrap();
+    assert_eq!(app.task2.activate(), Err(ActivateTaskError::QueueOverflow));
+
+    // Let `task1` and `task2` run
+    System::sleep(Duration::from_millis(100)).unwrap();
+    app.seq.expect_and_replace(5, 6);
+
+    // All queued activation requests have been consumed
+    app.task1.activate().unwrap();
+    System::sleep(Duration::from_millis(100)).unwrap();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    match app.seq.get() {
+        1 => app.seq.expect_and_replace(1, 2),
+        3 => app.seq.expect_and_replace(3, 4),
+        4 => app.seq.expect_and_replace(4, 5),
+        _ => {
+            app.seq.expect_and_replace(6, 7);
+            D::success();
+        }
+    }
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    D::app().seq.expect_and_replace(2, 3);
+}
@@ -0,0 +1,87 @@
+//! Checks the processor time accounting done by
+//! [`r3::kernel::task::TaskMethods::cpu_time`] and
+//! [`r3::kernel::Kernel::load_snapshot`].
+//!
+//! 1. `task1` spins until its processor time increases by 10ms.
+//! 2. `task1` sleeps for 50ms, which is counted as idle time.
+//!
+use core::hint::spin_loop;
+use r3::{
+    kernel::{prelude::*, traits, Cfg, GetTaskCpuTimeError, LoadSnapshotError, StaticTask},
+    time::Duration,
+};
+
+use super::Driver;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelRuntimeStats + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelRuntimeStats + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define().start(task2_body).priority(2).finish(b);
+
+        App { task1, task2 }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+
+    let snapshot0 = System::load_snapshot().unwrap();
+    let cpu0 = app.task1.cpu_time().unwrap();
+    log::debug!("snapshot0 = {snapshot0:?}, cpu0 = {cpu0}");
+
+    // Consume the processor time
+    while app.task1.cpu_time().unwrap() < cpu0 + 10_000 {
+        spin_loop();
+    }
+
+    let snapshot1 = System::load_snapshot().unwrap();
+    let cpu1 = app.task1.cpu_time().unwrap();
+    log::debug!("snapshot1 = {snapshot1:?}, cpu1 = {cpu1}");
+    assert!(snapshot1.busy_time - snapshot0.busy_time >= 10_000);
+
+    // Leave the processor idle
+    System::sleep(Duration::from_millis(50)).unwrap();
+
+    let snapshot2 = System::load_snapshot().unwrap();
+    let cpu2 = app.task1.cpu_time().unwrap();
+    log::debug!("snapshot2 = {snapshot2:?}, cpu2 = {cpu2}");
+    assert!(snapshot2.idle_time - snapshot1.idle_time >= 40_000);
+    assert!(snapshot2.busy_time >= snapshot1.busy_time);
+    assert!(cpu2 >= cpu1);
+
+    // `task2` has never run
+    assert_eq!(app.task2.cpu_time(), Ok(0));
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(app.task1.cpu_time(), Err(GetTaskCpuTimeError::BadContext));
+    assert_eq!(System::load_snapshot(), Err(LoadSnapshotError::BadContext));
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    D::success();
+}
+
+fn task2_body() {
+    unreachable!();
+}
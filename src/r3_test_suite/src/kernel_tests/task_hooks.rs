@@ -0,0 +1,138 @@
+//! Checks the invocation of `TaskSwitchHook`, `TaskExitHook`, and `IdleHook`.
+//!
+//! 1. (`seq`: 0 → 1) `task1` activates `task2`, which preempts `task1`.
+//! 2. (`seq`: 1 → 2) The task switch hooks observe `task1` → `task2`.
+//! 3. (`seq`: 2 → 4) `task2` exits, and the task exit hook observes it.
+//! 4. (`seq`: 4 → 5) The task switch hooks observe `task2` (exited) → `task1`.
+//! 5. (`seq`: 5 → 8) `task1` sleeps. The task switch hooks observe `task1` →
+//!    (idle), and then the idle hook runs.
+//! 6. (`seq`: 8 → 9) `task1` wakes up and completes the test.
+//!
+//! Every time the task switch hooks are invoked, `switch_seq` is used to check
+//! that they are executed in the ascending order of priority.
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, IdleHook, StaticTask, TaskExitHook, TaskSwitchHook},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+    switch_seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        // Registered out of order to check the sorting
+        TaskSwitchHook::define()
+            .start(task_switch_hook_late::<System, D>)
+            .priority(10)
+            .finish(b);
+        TaskSwitchHook::define()
+            .start(task_switch_hook::<System, D>)
+            .finish(b);
+        TaskExitHook::define()
+            .start(task_exit_hook::<System, D>)
+            .finish(b);
+        IdleHook::define().start(idle_hook::<System, D>).finish(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+        let switch_seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task1,
+            task2,
+            seq,
+            switch_seq,
+        }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(0, 1);
+    app.task2.activate().unwrap();
+
+    app.seq.expect_and_replace(5, 6);
+    System::sleep(Duration::from_millis(50)).unwrap();
+
+    app.seq.expect_and_replace(8, 9);
+    D::success();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(2, 3);
+}
+
+fn task_switch_hook<System: SupportedSystem, D: Driver<App<System>>>(
+    prev: Option<StaticTask<System>>,
+    next: Option<StaticTask<System>>,
+) {
+    log::trace!("task_switch_hook({prev:?}, {next:?})");
+    let app = D::app();
+    app.switch_seq.expect_and_replace(0, 1);
+
+    // Hooks run with CPU Lock active
+    assert!(System::has_cpu_lock());
+
+    let (task1, task2) = (Some(app.task1), Some(app.task2));
+    match app.seq.get() {
+        1 if (prev, next) == (task1, task2) => app.seq.expect_and_replace(1, 2),
+        4 if (prev, next) == (task2, task1) => app.seq.expect_and_replace(4, 5),
+        6 if (prev, next) == (task1, None) => app.seq.expect_and_replace(6, 7),
+        _ => {}
+    }
+}
+
+fn task_switch_hook_late<System: SupportedSystem, D: Driver<App<System>>>(
+    _: Option<StaticTask<System>>,
+    _: Option<StaticTask<System>>,
+) {
+    D::app().switch_seq.expect_and_replace(1, 0);
+}
+
+fn task_exit_hook<System: SupportedSystem, D: Driver<App<System>>>(task: StaticTask<System>) {
+    log::trace!("task_exit_hook({task:?})");
+    let app = D::app();
+    assert!(System::has_cpu_lock());
+
+    // The hook runs in the context of the exiting task
+    assert!(System::is_task_context());
+
+    // `task1` also exits after completing the test
+    if task == app.task2 {
+        app.seq.expect_and_replace(3, 4);
+    }
+}
+
+fn idle_hook<System: SupportedSystem, D: Driver<App<System>>>() {
+    log::trace!("idle_hook");
+    let app = D::app();
+    assert!(System::has_cpu_lock());
+
+    if app.seq.get() == 7 {
+        app.seq.expect_and_replace(7, 8);
+    }
+}
@@ -0,0 +1,130 @@
+//! Validates error codes returned by `Task::notify` and checks the behavior of
+//! `Kernel::wait_notification[_timeout]` and `NotifyAction`.
+//!
+//! 1. (`seq`: 0 → 1) `task0` checks the error codes.
+//! 2. (`seq`: 1 → 2) `task0` activates `task1`, which starts waiting for a
+//!    notification.
+//! 3. (`seq`: 2 → 4) `task0` notifies `task1` with `SetBits` and starts
+//!    sleeping. `task1` wakes up and receives the value. `task1` sees its next
+//!    wait operation time out and starts sleeping.
+//! 4. (`seq`: 4 → 5) `task0` wakes up and notifies `task1` with `Overwrite`,
+//!    `OverwriteIfEmpty`, and `Increment` while `task1` is sleeping.
+//! 5. (`seq`: 5 → 6) `task1` wakes up and receives the pending notification
+//!    without blocking.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, NotifyAction, NotifyTaskError, StaticTask,
+        WaitNotificationTimeoutError,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelTaskNotify + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelTaskNotify + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { task1, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(0, 1);
+
+    // The task is in the Dormant state
+    assert_eq!(
+        app.task1.notify(1, NotifyAction::SetBits),
+        Err(NotifyTaskError::BadObjectState)
+    );
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(
+        app.task1.notify(1, NotifyAction::SetBits),
+        Err(NotifyTaskError::BadContext)
+    );
+    assert_eq!(
+        System::wait_notification_timeout(0, 0, Duration::from_millis(100)),
+        Err(WaitNotificationTimeoutError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // `task1` starts waiting for a notification
+    app.task1.activate().unwrap();
+
+    // Wake up `task1`
+    app.seq.expect_and_replace(2, 3);
+    app.task1.notify(0b101, NotifyAction::SetBits).unwrap();
+
+    // Let `task1`'s next wait operation time out
+    System::sleep(Duration::from_millis(200)).unwrap();
+
+    // Update the notification value while `task1` is sleeping
+    app.seq.expect_and_replace(4, 5);
+    app.task1.notify(7, NotifyAction::Overwrite).unwrap();
+    assert_eq!(
+        app.task1.notify(9, NotifyAction::OverwriteIfEmpty),
+        Err(NotifyTaskError::QueueOverflow)
+    );
+    app.task1.notify(0, NotifyAction::Increment).unwrap();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(1, 2);
+    assert_eq!(System::wait_notification(0, 0b001), Ok(0b101));
+    app.seq.expect_and_replace(3, 4);
+
+    // `clear_on_exit` has been applied, and there's no pending notification
+    assert_eq!(
+        System::wait_notification_timeout(0, 0, Duration::from_millis(100)),
+        Err(WaitNotificationTimeoutError::Timeout)
+    );
+
+    // Let `task0` send notifications
+    System::sleep(Duration::from_millis(200)).unwrap();
+
+    // `clear_on_entry` has no effect if there's a pending notification
+    app.seq.expect_and_replace(5, 6);
+    assert_eq!(System::wait_notification(u32::MAX, u32::MAX), Ok(8));
+
+    // `clear_on_exit` has cleared all bits
+    app.task1.notify(0, NotifyAction::Increment).unwrap();
+    assert_eq!(System::wait_notification(0, 0), Ok(1));
+
+    D::success();
+}
@@ -0,0 +1,94 @@
+//! Checks the return values of [`r3::kernel::task::TaskMethods::stack_high_water_mark`].
+//!
+//! If [`Driver::TASKS_USE_KERNEL_STACKS`] is `false`, the stack usage can't be
+//! observed, and this test only checks that the high-water marks stay zero.
+//! `r3_port_std` checks the measurement by simulating stack usage in its own
+//! test instead.
+use core::hint::black_box;
+use r3::kernel::{prelude::*, traits, Cfg, GetTaskStackHighWaterMarkError, StaticTask};
+
+use super::Driver;
+
+const STACK_SIZE: usize = 2048;
+const USED_LEN: usize = 256;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelTaskStackHighWaterMark + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelTaskStackHighWaterMark + traits::KernelStatic>
+    SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .stack_size(STACK_SIZE)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body)
+            .priority(2)
+            .stack_size(STACK_SIZE)
+            .finish(b);
+
+        App { task1, task2 }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+
+    let hwm0 = app.task1.stack_high_water_mark().unwrap();
+    log::debug!("hwm0 = {hwm0}");
+    assert!(hwm0 <= STACK_SIZE);
+
+    use_stack();
+
+    let hwm1 = app.task1.stack_high_water_mark().unwrap();
+    log::debug!("hwm1 = {hwm1}");
+    assert!(hwm1 <= STACK_SIZE);
+    assert!(hwm1 >= hwm0);
+
+    if D::TASKS_USE_KERNEL_STACKS {
+        // `task1` has been running on its stack
+        assert!(hwm0 > 0);
+        assert!(hwm1 >= USED_LEN);
+    } else {
+        assert_eq!(hwm1, 0);
+    }
+
+    // `task2` has never run, so its stack is intact
+    assert_eq!(app.task2.stack_high_water_mark(), Ok(0));
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(
+        app.task1.stack_high_water_mark(),
+        Err(GetTaskStackHighWaterMarkError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    D::success();
+}
+
+#[inline(never)]
+fn use_stack() {
+    let buf = black_box([1u8; USED_LEN]);
+    black_box(&buf);
+}
+
+fn task2_body() {
+    unreachable!();
+}
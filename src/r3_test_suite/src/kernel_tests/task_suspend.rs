@@ -0,0 +1,109 @@
+//! Validates error codes returned by `Task::{suspend, resume}` and checks that
+//! a suspended task isn't scheduled until it's resumed.
+//!
+//! 1. (`seq`: 0 → 1) `task0` checks the error codes for a Dormant task.
+//! 2. (`seq`: 1 → 2) `task0` activates `task1`, suspends it before it gets a
+//!    chance to run, and sleeps. `task1` doesn't run.
+//! 3. (`seq`: 2 → 4) `task0` resumes `task1` and sleeps. `task1` runs and
+//!    suspends itself.
+//! 4. (`seq`: 4 → 6) `task0` resumes `task1` and sleeps. `task1` runs and
+//!    completes the test.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, LocalTask, ResumeTaskError, StaticTask, SuspendTaskError},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelTaskSuspend + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelTaskSuspend + traits::KernelStatic> SupportedSystem
+    for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(3)
+            .finish(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { task1, seq }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    // The task is in the Dormant state
+    app.seq.expect_and_replace(0, 1);
+    assert_eq!(app.task1.suspend(), Err(SuspendTaskError::BadObjectState));
+    assert_eq!(app.task1.resume(), Err(ResumeTaskError::BadObjectState));
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(app.task1.suspend(), Err(SuspendTaskError::BadContext));
+    assert_eq!(app.task1.resume(), Err(ResumeTaskError::BadContext));
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // Activate `task1` and suspend it while it's in the Ready state.
+    // `task1` has a lower priority, so it doesn't run in the meantime.
+    app.seq.expect_and_replace(1, 2);
+    app.task1.activate().unwrap();
+    app.task1.suspend().unwrap();
+
+    // Suspension doesn't nest
+    assert_eq!(app.task1.suspend(), Err(SuspendTaskError::QueueOverflow));
+
+    // `task1` shouldn't run while it's suspended
+    System::sleep(Duration::from_millis(100)).unwrap();
+    app.seq.expect_and_replace(2, 3);
+
+    // Resume `task1`
+    app.task1.resume().unwrap();
+
+    // The task is not suspended anymore
+    assert_eq!(app.task1.resume(), Err(ResumeTaskError::BadObjectState));
+
+    // Let `task1` run. It will suspend itself.
+    System::sleep(Duration::from_millis(100)).unwrap();
+    app.seq.expect_and_replace(4, 5);
+    assert_eq!(app.task1.suspend(), Err(SuspendTaskError::QueueOverflow));
+
+    // Resume `task1` and let it run
+    app.task1.resume().unwrap();
+    System::sleep(Duration::from_millis(100)).unwrap();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(3, 4);
+
+    // Suspend the current task
+    LocalTask::<System>::current().unwrap().suspend().unwrap();
+
+    app.seq.expect_and_replace(5, 6);
+    D::success();
+}
@@ -0,0 +1,119 @@
+//! Checks the behavior of the Waiting-Suspended state. A task suspended while
+//! waiting on a semaphore should keep its place in the wait queue.
+//!
+//! 1. (`seq`: 0 → 2) `task0` activates `task1` and `task2`, which start
+//!    waiting on `sem`. `task1` is closer to the front of the wait queue.
+//! 2. (`seq`: 2 → 3) `task0` suspends `task1` and signals `sem`. `task1` takes
+//!    the permit but doesn't run because it's suspended.
+//! 3. (`seq`: 3 → 5) `task0` resumes `task1`, which preempts `task0`.
+//! 4. (`seq`: 5 → 6) `task0` suspends and resumes `task2`. `task2` returns to
+//!    the Waiting state.
+//! 5. (`seq`: 6 → 8) `task0` signals `sem`. `task2` preempts `task0`.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, ResumeTaskError, StaticSemaphore, StaticTask},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelTaskSuspend + traits::KernelSemaphore + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelTaskSuspend
+            + traits::KernelSemaphore
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    sem: StaticSemaphore<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgSemaphore,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(3)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(2)
+            .finish(b);
+
+        let sem = StaticSemaphore::define().maximum(1).initial(0).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task1,
+            task2,
+            sem,
+            seq,
+        }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    // `task1` and `task2` start waiting on `sem`
+    app.task1.activate().unwrap();
+    app.task2.activate().unwrap();
+
+    // Suspend `task1` while it's waiting. It's still at the front of the wait
+    // queue, so it takes the permit, but it doesn't run.
+    app.seq.expect_and_replace(2, 3);
+    app.task1.suspend().unwrap();
+    app.sem.signal_one().unwrap();
+    System::sleep(Duration::from_millis(100)).unwrap();
+    assert_eq!(app.sem.get().unwrap(), 0);
+
+    // Resume `task1`. It preempts `task0`.
+    app.seq.expect_and_replace(3, 4);
+    app.task1.resume().unwrap();
+
+    // `task2` returns to the Waiting state when resumed
+    app.seq.expect_and_replace(5, 6);
+    app.task2.suspend().unwrap();
+    app.task2.resume().unwrap();
+    assert_eq!(app.task2.resume(), Err(ResumeTaskError::BadObjectState));
+
+    // `task2` takes the permit and preempts `task0`
+    app.sem.signal_one().unwrap();
+    app.seq.expect_and_replace(7, 8);
+
+    D::success();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(0, 1);
+    app.sem.wait_one().unwrap();
+    app.seq.expect_and_replace(4, 5);
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(1, 2);
+    app.sem.wait_one().unwrap();
+    app.seq.expect_and_replace(6, 7);
+}
@@ -0,0 +1,189 @@
+//! Validates error codes returned by `Task::terminate` and checks that a
+//! terminated task releases the resources it was holding and can be activated
+//! again.
+//!
+//! 1. (`seq`: 0 → 1) `task0` checks the error codes.
+//! 2. (`seq`: 1) `task0` activates `task1` and terminates it before it gets
+//!    a chance to run. The task exit hook observes `task1` (`exit_seq`: 0 → 1).
+//! 3. (`seq`: 1 → 3) `task0` activates `task2`. `task2` locks `mtx` and starts
+//!    waiting on `sem` with timeout.
+//! 4. (`seq`: 3 → 4) `task0` terminates `task2`. The task exit hook observes
+//!    `task2` (`exit_seq`: 1 → 2). `mtx` is abandoned, and `sem` doesn't have
+//!    a waiter anymore.
+//! 5. (`seq`: 4 → 6) `task0` sleeps past the original timeout and activates
+//!    `task2` again. `task2` completes the test.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, LocalTask, LockMutexError, StaticMutex, StaticSemaphore,
+        StaticTask, TaskExitHook, TerminateTaskError,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase
+    + traits::KernelTaskTerminate
+    + traits::KernelMutex
+    + traits::KernelSemaphore
+    + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelTaskTerminate
+            + traits::KernelMutex
+            + traits::KernelSemaphore
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    task2: StaticTask<System>,
+    mtx: StaticMutex<System>,
+    sem: StaticSemaphore<System>,
+    seq: Hunk<System, SeqTracker>,
+    exit_seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>
+            + ~const traits::CfgMutex
+            + ~const traits::CfgSemaphore,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define().start(task1_body).priority(3).finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let mtx = StaticMutex::define().finish(b);
+        let sem = StaticSemaphore::define().maximum(1).initial(0).finish(b);
+        TaskExitHook::define()
+            .start(task_exit_hook::<System, D>)
+            .finish(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+        let exit_seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task1,
+            task2,
+            mtx,
+            sem,
+            seq,
+            exit_seq,
+        }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(0, 1);
+
+    unsafe {
+        // The task is in the Dormant state
+        assert_eq!(
+            app.task1.terminate(),
+            Err(TerminateTaskError::BadObjectState)
+        );
+
+        // The task is in the Running state
+        assert_eq!(
+            LocalTask::<System>::current().unwrap().terminate(),
+            Err(TerminateTaskError::BadContext)
+        );
+
+        // CPU Lock active
+        System::acquire_cpu_lock().unwrap();
+        assert_eq!(app.task1.terminate(), Err(TerminateTaskError::BadContext));
+        System::release_cpu_lock().unwrap();
+    }
+
+    // Activate `task1` and terminate it while it's in the Ready state.
+    // `task1` has a lower priority, so it doesn't run in the meantime.
+    app.task1.activate().unwrap();
+    unsafe { app.task1.terminate() }.unwrap();
+    assert_eq!(app.exit_seq.get(), 1);
+    System::sleep(Duration::from_millis(100)).unwrap();
+
+    // Activate `task2`. It locks `mtx` and starts waiting on `sem`.
+    app.seq.expect_and_replace(1, 2);
+    app.task2.activate().unwrap();
+    app.seq.expect_and_replace(3, 4);
+
+    // Terminate `task2` while it's in the Waiting state
+    unsafe { app.task2.terminate() }.unwrap();
+    assert_eq!(app.exit_seq.get(), 2);
+    assert_eq!(
+        unsafe { app.task2.terminate() },
+        Err(TerminateTaskError::BadObjectState)
+    );
+
+    // `mtx` was abandoned by `task2`
+    assert_eq!(app.mtx.lock(), Err(LockMutexError::Abandoned));
+    app.mtx.mark_consistent().unwrap();
+    app.mtx.unlock().unwrap();
+
+    // `task2` isn't waiting on `sem` anymore
+    app.sem.signal_one().unwrap();
+    assert_eq!(app.sem.get().unwrap(), 1);
+
+    // The wait operation's timeout shouldn't fire
+    System::sleep(Duration::from_millis(300)).unwrap();
+
+    // Activate `task2` again
+    app.seq.expect_and_replace(4, 5);
+    app.task2.activate().unwrap();
+}
+
+fn task_exit_hook<System: SupportedSystem, D: Driver<App<System>, System = System>>(
+    task: StaticTask<System>,
+) {
+    log::trace!("task_exit_hook({task:?})");
+    let app = D::app();
+    assert!(System::has_cpu_lock());
+
+    // Terminated tasks are reported to the hook, which runs in the context of
+    // `task0`. The other tasks also exit normally later.
+    match app.seq.get() {
+        1 if task == app.task1 => app.exit_seq.expect_and_replace(0, 1),
+        4 if task == app.task2 => app.exit_seq.expect_and_replace(1, 2),
+        _ => {}
+    }
+}
+
+fn task1_body() {
+    unreachable!();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+
+    if app.seq.get() == 2 {
+        // The first activation
+        app.seq.expect_and_replace(2, 3);
+        app.mtx.lock().unwrap();
+        let _ = app.sem.wait_one_timeout(Duration::from_millis(200));
+        unreachable!();
+    }
+
+    // The second activation
+    app.seq.expect_and_replace(5, 6);
+    assert!(!app.mtx.is_locked().unwrap());
+    D::success();
+}
@@ -0,0 +1,85 @@
+//! Checks that tasks having the same priority are scheduled in a round-robin
+//! fashion when they have time slices.
+//!
+//! 1. (`seq`: 0 → 1) `task1` starts running and spins until `seq` reaches
+//!    `2`.
+//! 2. (`seq`: 1 → 2) `task1` exhausts its time slice and is moved to the back
+//!    of the ready queue. `task2` starts running and spins until `seq` reaches
+//!    `3`.
+//! 3. (`seq`: 2 → 3) `task2` exhausts its time slice. `task3` starts running
+//!    and exits. `task3` doesn't have a time slice but doesn't need one.
+//! 4. (`seq`: 3 → 4) `task1` resumes and exits.
+//! 5. (`seq`: 4 → 5) `task2` resumes and exits.
+//!
+use core::hint::spin_loop;
+use r3::{
+    hunk::Hunk,
+    kernel::{traits, Cfg, StaticTask},
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .time_slice(Duration::from_millis(10))
+            .finish(b);
+        StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .time_slice(Duration::from_millis(10))
+            .finish(b);
+        StaticTask::define()
+            .start(task3_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { seq }
+    }
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(0, 1);
+
+    // Spin until `task2` runs. This requires `task1` to be preempted.
+    while D::app().seq.get() < 2 {
+        spin_loop();
+    }
+
+    D::app().seq.expect_and_replace(3, 4);
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(1, 2);
+
+    // Spin until `task3` runs. This requires `task2` to be preempted.
+    while D::app().seq.get() < 3 {
+        spin_loop();
+    }
+
+    D::app().seq.expect_and_replace(4, 5);
+    D::success();
+}
+
+fn task3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().seq.expect_and_replace(2, 3);
+}
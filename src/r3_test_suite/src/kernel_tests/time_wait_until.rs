@@ -0,0 +1,152 @@
+//! Validates the behavior of the blocking operations taking absolute
+//! deadlines, such as `sleep_until`.
+//!
+//! 1. (`seq`: 0 → 1, 100ms) `task1` checks the error codes and the handling of
+//!    deadlines in the past.
+//! 2. (`seq`: 1 → 2, 250ms) `task1` calls `sleep_until` periodically and
+//!    checks that it doesn't accumulate a drift.
+//! 3. (`seq`: 2 → 3, 350ms) `task1` waits on `sem` until a deadline, which
+//!    times out.
+//! 4. (`seq`: 3 → 5, 350ms) `task1` activates `task2`, which starts sleeping
+//!    until system time 650ms.
+//! 5. (`seq`: 5 → 6, 500ms) `task1` changes the system time to 500ms using
+//!    `set_time`. This doesn't affect the wake-up time of `task2`. 50ms later,
+//!    `task1` changes the system time to 650ms using `adjust_time`, which
+//!    brings forward the wake-up time of `task2` by 100ms.
+//! 6. (`seq`: 6 → 7, 800ms) `task2` wakes up.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, SleepError, StaticSemaphore, StaticTask, WaitSemaphoreTimeoutError,
+    },
+    time::{Duration, Time},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelWaitUntil + traits::KernelAdjustTime + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelWaitUntil
+            + traits::KernelAdjustTime
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    task2: StaticTask<System>,
+    sem: StaticSemaphore<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgSemaphore,
+    {
+        StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task2 = StaticTask::define()
+            .start(task2_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let sem = StaticSemaphore::define().maximum(1).initial(0).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App { task2, sem, seq }
+    }
+}
+
+fn assert_time_ms_range<System: SupportedSystem>(range: core::ops::Range<u64>) {
+    let t = System::time().unwrap().as_millis();
+    log::trace!("time = {:?}ms (expected = {:?}ms)", t, range);
+    assert!(
+        range.contains(&t),
+        "time = {t:?}ms (expected = {range:?}ms)"
+    );
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+
+    app.seq.expect_and_replace(0, 1);
+    System::set_time(Time::from_millis(100)).unwrap();
+
+    // A deadline too far in the future
+    assert_eq!(
+        System::sleep_until(Time::from_secs(100_000)),
+        Err(SleepError::BadParam)
+    );
+    assert_eq!(
+        app.sem.wait_one_until(Time::from_secs(100_000)),
+        Err(WaitSemaphoreTimeoutError::BadParam)
+    );
+
+    // A deadline in the past. `sleep_until` returns immediately.
+    System::sleep_until(Time::from_millis(50)).unwrap();
+    assert_time_ms_range::<System>(100..150);
+
+    // A deadline in the past. `wait_one_until` still takes a permit if it can
+    // do so without blocking.
+    assert_eq!(
+        app.sem.wait_one_until(Time::from_millis(50)),
+        Err(WaitSemaphoreTimeoutError::Timeout)
+    );
+    app.sem.signal_one().unwrap();
+    app.sem.wait_one_until(Time::from_millis(50)).unwrap();
+
+    // Periodic wake-up. The time spent between the calls doesn't accumulate.
+    app.seq.expect_and_replace(1, 2);
+    let mut deadline = Time::from_millis(100);
+    for _ in 0..3 {
+        deadline += Duration::from_millis(50);
+        System::sleep_until(deadline).unwrap();
+        let t = deadline.as_millis();
+        assert_time_ms_range::<System>(t..t + 50);
+    }
+
+    // Time out at the deadline
+    app.seq.expect_and_replace(2, 3);
+    assert_eq!(
+        app.sem.wait_one_until(Time::from_millis(350)),
+        Err(WaitSemaphoreTimeoutError::Timeout)
+    );
+    assert_time_ms_range::<System>(350..400);
+
+    // `task2` starts sleeping
+    app.seq.expect_and_replace(3, 4);
+    app.task2.activate().unwrap();
+
+    // `set_time` doesn't affect the deadline, which has already been
+    // converted to a relative timeout
+    app.seq.expect_and_replace(5, 6);
+    System::set_time(Time::from_millis(500)).unwrap();
+
+    // `adjust_time` moves the deadline along with the system time
+    System::sleep(Duration::from_millis(50)).unwrap();
+    System::adjust_time(Duration::from_millis(100)).unwrap();
+}
+
+fn task2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+
+    // Start sleeping at system time 350ms
+    app.seq.expect_and_replace(4, 5);
+    System::sleep_until(Time::from_millis(650)).unwrap();
+
+    // The remaining 300ms were shortened by 100ms by `adjust_time`
+    app.seq.expect_and_replace(6, 7);
+    assert_time_ms_range::<System>(750..850);
+
+    D::success();
+}
@@ -0,0 +1,137 @@
+//! Checks that timers defined with `TimerContext::Task` have their callback
+//! functions called in a task context, in the order of expiration.
+//!
+//! 1. (`seq`: 0 → 1) `task` starts waiting on `sem`.
+//! 2. (`seq`: 1 → 2) `timer1` fires at 20ms. Its callback function locks
+//!    `mutex` and sleeps for 30ms.
+//! 3. `timer2` and `timer3` fire at 30ms and 40ms while `timer1`'s callback
+//!    function is still sleeping.
+//! 4. (`seq`: 2 → 3) `timer1`'s callback function wakes up, stops `timer3`,
+//!    and returns. `timer3`'s callback function is never called.
+//! 5. (`seq`: 3 → 4) `timer2`'s callback function is called and signals `sem`.
+//! 6. (`seq`: 4 → 5) `task` wakes up and completes the test.
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, StaticMutex, StaticSemaphore, StaticTask, StaticTimer,
+        TimerContext,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase
+    + traits::KernelMutex
+    + traits::KernelSemaphore
+    + traits::KernelTimer
+    + traits::KernelStatic
+{
+}
+impl<
+        T: traits::KernelBase
+            + traits::KernelMutex
+            + traits::KernelSemaphore
+            + traits::KernelTimer
+            + traits::KernelStatic,
+    > SupportedSystem for T
+{
+}
+
+pub struct App<System: SupportedSystem> {
+    timer3: StaticTimer<System>,
+    mutex: StaticMutex<System>,
+    sem: StaticSemaphore<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>
+            + ~const traits::CfgTimer
+            + ~const traits::CfgMutex
+            + ~const traits::CfgSemaphore,
+    {
+        StaticTask::define()
+            .start(task_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let context = TimerContext::Task {
+            priority: 1,
+            stack_size: None,
+        };
+        StaticTimer::define()
+            .delay(Duration::from_millis(20))
+            .active(true)
+            .context(context)
+            .start(timer1_body::<System, D>)
+            .finish(b);
+        StaticTimer::define()
+            .delay(Duration::from_millis(30))
+            .active(true)
+            .context(context)
+            .start(timer2_body::<System, D>)
+            .finish(b);
+        let timer3 = StaticTimer::define()
+            .delay(Duration::from_millis(40))
+            .active(true)
+            .context(context)
+            .start(timer3_body)
+            .finish(b);
+
+        let mutex = StaticMutex::define().finish(b);
+        let sem = StaticSemaphore::define().initial(0).maximum(1).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            timer3,
+            mutex,
+            sem,
+            seq,
+        }
+    }
+}
+
+fn task_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    app.sem.wait_one().unwrap();
+
+    app.seq.expect_and_replace(4, 5);
+    D::success();
+}
+
+fn timer1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    assert!(System::is_task_context());
+    assert!(!System::has_cpu_lock());
+    app.seq.expect_and_replace(1, 2);
+
+    // Blocking operations are allowed in a task context
+    app.mutex.lock().unwrap();
+    System::sleep(Duration::from_millis(30)).unwrap();
+    app.mutex.unlock().unwrap();
+
+    // `timer3`'s expiration is discarded
+    app.timer3.stop().unwrap();
+
+    app.seq.expect_and_replace(2, 3);
+}
+
+fn timer2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    assert!(System::is_task_context());
+    app.seq.expect_and_replace(3, 4);
+
+    app.sem.signal_one().unwrap();
+}
+
+fn timer3_body() {
+    unreachable!("the timer was stopped before the callback function was called");
+}
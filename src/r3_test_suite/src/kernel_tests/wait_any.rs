@@ -0,0 +1,203 @@
+//! Validates error codes returned by `Kernel::wait_any[_timeout]` and checks
+//! that a task waiting on multiple objects is woken up by whichever object
+//! satisfies its condition first.
+//!
+//! 1. (`seq`: 0 → 1) `task0` checks the error codes and completes some wait
+//!    operations without blocking.
+//! 2. (`seq`: 1 → 2) `task0` activates `task1`, which starts waiting on `sem`,
+//!    `eg`, and `mq`.
+//! 3. (`seq`: 2 → 4) `task0` sends a message to `mq`. `task1` wakes up and
+//!    receives the message. `task1` starts waiting on `eg` and `mq`.
+//! 4. (`seq`: 4 → 6) `task0` signals `sem`, which doesn't have a waiter
+//!    anymore. `task0` sets bits in `eg`, waking up `task1`. `task1` sees its
+//!    next wait operation time out.
+//! 5. (`seq`: 6 → 7) `task1` takes the permit `task0` deposited to `sem`.
+//!
+use r3::{
+    hunk::Hunk,
+    kernel::{
+        prelude::*, traits, Cfg, EventGroupBits, EventGroupWaitFlags, StaticEventGroup,
+        StaticMessageQueue, StaticSemaphore, StaticTask, WaitAny, WaitAnyError,
+        WaitAnyTimeoutError,
+    },
+    time::Duration,
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem:
+    traits::KernelBase + traits::KernelWaitAny + traits::KernelStatic
+{
+}
+impl<T: traits::KernelBase + traits::KernelWaitAny + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    task1: StaticTask<System>,
+    sem: StaticSemaphore<System>,
+    eg: StaticEventGroup<System>,
+    mq: StaticMessageQueue<System, u32>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self, System = System>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System>
+            + ~const traits::CfgSemaphore
+            + ~const traits::CfgEventGroup
+            + ~const traits::CfgMessageQueue,
+    {
+        StaticTask::define()
+            .start(task0_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+        let task1 = StaticTask::define()
+            .start(task1_body::<System, D>)
+            .priority(1)
+            .finish(b);
+
+        let sem = StaticSemaphore::define().maximum(1).initial(0).finish(b);
+        let eg = StaticEventGroup::define().finish(b);
+        let mq = StaticMessageQueue::define().capacity(1).finish(b);
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            task1,
+            sem,
+            eg,
+            mq,
+            seq,
+        }
+    }
+}
+
+fn task0_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+    let mut bits: EventGroupBits = 0;
+
+    app.seq.expect_and_replace(0, 1);
+
+    // CPU Lock active
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(
+        System::wait_any([WaitAny::semaphore(&app.sem)]),
+        Err(WaitAnyError::BadContext)
+    );
+    assert_eq!(
+        System::wait_any_timeout([WaitAny::semaphore(&app.sem)], Duration::from_millis(100)),
+        Err(WaitAnyTimeoutError::BadContext)
+    );
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    // No conditions
+    assert_eq!(System::wait_any([]), Err(WaitAnyError::BadParam));
+
+    // The same object specified twice
+    assert_eq!(
+        System::wait_any([WaitAny::semaphore(&app.sem), WaitAny::semaphore(&app.sem)]),
+        Err(WaitAnyError::BadParam)
+    );
+
+    // Negative timeout
+    assert_eq!(
+        System::wait_any_timeout([WaitAny::semaphore(&app.sem)], Duration::from_micros(-1)),
+        Err(WaitAnyTimeoutError::BadParam)
+    );
+
+    // The conditions are already satisfied. The first one is chosen.
+    app.sem.signal_one().unwrap();
+    app.eg.set(0b1).unwrap();
+    assert_eq!(
+        System::wait_any([
+            WaitAny::semaphore(&app.sem),
+            WaitAny::event_group(&app.eg, 0b1, EventGroupWaitFlags::CLEAR, &mut bits),
+        ]),
+        Ok(0)
+    );
+    assert_eq!(app.sem.get().unwrap(), 0);
+    assert_eq!(app.eg.get().unwrap(), 0b1);
+
+    assert_eq!(
+        System::wait_any([
+            WaitAny::semaphore(&app.sem),
+            WaitAny::event_group(&app.eg, 0b1, EventGroupWaitFlags::CLEAR, &mut bits),
+        ]),
+        Ok(1)
+    );
+    assert_eq!(bits, 0b1);
+    assert_eq!(app.eg.get().unwrap(), 0);
+
+    // `task1` starts waiting on `sem`, `eg`, and `mq`
+    app.task1.activate().unwrap();
+
+    // Wake up `task1` through `mq`
+    app.seq.expect_and_replace(2, 3);
+    app.mq.send(42).unwrap();
+
+    // `task1` is waiting on `eg` and `mq`. It isn't waiting on `sem` anymore.
+    app.seq.expect_and_replace(4, 5);
+    app.sem.signal_one().unwrap();
+    assert_eq!(app.sem.get().unwrap(), 1);
+
+    // Wake up `task1` through `eg`
+    app.eg.set(0b010).unwrap();
+    app.eg.set(0b100).unwrap();
+}
+
+fn task1_body<System: SupportedSystem, D: Driver<App<System>, System = System>>() {
+    let app = D::app();
+    let mut bits: EventGroupBits = 0;
+    let mut message = 0;
+
+    app.seq.expect_and_replace(1, 2);
+    assert_eq!(
+        System::wait_any([
+            WaitAny::semaphore(&app.sem),
+            WaitAny::event_group(&app.eg, 0b10, EventGroupWaitFlags::empty(), &mut bits),
+            WaitAny::message_queue(app.mq, &mut message),
+        ]),
+        Ok(2)
+    );
+    assert_eq!(message, 42);
+    app.seq.expect_and_replace(3, 4);
+
+    assert_eq!(
+        System::wait_any_timeout(
+            [
+                WaitAny::message_queue(app.mq, &mut message),
+                WaitAny::event_group(
+                    &app.eg,
+                    0b110,
+                    EventGroupWaitFlags::ALL | EventGroupWaitFlags::CLEAR,
+                    &mut bits,
+                ),
+            ],
+            Duration::from_millis(500),
+        ),
+        Ok(1)
+    );
+    assert_eq!(bits, 0b110);
+    assert_eq!(app.eg.get().unwrap(), 0);
+    app.seq.expect_and_replace(5, 6);
+
+    // Time out
+    assert_eq!(
+        System::wait_any_timeout(
+            [
+                WaitAny::message_queue(app.mq, &mut message),
+                WaitAny::event_group(&app.eg, 0b1, EventGroupWaitFlags::empty(), &mut bits),
+            ],
+            Duration::from_millis(100),
+        ),
+        Err(WaitAnyTimeoutError::Timeout)
+    );
+
+    // Take the permit deposited to `sem`
+    app.seq.expect_and_replace(6, 7);
+    assert_eq!(System::wait_any([WaitAny::semaphore(&app.sem)]), Ok(0));
+    assert_eq!(app.sem.get().unwrap(), 0);
+
+    D::success();
+}
@@ -0,0 +1,117 @@
+//! Cancels a delayed work item repeatedly while a periodic timer keeps
+//! resubmitting it from an interrupt context, and checks that a resubmission
+//! that preempts [`StaticDelayedWorkItem::cancel`] is not lost.
+//!
+//! After each cancellation, if the work item is pending again, `task` waits
+//! for the work item to be called. A resubmission whose timer was stopped by
+//! the preempted `cancel` would leave the work item pending without ever
+//! calling it.
+//!
+//! Whether the timer interrupt actually lands in the middle of `cancel`
+//! depends on the timing, so this test doesn't reproduce such an interleaving
+//! on every run.
+use core::sync::atomic::{AtomicUsize, Ordering};
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask, StaticTimer},
+    time::Duration,
+    workqueue::{StaticDelayedWorkItem, StaticWorkQueue},
+};
+
+use super::Driver;
+
+/// The number of times the timer fires before the test completes
+const NUM_TICKS: usize = 500;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelTimer + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelTimer + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    timer: StaticTimer<System>,
+    dwork: StaticDelayedWorkItem<System>,
+    /// The number of times `timer_body` was called
+    ticks: Hunk<System, AtomicUsize>,
+    /// The number of times `dwork_body` was called
+    count: Hunk<System, AtomicUsize>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgTimer,
+    {
+        StaticTask::define()
+            .start(task_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let timer = StaticTimer::define()
+            .delay(Duration::from_millis(0))
+            .period(Duration::from_millis(1))
+            .start(timer_body::<System, D>)
+            .active(true)
+            .finish(b);
+
+        let queue = StaticWorkQueue::define().priority(1).capacity(1).finish(b);
+        let dwork = StaticDelayedWorkItem::define()
+            .queue(queue)
+            .start(dwork_body::<System, D>)
+            .finish_delayed(b);
+
+        let ticks = Hunk::<_, AtomicUsize>::define().finish(b);
+        let count = Hunk::<_, AtomicUsize>::define().finish(b);
+
+        App {
+            timer,
+            dwork,
+            ticks,
+            count,
+        }
+    }
+}
+
+fn task_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    let mut num_resubmissions = 0;
+
+    while app.ticks.load(Ordering::Relaxed) < NUM_TICKS {
+        if app.dwork.cancel().is_err() {
+            continue;
+        }
+
+        if app.dwork.is_pending() {
+            // `timer_body` resubmitted the work item during or right after
+            // the cancellation. It must be called eventually.
+            num_resubmissions += 1;
+            let count = app.count.load(Ordering::Relaxed);
+            System::sleep(Duration::from_millis(30)).unwrap();
+            assert_ne!(
+                app.count.load(Ordering::Relaxed),
+                count,
+                "the resubmitted work item was never called"
+            );
+        }
+    }
+
+    log::debug!("{num_resubmissions} resubmission(s) observed");
+
+    app.timer.stop().unwrap();
+    System::sleep(Duration::from_millis(30)).unwrap();
+    assert!(!app.dwork.is_pending());
+
+    D::success();
+}
+
+fn timer_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.ticks.fetch_add(1, Ordering::Relaxed);
+
+    // This fails with `AlreadyQueued` unless `task` has just cancelled the
+    // work item
+    let _ = app.dwork.submit_after(Duration::from_millis(2));
+}
+
+fn dwork_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    D::app().count.fetch_add(1, Ordering::Relaxed);
+}
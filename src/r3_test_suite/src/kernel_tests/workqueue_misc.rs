@@ -0,0 +1,150 @@
+//! Checks miscellaneous properties of [`r3::workqueue`].
+//!
+//! 1. (`seq`: 0 → 1) `task` submits `work1` and `work2`, fills up the queue,
+//!    cancels `work1`, and submits `work3` and `dwork` (delayed by 20ms).
+//!    `task` has a higher priority than the worker task, so nothing runs
+//!    until `task` sleeps.
+//! 2. (`seq`: 1 → 2 → 3) The worker task calls `work2` and `work3`.
+//! 3. (`seq`: 3 → 4) `dwork`'s timer submits `dwork` in an interrupt context.
+//!    `dwork` submits `work1`.
+//! 4. (`seq`: 4 → 5) The worker task calls `work1`.
+//! 5. (`seq`: 5 → 6) `task` wakes up and checks that cancelling `dwork`
+//!    prevents it from being called.
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask},
+    time::Duration,
+    workqueue::{CancelError, StaticDelayedWorkItem, StaticWorkItem, StaticWorkQueue, SubmitError},
+};
+
+use super::Driver;
+use crate::utils::SeqTracker;
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelTimer + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelTimer + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    work1: StaticWorkItem<System>,
+    work2: StaticWorkItem<System>,
+    work3: StaticWorkItem<System>,
+    dwork: StaticDelayedWorkItem<System>,
+    seq: Hunk<System, SeqTracker>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgTimer,
+    {
+        StaticTask::define()
+            .start(task_body::<System, D>)
+            .priority(0)
+            .active(true)
+            .finish(b);
+
+        let queue = StaticWorkQueue::define().priority(1).capacity(2).finish(b);
+        let work1 = StaticWorkItem::define()
+            .queue(queue)
+            .start(work1_body::<System, D>)
+            .finish(b);
+        let work2 = StaticWorkItem::define()
+            .queue(queue)
+            .start(work2_body::<System, D>)
+            .finish(b);
+        let work3 = StaticWorkItem::define()
+            .queue(queue)
+            .start(work3_body::<System, D>)
+            .finish(b);
+        let dwork = StaticDelayedWorkItem::define()
+            .queue(queue)
+            .start(dwork_body::<System, D>)
+            .finish_delayed(b);
+
+        let seq = Hunk::<_, SeqTracker>::define().finish(b);
+
+        App {
+            work1,
+            work2,
+            work3,
+            dwork,
+            seq,
+        }
+    }
+}
+
+fn task_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(0, 1);
+
+    app.work1.submit().unwrap();
+    assert!(app.work1.is_pending());
+    assert_eq!(app.work1.submit(), Err(SubmitError::AlreadyQueued));
+    app.work2.submit().unwrap();
+    assert_eq!(app.work3.submit(), Err(SubmitError::QueueFull));
+    assert!(!app.work3.is_pending());
+
+    app.work1.cancel().unwrap();
+    assert!(!app.work1.is_pending());
+    assert_eq!(app.work1.cancel(), Err(CancelError::NotQueued));
+    app.work3.submit().unwrap();
+
+    assert_eq!(
+        app.dwork.submit_after(Duration::from_millis(-1)),
+        Err(SubmitError::BadParam)
+    );
+    app.dwork.submit_after(Duration::from_millis(20)).unwrap();
+    assert!(app.dwork.is_pending());
+    assert_eq!(app.dwork.submit(), Err(SubmitError::AlreadyQueued));
+    assert_eq!(
+        app.dwork.submit_after(Duration::from_millis(20)),
+        Err(SubmitError::AlreadyQueued)
+    );
+
+    // Submission requires CPU Lock to be inactive, but querying doesn't
+    System::acquire_cpu_lock().unwrap();
+    assert_eq!(app.work1.submit(), Err(SubmitError::BadContext));
+    assert_eq!(app.work2.cancel(), Err(CancelError::BadContext));
+    assert_eq!(app.dwork.cancel(), Err(CancelError::BadContext));
+    assert!(app.dwork.is_pending());
+    assert!(app.work2.is_pending());
+    unsafe { System::release_cpu_lock().unwrap() };
+
+    System::sleep(Duration::from_millis(50)).unwrap();
+    app.seq.expect_and_replace(5, 6);
+
+    // A cancelled delayed work item is not called
+    app.dwork.submit_after(Duration::from_millis(10)).unwrap();
+    app.dwork.cancel().unwrap();
+    assert!(!app.dwork.is_pending());
+    System::sleep(Duration::from_millis(30)).unwrap();
+    assert_eq!(app.seq.get(), 6);
+
+    D::success();
+}
+
+fn work1_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    assert!(System::is_task_context());
+    assert!(!app.work1.is_pending());
+    app.seq.expect_and_replace(4, 5);
+}
+
+fn work2_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    assert!(System::is_task_context());
+    app.seq.expect_and_replace(1, 2);
+}
+
+fn work3_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.seq.expect_and_replace(2, 3);
+}
+
+fn dwork_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    assert!(System::is_task_context());
+    assert!(!app.dwork.is_pending());
+    app.seq.expect_and_replace(3, 4);
+
+    app.work1.submit().unwrap();
+}
@@ -0,0 +1,125 @@
+//! Submits a delayed work item repeatedly while a periodic timer keeps
+//! cancelling and resubmitting it with a long delay from an interrupt context,
+//! and checks that a resubmission that preempts
+//! [`StaticDelayedWorkItem::submit_after`] is not overridden by the preempted
+//! call.
+//!
+//! `long_pending` is set while the latest submission is the one made by
+//! `timer_body`. Its delay doesn't elapse before the test completes, so the
+//! work item must not be called while `long_pending` is set. A preempted
+//! `submit_after` that restarted the timer with its own short delay after the
+//! resubmission would cause the work item to be called early.
+//!
+//! Whether the timer interrupt actually lands in the middle of `submit_after`
+//! depends on the timing, so this test doesn't reproduce such an interleaving
+//! on every run.
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use r3::{
+    hunk::Hunk,
+    kernel::{prelude::*, traits, Cfg, StaticTask, StaticTimer},
+    time::Duration,
+    workqueue::{StaticDelayedWorkItem, StaticWorkQueue},
+};
+
+use super::Driver;
+
+/// The number of times the timer fires before the test completes
+const NUM_TICKS: usize = 300;
+
+/// The delay used by `task`
+const SHORT_DELAY: Duration = Duration::from_millis(2);
+
+/// The delay used by `timer_body`, which doesn't elapse before the test
+/// completes
+const LONG_DELAY: Duration = Duration::from_secs(10);
+
+pub trait SupportedSystem: traits::KernelBase + traits::KernelTimer + traits::KernelStatic {}
+impl<T: traits::KernelBase + traits::KernelTimer + traits::KernelStatic> SupportedSystem for T {}
+
+pub struct App<System: SupportedSystem> {
+    timer: StaticTimer<System>,
+    dwork: StaticDelayedWorkItem<System>,
+    /// The number of times `timer_body` was called
+    ticks: Hunk<System, AtomicUsize>,
+    /// Set if the latest submission was made by `timer_body`
+    long_pending: Hunk<System, AtomicBool>,
+}
+
+impl<System: SupportedSystem> App<System> {
+    pub const fn new<C, D: Driver<Self>>(b: &mut Cfg<C>) -> Self
+    where
+        C: ~const traits::CfgTask<System = System> + ~const traits::CfgTimer,
+    {
+        StaticTask::define()
+            .start(task_body::<System, D>)
+            .priority(2)
+            .active(true)
+            .finish(b);
+
+        let timer = StaticTimer::define()
+            .delay(Duration::from_millis(0))
+            .period(Duration::from_millis(1))
+            .start(timer_body::<System, D>)
+            .active(true)
+            .finish(b);
+
+        let queue = StaticWorkQueue::define().priority(1).capacity(1).finish(b);
+        let dwork = StaticDelayedWorkItem::define()
+            .queue(queue)
+            .start(dwork_body::<System, D>)
+            .finish_delayed(b);
+
+        let ticks = Hunk::<_, AtomicUsize>::define().finish(b);
+        let long_pending = Hunk::<_, AtomicBool>::define().finish(b);
+
+        App {
+            timer,
+            dwork,
+            ticks,
+            long_pending,
+        }
+    }
+}
+
+fn task_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    let mut num_submissions = 0;
+
+    while app.ticks.load(Ordering::Relaxed) < NUM_TICKS {
+        let _ = app.dwork.cancel();
+        app.long_pending.store(false, Ordering::Relaxed);
+
+        // This fails with `AlreadyQueued` if `timer_body` has resubmitted the
+        // work item since the cancellation
+        if app.dwork.submit_after(SHORT_DELAY).is_ok() {
+            num_submissions += 1;
+        }
+
+        // Let the work item be called if the delay elapses
+        System::sleep(SHORT_DELAY + Duration::from_millis(1)).unwrap();
+    }
+
+    log::debug!("{num_submissions} submission(s) made");
+
+    app.timer.stop().unwrap();
+    let _ = app.dwork.cancel();
+
+    D::success();
+}
+
+fn timer_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    let app = D::app();
+    app.ticks.fetch_add(1, Ordering::Relaxed);
+
+    if app.dwork.cancel().is_ok() {
+        app.long_pending.store(true, Ordering::Relaxed);
+        app.dwork.submit_after(LONG_DELAY).unwrap();
+    }
+}
+
+fn dwork_body<System: SupportedSystem, D: Driver<App<System>>>() {
+    assert!(
+        !D::app().long_pending.load(Ordering::Relaxed),
+        "the work item was called before the latest submission's delay elapsed"
+    );
+}
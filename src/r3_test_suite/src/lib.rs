@@ -42,6 +42,8 @@ pub mod kernel_tests {
         //        Cargo feature
         type System: raw::KernelBase
             + raw::KernelEventGroup
+            + raw::KernelMemoryPool
+            + raw::KernelMessageQueue
             + raw::KernelMutex
             + raw::KernelSemaphore
             + raw::KernelTimer;
@@ -66,6 +68,14 @@ pub mod kernel_tests {
         /// [2]: r3::kernel::Kernel::adjust_time
         const TIME_USER_HEADROOM_IS_EXACT: bool = false;
 
+        /// Indicates whether tasks run on the stacks allocated by the kernel.
+        ///
+        /// Setting this to `false` disables the assertions on the stack usage
+        /// reported by [`Task::stack_high_water_mark`][1].
+        ///
+        /// [1]: r3::kernel::task::TaskMethods::stack_high_water_mark
+        const TASKS_USE_KERNEL_STACKS: bool = true;
+
         /// The list of interrupt lines that can be used by test programs.
         ///
         ///  - The list can have an arbitrary number of elements. Some tests
@@ -139,6 +149,22 @@ pub mod kernel_tests {
             None
         }
 
+        /// Create a `RawMemoryPoolId` for which the kernel functions will
+        /// return `Err(NoAccess)`. Returns `None` if this property cannot be
+        /// guarnateed for any object IDs.
+        fn bad_raw_memory_pool_id(
+        ) -> Option<<Self::System as raw::KernelMemoryPool>::RawMemoryPoolId> {
+            None
+        }
+
+        /// Create a `RawMessageQueueId` for which the kernel functions will
+        /// return `Err(NoAccess)`. Returns `None` if this property cannot be
+        /// guarnateed for any object IDs.
+        fn bad_raw_message_queue_id(
+        ) -> Option<<Self::System as raw::KernelMessageQueue>::RawMessageQueueId> {
+            None
+        }
+
         /// Create a `RawMutexId` for which the kernel functions will
         /// return `Err(NoAccess)`. Returns `None` if this property cannot be
         /// guarnateed for any object IDs.
@@ -259,6 +285,7 @@ pub mod kernel_tests {
         (mod event_group_set_and_dispatch {}, "event_group_set_and_dispatch"),
         (mod event_group_timeout {}, "event_group_timeout"),
         (mod event_group_wait_types {}, "event_group_wait_types"),
+        (mod executor_misc {}, "executor_misc"),
         (mod interrupt_disallowed_services {}, "interrupt_disallowed_services"),
         (mod interrupt_during_boot {}, "interrupt_during_boot"),
         (mod interrupt_handler_priority {}, "interrupt_handler_priority"),
@@ -266,9 +293,22 @@ pub mod kernel_tests {
         (mod interrupt_priority {}, "interrupt_priority"),
         (mod interrupt_task_activate {}, "interrupt_task_activate"),
         (mod interrupt_unmanaged {}, "interrupt_unmanaged"),
+        (mod memory_pool_misc {}, "memory_pool_misc"),
+        (mod memory_pool_release_and_dispatch {}, "memory_pool_release_and_dispatch"),
+        (mod memory_pool_timeout {}, "memory_pool_timeout"),
+        (mod message_queue_interrupt_handler {}, "message_queue_interrupt_handler"),
+        (mod message_queue_misc {}, "message_queue_misc"),
+        (mod message_queue_order {}, "message_queue_order"),
+        (mod message_queue_send_and_dispatch {}, "message_queue_send_and_dispatch"),
+        (mod message_queue_timeout {}, "message_queue_timeout"),
+        (mod message_queue_zero_capacity {}, "message_queue_zero_capacity"),
+        #[cfg(feature = "deadlock_detection")]
+        (mod mutex_deadlock {}, "mutex_deadlock"),
+        (mod mutex_inherit_transitive {}, "mutex_inherit_transitive"),
         (mod mutex_misc {}, "mutex_misc"),
         (mod mutex_nesting {}, "mutex_nesting"),
         (mod mutex_protect_priority_by_ceiling {}, "mutex_protect_priority_by_ceiling"),
+        (mod mutex_protect_priority_by_inheritance {}, "mutex_protect_priority_by_inheritance"),
         (mod mutex_timeout {}, "mutex_timeout"),
         (mod mutex_unlock_and_dispatch {}, "mutex_unlock_and_dispatch"),
         (mod priority_boost {}, "priority_boost"),
@@ -280,14 +320,28 @@ pub mod kernel_tests {
         (mod startup_hook_misc {}, "startup_hook_misc"),
         (mod startup_hook_pend_interrupt {}, "startup_hook_pend_interrupt"),
         (mod startup_hook_priority {}, "startup_hook_priority"),
+        (mod sync_channel_misc {}, "sync_channel_misc"),
+        (mod sync_condvar_misc {}, "sync_condvar_misc"),
         (mod sync_mutex_lock_and_dispatch {}, "sync_mutex_lock_and_dispatch"),
+        (mod sync_mutex_lock_timeout {}, "sync_mutex_lock_timeout"),
         (mod sync_mutex_misc {}, "sync_mutex_misc"),
+        #[cfg(feature = "deadlock_detection")]
+        (mod sync_recursive_mutex_deadlock {}, "sync_recursive_mutex_deadlock"),
         (mod sync_recursive_mutex_lock_and_dispatch {}, "sync_recursive_mutex_lock_and_dispatch"),
+        (mod sync_recursive_mutex_lock_timeout {}, "sync_recursive_mutex_lock_timeout"),
         (mod sync_recursive_mutex_misc {}, "sync_recursive_mutex_misc"),
+        (mod sync_rwlock_misc {}, "sync_rwlock_misc"),
+        #[cfg(feature = "system_time")]
+        (mod sync_rwlock_timeout {}, "sync_rwlock_timeout"),
         (mod task_activate_and_dispatch {}, "task_activate_and_dispatch"),
         (mod task_activate_and_do_not_dispatch {}, "task_activate_and_do_not_dispatch"),
+        (mod task_activate_queue {}, "task_activate_queue"),
+        #[cfg(feature = "runtime_stats")]
+        (mod task_cpu_time {}, "task_cpu_time"),
         (mod task_cpu_lock_reset {}, "task_cpu_lock_reset"),
+        (mod task_hooks {}, "task_hooks"),
         (mod task_misc {}, "task_misc"),
+        (mod task_notify {}, "task_notify"),
         (mod task_park {}, "task_park"),
         #[cfg(feature = "priority_boost")]
         (mod task_park_priority_boost {}, "task_park_priority_boost"),
@@ -296,7 +350,13 @@ pub mod kernel_tests {
         (mod task_priority_reset {}, "task_priority_reset"),
         (mod task_queue_fifo {}, "task_queue_fifo"),
         (mod task_set_priority {}, "task_set_priority"),
+        #[cfg(feature = "stack_check")]
+        (mod task_stack_high_water_mark {}, "task_stack_high_water_mark"),
+        (mod task_suspend {}, "task_suspend"),
+        (mod task_suspend_waiting {}, "task_suspend_waiting"),
         (mod task_take_interrupt_at_return {}, "task_take_interrupt_at_return"),
+        (mod task_terminate {}, "task_terminate"),
+        (mod task_time_slice {}, "task_time_slice"),
         (mod time_adjust_event {}, "time_adjust_event"),
         #[cfg(feature = "priority_boost")]
         (mod time_adjust_limits {}, "time_adjust_limits"),
@@ -304,11 +364,18 @@ pub mod kernel_tests {
         (mod time_set_event {}, "time_set_event"),
         #[cfg(feature = "system_time")]
         (mod time_stress {}, "time_stress"),
+        #[cfg(feature = "system_time")]
+        (mod time_wait_until {}, "time_wait_until"),
         (mod timer_misc {}, "timer_misc"),
         (mod timer_overdue {}, "timer_overdue"),
         (mod timer_periodic {}, "timer_periodic"),
         (mod timer_stop {}, "timer_stop"),
+        (mod timer_task_context {}, "timer_task_context"),
         (mod timer_zero_period {}, "timer_zero_period"),
+        (mod wait_any {}, "wait_any"),
+        (mod workqueue_cancel_race {}, "workqueue_cancel_race"),
+        (mod workqueue_misc {}, "workqueue_misc"),
+        (mod workqueue_submit_race {}, "workqueue_submit_race"),
     }
 
     /// Invoke the specified macro with a description of test cases
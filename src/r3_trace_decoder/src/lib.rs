@@ -0,0 +1,425 @@
+//! Decodes trace buffers recorded by the R3 original kernel's `trace` feature
+//! (`r3_kernel::trace`) into timelines.
+//!
+//! ```
+//! # let dump = [
+//! #     b'R', b'3', b'T', b'R', 1, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff,
+//! #     42, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1,
+//! # ];
+//! let trace = r3_trace_decoder::decode(&dump).unwrap();
+//! assert_eq!(trace.to_string(), "          42 µs  switch to task 1\n");
+//! ```
+use std::{error::Error, fmt};
+
+// [ref:trace_format] The following definitions must be kept in sync with
+// `r3_kernel::trace`.
+const MAGIC: [u8; 4] = *b"R3TR";
+const HEADER_LEN: usize = 16;
+const RECORD_LEN: usize = 12;
+const FLAG_NO_TICK_COUNT: u8 = 1;
+
+mod kind {
+    pub const EMPTY: u8 = 0;
+    pub const TASK_SWITCH: u8 = 1;
+    pub const TASK_STATE: u8 = 2;
+    pub const INTERRUPT_ENTER: u8 = 3;
+    pub const INTERRUPT_EXIT: u8 = 4;
+    pub const SEMAPHORE_SIGNAL: u8 = 5;
+    pub const SEMAPHORE_WAIT: u8 = 6;
+    pub const MUTEX_LOCK: u8 = 7;
+    pub const MUTEX_UNLOCK: u8 = 8;
+    pub const TIMER_FIRE: u8 = 9;
+}
+
+/// The contents of a trace buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    /// The recorded events, oldest first.
+    pub events: Vec<Event>,
+}
+
+/// An event recorded in a trace buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// The kernel's tick count (in microseconds) at the time of the event.
+    ///
+    /// The tick count recorded in the trace buffer wraps around. The decoder
+    /// extends it to 64 bits by assuming that no two consecutive events are
+    /// apart by more than one wrap-around period.
+    pub time: u64,
+    /// Set if the event was recorded without a tick count, which is the case
+    /// for interrupt handler activations. `time` is taken from the previous
+    /// event.
+    pub time_is_approximate: bool,
+    pub kind: EventKind,
+}
+
+/// The kind and the subject of an [`Event`]. The fields identify kernel
+/// objects by their one-based IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// The running task has changed. `None` means the processor became idle.
+    TaskSwitch { task: Option<u16> },
+    /// A task has transitioned into another state.
+    TaskState { task: u16, state: TaskState },
+    /// A port is about to call an interrupt handler.
+    InterruptEnter { line: u16 },
+    /// An interrupt handler has returned.
+    InterruptExit { line: u16 },
+    /// A semaphore is being signaled. `count` is saturated to `u16::MAX`.
+    SemaphoreSignal { semaphore: u16, count: u16 },
+    /// A task has started a wait or poll operation on a semaphore.
+    SemaphoreWait { semaphore: u16 },
+    /// A mutex has been given to a task.
+    MutexLock { mutex: u16, task: u16 },
+    /// A mutex is being released by its owner.
+    MutexUnlock { mutex: u16 },
+    /// A timer has fired.
+    TimerFire { timer: u16 },
+    /// An event unknown to this decoder.
+    Unknown { kind: u8, id: u16, arg: u16 },
+}
+
+/// A task state reported by [`EventKind::TaskState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Dormant,
+    Ready,
+    Running,
+    Waiting,
+    Suspended,
+    Unknown(u16),
+}
+
+/// An error returned by [`decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input doesn't start with a trace buffer header.
+    BadMagic,
+    /// The input is shorter than what the header indicates.
+    Truncated { expected_len: usize },
+    /// The header contains an out-of-range write position.
+    BadHead,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not a trace buffer (bad magic number)"),
+            Self::Truncated { expected_len } => {
+                write!(
+                    f,
+                    "the trace buffer is truncated (expected {expected_len} bytes)"
+                )
+            }
+            Self::BadHead => write!(f, "the trace buffer header is corrupted"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Decode the contents of a trace buffer.
+pub fn decode(buf: &[u8]) -> Result<Trace, DecodeError> {
+    let header = buf.get(..HEADER_LEN).ok_or(DecodeError::BadMagic)?;
+    if header[0..4] != MAGIC {
+        return Err(DecodeError::BadMagic);
+    }
+    let capacity = read_u32(&header[4..]) as usize;
+    let head = read_u32(&header[8..]) as usize;
+    let period = read_u32(&header[12..]) as u64 + 1;
+
+    let expected_len = HEADER_LEN + capacity * RECORD_LEN;
+    let records = buf
+        .get(HEADER_LEN..expected_len)
+        .ok_or(DecodeError::Truncated { expected_len })?;
+    if head >= capacity.max(1) {
+        return Err(DecodeError::BadHead);
+    }
+
+    // The slot at `head` holds the oldest record (or nothing)
+    let (newer, older) = records.split_at(head * RECORD_LEN);
+
+    let mut events = Vec::new();
+    let mut last_ticks = None;
+    let mut time = 0;
+    for record in older
+        .chunks_exact(RECORD_LEN)
+        .chain(newer.chunks_exact(RECORD_LEN))
+    {
+        let Some(kind) = decode_record(record) else { continue };
+
+        let time_is_approximate = record[8] & FLAG_NO_TICK_COUNT != 0;
+        if !time_is_approximate {
+            let ticks = read_u32(record) as u64;
+            time = match last_ticks {
+                None => ticks,
+                Some(last_ticks) if ticks >= last_ticks => time + (ticks - last_ticks),
+                Some(last_ticks) => time + (ticks + period - last_ticks),
+            };
+            last_ticks = Some(ticks);
+        }
+
+        events.push(Event {
+            time,
+            time_is_approximate,
+            kind,
+        });
+    }
+
+    Ok(Trace { events })
+}
+
+/// Decode a record slot. Returns `None` if the slot is empty.
+fn decode_record(record: &[u8]) -> Option<EventKind> {
+    let id = u16::from_le_bytes([record[4], record[5]]);
+    let arg = u16::from_le_bytes([record[6], record[7]]);
+    Some(match record[11] {
+        kind::EMPTY => return None,
+        kind::TASK_SWITCH => EventKind::TaskSwitch {
+            task: (id != 0).then_some(id),
+        },
+        kind::TASK_STATE => EventKind::TaskState {
+            task: id,
+            state: match arg {
+                0 => TaskState::Dormant,
+                1 => TaskState::Ready,
+                2 => TaskState::Running,
+                3 => TaskState::Waiting,
+                4 => TaskState::Suspended,
+                _ => TaskState::Unknown(arg),
+            },
+        },
+        kind::INTERRUPT_ENTER => EventKind::InterruptEnter { line: id },
+        kind::INTERRUPT_EXIT => EventKind::InterruptExit { line: id },
+        kind::SEMAPHORE_SIGNAL => EventKind::SemaphoreSignal {
+            semaphore: id,
+            count: arg,
+        },
+        kind::SEMAPHORE_WAIT => EventKind::SemaphoreWait { semaphore: id },
+        kind::MUTEX_LOCK => EventKind::MutexLock {
+            mutex: id,
+            task: arg,
+        },
+        kind::MUTEX_UNLOCK => EventKind::MutexUnlock { mutex: id },
+        kind::TIMER_FIRE => EventKind::TimerFire { timer: id },
+        kind => EventKind::Unknown { kind, id, arg },
+    })
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+/// Formats the trace as a timeline, one event per line.
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in self.events.iter() {
+            writeln!(f, "{event}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let approx = if self.time_is_approximate { "~" } else { "" };
+        write!(
+            f,
+            "{:>12} µs  {}",
+            format!("{approx}{}", self.time),
+            self.kind
+        )
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TaskSwitch { task: Some(task) } => write!(f, "switch to task {task}"),
+            Self::TaskSwitch { task: None } => write!(f, "switch to idle"),
+            Self::TaskState { task, state } => write!(f, "task {task} -> {state:?}"),
+            Self::InterruptEnter { line } => write!(f, "enter interrupt {line}"),
+            Self::InterruptExit { line } => write!(f, "exit interrupt {line}"),
+            Self::SemaphoreSignal { semaphore, count } => {
+                write!(f, "signal semaphore {semaphore} ({count})")
+            }
+            Self::SemaphoreWait { semaphore } => write!(f, "wait semaphore {semaphore}"),
+            Self::MutexLock { mutex, task } => write!(f, "lock mutex {mutex} by task {task}"),
+            Self::MutexUnlock { mutex } => write!(f, "unlock mutex {mutex}"),
+            Self::TimerFire { timer } => write!(f, "fire timer {timer}"),
+            Self::Unknown { kind, id, arg } => {
+                write!(f, "unknown event {kind} (id = {id}, arg = {arg})")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(head: u32, max_tick_count: u32, records: &[[u8; RECORD_LEN]]) -> Vec<u8> {
+        let mut buf = MAGIC.to_vec();
+        buf.extend((records.len() as u32).to_le_bytes());
+        buf.extend(head.to_le_bytes());
+        buf.extend(max_tick_count.to_le_bytes());
+        for record in records {
+            buf.extend(record);
+        }
+        buf
+    }
+
+    fn record(ticks: u32, kind: u8, id: u16, arg: u16) -> [u8; RECORD_LEN] {
+        let [t0, t1, t2, t3] = ticks.to_le_bytes();
+        let [i0, i1] = id.to_le_bytes();
+        let [a0, a1] = arg.to_le_bytes();
+        [t0, t1, t2, t3, i0, i1, a0, a1, 0, 0, 0, kind]
+    }
+
+    fn record_without_ticks(kind: u8, id: u16) -> [u8; RECORD_LEN] {
+        let mut record = record(0, kind, id, 0);
+        record[8] = FLAG_NO_TICK_COUNT;
+        record
+    }
+
+    #[test]
+    fn not_wrapped() {
+        let buf = buffer(
+            2,
+            u32::MAX,
+            &[
+                record(10, kind::TASK_SWITCH, 1, 0),
+                record(20, kind::TASK_STATE, 1, 3),
+                [0; RECORD_LEN],
+            ],
+        );
+        let trace = decode(&buf).unwrap();
+        assert_eq!(
+            trace.events,
+            [
+                Event {
+                    time: 10,
+                    time_is_approximate: false,
+                    kind: EventKind::TaskSwitch { task: Some(1) }
+                },
+                Event {
+                    time: 20,
+                    time_is_approximate: false,
+                    kind: EventKind::TaskState {
+                        task: 1,
+                        state: TaskState::Waiting
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn wrapped() {
+        // The oldest record is at `head`. The tick count wraps around after
+        // `999`.
+        let buf = buffer(
+            1,
+            999,
+            &[
+                record(5, kind::TIMER_FIRE, 2, 0),
+                record(990, kind::INTERRUPT_ENTER, 7, 0),
+                record(995, kind::INTERRUPT_EXIT, 7, 0),
+            ],
+        );
+        let trace = decode(&buf).unwrap();
+        let times: Vec<_> = trace.events.iter().map(|e| e.time).collect();
+        assert_eq!(times, [990, 995, 1005]);
+        assert_eq!(
+            trace.to_string(),
+            "         990 µs  enter interrupt 7\n\
+             \x20        995 µs  exit interrupt 7\n\
+             \x20       1005 µs  fire timer 2\n"
+        );
+    }
+
+    #[test]
+    fn object_events() {
+        let buf = buffer(
+            0,
+            u32::MAX,
+            &[
+                record(1, kind::SEMAPHORE_SIGNAL, 1, 2),
+                record(2, kind::SEMAPHORE_WAIT, 1, 0),
+                record(3, kind::MUTEX_LOCK, 3, 300),
+                record(4, kind::MUTEX_UNLOCK, 3, 0),
+                record(5, kind::TASK_SWITCH, 0, 0),
+                record(6, 200, 5, 6),
+            ],
+        );
+        let kinds: Vec<_> = decode(&buf)
+            .unwrap()
+            .events
+            .iter()
+            .map(|e| e.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::SemaphoreSignal {
+                    semaphore: 1,
+                    count: 2
+                },
+                EventKind::SemaphoreWait { semaphore: 1 },
+                EventKind::MutexLock {
+                    mutex: 3,
+                    task: 300
+                },
+                EventKind::MutexUnlock { mutex: 3 },
+                EventKind::TaskSwitch { task: None },
+                EventKind::Unknown {
+                    kind: 200,
+                    id: 5,
+                    arg: 6
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_tick_count() {
+        // Interrupt events don't have a tick count and inherit the time of the
+        // previous event
+        let buf = buffer(
+            0,
+            999,
+            &[
+                record_without_ticks(kind::INTERRUPT_ENTER, 7),
+                record(990, kind::TIMER_FIRE, 2, 0),
+                record_without_ticks(kind::INTERRUPT_EXIT, 7),
+                record(5, kind::TASK_SWITCH, 1, 0),
+            ],
+        );
+        let trace = decode(&buf).unwrap();
+        assert_eq!(
+            trace.to_string(),
+            "          ~0 µs  enter interrupt 7\n\
+             \x20        990 µs  fire timer 2\n\
+             \x20       ~990 µs  exit interrupt 7\n\
+             \x20       1005 µs  switch to task 1\n"
+        );
+    }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(decode(b"R3TR"), Err(DecodeError::BadMagic));
+        assert_eq!(
+            decode(&buffer(0, 0, &[[0; RECORD_LEN]; 2])[..20]),
+            Err(DecodeError::Truncated { expected_len: 40 })
+        );
+        assert_eq!(
+            decode(&buffer(2, 0, &[[0; RECORD_LEN]; 2])),
+            Err(DecodeError::BadHead)
+        );
+
+        let mut buf = buffer(0, 0, &[]);
+        buf[0] = b'X';
+        assert_eq!(decode(&buf), Err(DecodeError::BadMagic));
+    }
+}
@@ -0,0 +1,33 @@
+//! Prints the timeline of a trace buffer dumped from a target running the R3
+//! original kernel.
+//!
+//! ```text
+//! r3_trace_decoder trace.bin
+//! ```
+use std::{env, fs, process::ExitCode};
+
+fn main() -> ExitCode {
+    let Some(path) = env::args_os().nth(1) else {
+        eprintln!("usage: r3_trace_decoder TRACE_BUFFER_DUMP");
+        return ExitCode::FAILURE;
+    };
+
+    let buf = match fs::read(&path) {
+        Ok(buf) => buf,
+        Err(e) => {
+            eprintln!("error: failed to read {path:?}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match r3_trace_decoder::decode(&buf) {
+        Ok(trace) => {
+            print!("{trace}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Runs a kernel with a trace buffer on `r3_port_std` and checks the decoded
+//! timeline.
+//!
+//! 1. `task1` locks and unlocks `mutex`, and waits on `sem`.
+//! 2. `task2` signals `sem`, waking up `task1`.
+//! 3. `task1` starts `timer` and waits on `sem` again. `task2` exits.
+//! 4. `timer` signals `sem` in the timer interrupt, waking up `task1`.
+//! 5. `task1` dumps the trace buffer and shuts down the system.
+#![feature(const_refs_to_cell)]
+#![feature(const_trait_impl)]
+#![feature(const_mut_refs)]
+
+extern crate r3_core_ks as r3_core;
+
+use r3_core::{
+    kernel::{prelude::*, StaticMutex, StaticSemaphore, StaticTask, StaticTimer},
+    time::Duration,
+};
+use r3_trace_decoder::{EventKind, TaskState};
+use std::sync::Mutex;
+
+type System = r3_kernel::System<SystemTraits>;
+r3_port_std::use_port!(unsafe struct SystemTraits);
+
+struct Objects {
+    mutex: StaticMutex<System>,
+    sem: StaticSemaphore<System>,
+    timer: StaticTimer<System>,
+}
+
+const TRACE_BUFFER_CAPACITY: usize = 1024;
+
+static COTTAGE: Objects = r3_kernel::build!(SystemTraits, configure_app => Objects);
+
+const fn configure_app(b: &mut r3_kernel::Cfg<'_, SystemTraits>) -> Objects {
+    b.num_task_priority_levels(4);
+    b.raw().trace_buffer(TRACE_BUFFER_CAPACITY);
+
+    StaticTask::define()
+        .start(task1_body)
+        .priority(2)
+        .active(true)
+        .finish(b);
+    StaticTask::define()
+        .start(task2_body)
+        .priority(3)
+        .active(true)
+        .finish(b);
+
+    let mutex = StaticMutex::define().finish(b);
+    let sem = StaticSemaphore::define().initial(0).maximum(1).finish(b);
+    let timer = StaticTimer::define()
+        .start(timer_body)
+        .delay(Duration::from_millis(10))
+        .finish(b);
+
+    Objects { mutex, sem, timer }
+}
+
+static DUMP: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+fn task1_body() {
+    COTTAGE.mutex.lock().unwrap();
+    COTTAGE.mutex.unlock().unwrap();
+    COTTAGE.sem.wait_one().unwrap();
+
+    COTTAGE.timer.start().unwrap();
+    COTTAGE.sem.wait_one().unwrap();
+
+    let mut dump = DUMP.lock().unwrap();
+    dump.resize(
+        r3_kernel::trace::buffer_len_for_capacity(TRACE_BUFFER_CAPACITY),
+        0,
+    );
+    let len = r3_kernel::trace::dump::<SystemTraits>(&mut dump).unwrap();
+    assert_eq!(len, dump.len());
+    drop(dump);
+
+    r3_port_std::shutdown::<SystemTraits>();
+}
+
+fn task2_body() {
+    COTTAGE.sem.signal_one().unwrap();
+}
+
+fn timer_body() {
+    COTTAGE.sem.signal_one().unwrap();
+}
+
+#[test]
+fn timeline() {
+    let _ = env_logger::try_init();
+
+    port_std_impl::PORT_STATE.port_boot::<SystemTraits>();
+
+    let trace = r3_trace_decoder::decode(&DUMP.lock().unwrap()).unwrap();
+    // The rendered timeline has one line per event
+    assert_eq!(trace.to_string().lines().count(), trace.events.len());
+    let events: Vec<EventKind> = trace.events.iter().map(|e| e.kind).collect();
+
+    assert_subsequence(
+        &events,
+        &[
+            EventKind::TaskSwitch { task: Some(1) },
+            EventKind::MutexLock { mutex: 1, task: 1 },
+            EventKind::MutexUnlock { mutex: 1 },
+            EventKind::SemaphoreWait { semaphore: 1 },
+            EventKind::TaskState {
+                task: 1,
+                state: TaskState::Waiting,
+            },
+            EventKind::TaskSwitch { task: Some(2) },
+            EventKind::SemaphoreSignal {
+                semaphore: 1,
+                count: 1,
+            },
+            EventKind::TaskState {
+                task: 1,
+                state: TaskState::Ready,
+            },
+            EventKind::TaskSwitch { task: Some(1) },
+            EventKind::SemaphoreWait { semaphore: 1 },
+            EventKind::TaskSwitch { task: Some(2) },
+            EventKind::TaskState {
+                task: 2,
+                state: TaskState::Dormant,
+            },
+            EventKind::TaskSwitch { task: None },
+            EventKind::InterruptEnter {
+                line: r3_port_std::INTERRUPT_LINE_TIMER as u16,
+            },
+            EventKind::TimerFire { timer: 1 },
+            EventKind::SemaphoreSignal {
+                semaphore: 1,
+                count: 1,
+            },
+            EventKind::InterruptExit {
+                line: r3_port_std::INTERRUPT_LINE_TIMER as u16,
+            },
+            EventKind::TaskSwitch { task: Some(1) },
+        ],
+    );
+
+    // Timestamps never go back
+    assert!(trace.events.windows(2).all(|w| w[0].time <= w[1].time));
+
+    // Only interrupt events are recorded without a tick count
+    assert!(trace.events.iter().all(|e| e.time_is_approximate
+        == matches!(
+            e.kind,
+            EventKind::InterruptEnter { .. } | EventKind::InterruptExit { .. }
+        )));
+}
+
+/// Assert that `expected` appears in `events` in order, possibly interleaved
+/// with other events.
+#[track_caller]
+fn assert_subsequence(events: &[EventKind], expected: &[EventKind]) {
+    let mut events = events.iter();
+    for x in expected {
+        assert!(
+            events.any(|e| e == x),
+            "{x:?} is missing in the remaining part of the trace"
+        );
+    }
+}